@@ -119,9 +119,49 @@ impl Dir {
     }
 
     /// Returns an iterator of `Result<Entry>` which rewinds when finished.
+    ///
+    /// Iteration starts from the current position of the directory stream,
+    /// as set by [`Dir::seek`].
     pub fn iter(&mut self) -> Iter {
         Iter(self)
     }
+
+    /// Returns the current position of the directory stream.
+    ///
+    /// The position can later be passed to [`Dir::seek`] to resume reading
+    /// from the same entry.
+    #[doc(alias("telldir"))]
+    pub fn tell(&self) -> Result<DirPosition> {
+        tell(self)
+    }
+
+    /// Sets the position of the directory stream to `pos`, as previously
+    /// returned by [`Dir::tell`] or [`Iter::tell`] on this `Dir`.
+    #[doc(alias("seekdir"))]
+    pub fn seek(&mut self, pos: DirPosition) {
+        unsafe { libc::seekdir(self.0.as_ptr(), pos.0) }
+    }
+
+    /// Resets the position of the directory stream to the beginning of the
+    /// directory.
+    ///
+    /// This also makes the directory reflect entries created or removed since
+    /// it was opened or last rewound.
+    #[doc(alias("rewinddir"))]
+    pub fn rewind(&mut self) {
+        unsafe { libc::rewinddir(self.0.as_ptr()) }
+    }
+}
+
+/// A position within a directory stream, as returned by [`Dir::tell`].
+///
+/// Positions are only meaningful for the `Dir` that returned them.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DirPosition(libc::c_long);
+
+fn tell(dir: &Dir) -> Result<DirPosition> {
+    let res = unsafe { libc::telldir(dir.0.as_ptr()) };
+    Errno::result(res).map(DirPosition)
 }
 
 // `Dir` is not `Sync`. With the current implementation, it could be, but according to
@@ -198,9 +238,21 @@ impl<'d> Iterator for Iter<'d> {
     }
 }
 
+impl<'d> Iter<'d> {
+    /// Returns the current position of the directory stream, that is, the
+    /// position of the entry that would be returned next.
+    ///
+    /// Because the directory is rewound when the `Iter` is dropped, the
+    /// position must be restored with [`Dir::seek`] to resume iteration.
+    #[doc(alias("telldir"))]
+    pub fn tell(&self) -> Result<DirPosition> {
+        tell(self.0)
+    }
+}
+
 impl<'d> Drop for Iter<'d> {
     fn drop(&mut self) {
-        unsafe { libc::rewinddir((self.0).0.as_ptr()) }
+        self.0.rewind()
     }
 }
 
@@ -216,6 +268,15 @@ impl Iterator for OwningIter {
     }
 }
 
+impl OwningIter {
+    /// Returns the current position of the directory stream, that is, the
+    /// position of the entry that would be returned next.
+    #[doc(alias("telldir"))]
+    pub fn tell(&self) -> Result<DirPosition> {
+        tell(&self.0)
+    }
+}
+
 /// The file descriptor continues to be owned by the `OwningIter`,
 /// so callers must not keep a `RawFd` after the `OwningIter` is dropped.
 impl AsRawFd for OwningIter {
@@ -325,3 +386,165 @@ impl Entry {
         None
     }
 }
+
+/// A directory reader that uses `getdents64(2)` directly, without a libc `DIR`.
+///
+/// Entries are read into a caller-provided buffer and yielded as borrows of
+/// that buffer, so no allocation happens per entry.  Because each call to
+/// [`RawDir::next_entry`] may refill the buffer, `RawDir` cannot implement
+/// [`Iterator`]; use a `while let` loop instead.
+///
+/// Unlike [`Dir`], `RawDir` does not rewind the directory when it is
+/// exhausted; use [`lseek`](crate::unistd::lseek) on the file descriptor to
+/// do so.
+///
+/// # Examples
+///
+/// ```
+/// use nix::dir::RawDir;
+/// use nix::fcntl::{open, OFlag};
+/// use nix::sys::stat::Mode;
+///
+/// let fd = open(".", OFlag::O_RDONLY | OFlag::O_DIRECTORY, Mode::empty()).unwrap();
+/// let mut buf = [0u8; 4096];
+/// let mut dir = RawDir::new(&fd, &mut buf);
+/// while let Some(entry) = dir.next_entry() {
+///     let entry = entry.unwrap();
+///     println!("File name: {}", entry.file_name().to_str().unwrap());
+/// }
+/// ```
+#[cfg(linux_android)]
+#[derive(Debug)]
+pub struct RawDir<'buf, Fd: std::os::fd::AsFd> {
+    fd: Fd,
+    buf: &'buf mut [u8],
+    // Start of the next unread entry in `buf`.
+    offset: usize,
+    // Number of bytes of `buf` filled by the last `getdents64` call.
+    len: usize,
+}
+
+#[cfg(linux_android)]
+impl<'buf, Fd: std::os::fd::AsFd> RawDir<'buf, Fd> {
+    // Offsets of the fields of `struct linux_dirent64`.
+    const D_INO: usize = 0;
+    const D_OFF: usize = 8;
+    const D_RECLEN: usize = 16;
+    const D_TYPE: usize = 18;
+    const D_NAME: usize = 19;
+
+    /// Creates a reader for the directory open at `fd`, using `buf` to hold
+    /// the entries.
+    ///
+    /// `buf` must be large enough to hold at least one entry, or reading
+    /// will fail with `EINVAL`.  A few kilobytes is usually sufficient.
+    pub fn new(fd: Fd, buf: &'buf mut [u8]) -> Self {
+        // The kernel writes 8-byte aligned records, so start at an 8-byte
+        // aligned address.
+        let align = buf.as_ptr().align_offset(8).min(buf.len());
+        let buf = &mut buf[align..];
+        RawDir {
+            fd,
+            buf,
+            offset: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the next entry of the directory, or `None` once all entries
+    /// have been read.
+    pub fn next_entry(&mut self) -> Option<Result<RawDirEntry<'_>>> {
+        use std::os::fd::AsRawFd;
+
+        if self.offset >= self.len {
+            let res = unsafe {
+                libc::syscall(
+                    libc::SYS_getdents64,
+                    self.fd.as_fd().as_raw_fd(),
+                    self.buf.as_mut_ptr(),
+                    self.buf.len(),
+                )
+            };
+            match Errno::result(res) {
+                Ok(0) => return None,
+                Ok(n) => {
+                    self.offset = 0;
+                    self.len = n as usize;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        let record = &self.buf[self.offset..self.len];
+        let reclen = u16::from_ne_bytes([
+            record[Self::D_RECLEN],
+            record[Self::D_RECLEN + 1],
+        ]) as usize;
+        let record = &record[..reclen];
+        self.offset += reclen;
+
+        let mut ino = [0; 8];
+        ino.copy_from_slice(&record[Self::D_INO..Self::D_OFF]);
+        let mut off = [0; 8];
+        off.copy_from_slice(&record[Self::D_OFF..Self::D_RECLEN]);
+        // The name is NUL-terminated and padded with zeros up to `reclen`.
+        let name = ffi::CStr::from_bytes_until_nul(&record[Self::D_NAME..])
+            .expect("getdents64 returned an entry without a NUL terminator");
+
+        Some(Ok(RawDirEntry {
+            ino: u64::from_ne_bytes(ino),
+            offset: i64::from_ne_bytes(off),
+            d_type: record[Self::D_TYPE],
+            name,
+        }))
+    }
+}
+
+/// A directory entry borrowed from the buffer of a [`RawDir`].
+///
+/// Note that this may represent the `.` or `..` entries.
+#[cfg(linux_android)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RawDirEntry<'a> {
+    ino: u64,
+    offset: i64,
+    d_type: u8,
+    name: &'a ffi::CStr,
+}
+
+#[cfg(linux_android)]
+impl<'a> RawDirEntry<'a> {
+    /// Returns the inode number (`d_ino`) of this entry.
+    pub fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    /// Returns the opaque directory offset (`d_off`) of the entry following
+    /// this one, which can be passed to [`lseek`](crate::unistd::lseek) to
+    /// resume reading from there.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Returns the bare file name of this directory entry without any other leading path component.
+    pub fn file_name(&self) -> &'a ffi::CStr {
+        self.name
+    }
+
+    /// Returns the type of this directory entry, if known.
+    ///
+    /// Some filesystems don't report the file type.  The caller should use
+    /// `stat` or `fstat` if this returns `None`.
+    pub fn file_type(&self) -> Option<Type> {
+        match self.d_type {
+            libc::DT_FIFO => Some(Type::Fifo),
+            libc::DT_CHR => Some(Type::CharacterDevice),
+            libc::DT_DIR => Some(Type::Directory),
+            libc::DT_BLK => Some(Type::BlockDevice),
+            libc::DT_REG => Some(Type::File),
+            libc::DT_LNK => Some(Type::Symlink),
+            libc::DT_SOCK => Some(Type::Socket),
+            /* libc::DT_UNKNOWN | */ _ => None,
+        }
+    }
+}
@@ -18,13 +18,14 @@ use std::os::unix::ffi::OsStringExt;
 use std::os::unix::io::OwnedFd;
 use std::os::unix::io::RawFd;
 #[cfg(any(
+    linux_android,
     target_os = "netbsd",
     apple_targets,
     target_os = "dragonfly",
     all(target_os = "freebsd", target_arch = "x86_64"),
 ))]
 use std::path::PathBuf;
-#[cfg(any(linux_android, target_os = "freebsd"))]
+#[cfg(any(linux_android, target_os = "freebsd", apple_targets))]
 use std::ptr;
 
 #[cfg(feature = "fs")]
@@ -105,9 +106,45 @@ libc_bitflags! {
         /// performed using the effective user and group IDs instead of the real user and group ID
         #[cfg(not(target_os = "android"))]
         AT_EACCESS;
+        /// Used with [`statx`](crate::sys::statx::statx), force the attributes
+        /// to be synchronized with the server of a network filesystem.
+        #[cfg(any(
+            target_os = "android",
+            all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+        ))]
+        AT_STATX_FORCE_SYNC;
+        /// Used with [`statx`](crate::sys::statx::statx), don't synchronize
+        /// anything, use the locally cached attributes of a network
+        /// filesystem.
+        #[cfg(any(
+            target_os = "android",
+            all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+        ))]
+        AT_STATX_DONT_SYNC;
     }
 }
 
+// The variants of the `FchownatFlags`, `FchmodatFlags` and `UtimensatFlags`
+// enums, which are now aliases of `AtFlags`, so that we can help our users
+// migrate.
+#[allow(missing_docs)]
+#[cfg(not(target_os = "redox"))]
+#[cfg(any(feature = "fs", feature = "process", feature = "user"))]
+impl AtFlags {
+    #[deprecated(
+        since = "0.28.0",
+        note = "The variant is deprecated, please use `AtFlags` instead"
+    )]
+    #[allow(non_upper_case_globals)]
+    pub const FollowSymlink: AtFlags = AtFlags::empty();
+    #[deprecated(
+        since = "0.28.0",
+        note = "The variant is deprecated, please use `AtFlags` instead"
+    )]
+    #[allow(non_upper_case_globals)]
+    pub const NoFollowSymlink: AtFlags = AtFlags::AT_SYMLINK_NOFOLLOW;
+}
+
 #[cfg(any(
     feature = "fs",
     feature = "term",
@@ -412,6 +449,85 @@ cfg_if::cfg_if! {
     }
 }
 
+/// Create an unnamed temporary regular file in the directory `dir`.
+///
+/// The file is opened with `O_TMPFILE | O_RDWR | O_CLOEXEC`.  It has no name,
+/// and is removed once the last file descriptor referring to it is closed,
+/// unless it is given a name with [`materialize`] first.  This makes it
+/// possible to fully write a file before atomically publishing it.
+///
+/// Not every filesystem supports `O_TMPFILE`, `EOPNOTSUPP` is returned in that
+/// case.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::io::Write;
+/// # use nix::fcntl::{materialize, tmpfile_in, AT_FDCWD};
+/// # use nix::sys::stat::Mode;
+/// let fd = tmpfile_in("/tmp", Mode::from_bits_truncate(0o644)).unwrap();
+/// std::fs::File::from(fd.try_clone().unwrap()).write_all(b"done").unwrap();
+/// materialize(&fd, AT_FDCWD, "/tmp/result").unwrap();
+/// ```
+///
+/// # See Also
+/// * [`open`](https://man7.org/linux/man-pages/man2/open.2.html)
+#[cfg(linux_android)]
+pub fn tmpfile_in<P: ?Sized + NixPath>(dir: &P, mode: Mode) -> Result<OwnedFd> {
+    open(dir, OFlag::O_TMPFILE | OFlag::O_RDWR | OFlag::O_CLOEXEC, mode)
+}
+
+/// Give a name to an unnamed file created with [`tmpfile_in`].
+///
+/// This creates a hard link to the file referred to by `fd` at `name`, which
+/// is resolved relative to `dirfd`.  Linking with `AT_EMPTY_PATH` requires the
+/// `CAP_DAC_READ_SEARCH` capability, so if it fails, the link is created from
+/// `/proc/self/fd/<fd>` instead, which requires `/proc` to be mounted.
+///
+/// Like `linkat(2)`, this fails with `EEXIST` if `name` already exists.  To
+/// replace an existing file, materialize to a temporary name and
+/// [`renameat`] it over the destination.
+#[cfg(linux_android)]
+pub fn materialize<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd, P: ?Sized + NixPath>(
+    fd: Fd1,
+    dirfd: Fd2,
+    name: &P,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let fd = fd.as_fd().as_raw_fd();
+    let dirfd = dirfd.as_fd().as_raw_fd();
+
+    let res = name.with_nix_path(|cstr| unsafe {
+        libc::linkat(
+            fd,
+            b"\0".as_ptr().cast(),
+            dirfd,
+            cstr.as_ptr(),
+            libc::AT_EMPTY_PATH,
+        )
+    })?;
+    match Errno::result(res) {
+        // Without CAP_DAC_READ_SEARCH the link fails with ENOENT.
+        Err(Errno::ENOENT | Errno::EPERM) => (),
+        res => return res.map(drop),
+    }
+
+    let proc_path = format!("/proc/self/fd/{fd}");
+    let res = proc_path.with_nix_path(|proc_cstr| {
+        name.with_nix_path(|cstr| unsafe {
+            libc::linkat(
+                libc::AT_FDCWD,
+                proc_cstr.as_ptr(),
+                dirfd,
+                cstr.as_ptr(),
+                libc::AT_SYMLINK_FOLLOW,
+            )
+        })
+    })??;
+    Errno::result(res).map(drop)
+}
+
 /// Change the name of a file.
 ///
 /// The `renameat` function is equivalent to `rename` except in the case where either `old_path`
@@ -462,6 +578,24 @@ libc_bitflags! {
     }
 }
 
+#[cfg(apple_targets)]
+#[cfg(feature = "fs")]
+libc_bitflags! {
+    /// Flags for use with [`renameatx_np`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub struct RenameFlags: libc::c_uint {
+        /// Atomically swap `old_path` and `new_path`, which must both exist.
+        ///
+        /// This is the equivalent of `RENAME_EXCHANGE` on Linux.
+        RENAME_SWAP;
+        /// Don't overwrite `new_path` of the rename.  Return an error if
+        /// `new_path` already exists.
+        ///
+        /// This is the equivalent of `RENAME_NOREPLACE` on Linux.
+        RENAME_EXCL;
+    }
+}
+
 feature! {
 #![feature = "fs"]
 /// Like [`renameat`], but with an additional `flags` argument.
@@ -494,6 +628,164 @@ pub fn renameat2<P1: ?Sized + NixPath, P2: ?Sized + NixPath, Fd1: std::os::fd::A
     Errno::result(res).map(drop)
 }
 
+/// Like [`renameat`], but with an additional `flags` argument.
+///
+/// A `renameatx_np` call with an empty flags argument is equivalent to
+/// `renameat`.  The flags are only supported by some file systems, such as
+/// APFS; `ENOTSUP` is returned otherwise.
+///
+/// # See Also
+/// * [`renameatx_np(2)`](https://keith.github.io/xcode-man-pages/rename.2.html)
+#[cfg(apple_targets)]
+pub fn renameatx_np<P1: ?Sized + NixPath, P2: ?Sized + NixPath, Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    old_dirfd: Fd1,
+    old_path: &P1,
+    new_dirfd: Fd2,
+    new_path: &P2,
+    flags: RenameFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = old_path.with_nix_path(|old_cstr| {
+        new_path.with_nix_path(|new_cstr| unsafe {
+            libc::renameatx_np(
+                old_dirfd.as_fd().as_raw_fd(),
+                old_cstr.as_ptr(),
+                new_dirfd.as_fd().as_raw_fd(),
+                new_cstr.as_ptr(),
+                flags.bits(),
+            )
+        })
+    })??;
+    Errno::result(res).map(drop)
+}
+
+#[cfg(apple_targets)]
+// libc does not export the `CLONE_*` flags of `clonefileat`, so
+// `libc_bitflags!` can't be used.  The values are from `<sys/clonefile.h>`.
+::bitflags::bitflags! {
+    /// Flags for use with [`clonefileat`] and [`fclonefileat`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct ClonefileFlags: u32 {
+        /// Don't follow the source file if it is a symbolic link, clone the
+        /// link itself.
+        const CLONE_NOFOLLOW = 0x0001;
+        /// Don't copy the ownership of the source file, the clone is owned by
+        /// the calling user.
+        const CLONE_NOOWNERCOPY = 0x0002;
+        /// Copy the access control list of the source file, and don't apply
+        /// the inherited ACLs of the destination directory.  Since macOS 13.
+        const CLONE_ACL = 0x0004;
+    }
+}
+
+/// Create a copy-on-write clone of a file (`clonefileat(2)`).
+///
+/// The clone shares its data blocks with the source until either of them is
+/// modified, so this is instantaneous regardless of the file size.  It is only
+/// supported on APFS, `ENOTSUP` is returned otherwise, and `EXDEV` is returned
+/// if the source and destination are on different volumes.  The destination
+/// must not exist.
+///
+/// # See Also
+/// * [`clonefile(2)`](https://keith.github.io/xcode-man-pages/clonefile.2.html)
+#[cfg(apple_targets)]
+pub fn clonefileat<P1: ?Sized + NixPath, P2: ?Sized + NixPath, Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    src_dirfd: Fd1,
+    src: &P1,
+    dst_dirfd: Fd2,
+    dst: &P2,
+    flags: ClonefileFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = src.with_nix_path(|src_cstr| {
+        dst.with_nix_path(|dst_cstr| unsafe {
+            libc::clonefileat(
+                src_dirfd.as_fd().as_raw_fd(),
+                src_cstr.as_ptr(),
+                dst_dirfd.as_fd().as_raw_fd(),
+                dst_cstr.as_ptr(),
+                flags.bits(),
+            )
+        })
+    })??;
+    Errno::result(res).map(drop)
+}
+
+/// Like [`clonefileat`], but the source is the open file `src_fd`
+/// (`fclonefileat(2)`).
+///
+/// # See Also
+/// * [`clonefile(2)`](https://keith.github.io/xcode-man-pages/clonefile.2.html)
+#[cfg(apple_targets)]
+pub fn fclonefileat<P: ?Sized + NixPath, Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    src_fd: Fd1,
+    dst_dirfd: Fd2,
+    dst: &P,
+    flags: ClonefileFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = dst.with_nix_path(|dst_cstr| unsafe {
+        libc::fclonefileat(
+            src_fd.as_fd().as_raw_fd(),
+            dst_dirfd.as_fd().as_raw_fd(),
+            dst_cstr.as_ptr(),
+            flags.bits(),
+        )
+    })?;
+    Errno::result(res).map(drop)
+}
+
+#[cfg(apple_targets)]
+libc_bitflags! {
+    /// What to copy with [`fcopyfile`].
+    pub struct CopyfileFlags: libc::copyfile_flags_t {
+        /// Copy the access control list.
+        COPYFILE_ACL;
+        /// Copy the POSIX information: mode, ownership, flags and times.
+        COPYFILE_STAT;
+        /// Copy the extended attributes.
+        COPYFILE_XATTR;
+        /// Copy the data.
+        COPYFILE_DATA;
+        /// Shorthand for `COPYFILE_STAT | COPYFILE_ACL`.
+        COPYFILE_SECURITY;
+        /// Shorthand for `COPYFILE_SECURITY | COPYFILE_XATTR`.
+        COPYFILE_METADATA;
+        /// Preserve holes when copying the data of a sparse file.
+        COPYFILE_DATA_SPARSE;
+    }
+}
+
+/// Copy the contents and/or metadata of the open file `from` to the open
+/// file `to` (`fcopyfile(3)`).
+///
+/// The data is copied from and to the current file offsets.
+///
+/// # See Also
+/// * [`copyfile(3)`](https://keith.github.io/xcode-man-pages/copyfile.3.html)
+#[cfg(apple_targets)]
+pub fn fcopyfile<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    from: Fd1,
+    to: Fd2,
+    flags: CopyfileFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = unsafe {
+        libc::fcopyfile(
+            from.as_fd().as_raw_fd(),
+            to.as_fd().as_raw_fd(),
+            ptr::null_mut(),
+            flags.bits(),
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
 fn wrap_readlink_result(mut v: Vec<u8>, len: ssize_t) -> Result<OsString> {
     unsafe { v.set_len(len as usize) }
     v.shrink_to_fit();
@@ -778,10 +1070,31 @@ pub enum FcntlArg<'a> {
     /// fsync + issue barrier to drive
     #[cfg(apple_targets)]
     F_BARRIERFSYNC,
-    /// Return the capacity of a pipe
+    /// Turn data caching off (`true`) or on (`false`) for the file.
+    #[cfg(apple_targets)]
+    F_NOCACHE(bool),
+    /// Preallocate storage for the file.
+    ///
+    /// `fst_flags` selects `F_ALLOCATECONTIG` and/or `F_ALLOCATEALL`, and
+    /// `fst_posmode` is `F_PEOFPOSMODE` to allocate from the physical end of
+    /// the file or `F_VOLPOSMODE` to allocate from `fst_offset` on the volume.
+    /// On return, `fst_bytesalloc` holds the number of bytes allocated.  Note
+    /// that this does not change the size of the file.
+    #[cfg(apple_targets)]
+    F_PREALLOCATE(&'a mut libc::fstore_t),
+    /// Issue an advisory read-ahead of the given range of the file.
+    #[cfg(apple_targets)]
+    F_RDADVISE(&'a libc::radvisory),
+    /// Return the capacity of a pipe, in bytes.
     #[cfg(linux_android)]
     F_GETPIPE_SZ,
-    /// Change the capacity of a pipe
+    /// Change the capacity of a pipe to at least the given number of bytes.
+    ///
+    /// The kernel may round the requested size up (to a power-of-two number
+    /// of pages), so [`fcntl`] returns the capacity that was actually set.
+    /// Unprivileged processes cannot exceed `/proc/sys/fs/pipe-max-size`,
+    /// `EPERM` is returned in that case, and `EBUSY` is returned if the pipe
+    /// holds more data than would fit into the new capacity.
     #[cfg(linux_android)]
     F_SETPIPE_SZ(c_int),
     /// Look up the path of an open file descriptor, if possible.
@@ -867,6 +1180,12 @@ pub fn fcntl<Fd: std::os::fd::AsFd>(fd: Fd, arg: FcntlArg) -> Result<c_int> {
             F_FULLFSYNC => libc::fcntl(fd, libc::F_FULLFSYNC),
             #[cfg(apple_targets)]
             F_BARRIERFSYNC => libc::fcntl(fd, libc::F_BARRIERFSYNC),
+            #[cfg(apple_targets)]
+            F_NOCACHE(on) => libc::fcntl(fd, libc::F_NOCACHE, on as c_int),
+            #[cfg(apple_targets)]
+            F_PREALLOCATE(fstore) => libc::fcntl(fd, libc::F_PREALLOCATE, fstore),
+            #[cfg(apple_targets)]
+            F_RDADVISE(radvisory) => libc::fcntl(fd, libc::F_RDADVISE, radvisory),
             #[cfg(linux_android)]
             F_GETPIPE_SZ => libc::fcntl(fd, libc::F_GETPIPE_SZ),
             #[cfg(linux_android)]
@@ -911,6 +1230,54 @@ pub fn fcntl<Fd: std::os::fd::AsFd>(fd: Fd, arg: FcntlArg) -> Result<c_int> {
     Errno::result(res)
 }
 
+/// Look up the path of the file referred to by an open file descriptor.
+///
+/// This uses [`F_GETPATH`](FcntlArg::F_GETPATH) where available,
+/// [`F_KINFO`](FcntlArg::F_KINFO) on FreeBSD, and reads the
+/// `/proc/self/fd/N` symbolic link on Linux and Android, which requires
+/// `/proc` to be mounted.
+///
+/// The path is only informative: the file may have been renamed or removed
+/// since it was opened (on Linux, the path of a removed file ends with
+/// `" (deleted)"`), and pipes and sockets have no path at all.
+///
+/// # Example
+///
+/// ```
+/// # use nix::fcntl::fd_path;
+/// let file = tempfile::NamedTempFile::new().unwrap();
+/// let path = fd_path(&file).unwrap();
+/// assert_eq!(
+///     path.canonicalize().unwrap(),
+///     file.path().canonicalize().unwrap()
+/// );
+/// ```
+#[cfg(any(
+    linux_android,
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    apple_targets,
+    all(target_os = "freebsd", target_arch = "x86_64"),
+))]
+pub fn fd_path<Fd: std::os::fd::AsFd>(fd: Fd) -> Result<PathBuf> {
+    cfg_if::cfg_if! {
+        if #[cfg(linux_android)] {
+            use std::os::fd::AsRawFd;
+
+            let link = format!("/proc/self/fd/{}", fd.as_fd().as_raw_fd());
+            readlink(link.as_str()).map(PathBuf::from)
+        } else if #[cfg(target_os = "freebsd")] {
+            let mut path = PathBuf::new();
+            fcntl(fd, F_KINFO(&mut path))?;
+            Ok(path)
+        } else {
+            let mut path = PathBuf::new();
+            fcntl(fd, F_GETPATH(&mut path))?;
+            Ok(path)
+        }
+    }
+}
+
 /// Operations for use with [`Flock::lock`].
 #[cfg(not(any(target_os = "redox", target_os = "solaris")))]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -1140,7 +1507,10 @@ feature! {
 /// to the new position.
 ///
 /// On successful completion the number of bytes actually copied will be
-/// returned.
+/// returned.  This may be less than `len`, either because the end of `fd_in`
+/// was reached (a return value of 0 means there is nothing left to copy) or
+/// because the kernel decided to perform a short copy.  Use
+/// [`copy_file_all`] to copy the whole range.
 // Note: FreeBSD defines the offset argument as "off_t".  Linux and Android
 // define it as "loff_t".  But on both OSes, on all supported platforms, those
 // are 64 bits.  So Nix uses i64 to make the docs simple and consistent.
@@ -1192,6 +1562,140 @@ pub fn copy_file_range<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
     Errno::result(ret).map(|r| r as usize)
 }
 
+/// Copy `len` bytes of data from one file to another
+///
+/// This calls [`copy_file_range`] in a loop until `len` bytes have been
+/// copied or the end of `fd_in` has been reached, and returns the total number
+/// of bytes copied.  `off_in` and `off_out` have the same meaning as in
+/// [`copy_file_range`].
+///
+/// Older Linux kernels (before 5.3) can not copy between different
+/// filesystems, some filesystems do not support `copy_file_range` at all, and
+/// it can not be used on pipes and other non-regular files.  If the kernel
+/// reports `EXDEV`, `ENOSYS` or `EOPNOTSUPP`, the rest of the data is copied
+/// through a userspace buffer with `read`/`write` (or `pread`/`pwrite` if an
+/// offset was given) instead.  `EINVAL` only falls back when nothing has been
+/// copied yet and the files are on different filesystems, like a pipe and a
+/// regular file: otherwise, such as for overlapping ranges of the same file
+/// or an `fd_out` opened with `O_APPEND`, it is returned.
+///
+/// On error, some data may already have been copied.  If a `write` in the
+/// fallback makes no progress, `EIO` is returned.
+#[cfg(any(linux_android, target_os = "freebsd"))]
+pub fn copy_file_all<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    fd_in: Fd1,
+    mut off_in: Option<&mut i64>,
+    fd_out: Fd2,
+    mut off_out: Option<&mut i64>,
+    len: usize,
+) -> Result<usize> {
+    let fd_in = fd_in.as_fd();
+    let fd_out = fd_out.as_fd();
+    let mut copied = 0;
+
+    while copied < len {
+        match copy_file_range(
+            fd_in,
+            off_in.as_deref_mut(),
+            fd_out,
+            off_out.as_deref_mut(),
+            len - copied,
+        ) {
+            Ok(0) => break,
+            Ok(n) => copied += n,
+            Err(Errno::EINTR) => continue,
+            // `ENOTSUP` is the same value as `EOPNOTSUPP`.
+            Err(Errno::EXDEV | Errno::ENOSYS | Errno::EOPNOTSUPP) => {
+                return copy_file_all_fallback(
+                    fd_in, off_in, fd_out, off_out, len, copied,
+                );
+            }
+            Err(Errno::EINVAL)
+                if copied == 0 && !on_same_filesystem(fd_in, fd_out) =>
+            {
+                return copy_file_all_fallback(
+                    fd_in, off_in, fd_out, off_out, len, copied,
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Whether `fd1` and `fd2` are on the same filesystem, assumed if either
+/// can't be `fstat`ed.
+#[cfg(any(linux_android, target_os = "freebsd"))]
+fn on_same_filesystem(
+    fd1: std::os::fd::BorrowedFd<'_>,
+    fd2: std::os::fd::BorrowedFd<'_>,
+) -> bool {
+    use crate::sys::stat::fstat;
+
+    match (fstat(fd1), fstat(fd2)) {
+        (Ok(st1), Ok(st2)) => st1.st_dev == st2.st_dev,
+        _ => true,
+    }
+}
+
+/// The `read`/`write` based implementation of [`copy_file_all`], used when
+/// `copy_file_range` is not available for the given files.
+#[cfg(any(linux_android, target_os = "freebsd"))]
+fn copy_file_all_fallback(
+    fd_in: std::os::fd::BorrowedFd<'_>,
+    mut off_in: Option<&mut i64>,
+    fd_out: std::os::fd::BorrowedFd<'_>,
+    mut off_out: Option<&mut i64>,
+    len: usize,
+    mut copied: usize,
+) -> Result<usize> {
+    use crate::sys::uio::{pread, pwrite};
+    use crate::unistd::{read, write};
+
+    const BUF_SIZE: usize = 64 * 1024;
+    let mut buf = vec![0u8; BUF_SIZE.min(len - copied)];
+
+    while copied < len {
+        let want = buf.len().min(len - copied);
+        let res = match off_in.as_deref_mut() {
+            Some(off) => pread(fd_in, &mut buf[..want], *off as libc::off_t),
+            None => read(fd_in, &mut buf[..want]),
+        };
+        let n = match res {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(Errno::EINTR) => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(off) = off_in.as_deref_mut() {
+            *off += n as i64;
+        }
+
+        let mut written = 0;
+        while written < n {
+            let res = match off_out.as_deref_mut() {
+                Some(off) => pwrite(fd_out, &buf[written..n], *off as libc::off_t),
+                None => write(fd_out, &buf[written..n]),
+            };
+            let w = match res {
+                // Retrying a write that made no progress would loop forever.
+                Ok(0) => return Err(Errno::EIO),
+                Ok(w) => w,
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(off) = off_out.as_deref_mut() {
+                *off += w as i64;
+            }
+            written += w;
+        }
+        copied += n;
+    }
+
+    Ok(copied)
+}
+
 /// Splice data to/from a pipe
 ///
 /// # See Also
@@ -1261,10 +1765,499 @@ pub fn vmsplice<F: std::os::fd::AsFd>(
 }
 }
 
+feature! {
+#![feature = "fs"]
+
+/// Share the data of the whole file `src` with the file `dest`
+/// (`ioctl(FICLONE)`).
+///
+/// On filesystems supporting it (e.g. Btrfs, XFS), this creates a
+/// copy-on-write "reflink" of the file: no data is copied, and the two files
+/// share their storage until one of them is modified.  Both files must be on
+/// the same filesystem.
+///
+/// # See Also
+/// * [`ioctl_ficlone`](https://man7.org/linux/man-pages/man2/ioctl_ficlone.2.html)
+#[cfg(linux_android)]
+pub fn ficlone<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    dest: Fd1,
+    src: Fd2,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = unsafe {
+        libc::ioctl(
+            dest.as_fd().as_raw_fd(),
+            libc::FICLONE,
+            src.as_fd().as_raw_fd(),
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Share `src_length` bytes of the file `src` starting at `src_offset` with
+/// the file `dest` at `dest_offset` (`ioctl(FICLONERANGE)`).
+///
+/// This is like [`ficlone`], but only for a range of the file.  A
+/// `src_length` of 0 means until the end of `src`.  Most filesystems require
+/// the offsets and the length to be aligned to the filesystem block size.
+///
+/// # See Also
+/// * [`ioctl_ficlonerange`](https://man7.org/linux/man-pages/man2/ioctl_ficlonerange.2.html)
+#[cfg(linux_android)]
+pub fn ficlonerange<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd>(
+    dest: Fd1,
+    dest_offset: u64,
+    src: Fd2,
+    src_offset: u64,
+    src_length: u64,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let range = libc::file_clone_range {
+        src_fd: src.as_fd().as_raw_fd().into(),
+        src_offset,
+        src_length,
+        dest_offset,
+    };
+    let res = unsafe {
+        libc::ioctl(dest.as_fd().as_raw_fd(), libc::FICLONERANGE, &range)
+    };
+    Errno::result(res).map(drop)
+}
+
+// `struct file_dedupe_range` and `struct file_dedupe_range_info` from
+// `linux/fs.h`, which are not yet provided by libc.
+#[cfg(linux_android)]
+#[repr(C)]
+struct file_dedupe_range {
+    src_offset: u64,
+    src_length: u64,
+    dest_count: u16,
+    reserved1: u16,
+    reserved2: u32,
+}
+
+#[cfg(linux_android)]
+#[repr(C)]
+struct file_dedupe_range_info {
+    dest_fd: i64,
+    dest_offset: u64,
+    bytes_deduped: u64,
+    status: i32,
+    reserved: u32,
+}
+
+#[cfg(linux_android)]
+const FIDEDUPERANGE: libc::Ioctl = crate::request_code_readwrite!(
+    0x94,
+    54,
+    std::mem::size_of::<file_dedupe_range>()
+);
+#[cfg(linux_android)]
+const FILE_DEDUPE_RANGE_DIFFERS: i32 = 1;
+
+/// The result of deduplicating a range into one destination with
+/// [`fideduperange`].
+#[cfg(linux_android)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DedupeRangeStatus {
+    /// The ranges had the same contents, and the given number of bytes now
+    /// share their storage.
+    ///
+    /// This may be less than the requested length, in which case the rest of
+    /// the range should be submitted again.
+    Same(u64),
+    /// The contents of the ranges differ, nothing was deduplicated.
+    Differs,
+    /// Deduplicating into this destination failed.
+    Error(Errno),
+}
+
+/// Deduplicate `src_length` bytes of the file `src` starting at `src_offset`
+/// with each of the `(file, offset)` destinations in `dests`
+/// (`ioctl(FIDEDUPERANGE)`).
+///
+/// For each destination, the kernel compares the contents of the two ranges,
+/// and if they are identical, makes them share their storage.  Unlike
+/// [`ficlonerange`], the contents of the destinations never change.  The
+/// returned `Vec` holds the status of each destination, in the same order as
+/// `dests`.
+///
+/// # See Also
+/// * [`ioctl_fideduperange`](https://man7.org/linux/man-pages/man2/ioctl_fideduperange.2.html)
+#[cfg(linux_android)]
+pub fn fideduperange<Fd: std::os::fd::AsFd>(
+    src: Fd,
+    src_offset: u64,
+    src_length: u64,
+    dests: &[(std::os::fd::BorrowedFd<'_>, u64)],
+) -> Result<Vec<DedupeRangeStatus>> {
+    use std::mem::size_of;
+    use std::os::fd::AsRawFd;
+
+    let dest_count = u16::try_from(dests.len()).map_err(|_| Errno::EINVAL)?;
+    let len = size_of::<file_dedupe_range>()
+        + dests.len() * size_of::<file_dedupe_range_info>();
+    // Use a `Vec<u64>` so the buffer is suitably aligned for both structures.
+    let mut buf = vec![0u64; (len + size_of::<u64>() - 1) / size_of::<u64>()];
+
+    let header = buf.as_mut_ptr().cast::<file_dedupe_range>();
+    // SAFETY: `buf` is large enough, and aligned, for the header and
+    // `dests.len()` trailing `file_dedupe_range_info` structures.
+    let infos = unsafe {
+        header.write(file_dedupe_range {
+            src_offset,
+            src_length,
+            dest_count,
+            reserved1: 0,
+            reserved2: 0,
+        });
+        header.add(1).cast::<file_dedupe_range_info>()
+    };
+    for (i, (fd, offset)) in dests.iter().enumerate() {
+        unsafe {
+            infos.add(i).write(file_dedupe_range_info {
+                dest_fd: fd.as_raw_fd().into(),
+                dest_offset: *offset,
+                bytes_deduped: 0,
+                status: 0,
+                reserved: 0,
+            });
+        }
+    }
+
+    let res = unsafe {
+        libc::ioctl(src.as_fd().as_raw_fd(), FIDEDUPERANGE, header)
+    };
+    Errno::result(res)?;
+
+    Ok((0..dests.len())
+        .map(|i| {
+            let info = unsafe { &*infos.add(i) };
+            match info.status {
+                0 => DedupeRangeStatus::Same(info.bytes_deduped),
+                FILE_DEDUPE_RANGE_DIFFERS => DedupeRangeStatus::Differs,
+                errno => DedupeRangeStatus::Error(Errno::from_raw(-errno)),
+            }
+        })
+        .collect())
+}
+
+// `struct fiemap` and `struct fiemap_extent` from `linux/fiemap.h`, which
+// are not yet provided by libc.
+#[cfg(linux_android)]
+#[repr(C)]
+struct fiemap {
+    fm_start: u64,
+    fm_length: u64,
+    fm_flags: u32,
+    fm_mapped_extents: u32,
+    fm_extent_count: u32,
+    fm_reserved: u32,
+}
+
+#[cfg(linux_android)]
+#[repr(C)]
+struct fiemap_extent {
+    fe_logical: u64,
+    fe_physical: u64,
+    fe_length: u64,
+    fe_reserved64: [u64; 2],
+    fe_flags: u32,
+    fe_reserved: [u32; 3],
+}
+
+#[cfg(linux_android)]
+const FS_IOC_FIEMAP: libc::Ioctl =
+    crate::request_code_readwrite!(b'f', 11, std::mem::size_of::<fiemap>());
+
+#[cfg(linux_android)]
+::bitflags::bitflags! {
+    /// Flags for [`fiemap`] controlling how the extents are looked up.
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct FiemapFlags: u32 {
+        /// Sync the file before mapping its extents.
+        const FIEMAP_FLAG_SYNC = 0x0000_0001;
+        /// Map the extended attribute tree instead of the file data.
+        const FIEMAP_FLAG_XATTR = 0x0000_0002;
+        /// Request caching of the extents.
+        const FIEMAP_FLAG_CACHE = 0x0000_0004;
+    }
+}
+
+#[cfg(linux_android)]
+::bitflags::bitflags! {
+    /// Flags describing an extent returned by [`fiemap`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct FiemapExtentFlags: u32 {
+        /// This is the last extent of the file.
+        const FIEMAP_EXTENT_LAST = 0x0000_0001;
+        /// The location of the data is unknown.
+        const FIEMAP_EXTENT_UNKNOWN = 0x0000_0002;
+        /// The location of the data is not known yet because of delayed
+        /// allocation.  Implies `FIEMAP_EXTENT_UNKNOWN`.
+        const FIEMAP_EXTENT_DELALLOC = 0x0000_0004;
+        /// The data is not readable directly from the device, e.g. because it
+        /// is compressed.
+        const FIEMAP_EXTENT_ENCODED = 0x0000_0008;
+        /// The data is encrypted.  Implies `FIEMAP_EXTENT_ENCODED`.
+        const FIEMAP_EXTENT_DATA_ENCRYPTED = 0x0000_0080;
+        /// The extent offsets may not be block aligned.
+        const FIEMAP_EXTENT_NOT_ALIGNED = 0x0000_0100;
+        /// The data is stored in a metadata block.  Implies
+        /// `FIEMAP_EXTENT_NOT_ALIGNED`.
+        const FIEMAP_EXTENT_DATA_INLINE = 0x0000_0200;
+        /// The data is packed into a block together with data of other files.
+        /// Implies `FIEMAP_EXTENT_NOT_ALIGNED`.
+        const FIEMAP_EXTENT_DATA_TAIL = 0x0000_0400;
+        /// The space is allocated, but no data has been written to it yet.
+        const FIEMAP_EXTENT_UNWRITTEN = 0x0000_0800;
+        /// The filesystem does not track extents, the extent was merged from
+        /// blocks for reporting.
+        const FIEMAP_EXTENT_MERGED = 0x0000_1000;
+        /// The storage of this extent is shared with other files.
+        const FIEMAP_EXTENT_SHARED = 0x0000_2000;
+    }
+}
+
+/// A file extent returned by [`fiemap`].
+#[cfg(linux_android)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FiemapExtent {
+    /// Offset of the extent in the file, in bytes.
+    pub logical: u64,
+    /// Offset of the extent on the device, in bytes.
+    pub physical: u64,
+    /// Length of the extent, in bytes.
+    pub length: u64,
+    /// Flags describing the extent.
+    pub flags: FiemapExtentFlags,
+}
+
+/// Get the extents mapping the range of `length` bytes of a file starting at
+/// `start` to physical storage (`ioctl(FS_IOC_FIEMAP)`).
+///
+/// Holes are not reported, and the extents returned may begin before `start`
+/// or end after `start + length`.  Use `u64::MAX` as `length` to map the whole
+/// file.  The buffer holding the extents is sized internally, so the ioctl
+/// may be issued several times for a large or changing file.
+///
+/// # See Also
+/// * [`fiemap`](https://www.kernel.org/doc/html/latest/filesystems/fiemap.html)
+#[cfg(linux_android)]
+pub fn fiemap<Fd: std::os::fd::AsFd>(
+    fd: Fd,
+    start: u64,
+    length: u64,
+    flags: FiemapFlags,
+) -> Result<Vec<FiemapExtent>> {
+    use std::mem::size_of;
+    use std::os::fd::AsRawFd;
+
+    let fd = fd.as_fd().as_raw_fd();
+    let end = start.saturating_add(length);
+    let mut start = start;
+    let mut extents = Vec::new();
+    let mut buf: Vec<u64> = Vec::new();
+
+    while start < end {
+        // Figure out how many extents there are, then fetch them.
+        let mut count = 0;
+        let mut mapped;
+        loop {
+            let len = size_of::<fiemap>() + count * size_of::<fiemap_extent>();
+            buf.clear();
+            buf.resize(len / size_of::<u64>(), 0);
+            let header = buf.as_mut_ptr().cast::<fiemap>();
+            // SAFETY: `buf` is large enough, and aligned, for the header.
+            unsafe {
+                header.write(fiemap {
+                    fm_start: start,
+                    fm_length: end - start,
+                    fm_flags: flags.bits(),
+                    fm_mapped_extents: 0,
+                    fm_extent_count: count as u32,
+                    fm_reserved: 0,
+                });
+            }
+            let res = unsafe { libc::ioctl(fd, FS_IOC_FIEMAP, header) };
+            Errno::result(res)?;
+            mapped = unsafe { (*header).fm_mapped_extents } as usize;
+            if count != 0 || mapped == 0 {
+                break;
+            }
+            count = mapped;
+        }
+        if mapped == 0 {
+            break;
+        }
+
+        // SAFETY: the kernel filled in `mapped` extents following the header.
+        let batch = unsafe {
+            std::slice::from_raw_parts(
+                buf.as_ptr()
+                    .cast::<fiemap>()
+                    .add(1)
+                    .cast::<fiemap_extent>(),
+                mapped,
+            )
+        };
+        extents.extend(batch.iter().map(|e| FiemapExtent {
+            logical: e.fe_logical,
+            physical: e.fe_physical,
+            length: e.fe_length,
+            flags: FiemapExtentFlags::from_bits_retain(e.fe_flags),
+        }));
+
+        let last = &batch[mapped - 1];
+        if mapped < count
+            || last.fe_flags & FiemapExtentFlags::FIEMAP_EXTENT_LAST.bits() != 0
+        {
+            break;
+        }
+        // The file may have gained extents since they were counted, continue
+        // after the last one.
+        start = last.fe_logical.saturating_add(last.fe_length);
+    }
+
+    Ok(extents)
+}
+}
+
+#[cfg(linux_android)]
+#[cfg(feature = "fs")]
+::bitflags::bitflags! {
+    /// Inode flags of a file, as shown by `lsattr(1)` and changed by
+    /// `chattr(1)`, see [`get_inode_flags`].
+    ///
+    /// Support for each flag depends on the filesystem.
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct InodeFlags: c_int {
+        /// Securely delete the file (`s`), unsupported by most filesystems.
+        const FS_SECRM_FL = 0x0000_0001;
+        /// Allow undeleting the file (`u`), unsupported by most filesystems.
+        const FS_UNRM_FL = 0x0000_0002;
+        /// Compress the file (`c`).
+        const FS_COMPR_FL = 0x0000_0004;
+        /// Write the file synchronously (`S`).
+        const FS_SYNC_FL = 0x0000_0008;
+        /// The file can't be modified, deleted, renamed or linked to (`i`).
+        /// Requires the `CAP_LINUX_IMMUTABLE` capability to change.
+        const FS_IMMUTABLE_FL = 0x0000_0010;
+        /// The file can only be opened for appending (`a`).  Requires the
+        /// `CAP_LINUX_IMMUTABLE` capability to change.
+        const FS_APPEND_FL = 0x0000_0020;
+        /// Skip the file when backing up with `dump(8)` (`d`).
+        const FS_NODUMP_FL = 0x0000_0040;
+        /// Don't update the access time of the file (`A`).
+        const FS_NOATIME_FL = 0x0000_0080;
+        /// Don't compress the file (`m`).
+        const FS_NOCOMP_FL = 0x0000_0400;
+        /// The file is encrypted (`E`), read-only.
+        const FS_ENCRYPT_FL = 0x0000_0800;
+        /// The directory is indexed with hashed trees (`I`), read-only.
+        const FS_INDEX_FL = 0x0000_1000;
+        /// Write the data of the file to the journal first (`j`).
+        const FS_JOURNAL_DATA_FL = 0x0000_4000;
+        /// Don't merge the tail of the file with other files (`t`).
+        const FS_NOTAIL_FL = 0x0000_8000;
+        /// Write the changes to the directory synchronously (`D`).
+        const FS_DIRSYNC_FL = 0x0001_0000;
+        /// The directory is the top of a directory hierarchy, for the Orlov
+        /// block allocator (`T`).
+        const FS_TOPDIR_FL = 0x0002_0000;
+        /// The file is huge (`h`), read-only.
+        const FS_HUGE_FILE_FL = 0x0004_0000;
+        /// The file uses extents (`e`).
+        const FS_EXTENT_FL = 0x0008_0000;
+        /// The file is protected by fs-verity (`V`), read-only.
+        const FS_VERITY_FL = 0x0010_0000;
+        /// Don't copy on write the data of the file (`C`), on copy-on-write
+        /// filesystems like Btrfs.  Only effective on empty files.
+        const FS_NOCOW_FL = 0x0080_0000;
+        /// Access the file directly, bypassing the page cache (`x`).
+        const FS_DAX_FL = 0x0200_0000;
+        /// The data of the file is stored in its inode (`N`), read-only.
+        const FS_INLINE_DATA_FL = 0x1000_0000;
+        /// New files in the directory inherit its project id (`P`).
+        const FS_PROJINHERIT_FL = 0x2000_0000;
+        /// Look up names in the directory case-insensitively (`F`).
+        const FS_CASEFOLD_FL = 0x4000_0000;
+    }
+}
+
+feature! {
+#![feature = "fs"]
+
+/// Get the inode flags of a file (`ioctl(FS_IOC_GETFLAGS)`).
+///
+/// Fails with `ENOTTY` if the filesystem doesn't support inode flags.
+///
+/// # See Also
+/// * [`ioctl_iflags`](https://man7.org/linux/man-pages/man2/ioctl_iflags.2.html)
+#[cfg(linux_android)]
+pub fn get_inode_flags<Fd: std::os::fd::AsFd>(fd: Fd) -> Result<InodeFlags> {
+    use std::os::fd::AsRawFd;
+
+    // Despite its definition, the ioctl takes an `int`.
+    let mut flags: c_int = 0;
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::FS_IOC_GETFLAGS,
+            &mut flags as *mut c_int,
+        )
+    };
+    Errno::result(res).map(|_| InodeFlags::from_bits_retain(flags))
+}
+
+/// Set the inode flags of a file (`ioctl(FS_IOC_SETFLAGS)`).
+///
+/// All the flags are replaced, so they should be obtained with
+/// [`get_inode_flags`] and modified, to preserve the flags unknown to Nix.
+///
+/// # See Also
+/// * [`ioctl_iflags`](https://man7.org/linux/man-pages/man2/ioctl_iflags.2.html)
+#[cfg(linux_android)]
+pub fn set_inode_flags<Fd: std::os::fd::AsFd>(
+    fd: Fd,
+    flags: InodeFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let flags = flags.bits();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::FS_IOC_SETFLAGS,
+            &flags as *const c_int,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+}
+
 #[cfg(target_os = "linux")]
 #[cfg(feature = "fs")]
 libc_bitflags!(
     /// Mode argument flags for fallocate determining operation performed on a given range.
+    ///
+    /// Not every combination of flags is valid:
+    ///
+    /// * `FALLOC_FL_PUNCH_HOLE` must be combined with `FALLOC_FL_KEEP_SIZE`.
+    /// * `FALLOC_FL_ZERO_RANGE` and `FALLOC_FL_UNSHARE_RANGE` may be combined
+    ///   with `FALLOC_FL_KEEP_SIZE`.
+    /// * `FALLOC_FL_COLLAPSE_RANGE` and `FALLOC_FL_INSERT_RANGE` must be used
+    ///   on their own, with an offset and length that are multiples of the
+    ///   filesystem block size.
+    ///
+    /// Support for each operation depends on the filesystem, `EOPNOTSUPP` is
+    /// returned if it is not supported.
     #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
     pub struct FallocateFlags: c_int {
         /// File size is not changed.
@@ -1287,20 +2280,44 @@ libc_bitflags!(
         ///
         /// Does not overwrite existing data. Hole starts at offset and continues for len bytes.
         FALLOC_FL_INSERT_RANGE;
-        /// Shared file data extants are made private to the file.
+        /// Shared file data extents are made private to the file.
         ///
-        /// Gaurantees that a subsequent write will not fail due to lack of space.
+        /// Guarantees that a subsequent write will not fail due to lack of space.
         FALLOC_FL_UNSHARE_RANGE;
     }
 );
 
+#[cfg(target_os = "linux")]
+#[cfg(feature = "fs")]
+libc_bitflags!(
+    /// Flags for [`sync_file_range`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub struct SyncFileRangeFlags: c_uint {
+        /// Wait upon write-out of all pages in the specified range that have
+        /// already been submitted to the device driver for write-out before
+        /// performing any write.
+        SYNC_FILE_RANGE_WAIT_BEFORE;
+        /// Initiate write-out of all dirty pages in the specified range which
+        /// are not presently submitted write-out.
+        SYNC_FILE_RANGE_WRITE;
+        /// Wait upon write-out of all pages in the range after performing any
+        /// write.
+        SYNC_FILE_RANGE_WAIT_AFTER;
+    }
+);
+
 feature! {
 #![feature = "fs"]
 
 /// Manipulates file space.
 ///
 /// Allows the caller to directly manipulate the allocated disk space for the
-/// file referred to by fd.
+/// file referred to by fd.  See [`FallocateFlags`] for the supported
+/// operations, and [`posix_fallocate`] for a portable way to preallocate
+/// storage.
+///
+/// # See Also
+/// * [`fallocate`](https://man7.org/linux/man-pages/man2/fallocate.2.html)
 #[cfg(target_os = "linux")]
 #[cfg(feature = "fs")]
 pub fn fallocate<Fd: std::os::fd::AsFd>(
@@ -1315,6 +2332,59 @@ pub fn fallocate<Fd: std::os::fd::AsFd>(
     Errno::result(res).map(drop)
 }
 
+/// Sync a file segment with disk.
+///
+/// Unlike `fsync`/`fdatasync`, this only flushes the byte range starting at
+/// `offset` and continuing for `nbytes` bytes.  An `nbytes` of 0 means until
+/// the end of the file.  It does not flush file metadata, and gives no
+/// guarantee that the data is durable, as the disk write cache is not flushed.
+///
+/// To write out a range and wait for the write to complete, use
+/// `SYNC_FILE_RANGE_WAIT_BEFORE | SYNC_FILE_RANGE_WRITE | SYNC_FILE_RANGE_WAIT_AFTER`.
+///
+/// # See Also
+/// * [`sync_file_range`](https://man7.org/linux/man-pages/man2/sync_file_range.2.html)
+#[cfg(target_os = "linux")]
+pub fn sync_file_range<Fd: std::os::fd::AsFd>(
+    fd: Fd,
+    offset: libc::off64_t,
+    nbytes: libc::off64_t,
+    flags: SyncFileRangeFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = unsafe {
+        libc::sync_file_range(
+            fd.as_fd().as_raw_fd(),
+            offset,
+            nbytes,
+            flags.bits(),
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Initiate readahead on a file, populating the page cache with the data in
+/// the range starting at `offset` and continuing for `count` bytes.
+///
+/// This blocks until the data has been read.  Like
+/// [`posix_fadvise`] with `POSIX_FADV_WILLNEED`, it is only a hint: the
+/// kernel may read less than requested.
+///
+/// # See Also
+/// * [`readahead`](https://man7.org/linux/man-pages/man2/readahead.2.html)
+#[cfg(target_os = "linux")]
+pub fn readahead<Fd: std::os::fd::AsFd>(
+    fd: Fd,
+    offset: libc::off64_t,
+    count: usize,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = unsafe { libc::readahead(fd.as_fd().as_raw_fd(), offset, count) };
+    Errno::result(res).map(drop)
+}
+
 /// Argument to [`fspacectl`] describing the range to zero.  The first member is
 /// the file offset, and the second is the length of the region.
 #[cfg(any(target_os = "freebsd"))]
@@ -1526,11 +2596,16 @@ mod posix_fadvise {
 
 /// Pre-allocate storage for a range in a file
 ///
+/// After a successful call, subsequent writes to the range `offset..offset+len`
+/// are guaranteed not to fail because of a lack of disk space.  If
+/// `offset + len` is greater than the file size, the file is extended.
+///
 /// # See Also
 /// * [`posix_fallocate`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/posix_fallocate.html)
 #[cfg(any(
     linux_android,
     freebsdlike,
+    solarish,
     target_os = "emscripten",
     target_os = "fuchsia",
     target_os = "wasi",
@@ -1,6 +1,9 @@
 //! Load and unload kernel modules.
 //!
-//! For more details see
+//! For more details see [init_module(2)] and [delete_module(2)].
+//!
+//! [init_module(2)]: https://man7.org/linux/man-pages/man2/init_module.2.html
+//! [delete_module(2)]: https://man7.org/linux/man-pages/man2/delete_module.2.html
 
 use std::ffi::CStr;
 use std::os::unix::io::{AsFd, AsRawFd};
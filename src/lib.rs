@@ -9,6 +9,8 @@
 //! They may be enabled in any combination.
 //! * `acct` - Process accounting
 //! * `aio` - POSIX AIO
+//! * `caps` - Linux's thread capabilities
+//! * `capsicum` - FreeBSD's Capsicum capability mode and rights
 //! * `dir` - Stuff relating to directory iteration
 //! * `env` - Manipulate environment variables
 //! * `event` - Event-driven APIs, like `kqueue` and `epoll`
@@ -17,12 +19,18 @@
 //! * `fs` - File system functionality
 //! * `hostname` - Get and set the system's hostname
 //! * `inotify` - Linux's `inotify` file system notification API
+//! * `io_uring` - Linux's `io_uring` asynchronous I/O interface
 //! * `ioctl` - The `ioctl` syscall, and wrappers for many specific instances
+//! * `ipc` - System V IPC: semaphores and message queues
+//! * `jail` - FreeBSD jails
+//! * `key` - Linux's kernel key retention service
 //! * `kmod` - Load and unload kernel modules
+//! * `landlock` - Linux's Landlock unprivileged sandboxing
 //! * `mman` - Stuff relating to memory management
 //! * `mount` - Mount and unmount file systems
 //! * `mqueue` - POSIX message queues
 //! * `net` - Networking-related functionality
+//! * `perf_event` - Linux's performance monitoring counters
 //! * `personality` - Set the process execution domain
 //! * `poll` - APIs like `poll` and `select`
 //! * `process` - Stuff relating to running processes
@@ -32,6 +40,7 @@
 //! * `reboot` - Reboot the system
 //! * `resource` - Process resource limits
 //! * `sched` - Manipulate process's scheduling
+//! * `seccomp` - Linux's seccomp system call filtering
 //! * `socket` - Sockets, whether for networking or local use
 //! * `signal` - Send and receive signals to processes
 //! * `term` - Terminal control APIs
@@ -50,6 +59,8 @@
     not(all(
         feature = "acct",
         feature = "aio",
+        feature = "caps",
+        feature = "capsicum",
         feature = "dir",
         feature = "env",
         feature = "event",
@@ -58,12 +69,18 @@
         feature = "fs",
         feature = "hostname",
         feature = "inotify",
+        feature = "io_uring",
         feature = "ioctl",
+        feature = "ipc",
+        feature = "jail",
+        feature = "key",
         feature = "kmod",
+        feature = "landlock",
         feature = "mman",
         feature = "mount",
         feature = "mqueue",
         feature = "net",
+        feature = "perf_event",
         feature = "personality",
         feature = "poll",
         feature = "process",
@@ -73,6 +90,7 @@
         feature = "reboot",
         feature = "resource",
         feature = "sched",
+        feature = "seccomp",
         feature = "socket",
         feature = "signal",
         feature = "term",
@@ -367,7 +385,8 @@ impl NixPath for PathBuf {
 /// A NULL pointer will be provided if `path.is_none()`.
 #[cfg(any(
     all(apple_targets, feature = "mount"),
-    all(linux_android, any(feature = "mount", feature = "fanotify"))
+    all(linux_android, any(feature = "mount", feature = "fanotify")),
+    all(target_os = "openbsd", feature = "fs")
 ))]
 pub(crate) fn with_opt_nix_path<P, T, F>(path: Option<&P>, f: F) -> Result<T>
 where
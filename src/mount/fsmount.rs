@@ -0,0 +1,831 @@
+use super::MsFlags;
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+use libc::{self, c_uint, c_ulong};
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::OsStrExt;
+
+libc_bitflags!(
+    /// Used with [`fsopen`].
+    pub struct FsopenFlags: c_uint {
+        /// Set the close-on-exec flag on the file system context.
+        FSOPEN_CLOEXEC;
+    }
+);
+
+libc_bitflags!(
+    /// Used with [`fspick`].
+    pub struct FspickFlags: c_uint {
+        /// Set the close-on-exec flag on the file system context.
+        FSPICK_CLOEXEC;
+        /// Don't dereference `path` if it is a symbolic link.
+        FSPICK_SYMLINK_NOFOLLOW;
+        /// Don't automount the last component of `path`.
+        FSPICK_NO_AUTOMOUNT;
+        /// If `path` is empty, operate on `dirfd`.
+        FSPICK_EMPTY_PATH;
+    }
+);
+
+libc_bitflags!(
+    /// Used with [`fsmount`].
+    pub struct FsmountFlags: c_uint {
+        /// Set the close-on-exec flag on the mount file descriptor.
+        FSMOUNT_CLOEXEC;
+    }
+);
+
+libc_bitflags!(
+    /// Attributes of a mount, used with [`fsmount`] and [`MountAttr`].
+    ///
+    /// Without any of the atime flags, the mount uses `relatime`.  Use
+    /// [`MountAttr::atime`] to change the atime mode of an existing mount.
+    pub struct MountAttrFlags: u64 {
+        /// Mount read-only.
+        MOUNT_ATTR_RDONLY;
+        /// Ignore suid and sgid bits.
+        MOUNT_ATTR_NOSUID;
+        /// Disallow access to device special files.
+        MOUNT_ATTR_NODEV;
+        /// Disallow program execution.
+        MOUNT_ATTR_NOEXEC;
+        /// Do not update access times.
+        MOUNT_ATTR_NOATIME;
+        /// Always update the last access time.
+        MOUNT_ATTR_STRICTATIME;
+        /// Do not update directory access times.
+        MOUNT_ATTR_NODIRATIME;
+        /// Do not follow symbolic links when resolving paths.
+        MOUNT_ATTR_NOSYMFOLLOW;
+    }
+);
+
+libc_bitflags!(
+    /// Used with [`move_mount`].
+    pub struct MoveMountFlags: c_uint {
+        /// Follow symbolic links in `from_path`.
+        MOVE_MOUNT_F_SYMLINKS;
+        /// Follow automounts in `from_path`.
+        MOVE_MOUNT_F_AUTOMOUNTS;
+        /// If `from_path` is empty, move the mount referred to by
+        /// `from_dirfd`, e.g. one created by [`fsmount`].
+        MOVE_MOUNT_F_EMPTY_PATH;
+        /// Follow symbolic links in `to_path`.
+        MOVE_MOUNT_T_SYMLINKS;
+        /// Follow automounts in `to_path`.
+        MOVE_MOUNT_T_AUTOMOUNTS;
+        /// If `to_path` is empty, attach the mount onto `to_dirfd`.
+        MOVE_MOUNT_T_EMPTY_PATH;
+        /// Add the destination to the peer group of the source, instead of
+        /// moving the mount.
+        MOVE_MOUNT_SET_GROUP;
+        /// Mount beneath the top mount of the destination.
+        MOVE_MOUNT_BENEATH;
+    }
+);
+
+libc_bitflags!(
+    /// Used with [`open_tree`].
+    pub struct OpenTreeFlags: c_uint {
+        /// Create a detached clone of the mount at the path, instead of
+        /// opening the mount itself.
+        OPEN_TREE_CLONE;
+        /// Set the close-on-exec flag on the returned file descriptor.
+        OPEN_TREE_CLOEXEC;
+        /// With `OPEN_TREE_CLONE`, clone the whole mount subtree, like
+        /// `MS_BIND | MS_REC`.
+        AT_RECURSIVE as c_uint;
+        /// If `path` is empty, operate on `dirfd`.
+        AT_EMPTY_PATH as c_uint;
+        /// Don't dereference `path` if it is a symbolic link.
+        AT_SYMLINK_NOFOLLOW as c_uint;
+        /// Don't automount the last component of `path`.
+        AT_NO_AUTOMOUNT as c_uint;
+    }
+);
+
+libc_bitflags!(
+    /// Used with [`mount_setattr`].
+    pub struct MountSetattrFlags: c_uint {
+        /// Change the whole mount subtree.
+        AT_RECURSIVE as c_uint;
+        /// If `path` is empty, operate on `dirfd`.
+        AT_EMPTY_PATH as c_uint;
+        /// Don't dereference `path` if it is a symbolic link.
+        AT_SYMLINK_NOFOLLOW as c_uint;
+        /// Don't automount the last component of `path`.
+        AT_NO_AUTOMOUNT as c_uint;
+    }
+);
+
+/// How the access times of files are updated on a mount.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MountAtime {
+    /// Only update the access time if it is older than the modification
+    /// time, or than one day.
+    Relatime,
+    /// Do not update access times.
+    Noatime,
+    /// Always update the access time.
+    Strictatime,
+}
+
+/// Changes to the attributes of a mount, used with [`mount_setattr`].
+///
+/// # Example
+///
+/// Make a mount read-only and stop updating access times:
+///
+/// ```
+/// # use nix::mount::{MountAtime, MountAttr, MountAttrFlags};
+/// let attr = MountAttr::new()
+///     .set(MountAttrFlags::MOUNT_ATTR_RDONLY)
+///     .atime(MountAtime::Noatime);
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct MountAttr<'fd> {
+    attr: libc::mount_attr,
+    _userns: PhantomData<BorrowedFd<'fd>>,
+}
+
+impl<'fd> MountAttr<'fd> {
+    /// Create a `MountAttr` that does not change anything.
+    pub fn new() -> Self {
+        MountAttr {
+            attr: libc::mount_attr {
+                attr_set: 0,
+                attr_clr: 0,
+                propagation: 0,
+                userns_fd: 0,
+            },
+            _userns: PhantomData,
+        }
+    }
+
+    /// Set the attributes `flags`, in addition to any previously set.
+    pub fn set(mut self, flags: MountAttrFlags) -> Self {
+        self.attr.attr_set |= flags.bits();
+        self
+    }
+
+    /// Clear the attributes `flags`, in addition to any previously cleared.
+    pub fn clear(mut self, flags: MountAttrFlags) -> Self {
+        self.attr.attr_clr |= flags.bits();
+        self
+    }
+
+    /// Change the atime mode of the mount.
+    pub fn atime(mut self, atime: MountAtime) -> Self {
+        let flag = match atime {
+            MountAtime::Relatime => libc::MOUNT_ATTR_RELATIME,
+            MountAtime::Noatime => libc::MOUNT_ATTR_NOATIME,
+            MountAtime::Strictatime => libc::MOUNT_ATTR_STRICTATIME,
+        };
+        self.attr.attr_set =
+            (self.attr.attr_set & !libc::MOUNT_ATTR__ATIME) | flag;
+        self.attr.attr_clr |= libc::MOUNT_ATTR__ATIME;
+        self
+    }
+
+    /// Make the mount idmapped, with the user and group id mappings of the
+    /// user namespace `userns`.
+    ///
+    /// This is only possible on detached mounts, e.g. created by
+    /// [`open_tree`] with `OPEN_TREE_CLONE`, that are not idmapped yet.
+    pub fn idmap(mut self, userns: BorrowedFd<'fd>) -> Self {
+        self.attr.attr_set |= libc::MOUNT_ATTR_IDMAP;
+        self.attr.userns_fd = userns.as_raw_fd() as u64;
+        self
+    }
+
+    /// The attributes that are set.
+    pub fn attr_set(&self) -> MountAttrFlags {
+        MountAttrFlags::from_bits_truncate(self.attr.attr_set)
+    }
+
+    /// The attributes that are cleared.
+    pub fn attr_clr(&self) -> MountAttrFlags {
+        MountAttrFlags::from_bits_truncate(self.attr.attr_clr)
+    }
+}
+
+impl Default for MountAttr<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A command of [`fsconfig`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum FsconfigCmd<'a> {
+    /// Set the boolean parameter `key`, e.g. `ro`.
+    SetFlag(&'a str),
+    /// Set the parameter `key` to the string `value`, e.g. `size` to `1M`.
+    SetString {
+        /// The name of the parameter.
+        key: &'a str,
+        /// The value of the parameter.
+        value: &'a str,
+    },
+    /// Set the parameter `key` to the file descriptor `fd`, e.g. `source`
+    /// to an opened block device.
+    SetFd {
+        /// The name of the parameter.
+        key: &'a str,
+        /// The value of the parameter.
+        fd: BorrowedFd<'a>,
+    },
+    /// Create the superblock, once all of the parameters have been set.
+    Create,
+    /// Apply the changed parameters to the superblock of a context created
+    /// by [`fspick`].
+    Reconfigure,
+}
+
+fn fsconfig_raw(
+    fs_fd: RawFd,
+    cmd: libc::fsconfig_command,
+    key: Option<&str>,
+    value: Option<&str>,
+    aux: RawFd,
+) -> Result<()> {
+    let res = crate::with_opt_nix_path(key, |k| {
+        crate::with_opt_nix_path(value, |v| unsafe {
+            libc::syscall(libc::SYS_fsconfig, fs_fd, cmd, k, v, aux)
+        })
+    })??;
+
+    Errno::result(res).map(drop)
+}
+
+/// Create a new file system context for the file system type `fsname`,
+/// to be configured with [`fsconfig`] and mounted with [`fsmount`].
+///
+/// # See Also
+/// [`fsopen`](https://man7.org/linux/man-pages/man2/fsopen.2.html)
+pub fn fsopen<P: ?Sized + NixPath>(
+    fsname: &P,
+    flags: FsopenFlags,
+) -> Result<OwnedFd> {
+    let res = fsname.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_fsopen, cstr.as_ptr(), flags.bits())
+    })?;
+    let fd = Errno::result(res)? as RawFd;
+
+    // SAFETY: `fsopen(2)` returns a valid owned fd on success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Configure the file system context `fs_fd`.
+///
+/// The file system may log detailed error messages, which can be read from
+/// `fs_fd`.
+///
+/// # See Also
+/// [`fsconfig`](https://man7.org/linux/man-pages/man2/fsconfig.2.html)
+pub fn fsconfig<Fd: AsFd>(fs_fd: Fd, cmd: FsconfigCmd<'_>) -> Result<()> {
+    let fs_fd = fs_fd.as_fd().as_raw_fd();
+    match cmd {
+        FsconfigCmd::SetFlag(key) => {
+            fsconfig_raw(fs_fd, libc::FSCONFIG_SET_FLAG, Some(key), None, 0)
+        }
+        FsconfigCmd::SetString { key, value } => fsconfig_raw(
+            fs_fd,
+            libc::FSCONFIG_SET_STRING,
+            Some(key),
+            Some(value),
+            0,
+        ),
+        FsconfigCmd::SetFd { key, fd } => fsconfig_raw(
+            fs_fd,
+            libc::FSCONFIG_SET_FD,
+            Some(key),
+            None,
+            fd.as_raw_fd(),
+        ),
+        FsconfigCmd::Create => {
+            fsconfig_raw(fs_fd, libc::FSCONFIG_CMD_CREATE, None, None, 0)
+        }
+        FsconfigCmd::Reconfigure => {
+            fsconfig_raw(fs_fd, libc::FSCONFIG_CMD_RECONFIGURE, None, None, 0)
+        }
+    }
+}
+
+/// Create a file system context for the superblock of the mount at `path`,
+/// relative to `dirfd`, to reconfigure it with [`fsconfig`].
+///
+/// This replaces remounting with `MS_REMOUNT`, and only changes the
+/// parameters that are explicitly set.
+///
+/// # Example
+///
+/// Grow a tmpfs:
+///
+/// ```no_run
+/// # use nix::mount::*;
+/// # use std::fs::File;
+/// let fs_fd = fspick(
+///     File::open("/").unwrap(),
+///     "/tmp",
+///     FspickFlags::FSPICK_CLOEXEC,
+/// )
+/// .unwrap();
+/// fsconfig(&fs_fd, FsconfigCmd::SetString { key: "size", value: "2G" })
+///     .unwrap();
+/// fsconfig(&fs_fd, FsconfigCmd::Reconfigure).unwrap();
+/// ```
+///
+/// # See Also
+/// [`fspick`](https://man7.org/linux/man-pages/man2/fspick.2.html)
+pub fn fspick<Fd: AsFd, P: ?Sized + NixPath>(
+    dirfd: Fd,
+    path: &P,
+    flags: FspickFlags,
+) -> Result<OwnedFd> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(
+            libc::SYS_fspick,
+            dirfd.as_fd().as_raw_fd(),
+            cstr.as_ptr(),
+            flags.bits(),
+        )
+    })?;
+    let fd = Errno::result(res)? as RawFd;
+
+    // SAFETY: `fspick(2)` returns a valid owned fd on success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Create a detached mount of the superblock created in the file system
+/// context `fs_fd`, with the attributes `attr_flags`.
+///
+/// The mount can be attached with [`move_mount`], and is unmounted when the
+/// returned file descriptor is closed while it is still detached.
+///
+/// # See Also
+/// [`fsmount`](https://man7.org/linux/man-pages/man2/fsmount.2.html)
+pub fn fsmount<Fd: AsFd>(
+    fs_fd: Fd,
+    flags: FsmountFlags,
+    attr_flags: MountAttrFlags,
+) -> Result<OwnedFd> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_fsmount,
+            fs_fd.as_fd().as_raw_fd(),
+            flags.bits(),
+            attr_flags.bits() as c_uint,
+        )
+    };
+    let fd = Errno::result(res)? as RawFd;
+
+    // SAFETY: `fsmount(2)` returns a valid owned fd on success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Move the mount at `from_path`, relative to `from_dirfd`, to `to_path`,
+/// relative to `to_dirfd`.
+///
+/// # Example
+///
+/// Attach a mount created by [`fsmount`]:
+///
+/// ```no_run
+/// # use nix::mount::*;
+/// # use std::fs::File;
+/// # use std::os::fd::AsFd;
+/// let fs_fd = fsopen("tmpfs", FsopenFlags::FSOPEN_CLOEXEC).unwrap();
+/// fsconfig(&fs_fd, FsconfigCmd::Create).unwrap();
+/// let mnt_fd = fsmount(
+///     &fs_fd,
+///     FsmountFlags::FSMOUNT_CLOEXEC,
+///     MountAttrFlags::empty(),
+/// )
+/// .unwrap();
+/// move_mount(
+///     mnt_fd.as_fd(),
+///     "",
+///     File::open("/").unwrap(),
+///     "mnt",
+///     MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+/// )
+/// .unwrap();
+/// ```
+///
+/// # See Also
+/// [`move_mount`](https://man7.org/linux/man-pages/man2/move_mount.2.html)
+pub fn move_mount<
+    Fd1: AsFd,
+    P1: ?Sized + NixPath,
+    Fd2: AsFd,
+    P2: ?Sized + NixPath,
+>(
+    from_dirfd: Fd1,
+    from_path: &P1,
+    to_dirfd: Fd2,
+    to_path: &P2,
+    flags: MoveMountFlags,
+) -> Result<()> {
+    let res = from_path.with_nix_path(|from| {
+        to_path.with_nix_path(|to| unsafe {
+            libc::syscall(
+                libc::SYS_move_mount,
+                from_dirfd.as_fd().as_raw_fd(),
+                from.as_ptr(),
+                to_dirfd.as_fd().as_raw_fd(),
+                to.as_ptr(),
+                flags.bits(),
+            )
+        })
+    })??;
+
+    Errno::result(res).map(drop)
+}
+
+/// Open the mount at `path`, relative to `dirfd`.
+///
+/// With `OPEN_TREE_CLONE`, this creates a detached copy of the mount, or of
+/// the whole subtree with `AT_RECURSIVE`, that can be attached elsewhere
+/// with [`move_mount`].  It is unmounted when the returned file descriptor
+/// is closed while it is still detached.
+///
+/// # See Also
+/// [`open_tree`](https://man7.org/linux/man-pages/man2/open_tree.2.html)
+pub fn open_tree<Fd: AsFd, P: ?Sized + NixPath>(
+    dirfd: Fd,
+    path: &P,
+    flags: OpenTreeFlags,
+) -> Result<OwnedFd> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(
+            libc::SYS_open_tree,
+            dirfd.as_fd().as_raw_fd(),
+            cstr.as_ptr(),
+            flags.bits(),
+        )
+    })?;
+    let fd = Errno::result(res)? as RawFd;
+
+    // SAFETY: `open_tree(2)` returns a valid owned fd on success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Change the attributes of the mount at `path`, relative to `dirfd`, or of
+/// the whole subtree with `AT_RECURSIVE`.
+///
+/// # See Also
+/// [`mount_setattr`](https://man7.org/linux/man-pages/man2/mount_setattr.2.html)
+pub fn mount_setattr<Fd: AsFd, P: ?Sized + NixPath>(
+    dirfd: Fd,
+    path: &P,
+    flags: MountSetattrFlags,
+    attr: &MountAttr<'_>,
+) -> Result<()> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(
+            libc::SYS_mount_setattr,
+            dirfd.as_fd().as_raw_fd(),
+            cstr.as_ptr(),
+            flags.bits(),
+            &attr.attr as *const libc::mount_attr,
+            std::mem::size_of::<libc::mount_attr>(),
+        )
+    })?;
+
+    Errno::result(res).map(drop)
+}
+
+// The numbers of `statmount` and `listmount`, which libc doesn't export on
+// every architecture.  Like other recent syscalls, they have the same numbers
+// everywhere, plus the per-ABI offset on mips and x32.
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "mips")] {
+        const SYS_STATMOUNT: libc::c_long = 4000 + 457;
+    } else if #[cfg(all(target_arch = "mips64", target_pointer_width = "64"))] {
+        const SYS_STATMOUNT: libc::c_long = 5000 + 457;
+    } else if #[cfg(target_arch = "mips64")] {
+        const SYS_STATMOUNT: libc::c_long = 6000 + 457;
+    } else if #[cfg(all(target_arch = "x86_64", target_pointer_width = "32"))] {
+        const SYS_STATMOUNT: libc::c_long = 0x4000_0000 + 457;
+    } else {
+        const SYS_STATMOUNT: libc::c_long = 457;
+    }
+}
+const SYS_LISTMOUNT: libc::c_long = SYS_STATMOUNT + 1;
+
+/// The mount id to use with [`listmount`] to list all of the mounts of the
+/// mount namespace.
+pub const LSMT_ROOT: u64 = u64::MAX;
+
+bitflags::bitflags! {
+    /// The information requested from [`statmount`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct StatmountMask: u64 {
+        /// The device, magic and flags of the superblock.
+        const STATMOUNT_SB_BASIC = 0x0000_0001;
+        /// The ids, attributes and propagation of the mount.
+        const STATMOUNT_MNT_BASIC = 0x0000_0002;
+        /// The peer group the mount receives propagation from.
+        const STATMOUNT_PROPAGATE_FROM = 0x0000_0004;
+        /// The root of the mount, relative to the root of the file system.
+        const STATMOUNT_MNT_ROOT = 0x0000_0008;
+        /// The mount point, relative to the root of the process.
+        const STATMOUNT_MNT_POINT = 0x0000_0010;
+        /// The file system type.
+        const STATMOUNT_FS_TYPE = 0x0000_0020;
+        /// The id of the mount namespace.
+        const STATMOUNT_MNT_NS_ID = 0x0000_0040;
+        /// The file system specific mount options.
+        const STATMOUNT_MNT_OPTS = 0x0000_0080;
+        /// The file system subtype.
+        const STATMOUNT_FS_SUBTYPE = 0x0000_0100;
+        /// The source of the superblock, e.g. a device.
+        const STATMOUNT_SB_SOURCE = 0x0000_0200;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`listmount`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct ListmountFlags: c_uint {
+        /// List the mounts from the newest to the oldest.
+        const LISTMOUNT_REVERSE = 0x0000_0001;
+    }
+}
+
+#[repr(C)]
+struct mnt_id_req {
+    size: u32,
+    spare: u32,
+    mnt_id: u64,
+    param: u64,
+    mnt_ns_id: u64,
+}
+
+impl mnt_id_req {
+    fn new(mnt_id: u64, param: u64) -> Self {
+        mnt_id_req {
+            size: std::mem::size_of::<mnt_id_req>() as u32,
+            spare: 0,
+            mnt_id,
+            param,
+            mnt_ns_id: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct statmount_hdr {
+    size: u32,
+    mnt_opts: u32,
+    mask: u64,
+    sb_dev_major: u32,
+    sb_dev_minor: u32,
+    sb_magic: u64,
+    sb_flags: u32,
+    fs_type: u32,
+    mnt_id: u64,
+    mnt_parent_id: u64,
+    mnt_id_old: u32,
+    mnt_parent_id_old: u32,
+    mnt_attr: u64,
+    mnt_propagation: u64,
+    mnt_peer_group: u64,
+    mnt_master: u64,
+    propagate_from: u64,
+    mnt_root: u32,
+    mnt_point: u32,
+    mnt_ns_id: u64,
+    fs_subtype: u32,
+    sb_source: u32,
+    __spare: [u64; 48],
+}
+
+const STATMOUNT_HDR_SIZE: usize = std::mem::size_of::<statmount_hdr>();
+const _: () = assert!(STATMOUNT_HDR_SIZE == 512);
+
+/// Information about a mount, returned by [`statmount`].
+///
+/// Each accessor returns `None` if the information was not requested, or is
+/// not supported by the kernel.
+#[derive(Clone)]
+pub struct Statmount {
+    // Stored as `u64`s so that the header is properly aligned.
+    buf: Vec<u64>,
+}
+
+impl Statmount {
+    fn hdr(&self) -> &statmount_hdr {
+        unsafe { &*self.buf.as_ptr().cast() }
+    }
+
+    fn has(&self, mask: StatmountMask) -> bool {
+        self.mask().contains(mask)
+    }
+
+    fn str(&self, mask: StatmountMask, offset: u32) -> Option<&OsStr> {
+        if !self.has(mask) {
+            return None;
+        }
+        let len = (self.hdr().size as usize).min(self.buf.len() * 8);
+        let bytes = unsafe {
+            std::slice::from_raw_parts(self.buf.as_ptr().cast::<u8>(), len)
+        };
+        let s = bytes.get(STATMOUNT_HDR_SIZE + offset as usize..)?;
+        let end = s.iter().position(|&b| b == 0)?;
+        Some(OsStr::from_bytes(&s[..end]))
+    }
+
+    /// The information that was returned.
+    pub fn mask(&self) -> StatmountMask {
+        StatmountMask::from_bits_truncate(self.hdr().mask)
+    }
+
+    /// The device of the superblock.
+    pub fn sb_dev(&self) -> Option<libc::dev_t> {
+        let hdr = self.hdr();
+        self.has(StatmountMask::STATMOUNT_SB_BASIC)
+            .then(|| libc::makedev(hdr.sb_dev_major, hdr.sb_dev_minor))
+    }
+
+    /// The magic number of the file system, as in `statfs`.
+    pub fn sb_magic(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_SB_BASIC)
+            .then_some(self.hdr().sb_magic)
+    }
+
+    /// The flags of the superblock: `MS_RDONLY`, `MS_SYNCHRONOUS`,
+    /// `MS_DIRSYNC` and `MS_LAZYTIME`.
+    pub fn sb_flags(&self) -> Option<MsFlags> {
+        self.has(StatmountMask::STATMOUNT_SB_BASIC).then(|| {
+            MsFlags::from_bits_truncate(self.hdr().sb_flags as c_ulong)
+        })
+    }
+
+    /// The unique id of the mount.
+    pub fn mnt_id(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_id)
+    }
+
+    /// The unique id of the parent mount.
+    pub fn mnt_parent_id(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_parent_id)
+    }
+
+    /// The id of the mount, as in `/proc/self/mountinfo`.
+    pub fn mnt_id_old(&self) -> Option<u32> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_id_old)
+    }
+
+    /// The id of the parent mount, as in `/proc/self/mountinfo`.
+    pub fn mnt_parent_id_old(&self) -> Option<u32> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_parent_id_old)
+    }
+
+    /// The attributes of the mount.
+    pub fn mnt_attr(&self) -> Option<MountAttrFlags> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then(|| MountAttrFlags::from_bits_truncate(self.hdr().mnt_attr))
+    }
+
+    /// The propagation type of the mount: `MS_SHARED`, `MS_SLAVE`,
+    /// `MS_PRIVATE` or `MS_UNBINDABLE`.
+    pub fn mnt_propagation(&self) -> Option<MsFlags> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC).then(|| {
+            MsFlags::from_bits_truncate(self.hdr().mnt_propagation as c_ulong)
+        })
+    }
+
+    /// The peer group of the mount, if it is shared.
+    pub fn mnt_peer_group(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_peer_group)
+    }
+
+    /// The peer group the mount is a slave of, if any.
+    pub fn mnt_master(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_MNT_BASIC)
+            .then_some(self.hdr().mnt_master)
+    }
+
+    /// The nearest dominant peer group the mount receives propagation from.
+    pub fn propagate_from(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_PROPAGATE_FROM)
+            .then_some(self.hdr().propagate_from)
+    }
+
+    /// The root of the mount, relative to the root of the file system.
+    pub fn mnt_root(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_MNT_ROOT, self.hdr().mnt_root)
+    }
+
+    /// The mount point, relative to the root of the process.
+    pub fn mnt_point(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_MNT_POINT, self.hdr().mnt_point)
+    }
+
+    /// The file system type, e.g. `ext4`.
+    pub fn fs_type(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_FS_TYPE, self.hdr().fs_type)
+    }
+
+    /// The id of the mount namespace of the mount.
+    pub fn mnt_ns_id(&self) -> Option<u64> {
+        self.has(StatmountMask::STATMOUNT_MNT_NS_ID)
+            .then_some(self.hdr().mnt_ns_id)
+    }
+
+    /// The comma-separated file system specific mount options.
+    pub fn mnt_opts(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_MNT_OPTS, self.hdr().mnt_opts)
+    }
+
+    /// The file system subtype, e.g. for FUSE file systems.
+    pub fn fs_subtype(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_FS_SUBTYPE, self.hdr().fs_subtype)
+    }
+
+    /// The source of the superblock, e.g. `/dev/sda1`.
+    pub fn sb_source(&self) -> Option<&OsStr> {
+        self.str(StatmountMask::STATMOUNT_SB_SOURCE, self.hdr().sb_source)
+    }
+}
+
+impl std::fmt::Debug for Statmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Statmount")
+            .field("mask", &self.mask())
+            .field("mnt_id", &self.mnt_id())
+            .field("mnt_point", &self.mnt_point())
+            .field("fs_type", &self.fs_type())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Get the information `mask` about the mount with the unique id `mnt_id`,
+/// as returned by [`listmount`] or by `statx` with `STATX_MNT_ID_UNIQUE`.
+///
+/// # See Also
+/// [`statmount`](https://man7.org/linux/man-pages/man2/statmount.2.html)
+pub fn statmount(mnt_id: u64, mask: StatmountMask) -> Result<Statmount> {
+    let req = mnt_id_req::new(mnt_id, mask.bits());
+    let mut buf = vec![0u64; 512];
+    loop {
+        let res = unsafe {
+            libc::syscall(
+                SYS_STATMOUNT,
+                &req as *const mnt_id_req,
+                buf.as_mut_ptr(),
+                buf.len() * 8,
+                0,
+            )
+        };
+        match Errno::result(res) {
+            // The strings do not fit in the buffer.
+            Err(Errno::EOVERFLOW) => buf.resize(buf.len() * 2, 0),
+            Err(e) => return Err(e),
+            Ok(_) => return Ok(Statmount { buf }),
+        }
+    }
+}
+
+/// List the unique ids of the child mounts of the mount `mnt_id`, or of all
+/// of the mounts of the mount namespace with [`LSMT_ROOT`].
+///
+/// # See Also
+/// [`listmount`](https://man7.org/linux/man-pages/man2/listmount.2.html)
+pub fn listmount(mnt_id: u64, flags: ListmountFlags) -> Result<Vec<u64>> {
+    let mut ids = Vec::new();
+    let mut batch = [0u64; 256];
+    let mut last = 0;
+    loop {
+        let req = mnt_id_req::new(mnt_id, last);
+        let res = unsafe {
+            libc::syscall(
+                SYS_LISTMOUNT,
+                &req as *const mnt_id_req,
+                batch.as_mut_ptr(),
+                batch.len(),
+                flags.bits(),
+            )
+        };
+        let n = Errno::result(res)? as usize;
+        ids.extend_from_slice(&batch[..n]);
+        if n < batch.len() {
+            return Ok(ids);
+        }
+        last = batch[n - 1];
+    }
+}
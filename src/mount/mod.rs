@@ -5,6 +5,12 @@ mod linux;
 #[cfg(linux_android)]
 pub use self::linux::*;
 
+#[cfg(target_os = "linux")]
+mod fsmount;
+
+#[cfg(target_os = "linux")]
+pub use self::fsmount::*;
+
 #[cfg(bsd_without_apple)]
 mod bsd_without_apple;
 
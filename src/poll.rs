@@ -2,7 +2,7 @@
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd};
 
 use crate::errno::Errno;
-pub use crate::poll_timeout::PollTimeout;
+pub use crate::poll_timeout::{PollTimeout, Timeout};
 use crate::Result;
 
 /// This is a wrapper around `libc::pollfd`.
@@ -219,25 +219,73 @@ feature! {
 ///
 /// `ppoll` behaves like `poll`, but let you specify what signals may interrupt it
 /// with the `sigmask` argument. If you want `ppoll` to block indefinitely,
-/// specify `None` as `timeout` (it is like `timeout = -1` for `poll`).
+/// specify [`Timeout::Infinite`] as `timeout` (it is like `timeout = -1` for
+/// `poll`).
 /// If `sigmask` is `None`, then no signal mask manipulation is performed,
 /// so in that case `ppoll` differs from `poll` only in the precision of the
 /// timeout argument.
 ///
-#[cfg(any(linux_android, freebsdlike))]
-pub fn ppoll(
+/// On platforms without a native `ppoll`, such as Apple platforms, it is
+/// emulated with [`SigSet::thread_swap_mask`] and `poll`: the timeout is
+/// rounded up to the next millisecond, and the signal mask is not changed
+/// atomically, so a signal that arrives just before `poll` is called does not
+/// interrupt it.
+///
+/// [`SigSet::thread_swap_mask`]: crate::sys::signal::SigSet::thread_swap_mask
+pub fn ppoll<T: Into<Timeout>>(
     fds: &mut [PollFd],
-    timeout: Option<crate::sys::time::TimeSpec>,
+    timeout: T,
     sigmask: Option<crate::sys::signal::SigSet>
     ) -> Result<libc::c_int>
 {
-    let timeout = timeout.as_ref().map_or(core::ptr::null(), |r| r.as_ref());
-    let sigmask = sigmask.as_ref().map_or(core::ptr::null(), |r| r.as_ref());
-    let res = unsafe {
-        libc::ppoll(fds.as_mut_ptr().cast(),
-                    fds.len() as libc::nfds_t,
-                    timeout,
-                    sigmask)
+    let timeout = timeout.into();
+    #[cfg(any(
+        linux_android,
+        freebsdlike,
+        netbsdlike,
+        target_os = "haiku",
+        target_os = "hurd",
+        target_os = "fuchsia"
+    ))]
+    let res = {
+        let timeout = timeout.as_timespec();
+        let timeout = timeout.as_ref().map_or(core::ptr::null(), |r| r.as_ref());
+        let sigmask = sigmask.as_ref().map_or(core::ptr::null(), |r| r.as_ref());
+        unsafe {
+            libc::ppoll(fds.as_mut_ptr().cast(),
+                        fds.len() as libc::nfds_t,
+                        timeout,
+                        sigmask)
+        }
+    };
+    #[cfg(not(any(
+        linux_android,
+        freebsdlike,
+        netbsdlike,
+        target_os = "haiku",
+        target_os = "hurd",
+        target_os = "fuchsia"
+    )))]
+    let res = {
+        use crate::sys::signal::SigmaskHow;
+
+        let timeout_ms = i32::from(PollTimeout::from(timeout));
+        let oldmask = sigmask
+            .as_ref()
+            .map(|sigmask| sigmask.thread_swap_mask(SigmaskHow::SIG_SETMASK))
+            .transpose()?;
+        let res = unsafe {
+            libc::poll(fds.as_mut_ptr().cast(),
+                       fds.len() as libc::nfds_t,
+                       timeout_ms)
+        };
+        if let Some(oldmask) = oldmask {
+            // Don't let restoring the mask clobber the errno of `poll`.
+            let errno = Errno::last_raw();
+            let _ = oldmask.thread_set_mask();
+            Errno::set_raw(errno);
+        }
+        res
     };
     Errno::result(res)
 }
@@ -1,3 +1,4 @@
+use crate::sys::time::{TimeSpec, TimeValLike};
 use std::time::Duration;
 
 /// PollTimeout argument for polling.
@@ -27,7 +28,7 @@ impl PollTimeout {
     }
     /// Returns the timeout in milliseconds if there is some, otherwise returns `None`.
     pub fn as_millis(&self) -> Option<u32> {
-        self.is_some().then_some(u32::try_from(self.0).unwrap())
+        self.is_some().then(|| u32::try_from(self.0).unwrap())
     }
     /// Returns the timeout as a `Duration` if there is some, otherwise returns `None`.
     pub fn duration(&self) -> Option<Duration> {
@@ -222,3 +223,114 @@ impl TryFrom<PollTimeout> for i8 {
         Self::try_from(x.0)
     }
 }
+
+/// A timeout for the functions waiting for events, like `ppoll`, `pselect`
+/// or `kevent`.
+///
+/// It can be converted from a [`Duration`], a [`TimeSpec`], a
+/// [`PollTimeout`], or an `Option<TimeSpec>`, `None` meaning
+/// [`Timeout::Infinite`], and converted to a [`PollTimeout`] for the functions
+/// with a millisecond resolution, like `poll`.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use nix::poll::{PollTimeout, Timeout};
+/// let timeout = Timeout::from(Duration::from_micros(1500));
+/// // Rounded up to the next millisecond.
+/// assert_eq!(PollTimeout::from(timeout), PollTimeout::from(2u8));
+/// assert_eq!(PollTimeout::from(Timeout::Infinite), PollTimeout::NONE);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Timeout {
+    /// Block until an event happens.
+    Infinite,
+    /// Return immediately, even if no event happened.
+    ZeroPoll,
+    /// Block for at most the given duration.
+    Duration(Duration),
+}
+
+impl Timeout {
+    /// The timeout as a `TimeSpec`, or `None` if it is infinite.
+    ///
+    /// Durations that do not fit in a `TimeSpec` saturate to its maximum.
+    pub fn as_timespec(&self) -> Option<TimeSpec> {
+        match *self {
+            Timeout::Infinite => None,
+            Timeout::ZeroPoll => Some(TimeSpec::new(0, 0)),
+            Timeout::Duration(d) => {
+                let max = libc::time_t::MAX;
+                Some(if d.as_secs() > max as u64 {
+                    TimeSpec::new(max, 999_999_999)
+                } else {
+                    TimeSpec::from_duration(d)
+                })
+            }
+        }
+    }
+
+    /// The timeout as a `Duration`, or `None` if it is infinite.
+    pub fn duration(&self) -> Option<Duration> {
+        match *self {
+            Timeout::Infinite => None,
+            Timeout::ZeroPoll => Some(Duration::ZERO),
+            Timeout::Duration(d) => Some(d),
+        }
+    }
+}
+
+impl From<Duration> for Timeout {
+    fn from(x: Duration) -> Self {
+        if x.is_zero() {
+            Timeout::ZeroPoll
+        } else {
+            Timeout::Duration(x)
+        }
+    }
+}
+impl From<TimeSpec> for Timeout {
+    /// Negative `TimeSpec`s are treated as zero.
+    fn from(x: TimeSpec) -> Self {
+        let nanos = x.num_nanoseconds().max(0) as u64;
+        Self::from(Duration::from_nanos(nanos))
+    }
+}
+impl From<&TimeSpec> for Timeout {
+    fn from(x: &TimeSpec) -> Self {
+        Self::from(*x)
+    }
+}
+impl From<libc::timespec> for Timeout {
+    fn from(x: libc::timespec) -> Self {
+        Self::from(TimeSpec::from(x))
+    }
+}
+impl From<PollTimeout> for Timeout {
+    fn from(x: PollTimeout) -> Self {
+        x.duration().map_or(Timeout::Infinite, Self::from)
+    }
+}
+// Only one `Option` is supported, so that `None` can still be passed to the
+// functions that used to take an `Option<TimeSpec>`.
+impl From<Option<TimeSpec>> for Timeout {
+    /// `None` means [`Timeout::Infinite`].
+    fn from(x: Option<TimeSpec>) -> Self {
+        x.map_or(Timeout::Infinite, Self::from)
+    }
+}
+impl From<Timeout> for PollTimeout {
+    /// Durations are rounded up to the next millisecond, and saturate to
+    /// [`PollTimeout::MAX`].
+    fn from(x: Timeout) -> Self {
+        match x {
+            Timeout::Infinite => PollTimeout::NONE,
+            Timeout::ZeroPoll => PollTimeout::ZERO,
+            Timeout::Duration(d) => {
+                let ms = (d.as_nanos() + 999_999) / 1_000_000;
+                PollTimeout(i32::try_from(ms).unwrap_or(i32::MAX))
+            }
+        }
+    }
+}
@@ -212,6 +212,109 @@ pub fn ptsname_r(fd: &PtyMaster) -> Result<String> {
     Ok(name)
 }
 
+/// Open the slave pseudoterminal corresponding to the master referred to by
+/// `fd` (see [`ioctl_tty(2)`](https://man7.org/linux/man-pages/man2/TIOCGPTPEER.2const.html))
+///
+/// `flags` are the flags the slave is opened with, such as `O_RDWR` and
+/// `O_NOCTTY`. On Linux since 4.13 this uses the `TIOCGPTPEER` ioctl, which
+/// does not look up the slave by name and therefore can't be confused by
+/// another `devpts` instance mounted over `/dev/pts`. On older kernels and on
+/// Android, this falls back to opening the path returned by `ptsname_r()`.
+///
+/// The slave must have been unlocked with `unlockpt()` first.
+#[cfg(linux_android)]
+pub fn open_slave(fd: &PtyMaster, flags: fcntl::OFlag) -> Result<OwnedFd> {
+    #[cfg(target_os = "linux")]
+    {
+        let res = unsafe {
+            libc::ioctl(fd.as_raw_fd(), libc::TIOCGPTPEER, flags.bits())
+        };
+        match Errno::result(res) {
+            Ok(slave) => return Ok(unsafe { OwnedFd::from_raw_fd(slave) }),
+            Err(Errno::EINVAL | Errno::ENOTTY) => (),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let name = ptsname_r(fd)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Errno::EINVAL)?;
+    let slave = unsafe { libc::open(name.as_ptr(), flags.bits()) };
+    Errno::result(slave).map(|slave| unsafe { OwnedFd::from_raw_fd(slave) })
+}
+
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike))]
+bitflags::bitflags! {
+    /// Control events reported by the master of a pseudoterminal in packet
+    /// mode, see [`read_packet`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct PacketControl: u8 {
+        /// The input queue of the slave was flushed.
+        const TIOCPKT_FLUSHREAD = 0x01;
+        /// The output queue of the slave was flushed.
+        const TIOCPKT_FLUSHWRITE = 0x02;
+        /// Output to the slave was stopped, as with `^S`.
+        const TIOCPKT_STOP = 0x04;
+        /// Output to the slave was restarted, as with `^Q`.
+        const TIOCPKT_START = 0x08;
+        /// The stop and start characters are no longer `^S` and `^Q`.
+        const TIOCPKT_NOSTOP = 0x10;
+        /// The stop and start characters are now `^S` and `^Q`.
+        const TIOCPKT_DOSTOP = 0x20;
+        /// The settings of the slave were changed with `tcsetattr()` or
+        /// another ioctl, if `EXTPROC` is set in its local flags.
+        const TIOCPKT_IOCTL = 0x40;
+    }
+}
+
+/// A packet read from the master of a pseudoterminal in packet mode, see
+/// [`read_packet`].
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Packet<'a> {
+    /// Data written to the slave.
+    Data(&'a [u8]),
+    /// A change in the state of the slave.
+    Control(PacketControl),
+}
+
+/// Enable or disable packet mode on the master of a pseudoterminal
+/// (`ioctl(TIOCPKT)`)
+///
+/// In packet mode, each read from the master returns either data written to
+/// the slave or control events, such as the slave's output being stopped or
+/// flushed, which a terminal multiplexer needs to mirror. Use
+/// [`read_packet`] to tell them apart.
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike))]
+pub fn set_packet_mode<Fd: AsFd>(fd: Fd, enable: bool) -> Result<()> {
+    let enable = libc::c_int::from(enable);
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCPKT,
+            &enable as *const libc::c_int,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Read a packet from the master of a pseudoterminal in packet mode
+///
+/// The packet is read into `buf`, whose first byte is used for the header of
+/// the packet. An empty [`Packet::Data`] means end of file.
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike))]
+pub fn read_packet<Fd: AsFd>(fd: Fd, buf: &mut [u8]) -> Result<Packet<'_>> {
+    let n = unistd::read(fd, buf)?;
+    match buf[..n] {
+        [] => Ok(Packet::Data(&[])),
+        [0, ..] => Ok(Packet::Data(&buf[1..n])),
+        [control, ..] => {
+            Ok(Packet::Control(PacketControl::from_bits_retain(control)))
+        }
+    }
+}
+
 /// Unlock a pseudoterminal master/slave pseudoterminal pair (see
 /// [`unlockpt(3)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/unlockpt.html))
 ///
@@ -374,3 +477,51 @@ pub unsafe fn forkpty<'a, 'b, T: Into<Option<&'a Winsize>>, U: Into<Option<&'b T
     Ok(forkpty_result)
 }
 }
+
+/// Make the terminal `fd` the controlling terminal and the standard input,
+/// output and error of the calling process (see
+/// [`login_tty(3)`](https://man7.org/linux/man-pages/man3/login_tty.3.html))
+///
+/// This creates a new session, acquires `fd` as its controlling terminal
+/// and duplicates it to file descriptors 0, 1 and 2. `fd` itself is closed
+/// unless it is one of them. It is typically called by the child after a
+/// `fork()`, with the slave returned by [`openpty`].
+///
+/// On the platforms whose libc lacks `login_tty()`, it is emulated with
+/// `setsid()`, `TIOCSCTTY` and `dup2()`.
+#[cfg(not(target_os = "aix"))]
+pub fn login_tty(fd: OwnedFd) -> Result<()> {
+    let fd = fd.into_raw_fd();
+
+    #[cfg(not(solarish))]
+    let res = Errno::result(unsafe { libc::login_tty(fd) }).map(drop);
+    #[cfg(solarish)]
+    let res = emulate_login_tty(fd);
+
+    res.map_err(|e| {
+        // `fd` is left open on failure, and may already be a standard stream.
+        if fd > 2 {
+            drop(unsafe { OwnedFd::from_raw_fd(fd) });
+        }
+        e
+    })
+}
+
+/// `login_tty()` for the platforms whose libc lacks it.  Like `login_tty()`,
+/// it leaves `fd` open on failure.
+#[cfg(solarish)]
+fn emulate_login_tty(fd: RawFd) -> Result<()> {
+    unsafe {
+        Errno::result(libc::setsid())?;
+        Errno::result(libc::ioctl(fd, libc::TIOCSCTTY, 0))?;
+        for stdio in 0..3 {
+            if fd != stdio {
+                Errno::result(libc::dup2(fd, stdio))?;
+            }
+        }
+        if fd > 2 {
+            libc::close(fd);
+        }
+    }
+    Ok(())
+}
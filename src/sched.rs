@@ -7,11 +7,14 @@ use crate::{Errno, Result};
 #[cfg(linux_android)]
 pub use self::sched_linux_like::*;
 
+#[cfg(target_os = "linux")]
+pub mod nsfs;
+
 #[cfg(linux_android)]
 mod sched_linux_like {
     use crate::errno::Errno;
     use crate::unistd::Pid;
-    use crate::Result;
+    use crate::{NixPath, Result};
     use libc::{self, c_int, c_void};
     use std::mem;
     use std::option::Option;
@@ -153,6 +156,172 @@ mod sched_linux_like {
 
         Errno::result(res).map(drop)
     }
+
+    /// reassociate thread with several namespaces of a process at once
+    ///
+    /// `pidfd` is a PID file descriptor of the process, and `nstypes` the
+    /// namespaces to join, e.g. `CLONE_NEWNS | CLONE_NEWNET`.  Either all of
+    /// the namespaces are joined, or none of them.  Since Linux 5.8.
+    ///
+    /// See also [setns(2)](https://man7.org/linux/man-pages/man2/setns.2.html)
+    pub fn setns_pidfd<Fd: AsFd>(pidfd: Fd, nstypes: CloneFlags) -> Result<()> {
+        // The same system call as `setns`, which tells the two kinds of file
+        // descriptors apart itself.
+        setns(pidfd, nstypes)
+    }
+
+    /// The maximum number of entries of a user namespace id map.
+    const ID_MAP_MAX_ENTRIES: usize = 340;
+
+    /// A range of ids mapped into a user namespace, see [`setup_userns`].
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct IdMapping {
+        /// The first id of the range inside of the namespace.
+        pub inside: u32,
+        /// The first id of the range in the parent user namespace of the
+        /// namespace.
+        pub outside: u32,
+        /// The number of ids of the range.
+        pub count: u32,
+    }
+
+    impl IdMapping {
+        /// Map the `count` ids starting at `outside` to the ids starting at
+        /// `inside`.
+        pub const fn new(inside: u32, outside: u32, count: u32) -> Self {
+            IdMapping {
+                inside,
+                outside,
+                count,
+            }
+        }
+
+        /// Map the single id `outside` to `inside`.
+        pub const fn single(inside: u32, outside: u32) -> Self {
+            Self::new(inside, outside, 1)
+        }
+    }
+
+    /// Whether the processes of a user namespace may call `setgroups(2)`,
+    /// see [`setup_userns`].
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum Setgroups {
+        /// Allow `setgroups`.  Writing the group id map then requires
+        /// `CAP_SETGID` in the parent user namespace.
+        Allow,
+        /// Deny `setgroups`, so that an unprivileged process can write the
+        /// group id map.  This cannot be undone.
+        Deny,
+    }
+
+    fn validate_id_map(map: &[IdMapping]) -> Result<()> {
+        if map.is_empty() || map.len() > ID_MAP_MAX_ENTRIES {
+            return Err(Errno::EINVAL);
+        }
+        let mut inside = Vec::with_capacity(map.len());
+        let mut outside = Vec::with_capacity(map.len());
+        for m in map {
+            if m.count == 0 {
+                return Err(Errno::EINVAL);
+            }
+            // The ranges may not include `u32::MAX`, which is not a valid id.
+            let end =
+                |first: u32| first.checked_add(m.count).ok_or(Errno::EINVAL);
+            inside.push((m.inside, end(m.inside)?));
+            outside.push((m.outside, end(m.outside)?));
+        }
+        for ranges in [&mut inside, &mut outside] {
+            ranges.sort_unstable();
+            if ranges.windows(2).any(|w| w[0].1 > w[1].0) {
+                return Err(Errno::EINVAL);
+            }
+        }
+        Ok(())
+    }
+
+    fn format_id_map(map: &[IdMapping]) -> String {
+        map.iter()
+            .map(|m| format!("{} {} {}\n", m.inside, m.outside, m.count))
+            .collect()
+    }
+
+    // The id maps must be written with a single `write(2)`.
+    fn write_proc_file(path: &str, contents: &[u8]) -> Result<()> {
+        use std::os::unix::io::{FromRawFd, OwnedFd};
+
+        let fd = path.with_nix_path(|cstr| unsafe {
+            libc::open(cstr.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC)
+        })?;
+        // SAFETY: `open(2)` returns a valid owned fd on success
+        let fd = unsafe { OwnedFd::from_raw_fd(Errno::result(fd)?) };
+        let res = unsafe {
+            libc::write(
+                fd.as_raw_fd(),
+                contents.as_ptr().cast(),
+                contents.len(),
+            )
+        };
+        if Errno::result(res)? as usize != contents.len() {
+            return Err(Errno::EIO);
+        }
+        Ok(())
+    }
+
+    /// Set up the id mappings of the user namespace of the process `pid`, or
+    /// of the calling process if `None`, typically right after
+    /// `unshare(CLONE_NEWUSER)`.
+    ///
+    /// This validates the maps, then performs the documented sequence:
+    /// write `setgroups`, then `gid_map`, then `uid_map`.  Each map may only
+    /// be written once, and must contain between 1 and 340 entries whose
+    /// ranges do not overlap, neither inside nor outside of the namespace.
+    /// Invalid maps fail with `EINVAL` before anything is written.
+    ///
+    /// # Example
+    ///
+    /// Become root in a new user namespace, as an unprivileged user:
+    ///
+    /// ```no_run
+    /// # use nix::sched::{setup_userns, unshare, CloneFlags, IdMapping, Setgroups};
+    /// let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    /// unshare(CloneFlags::CLONE_NEWUSER).unwrap();
+    /// setup_userns(
+    ///     None,
+    ///     &[IdMapping::single(0, uid)],
+    ///     &[IdMapping::single(0, gid)],
+    ///     Setgroups::Deny,
+    /// )
+    /// .unwrap();
+    /// ```
+    ///
+    /// See also [user_namespaces(7)](https://man7.org/linux/man-pages/man7/user_namespaces.7.html)
+    pub fn setup_userns(
+        pid: Option<Pid>,
+        uid_map: &[IdMapping],
+        gid_map: &[IdMapping],
+        setgroups: Setgroups,
+    ) -> Result<()> {
+        validate_id_map(uid_map)?;
+        validate_id_map(gid_map)?;
+
+        let dir = match pid {
+            Some(pid) => format!("/proc/{pid}"),
+            None => "/proc/self".to_owned(),
+        };
+        let setgroups: &[u8] = match setgroups {
+            Setgroups::Allow => b"allow",
+            Setgroups::Deny => b"deny",
+        };
+        write_proc_file(&format!("{dir}/setgroups"), setgroups)?;
+        write_proc_file(
+            &format!("{dir}/gid_map"),
+            format_id_map(gid_map).as_bytes(),
+        )?;
+        write_proc_file(
+            &format!("{dir}/uid_map"),
+            format_id_map(uid_map).as_bytes(),
+        )
+    }
 }
 
 #[cfg(any(linux_android, freebsdlike))]
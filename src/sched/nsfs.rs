@@ -0,0 +1,73 @@
+//! Introspection of namespaces, through the file descriptors of
+//! `/proc/[pid]/ns/*`.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::sched::CloneFlags;
+//! # use nix::sched::nsfs::{ns_get_nstype, ns_get_userns};
+//! let net = std::fs::File::open("/proc/self/ns/net").unwrap();
+//! assert_eq!(ns_get_nstype(&net).unwrap(), CloneFlags::CLONE_NEWNET);
+//! // The user namespace owning the network namespace.
+//! # let Ok(userns) = ns_get_userns(&net) else { return };
+//! assert_eq!(ns_get_nstype(&userns).unwrap(), CloneFlags::CLONE_NEWUSER);
+//! ```
+//!
+//! # See Also
+//! [ioctl_ns(2)](https://man7.org/linux/man-pages/man2/ioctl_ns.2.html)
+
+use crate::errno::Errno;
+use crate::sched::CloneFlags;
+use crate::Result;
+use std::os::unix::io::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+
+fn ns_ioctl_fd<Fd: AsFd>(fd: Fd, request: libc::Ioctl) -> Result<OwnedFd> {
+    let res = unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), request) };
+    let fd = Errno::result(res)?;
+
+    // SAFETY: these ioctls return a new owned fd on success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Open the user namespace that owns the namespace `fd` (`NS_GET_USERNS`).
+///
+/// Fails with `EPERM` if that user namespace is outside of the user
+/// namespace of the caller.
+pub fn ns_get_userns<Fd: AsFd>(fd: Fd) -> Result<OwnedFd> {
+    ns_ioctl_fd(fd, libc::NS_GET_USERNS)
+}
+
+/// Open the parent of the PID or user namespace `fd` (`NS_GET_PARENT`).
+///
+/// Fails with `EPERM` if the parent is outside of the namespace of the
+/// caller, e.g. for the initial namespace, and with `EINVAL` for namespaces
+/// that are not hierarchical.
+pub fn ns_get_parent<Fd: AsFd>(fd: Fd) -> Result<OwnedFd> {
+    ns_ioctl_fd(fd, libc::NS_GET_PARENT)
+}
+
+/// Get the type of the namespace `fd` (`NS_GET_NSTYPE`), e.g.
+/// `CLONE_NEWNET`.
+pub fn ns_get_nstype<Fd: AsFd>(fd: Fd) -> Result<CloneFlags> {
+    let res =
+        unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), libc::NS_GET_NSTYPE) };
+    Errno::result(res).map(CloneFlags::from_bits_truncate)
+}
+
+/// Get the id of the user that created the user namespace `fd`
+/// (`NS_GET_OWNER_UID`).
+#[cfg(feature = "user")]
+#[cfg_attr(docsrs, doc(cfg(feature = "user")))]
+pub fn ns_get_owner_uid<Fd: AsFd>(fd: Fd) -> Result<crate::unistd::Uid> {
+    let mut uid: libc::uid_t = 0;
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::NS_GET_OWNER_UID,
+            &mut uid as *mut libc::uid_t,
+        )
+    };
+    Errno::result(res)?;
+
+    Ok(crate::unistd::Uid::from_raw(uid))
+}
@@ -0,0 +1,893 @@
+//! Bulk file attribute access with `getattrlist(2)` and friends.
+//!
+//! These Apple-specific calls return many attributes of a file, or of every
+//! entry of a directory in the case of [`getattrlistbulk`], in a single
+//! system call.  The attributes to fetch are selected with an [`AttrList`],
+//! and the packed buffer filled by the kernel is decoded into
+//! [`Attributes`].
+//!
+//! Volume and fork attributes are not supported.
+//!
+//! # Example
+//!
+//! List the names and sizes of the entries of the current directory:
+//!
+//! ```no_run
+//! # use nix::fcntl::{open, OFlag};
+//! # use nix::sys::attrlist::*;
+//! # use nix::sys::stat::Mode;
+//! let dir = open(".", OFlag::O_RDONLY | OFlag::O_DIRECTORY, Mode::empty()).unwrap();
+//! let attrs = AttrList::new()
+//!     .common(CommonAttr::ATTR_CMN_NAME)
+//!     .file(FileAttr::ATTR_FILE_DATALENGTH);
+//! let mut buf = vec![0u8; 64 * 1024];
+//! loop {
+//!     let entries = getattrlistbulk(&dir, &attrs, &mut buf, FsOpt::empty()).unwrap();
+//!     if entries.is_empty() {
+//!         break;
+//!     }
+//!     for entry in entries {
+//!         let entry = entry.unwrap();
+//!         println!("{:?} {:?}", entry.name(), entry.data_length());
+//!     }
+//! }
+//! ```
+//!
+//! # See Also
+//! * [`getattrlist(2)`](https://developer.apple.com/library/archive/documentation/System/Conceptual/ManPages_iPhoneOS/man2/getattrlist.2.html)
+//! * [`getattrlistbulk(2)`](https://developer.apple.com/library/archive/documentation/System/Conceptual/ManPages_iPhoneOS/man2/getattrlistbulk.2.html)
+//! * [`setattrlist(2)`](https://developer.apple.com/library/archive/documentation/System/Conceptual/ManPages_iPhoneOS/man2/setattrlist.2.html)
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd};
+
+use libc::{attrgroup_t, c_void, dev_t, gid_t, off_t, uid_t};
+
+use crate::errno::Errno;
+use crate::sys::stat::{FileFlag, Mode};
+use crate::sys::time::TimeSpec;
+use crate::{NixPath, Result};
+
+libc_bitflags! {
+    /// Attributes common to all file system objects.
+    pub struct CommonAttr: attrgroup_t {
+        /// The name of the object, see [`Attributes::name`].
+        ATTR_CMN_NAME;
+        /// The device the object resides on.
+        ATTR_CMN_DEVID;
+        /// The file system the object resides on.
+        ATTR_CMN_FSID;
+        /// The type of the object, a `vtype` such as `VREG` or `VDIR`.
+        ATTR_CMN_OBJTYPE;
+        /// The type of file system the object resides on, a `vtagtype`.
+        ATTR_CMN_OBJTAG;
+        /// The identifier of the object, not persistent across reboots.
+        ATTR_CMN_OBJID;
+        /// The persistent identifier of the object.
+        ATTR_CMN_OBJPERMANENTID;
+        /// The identifier of the parent directory, not persistent across
+        /// reboots.
+        ATTR_CMN_PAROBJID;
+        /// The text encoding hint of the name.
+        ATTR_CMN_SCRIPT;
+        /// The creation time.
+        ATTR_CMN_CRTIME;
+        /// The last data modification time.
+        ATTR_CMN_MODTIME;
+        /// The last status change time.
+        ATTR_CMN_CHGTIME;
+        /// The last access time.
+        ATTR_CMN_ACCTIME;
+        /// The last backup time.
+        ATTR_CMN_BKUPTIME;
+        /// The 32 bytes of Finder information.
+        ATTR_CMN_FNDRINFO;
+        /// The owner.
+        ATTR_CMN_OWNERID;
+        /// The group.
+        ATTR_CMN_GRPID;
+        /// The access permissions.
+        ATTR_CMN_ACCESSMASK;
+        /// The BSD file flags, see [`FileFlag`].
+        ATTR_CMN_FLAGS;
+        /// A counter incremented on every modification of a regular file or
+        /// symbolic link.
+        ATTR_CMN_GEN_COUNT;
+        /// The document identifier, persistent across safe saves.
+        ATTR_CMN_DOCUMENT_ID;
+        /// The effective access permissions of the calling process,
+        /// a combination of `R_OK`, `W_OK` and `X_OK`.
+        ATTR_CMN_USERACCESS;
+        /// The UUID of the owner.
+        ATTR_CMN_UUID;
+        /// The UUID of the group.
+        ATTR_CMN_GRPUUID;
+        /// The 64-bit file identifier, the inode number.
+        ATTR_CMN_FILEID;
+        /// The 64-bit file identifier of the parent directory.
+        ATTR_CMN_PARENTID;
+        /// The absolute path of the object.
+        ATTR_CMN_FULLPATH;
+        /// The time the object was added to its parent directory.
+        ATTR_CMN_ADDEDTIME;
+        /// The data protection class of the object.
+        ATTR_CMN_DATA_PROTECT_FLAGS;
+    }
+}
+
+libc_bitflags! {
+    /// Attributes of directories.
+    pub struct DirAttr: attrgroup_t {
+        /// The number of hard links to the directory.
+        ATTR_DIR_LINKCOUNT;
+        /// The number of entries in the directory, excluding `.` and `..`.
+        ATTR_DIR_ENTRYCOUNT;
+        /// Whether the directory is a mount point, `DIR_MNTSTATUS_MNTPOINT`.
+        ATTR_DIR_MOUNTSTATUS;
+        /// The size of the storage allocated to the directory.
+        ATTR_DIR_ALLOCSIZE;
+        /// The optimal I/O block size of the directory.
+        ATTR_DIR_IOBLOCKSIZE;
+        /// The logical size of the directory.
+        ATTR_DIR_DATALENGTH;
+    }
+}
+
+libc_bitflags! {
+    /// Attributes of regular files and other non-directory objects.
+    pub struct FileAttr: attrgroup_t {
+        /// The number of hard links to the file.
+        ATTR_FILE_LINKCOUNT;
+        /// The total logical size of all forks of the file.
+        ATTR_FILE_TOTALSIZE;
+        /// The total size of the storage allocated to all forks of the file.
+        ATTR_FILE_ALLOCSIZE;
+        /// The optimal I/O block size of the file.
+        ATTR_FILE_IOBLOCKSIZE;
+        /// The device type of a block or character special file.
+        ATTR_FILE_DEVTYPE;
+        /// The logical size of the data fork.
+        ATTR_FILE_DATALENGTH;
+        /// The size of the storage allocated to the data fork.
+        ATTR_FILE_DATAALLOCSIZE;
+        /// The logical size of the resource fork.
+        ATTR_FILE_RSRCLENGTH;
+        /// The size of the storage allocated to the resource fork.
+        ATTR_FILE_RSRCALLOCSIZE;
+    }
+}
+
+libc_bitflags! {
+    /// Options for [`getattrlist`], [`setattrlist`] and their variants.
+    pub struct FsOpt: u32 {
+        /// Do not follow a symbolic link in the last component of the path.
+        FSOPT_NOFOLLOW;
+        /// Do not follow symbolic links in any component of the path.
+        FSOPT_NOFOLLOW_ANY;
+        /// Report the full size of the attributes, even if the buffer was
+        /// too small to hold them.  Decoding then fails with `ERANGE`
+        /// instead of returning truncated attributes.
+        FSOPT_REPORT_FULLSIZE;
+    }
+}
+
+/// The set of attributes to request from [`getattrlist`] and its variants.
+///
+/// Attributes of the wrong kind for an object, such as [`DirAttr`] for a
+/// regular file, or not supported by its file system, are silently left out
+/// of the result.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AttrList {
+    common: CommonAttr,
+    dir: DirAttr,
+    file: FileAttr,
+}
+
+impl AttrList {
+    /// Creates an empty `AttrList`.
+    pub const fn new() -> Self {
+        AttrList {
+            common: CommonAttr::empty(),
+            dir: DirAttr::empty(),
+            file: FileAttr::empty(),
+        }
+    }
+
+    /// Adds common attributes to the request.
+    pub fn common(mut self, attrs: CommonAttr) -> Self {
+        self.common |= attrs;
+        self
+    }
+
+    /// Adds directory attributes to the request.
+    pub fn dir(mut self, attrs: DirAttr) -> Self {
+        self.dir |= attrs;
+        self
+    }
+
+    /// Adds file attributes to the request.
+    pub fn file(mut self, attrs: FileAttr) -> Self {
+        self.file |= attrs;
+        self
+    }
+
+    fn to_libc(self) -> libc::attrlist {
+        libc::attrlist {
+            bitmapcount: libc::ATTR_BIT_MAP_COUNT,
+            reserved: 0,
+            // Always ask for the set of returned attributes, as the decoder
+            // relies on it.
+            commonattr: self.common.bits() | libc::ATTR_CMN_RETURNED_ATTRS,
+            volattr: 0,
+            dirattr: self.dir.bits(),
+            fileattr: self.file.bits(),
+            forkattr: 0,
+        }
+    }
+}
+
+impl Default for AttrList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const REF: usize = mem::size_of::<libc::attrreference_t>();
+const TS: usize = mem::size_of::<libc::timespec>();
+
+// Size of each common attribute in the packed buffer, indexed by bit number.
+// Zero marks the attributes that can't be decoded.
+const COMMON_SIZES: [usize; 32] = [
+    REF, // ATTR_CMN_NAME
+    4,   // ATTR_CMN_DEVID
+    8,   // ATTR_CMN_FSID
+    4,   // ATTR_CMN_OBJTYPE
+    4,   // ATTR_CMN_OBJTAG
+    8,   // ATTR_CMN_OBJID
+    8,   // ATTR_CMN_OBJPERMANENTID
+    8,   // ATTR_CMN_PAROBJID
+    4,   // ATTR_CMN_SCRIPT
+    TS,  // ATTR_CMN_CRTIME
+    TS,  // ATTR_CMN_MODTIME
+    TS,  // ATTR_CMN_CHGTIME
+    TS,  // ATTR_CMN_ACCTIME
+    TS,  // ATTR_CMN_BKUPTIME
+    32,  // ATTR_CMN_FNDRINFO
+    4,   // ATTR_CMN_OWNERID
+    4,   // ATTR_CMN_GRPID
+    4,   // ATTR_CMN_ACCESSMASK
+    4,   // ATTR_CMN_FLAGS
+    4,   // ATTR_CMN_GEN_COUNT
+    4,   // ATTR_CMN_DOCUMENT_ID
+    4,   // ATTR_CMN_USERACCESS
+    REF, // ATTR_CMN_EXTENDED_SECURITY
+    16,  // ATTR_CMN_UUID
+    16,  // ATTR_CMN_GRPUUID
+    8,   // ATTR_CMN_FILEID
+    8,   // ATTR_CMN_PARENTID
+    REF, // ATTR_CMN_FULLPATH
+    TS,  // ATTR_CMN_ADDEDTIME
+    0,   // ATTR_CMN_ERROR
+    4,   // ATTR_CMN_DATA_PROTECT_FLAGS
+    0,   // ATTR_CMN_RETURNED_ATTRS, always first
+];
+
+const DIR_SIZES: [usize; 32] = {
+    let mut sizes = [0; 32];
+    sizes[0] = 4; // ATTR_DIR_LINKCOUNT
+    sizes[1] = 4; // ATTR_DIR_ENTRYCOUNT
+    sizes[2] = 4; // ATTR_DIR_MOUNTSTATUS
+    sizes[3] = 8; // ATTR_DIR_ALLOCSIZE
+    sizes[4] = 4; // ATTR_DIR_IOBLOCKSIZE
+    sizes[5] = 8; // ATTR_DIR_DATALENGTH
+    sizes
+};
+
+const FILE_SIZES: [usize; 32] = {
+    let mut sizes = [0; 32];
+    sizes[0] = 4; // ATTR_FILE_LINKCOUNT
+    sizes[1] = 8; // ATTR_FILE_TOTALSIZE
+    sizes[2] = 8; // ATTR_FILE_ALLOCSIZE
+    sizes[3] = 4; // ATTR_FILE_IOBLOCKSIZE
+    sizes[4] = 4; // ATTR_FILE_CLUMPSIZE
+    sizes[5] = 4; // ATTR_FILE_DEVTYPE
+    sizes[6] = 4; // ATTR_FILE_FILETYPE
+    sizes[7] = 4; // ATTR_FILE_FORKCOUNT
+    sizes[8] = REF; // ATTR_FILE_FORKLIST
+    sizes[9] = 8; // ATTR_FILE_DATALENGTH
+    sizes[10] = 8; // ATTR_FILE_DATAALLOCSIZE
+    sizes[12] = 8; // ATTR_FILE_RSRCLENGTH
+    sizes[13] = 8; // ATTR_FILE_RSRCALLOCSIZE
+    sizes
+};
+
+const COMMON: usize = 0;
+const DIR: usize = 1;
+const FILE: usize = 2;
+const ABSENT: u32 = u32::MAX;
+
+/// The attributes of one file system object, decoded from the buffer filled
+/// by [`getattrlist`] or [`getattrlistbulk`].
+///
+/// Each accessor returns `None` if the attribute was not requested, or was
+/// not returned by the file system.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Attributes<'a> {
+    buf: &'a [u8],
+    // Offset in `buf` of each attribute, indexed by group and bit number.
+    offsets: [[u32; 32]; 3],
+}
+
+macro_rules! attr_getters {
+    ($($(#[$attr:meta])* $name:ident: $group:ident $flag:ident => $ty:ty, $read:ident;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self) -> Option<$ty> {
+                self.get($group, libc::$flag).map(|bytes| $read(bytes))
+            }
+        )*
+    };
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_ne_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_ne_bytes(bytes[..8].try_into().unwrap())
+}
+
+fn read_off(bytes: &[u8]) -> off_t {
+    off_t::from_ne_bytes(bytes[..8].try_into().unwrap())
+}
+
+fn read_dev(bytes: &[u8]) -> dev_t {
+    dev_t::from_ne_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn read_id_pair(bytes: &[u8]) -> (u32, u32) {
+    (read_u32(bytes), read_u32(&bytes[4..]))
+}
+
+fn read_fsid(bytes: &[u8]) -> [i32; 2] {
+    [read_u32(bytes) as i32, read_u32(&bytes[4..]) as i32]
+}
+
+fn read_timespec(bytes: &[u8]) -> TimeSpec {
+    // SAFETY: the caller provides `TS` bytes, and any bit pattern is a valid
+    // timespec.
+    let ts =
+        unsafe { bytes.as_ptr().cast::<libc::timespec>().read_unaligned() };
+    TimeSpec::from(ts)
+}
+
+fn read_uuid(bytes: &[u8]) -> [u8; 16] {
+    bytes[..16].try_into().unwrap()
+}
+
+fn read_finder_info(bytes: &[u8]) -> [u8; 32] {
+    bytes[..32].try_into().unwrap()
+}
+
+fn read_mode(bytes: &[u8]) -> Mode {
+    Mode::from_bits_truncate(read_u32(bytes) as libc::mode_t)
+}
+
+fn read_file_flags(bytes: &[u8]) -> FileFlag {
+    FileFlag::from_bits_truncate(read_u32(bytes) as _)
+}
+
+impl<'a> Attributes<'a> {
+    // Decodes one packed entry, starting with its `u32` length.
+    fn parse(buf: &'a [u8]) -> Result<Self> {
+        const SET: usize = mem::size_of::<libc::attribute_set_t>();
+
+        if buf.len() < 4 + SET {
+            return Err(Errno::ERANGE);
+        }
+        let len = read_u32(buf) as usize;
+        if len > buf.len() || len < 4 + SET {
+            return Err(Errno::ERANGE);
+        }
+        let buf = &buf[..len];
+
+        let mut offsets = [[ABSENT; 32]; 3];
+        let mut offset = 4 + SET;
+        let groups = [
+            (COMMON, read_u32(&buf[4..]), &COMMON_SIZES),
+            (DIR, read_u32(&buf[12..]), &DIR_SIZES),
+            (FILE, read_u32(&buf[16..]), &FILE_SIZES),
+        ];
+        // Volume and fork attributes are never requested.
+        if read_u32(&buf[8..]) != 0 || read_u32(&buf[20..]) != 0 {
+            return Err(Errno::EINVAL);
+        }
+        for (group, returned, sizes) in groups {
+            let returned = if group == COMMON {
+                returned & !libc::ATTR_CMN_RETURNED_ATTRS
+            } else {
+                returned
+            };
+            for bit in 0..32 {
+                if returned & (1 << bit) == 0 {
+                    continue;
+                }
+                let size = sizes[bit];
+                if size == 0 {
+                    return Err(Errno::EINVAL);
+                }
+                offsets[group][bit] = offset as u32;
+                // Each attribute is aligned to 4 bytes.
+                offset += (size + 3) & !3;
+            }
+        }
+        if offset > len {
+            return Err(Errno::ERANGE);
+        }
+
+        let attrs = Attributes { buf, offsets };
+        // Check the variable-length attributes up front, so their getters
+        // can't fail.
+        for flag in [libc::ATTR_CMN_NAME, libc::ATTR_CMN_FULLPATH] {
+            if let Some(offset) = attrs.offset(COMMON, flag) {
+                attrs.reference(offset)?;
+            }
+        }
+        Ok(attrs)
+    }
+
+    fn offset(&self, group: usize, flag: attrgroup_t) -> Option<usize> {
+        match self.offsets[group][flag.trailing_zeros() as usize] {
+            ABSENT => None,
+            offset => Some(offset as usize),
+        }
+    }
+
+    fn get(&self, group: usize, flag: attrgroup_t) -> Option<&'a [u8]> {
+        self.offset(group, flag).map(|offset| &self.buf[offset..])
+    }
+
+    // Resolves the `attrreference_t` at `offset` to the C string it points
+    // to.
+    fn reference(&self, offset: usize) -> Result<&'a CStr> {
+        let data_offset = read_u32(&self.buf[offset..]) as i32;
+        let length = read_u32(&self.buf[offset + 4..]) as usize;
+        let start = usize::try_from(offset as isize + data_offset as isize)
+            .map_err(|_| Errno::EINVAL)?;
+        let end = start.checked_add(length).ok_or(Errno::EINVAL)?;
+        let data = self.buf.get(start..end).ok_or(Errno::ERANGE)?;
+        CStr::from_bytes_until_nul(data).map_err(|_| Errno::EINVAL)
+    }
+
+    /// Returns the set of common attributes that were returned.
+    pub fn common(&self) -> CommonAttr {
+        CommonAttr::from_bits_truncate(read_u32(&self.buf[4..]))
+    }
+
+    /// Returns the set of directory attributes that were returned.
+    pub fn dir(&self) -> DirAttr {
+        DirAttr::from_bits_truncate(read_u32(&self.buf[12..]))
+    }
+
+    /// Returns the set of file attributes that were returned.
+    pub fn file(&self) -> FileAttr {
+        FileAttr::from_bits_truncate(read_u32(&self.buf[16..]))
+    }
+
+    /// The name of the object (`ATTR_CMN_NAME`).
+    pub fn name(&self) -> Option<&'a CStr> {
+        self.offset(COMMON, libc::ATTR_CMN_NAME)
+            .map(|offset| self.reference(offset).unwrap())
+    }
+
+    /// The absolute path of the object (`ATTR_CMN_FULLPATH`).
+    pub fn full_path(&self) -> Option<&'a CStr> {
+        self.offset(COMMON, libc::ATTR_CMN_FULLPATH)
+            .map(|offset| self.reference(offset).unwrap())
+    }
+
+    attr_getters! {
+        /// The device the object resides on (`ATTR_CMN_DEVID`).
+        dev_id: COMMON ATTR_CMN_DEVID => dev_t, read_dev;
+        /// The file system the object resides on (`ATTR_CMN_FSID`).
+        fs_id: COMMON ATTR_CMN_FSID => [i32; 2], read_fsid;
+        /// The type of the object (`ATTR_CMN_OBJTYPE`).
+        obj_type: COMMON ATTR_CMN_OBJTYPE => u32, read_u32;
+        /// The type of file system the object resides on (`ATTR_CMN_OBJTAG`).
+        obj_tag: COMMON ATTR_CMN_OBJTAG => u32, read_u32;
+        /// The object number and generation of the object
+        /// (`ATTR_CMN_OBJID`).
+        obj_id: COMMON ATTR_CMN_OBJID => (u32, u32), read_id_pair;
+        /// The persistent object number and generation of the object
+        /// (`ATTR_CMN_OBJPERMANENTID`).
+        obj_permanent_id: COMMON ATTR_CMN_OBJPERMANENTID => (u32, u32), read_id_pair;
+        /// The object number and generation of the parent directory
+        /// (`ATTR_CMN_PAROBJID`).
+        parent_obj_id: COMMON ATTR_CMN_PAROBJID => (u32, u32), read_id_pair;
+        /// The text encoding hint of the name (`ATTR_CMN_SCRIPT`).
+        script: COMMON ATTR_CMN_SCRIPT => u32, read_u32;
+        /// The creation time (`ATTR_CMN_CRTIME`).
+        creation_time: COMMON ATTR_CMN_CRTIME => TimeSpec, read_timespec;
+        /// The last data modification time (`ATTR_CMN_MODTIME`).
+        modification_time: COMMON ATTR_CMN_MODTIME => TimeSpec, read_timespec;
+        /// The last status change time (`ATTR_CMN_CHGTIME`).
+        change_time: COMMON ATTR_CMN_CHGTIME => TimeSpec, read_timespec;
+        /// The last access time (`ATTR_CMN_ACCTIME`).
+        access_time: COMMON ATTR_CMN_ACCTIME => TimeSpec, read_timespec;
+        /// The last backup time (`ATTR_CMN_BKUPTIME`).
+        backup_time: COMMON ATTR_CMN_BKUPTIME => TimeSpec, read_timespec;
+        /// The Finder information (`ATTR_CMN_FNDRINFO`).
+        finder_info: COMMON ATTR_CMN_FNDRINFO => [u8; 32], read_finder_info;
+        /// The owner (`ATTR_CMN_OWNERID`).
+        owner: COMMON ATTR_CMN_OWNERID => uid_t, read_u32;
+        /// The group (`ATTR_CMN_GRPID`).
+        group: COMMON ATTR_CMN_GRPID => gid_t, read_u32;
+        /// The access permissions (`ATTR_CMN_ACCESSMASK`).
+        access_mask: COMMON ATTR_CMN_ACCESSMASK => Mode, read_mode;
+        /// The BSD file flags (`ATTR_CMN_FLAGS`).
+        flags: COMMON ATTR_CMN_FLAGS => FileFlag, read_file_flags;
+        /// The generation count (`ATTR_CMN_GEN_COUNT`).
+        gen_count: COMMON ATTR_CMN_GEN_COUNT => u32, read_u32;
+        /// The document identifier (`ATTR_CMN_DOCUMENT_ID`).
+        document_id: COMMON ATTR_CMN_DOCUMENT_ID => u32, read_u32;
+        /// The effective access permissions of the calling process
+        /// (`ATTR_CMN_USERACCESS`).
+        user_access: COMMON ATTR_CMN_USERACCESS => u32, read_u32;
+        /// The UUID of the owner (`ATTR_CMN_UUID`).
+        uuid: COMMON ATTR_CMN_UUID => [u8; 16], read_uuid;
+        /// The UUID of the group (`ATTR_CMN_GRPUUID`).
+        group_uuid: COMMON ATTR_CMN_GRPUUID => [u8; 16], read_uuid;
+        /// The inode number (`ATTR_CMN_FILEID`).
+        file_id: COMMON ATTR_CMN_FILEID => u64, read_u64;
+        /// The inode number of the parent directory (`ATTR_CMN_PARENTID`).
+        parent_id: COMMON ATTR_CMN_PARENTID => u64, read_u64;
+        /// The time the object was added to its parent directory
+        /// (`ATTR_CMN_ADDEDTIME`).
+        added_time: COMMON ATTR_CMN_ADDEDTIME => TimeSpec, read_timespec;
+        /// The data protection class (`ATTR_CMN_DATA_PROTECT_FLAGS`).
+        data_protect_flags: COMMON ATTR_CMN_DATA_PROTECT_FLAGS => u32, read_u32;
+
+        /// The number of hard links to a directory (`ATTR_DIR_LINKCOUNT`).
+        dir_link_count: DIR ATTR_DIR_LINKCOUNT => u32, read_u32;
+        /// The number of entries in a directory (`ATTR_DIR_ENTRYCOUNT`).
+        entry_count: DIR ATTR_DIR_ENTRYCOUNT => u32, read_u32;
+        /// The mount status of a directory (`ATTR_DIR_MOUNTSTATUS`).
+        mount_status: DIR ATTR_DIR_MOUNTSTATUS => u32, read_u32;
+        /// The storage allocated to a directory (`ATTR_DIR_ALLOCSIZE`).
+        dir_alloc_size: DIR ATTR_DIR_ALLOCSIZE => off_t, read_off;
+        /// The optimal I/O block size of a directory
+        /// (`ATTR_DIR_IOBLOCKSIZE`).
+        dir_io_block_size: DIR ATTR_DIR_IOBLOCKSIZE => u32, read_u32;
+        /// The logical size of a directory (`ATTR_DIR_DATALENGTH`).
+        dir_data_length: DIR ATTR_DIR_DATALENGTH => off_t, read_off;
+
+        /// The number of hard links to a file (`ATTR_FILE_LINKCOUNT`).
+        link_count: FILE ATTR_FILE_LINKCOUNT => u32, read_u32;
+        /// The logical size of all forks of a file (`ATTR_FILE_TOTALSIZE`).
+        total_size: FILE ATTR_FILE_TOTALSIZE => off_t, read_off;
+        /// The storage allocated to all forks of a file
+        /// (`ATTR_FILE_ALLOCSIZE`).
+        alloc_size: FILE ATTR_FILE_ALLOCSIZE => off_t, read_off;
+        /// The optimal I/O block size of a file (`ATTR_FILE_IOBLOCKSIZE`).
+        io_block_size: FILE ATTR_FILE_IOBLOCKSIZE => u32, read_u32;
+        /// The device type of a special file (`ATTR_FILE_DEVTYPE`).
+        dev_type: FILE ATTR_FILE_DEVTYPE => dev_t, read_dev;
+        /// The logical size of the data fork (`ATTR_FILE_DATALENGTH`).
+        data_length: FILE ATTR_FILE_DATALENGTH => off_t, read_off;
+        /// The storage allocated to the data fork
+        /// (`ATTR_FILE_DATAALLOCSIZE`).
+        data_alloc_size: FILE ATTR_FILE_DATAALLOCSIZE => off_t, read_off;
+        /// The logical size of the resource fork (`ATTR_FILE_RSRCLENGTH`).
+        rsrc_length: FILE ATTR_FILE_RSRCLENGTH => off_t, read_off;
+        /// The storage allocated to the resource fork
+        /// (`ATTR_FILE_RSRCALLOCSIZE`).
+        rsrc_alloc_size: FILE ATTR_FILE_RSRCALLOCSIZE => off_t, read_off;
+    }
+}
+
+/// Get the attributes of the file at `path`.
+///
+/// The attributes are written to `buf`, which the returned [`Attributes`]
+/// borrows.  If `buf` is too small, the attributes are silently truncated;
+/// use [`FsOpt::FSOPT_REPORT_FULLSIZE`] to detect that.
+pub fn getattrlist<'a, P: ?Sized + NixPath>(
+    path: &P,
+    attrs: &AttrList,
+    buf: &'a mut [u8],
+    options: FsOpt,
+) -> Result<Attributes<'a>> {
+    let mut list = attrs.to_libc();
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::getattrlist(
+            cstr.as_ptr(),
+            (&mut list as *mut libc::attrlist).cast(),
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            options.bits(),
+        )
+    })?;
+    Errno::result(res)?;
+    Attributes::parse(buf)
+}
+
+/// Get the attributes of the open file `fd`.
+///
+/// See [`getattrlist`].
+pub fn fgetattrlist<'a, Fd: AsFd>(
+    fd: Fd,
+    attrs: &AttrList,
+    buf: &'a mut [u8],
+    options: FsOpt,
+) -> Result<Attributes<'a>> {
+    let mut list = attrs.to_libc();
+    let res = unsafe {
+        libc::fgetattrlist(
+            fd.as_fd().as_raw_fd(),
+            (&mut list as *mut libc::attrlist).cast(),
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            options.bits(),
+        )
+    };
+    Errno::result(res)?;
+    Attributes::parse(buf)
+}
+
+/// The entries returned by one call to [`getattrlistbulk`].
+///
+/// Iterating over it yields the [`Attributes`] of each entry.
+#[derive(Clone, Debug)]
+pub struct BulkAttributes<'a> {
+    buf: &'a [u8],
+    remaining: usize,
+}
+
+impl<'a> BulkAttributes<'a> {
+    /// Returns the number of entries not yet yielded.
+    pub fn len(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns `true` if there are no more entries.  If this is the case
+    /// right after calling [`getattrlistbulk`], the end of the directory has
+    /// been reached.
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+impl<'a> Iterator for BulkAttributes<'a> {
+    type Item = Result<Attributes<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let attrs = match Attributes::parse(self.buf) {
+            Ok(attrs) => attrs,
+            Err(e) => {
+                // The entries can't be located past a malformed one.
+                self.remaining = 0;
+                return Some(Err(e));
+            }
+        };
+        self.buf = &self.buf[attrs.buf.len()..];
+        Some(Ok(attrs))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// Get the attributes of the next entries of the directory open at `dirfd`.
+///
+/// As many entries as fit in `buf` are read, starting at the current
+/// position of `dirfd`, which is then advanced past them.  Once all entries
+/// have been read, the returned [`BulkAttributes`] is empty.  The `.` and
+/// `..` entries are never returned.
+///
+/// The only option that may be used is [`FsOpt::empty()`]; the others are
+/// rejected with `EINVAL`.
+pub fn getattrlistbulk<'a, Fd: AsFd>(
+    dirfd: Fd,
+    attrs: &AttrList,
+    buf: &'a mut [u8],
+    options: FsOpt,
+) -> Result<BulkAttributes<'a>> {
+    let mut list = attrs.to_libc();
+    let res = unsafe {
+        libc::getattrlistbulk(
+            dirfd.as_fd().as_raw_fd(),
+            (&mut list as *mut libc::attrlist).cast(),
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            options.bits().into(),
+        )
+    };
+    let count = Errno::result(res)?;
+    Ok(BulkAttributes {
+        buf,
+        remaining: count as usize,
+    })
+}
+
+/// Attribute values to change with [`setattrlist`].
+///
+/// Only the attributes that were given a value are changed.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AttrValues {
+    creation_time: Option<TimeSpec>,
+    modification_time: Option<TimeSpec>,
+    change_time: Option<TimeSpec>,
+    access_time: Option<TimeSpec>,
+    backup_time: Option<TimeSpec>,
+    finder_info: Option<[u8; 32]>,
+    owner: Option<uid_t>,
+    group: Option<gid_t>,
+    access_mask: Option<Mode>,
+    flags: Option<FileFlag>,
+}
+
+impl AttrValues {
+    /// Creates an empty `AttrValues`, which changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the creation time (`ATTR_CMN_CRTIME`).
+    pub fn creation_time(mut self, time: TimeSpec) -> Self {
+        self.creation_time = Some(time);
+        self
+    }
+
+    /// Sets the last data modification time (`ATTR_CMN_MODTIME`).
+    pub fn modification_time(mut self, time: TimeSpec) -> Self {
+        self.modification_time = Some(time);
+        self
+    }
+
+    /// Sets the last status change time (`ATTR_CMN_CHGTIME`).
+    pub fn change_time(mut self, time: TimeSpec) -> Self {
+        self.change_time = Some(time);
+        self
+    }
+
+    /// Sets the last access time (`ATTR_CMN_ACCTIME`).
+    pub fn access_time(mut self, time: TimeSpec) -> Self {
+        self.access_time = Some(time);
+        self
+    }
+
+    /// Sets the last backup time (`ATTR_CMN_BKUPTIME`).
+    pub fn backup_time(mut self, time: TimeSpec) -> Self {
+        self.backup_time = Some(time);
+        self
+    }
+
+    /// Sets the Finder information (`ATTR_CMN_FNDRINFO`).
+    pub fn finder_info(mut self, info: [u8; 32]) -> Self {
+        self.finder_info = Some(info);
+        self
+    }
+
+    /// Sets the owner (`ATTR_CMN_OWNERID`).
+    pub fn owner(mut self, owner: uid_t) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Sets the group (`ATTR_CMN_GRPID`).
+    pub fn group(mut self, group: gid_t) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Sets the access permissions (`ATTR_CMN_ACCESSMASK`).
+    pub fn access_mask(mut self, mode: Mode) -> Self {
+        self.access_mask = Some(mode);
+        self
+    }
+
+    /// Sets the BSD file flags (`ATTR_CMN_FLAGS`).
+    pub fn flags(mut self, flags: FileFlag) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    // Packs the values in attribute order, as expected by `setattrlist(2)`.
+    fn pack(&self) -> (libc::attrlist, Vec<u8>) {
+        let mut common = 0;
+        let mut buf = Vec::new();
+        let times = [
+            (libc::ATTR_CMN_CRTIME, self.creation_time),
+            (libc::ATTR_CMN_MODTIME, self.modification_time),
+            (libc::ATTR_CMN_CHGTIME, self.change_time),
+            (libc::ATTR_CMN_ACCTIME, self.access_time),
+            (libc::ATTR_CMN_BKUPTIME, self.backup_time),
+        ];
+        for (flag, time) in times {
+            if let Some(time) = time {
+                common |= flag;
+                let ts: &libc::timespec = time.as_ref();
+                // SAFETY: a timespec is plain old data.
+                buf.extend_from_slice(unsafe {
+                    std::slice::from_raw_parts(
+                        (ts as *const libc::timespec).cast::<u8>(),
+                        TS,
+                    )
+                });
+            }
+        }
+        if let Some(info) = self.finder_info {
+            common |= libc::ATTR_CMN_FNDRINFO;
+            buf.extend_from_slice(&info);
+        }
+        let words = [
+            (libc::ATTR_CMN_OWNERID, self.owner),
+            (libc::ATTR_CMN_GRPID, self.group),
+            (
+                libc::ATTR_CMN_ACCESSMASK,
+                self.access_mask.map(|mode| u32::from(mode.bits())),
+            ),
+            (libc::ATTR_CMN_FLAGS, self.flags.map(|flags| flags.bits())),
+        ];
+        for (flag, word) in words {
+            if let Some(word) = word {
+                common |= flag;
+                buf.extend_from_slice(&word.to_ne_bytes());
+            }
+        }
+
+        let list = libc::attrlist {
+            bitmapcount: libc::ATTR_BIT_MAP_COUNT,
+            reserved: 0,
+            commonattr: common,
+            volattr: 0,
+            dirattr: 0,
+            fileattr: 0,
+            forkattr: 0,
+        };
+        (list, buf)
+    }
+}
+
+/// Change the attributes of the file at `path`.
+pub fn setattrlist<P: ?Sized + NixPath>(
+    path: &P,
+    values: &AttrValues,
+    options: FsOpt,
+) -> Result<()> {
+    let (mut list, mut buf) = values.pack();
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::setattrlist(
+            cstr.as_ptr(),
+            (&mut list as *mut libc::attrlist).cast(),
+            buf.as_mut_ptr().cast::<c_void>(),
+            buf.len(),
+            options.bits(),
+        )
+    })?;
+    Errno::result(res).map(drop)
+}
+
+/// Change the attributes of the open file `fd`.
+pub fn fsetattrlist<Fd: AsFd>(
+    fd: Fd,
+    values: &AttrValues,
+    options: FsOpt,
+) -> Result<()> {
+    let (mut list, mut buf) = values.pack();
+    let res = unsafe {
+        libc::fsetattrlist(
+            fd.as_fd().as_raw_fd(),
+            (&mut list as *mut libc::attrlist).cast(),
+            buf.as_mut_ptr().cast::<c_void>(),
+            buf.len(),
+            options.bits(),
+        )
+    };
+    Errno::result(res).map(drop)
+}
@@ -0,0 +1,400 @@
+//! Capabilities of threads.
+//!
+//! The privileges of the superuser are divided into capabilities, that each
+//! thread has in three sets:
+//! * The effective set, that is used for permission checks.
+//! * The permitted set, that limits the effective set.
+//! * The inheritable set, that is preserved across `execve(2)`.
+//!
+//! # Example
+//!
+//! Drop a capability:
+//!
+//! ```
+//! # use nix::sys::caps::{capget, capset, Capability};
+//! let mut caps = capget(None).unwrap();
+//! caps.effective.remove(Capability::CAP_SYS_ADMIN.into());
+//! caps.permitted.remove(Capability::CAP_SYS_ADMIN.into());
+//! capset(&caps).unwrap();
+//! ```
+//!
+//! # See Also
+//! [capabilities(7)](https://man7.org/linux/man-pages/man7/capabilities.7.html)
+
+use crate::errno::Errno;
+use crate::unistd::Pid;
+use crate::Result;
+
+const _LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[repr(C)]
+struct cap_user_header {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct cap_user_data {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// A capability.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Capability {
+    /// Change the owner and group of files.
+    CAP_CHOWN = 0,
+    /// Bypass file read, write and execute permission checks.
+    CAP_DAC_OVERRIDE = 1,
+    /// Bypass file read, and directory read and execute permission
+    /// checks.
+    CAP_DAC_READ_SEARCH = 2,
+    /// Bypass the checks that the caller owns a file.
+    CAP_FOWNER = 3,
+    /// Keep the set-user-ID and set-group-ID bits of modified files.
+    CAP_FSETID = 4,
+    /// Bypass the permission checks for sending signals.
+    CAP_KILL = 5,
+    /// Manipulate group ids.
+    CAP_SETGID = 6,
+    /// Manipulate user ids.
+    CAP_SETUID = 7,
+    /// Change the bounding set, and add any permitted capability to
+    /// the inheritable set.
+    CAP_SETPCAP = 8,
+    /// Set the immutable and append-only file flags.
+    CAP_LINUX_IMMUTABLE = 9,
+    /// Bind sockets to privileged ports.
+    CAP_NET_BIND_SERVICE = 10,
+    /// Unused.
+    CAP_NET_BROADCAST = 11,
+    /// Perform network administration.
+    CAP_NET_ADMIN = 12,
+    /// Use raw and packet sockets.
+    CAP_NET_RAW = 13,
+    /// Lock memory.
+    CAP_IPC_LOCK = 14,
+    /// Bypass the permission checks of System V IPC objects.
+    CAP_IPC_OWNER = 15,
+    /// Load and unload kernel modules.
+    CAP_SYS_MODULE = 16,
+    /// Perform I/O port operations, and access devices directly.
+    CAP_SYS_RAWIO = 17,
+    /// Use `chroot(2)`.
+    CAP_SYS_CHROOT = 18,
+    /// Trace arbitrary processes.
+    CAP_SYS_PTRACE = 19,
+    /// Use `acct(2)`.
+    CAP_SYS_PACCT = 20,
+    /// Perform a wide range of system administration operations.
+    CAP_SYS_ADMIN = 21,
+    /// Use `reboot(2)` and `kexec_load(2)`.
+    CAP_SYS_BOOT = 22,
+    /// Raise the priority of processes, and change the scheduling of
+    /// arbitrary processes.
+    CAP_SYS_NICE = 23,
+    /// Override resource limits.
+    CAP_SYS_RESOURCE = 24,
+    /// Set the system clock.
+    CAP_SYS_TIME = 25,
+    /// Use `vhangup(2)`.
+    CAP_SYS_TTY_CONFIG = 26,
+    /// Create special files with `mknod(2)`.
+    CAP_MKNOD = 27,
+    /// Take leases on arbitrary files.
+    CAP_LEASE = 28,
+    /// Write records to the kernel audit log.
+    CAP_AUDIT_WRITE = 29,
+    /// Configure kernel auditing.
+    CAP_AUDIT_CONTROL = 30,
+    /// Set the capabilities of files.
+    CAP_SETFCAP = 31,
+    /// Override Mandatory Access Control.
+    CAP_MAC_OVERRIDE = 32,
+    /// Configure Mandatory Access Control.
+    CAP_MAC_ADMIN = 33,
+    /// Perform privileged `syslog(2)` operations.
+    CAP_SYSLOG = 34,
+    /// Trigger something that will wake up the system.
+    CAP_WAKE_ALARM = 35,
+    /// Block system suspend.
+    CAP_BLOCK_SUSPEND = 36,
+    /// Read the kernel audit log.
+    CAP_AUDIT_READ = 37,
+    /// Use performance monitoring.
+    CAP_PERFMON = 38,
+    /// Perform privileged BPF operations.
+    CAP_BPF = 39,
+    /// Perform checkpoint and restore operations.
+    CAP_CHECKPOINT_RESTORE = 40,
+}
+
+impl TryFrom<u32> for Capability {
+    type Error = Errno;
+
+    fn try_from(cap: u32) -> Result<Self> {
+        use Capability::*;
+
+        match cap {
+            0 => Ok(CAP_CHOWN),
+            1 => Ok(CAP_DAC_OVERRIDE),
+            2 => Ok(CAP_DAC_READ_SEARCH),
+            3 => Ok(CAP_FOWNER),
+            4 => Ok(CAP_FSETID),
+            5 => Ok(CAP_KILL),
+            6 => Ok(CAP_SETGID),
+            7 => Ok(CAP_SETUID),
+            8 => Ok(CAP_SETPCAP),
+            9 => Ok(CAP_LINUX_IMMUTABLE),
+            10 => Ok(CAP_NET_BIND_SERVICE),
+            11 => Ok(CAP_NET_BROADCAST),
+            12 => Ok(CAP_NET_ADMIN),
+            13 => Ok(CAP_NET_RAW),
+            14 => Ok(CAP_IPC_LOCK),
+            15 => Ok(CAP_IPC_OWNER),
+            16 => Ok(CAP_SYS_MODULE),
+            17 => Ok(CAP_SYS_RAWIO),
+            18 => Ok(CAP_SYS_CHROOT),
+            19 => Ok(CAP_SYS_PTRACE),
+            20 => Ok(CAP_SYS_PACCT),
+            21 => Ok(CAP_SYS_ADMIN),
+            22 => Ok(CAP_SYS_BOOT),
+            23 => Ok(CAP_SYS_NICE),
+            24 => Ok(CAP_SYS_RESOURCE),
+            25 => Ok(CAP_SYS_TIME),
+            26 => Ok(CAP_SYS_TTY_CONFIG),
+            27 => Ok(CAP_MKNOD),
+            28 => Ok(CAP_LEASE),
+            29 => Ok(CAP_AUDIT_WRITE),
+            30 => Ok(CAP_AUDIT_CONTROL),
+            31 => Ok(CAP_SETFCAP),
+            32 => Ok(CAP_MAC_OVERRIDE),
+            33 => Ok(CAP_MAC_ADMIN),
+            34 => Ok(CAP_SYSLOG),
+            35 => Ok(CAP_WAKE_ALARM),
+            36 => Ok(CAP_BLOCK_SUSPEND),
+            37 => Ok(CAP_AUDIT_READ),
+            38 => Ok(CAP_PERFMON),
+            39 => Ok(CAP_BPF),
+            40 => Ok(CAP_CHECKPOINT_RESTORE),
+            _ => Err(Errno::EINVAL),
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// A set of capabilities.
+    #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct CapabilitySet: u64 {
+        /// Change the owner and group of files.
+        const CAP_CHOWN = 1 << 0;
+        /// Bypass file read, write and execute permission checks.
+        const CAP_DAC_OVERRIDE = 1 << 1;
+        /// Bypass file read, and directory read and execute
+        /// permission checks.
+        const CAP_DAC_READ_SEARCH = 1 << 2;
+        /// Bypass the checks that the caller owns a file.
+        const CAP_FOWNER = 1 << 3;
+        /// Keep the set-user-ID and set-group-ID bits of modified
+        /// files.
+        const CAP_FSETID = 1 << 4;
+        /// Bypass the permission checks for sending signals.
+        const CAP_KILL = 1 << 5;
+        /// Manipulate group ids.
+        const CAP_SETGID = 1 << 6;
+        /// Manipulate user ids.
+        const CAP_SETUID = 1 << 7;
+        /// Change the bounding set, and add any permitted
+        /// capability to the inheritable set.
+        const CAP_SETPCAP = 1 << 8;
+        /// Set the immutable and append-only file flags.
+        const CAP_LINUX_IMMUTABLE = 1 << 9;
+        /// Bind sockets to privileged ports.
+        const CAP_NET_BIND_SERVICE = 1 << 10;
+        /// Unused.
+        const CAP_NET_BROADCAST = 1 << 11;
+        /// Perform network administration.
+        const CAP_NET_ADMIN = 1 << 12;
+        /// Use raw and packet sockets.
+        const CAP_NET_RAW = 1 << 13;
+        /// Lock memory.
+        const CAP_IPC_LOCK = 1 << 14;
+        /// Bypass the permission checks of System V IPC objects.
+        const CAP_IPC_OWNER = 1 << 15;
+        /// Load and unload kernel modules.
+        const CAP_SYS_MODULE = 1 << 16;
+        /// Perform I/O port operations, and access devices
+        /// directly.
+        const CAP_SYS_RAWIO = 1 << 17;
+        /// Use `chroot(2)`.
+        const CAP_SYS_CHROOT = 1 << 18;
+        /// Trace arbitrary processes.
+        const CAP_SYS_PTRACE = 1 << 19;
+        /// Use `acct(2)`.
+        const CAP_SYS_PACCT = 1 << 20;
+        /// Perform a wide range of system administration
+        /// operations.
+        const CAP_SYS_ADMIN = 1 << 21;
+        /// Use `reboot(2)` and `kexec_load(2)`.
+        const CAP_SYS_BOOT = 1 << 22;
+        /// Raise the priority of processes, and change the
+        /// scheduling of arbitrary processes.
+        const CAP_SYS_NICE = 1 << 23;
+        /// Override resource limits.
+        const CAP_SYS_RESOURCE = 1 << 24;
+        /// Set the system clock.
+        const CAP_SYS_TIME = 1 << 25;
+        /// Use `vhangup(2)`.
+        const CAP_SYS_TTY_CONFIG = 1 << 26;
+        /// Create special files with `mknod(2)`.
+        const CAP_MKNOD = 1 << 27;
+        /// Take leases on arbitrary files.
+        const CAP_LEASE = 1 << 28;
+        /// Write records to the kernel audit log.
+        const CAP_AUDIT_WRITE = 1 << 29;
+        /// Configure kernel auditing.
+        const CAP_AUDIT_CONTROL = 1 << 30;
+        /// Set the capabilities of files.
+        const CAP_SETFCAP = 1 << 31;
+        /// Override Mandatory Access Control.
+        const CAP_MAC_OVERRIDE = 1 << 32;
+        /// Configure Mandatory Access Control.
+        const CAP_MAC_ADMIN = 1 << 33;
+        /// Perform privileged `syslog(2)` operations.
+        const CAP_SYSLOG = 1 << 34;
+        /// Trigger something that will wake up the system.
+        const CAP_WAKE_ALARM = 1 << 35;
+        /// Block system suspend.
+        const CAP_BLOCK_SUSPEND = 1 << 36;
+        /// Read the kernel audit log.
+        const CAP_AUDIT_READ = 1 << 37;
+        /// Use performance monitoring.
+        const CAP_PERFMON = 1 << 38;
+        /// Perform privileged BPF operations.
+        const CAP_BPF = 1 << 39;
+        /// Perform checkpoint and restore operations.
+        const CAP_CHECKPOINT_RESTORE = 1 << 40;
+    }
+}
+
+impl From<Capability> for CapabilitySet {
+    fn from(cap: Capability) -> Self {
+        CapabilitySet::from_bits_retain(1 << cap as u32)
+    }
+}
+
+impl CapabilitySet {
+    /// Whether the set contains the capability `cap`.
+    pub fn has(&self, cap: Capability) -> bool {
+        self.contains(cap.into())
+    }
+
+    /// The capabilities of the set.
+    pub fn capabilities(&self) -> impl Iterator<Item = Capability> + '_ {
+        (0..64)
+            .filter(move |&i| self.bits() & (1 << i) != 0)
+            .filter_map(|i| Capability::try_from(i).ok())
+    }
+}
+
+/// The capability sets of a thread, see [`capget`] and [`capset`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Capabilities {
+    /// The capabilities used for permission checks.
+    pub effective: CapabilitySet,
+    /// The capabilities that the thread may add to its effective and
+    /// inheritable sets.
+    pub permitted: CapabilitySet,
+    /// The capabilities preserved across `execve(2)`.
+    pub inheritable: CapabilitySet,
+}
+
+/// Get the capability version preferred by the kernel, e.g.
+/// `0x20080522` for 64-bit capability sets.
+///
+/// [`capget`] and [`capset`] always use that version, which all of the
+/// supported kernels understand.
+pub fn capget_version() -> Result<u32> {
+    let mut header = cap_user_header { version: 0, pid: 0 };
+    // With an unknown version, the kernel fails but writes its preferred
+    // version into the header.
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_capget,
+            &mut header as *mut cap_user_header,
+            std::ptr::null_mut::<cap_user_data>(),
+        )
+    };
+    match Errno::result(res) {
+        Ok(_) | Err(Errno::EINVAL) => Ok(header.version),
+        Err(e) => Err(e),
+    }
+}
+
+/// Get the capability sets of the thread `pid`, or of the calling thread if
+/// `None`.
+///
+/// # See Also
+/// [capget(2)](https://man7.org/linux/man-pages/man2/capget.2.html)
+pub fn capget(pid: Option<Pid>) -> Result<Capabilities> {
+    let mut header = cap_user_header {
+        version: _LINUX_CAPABILITY_VERSION_3,
+        pid: pid.map_or(0, Pid::as_raw),
+    };
+    let mut data = [cap_user_data::default(); 2];
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_capget,
+            &mut header as *mut cap_user_header,
+            data.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+
+    let set = |f: fn(&cap_user_data) -> u32| {
+        CapabilitySet::from_bits_retain(
+            u64::from(f(&data[0])) | u64::from(f(&data[1])) << 32,
+        )
+    };
+    Ok(Capabilities {
+        effective: set(|d| d.effective),
+        permitted: set(|d| d.permitted),
+        inheritable: set(|d| d.inheritable),
+    })
+}
+
+/// Set the capability sets of the calling thread.
+///
+/// The new permitted set must be a subset of the current one, the new
+/// effective set a subset of the new permitted set, and the new inheritable
+/// set a subset of the current inheritable and permitted sets, unless the
+/// thread has `CAP_SETPCAP`.  Otherwise, this fails with `EPERM`.
+///
+/// # See Also
+/// [capset(2)](https://man7.org/linux/man-pages/man2/capset.2.html)
+pub fn capset(caps: &Capabilities) -> Result<()> {
+    let mut header = cap_user_header {
+        version: _LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let data = [0, 32].map(|shift| cap_user_data {
+        effective: (caps.effective.bits() >> shift) as u32,
+        permitted: (caps.permitted.bits() >> shift) as u32,
+        inheritable: (caps.inheritable.bits() >> shift) as u32,
+    });
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_capset,
+            &mut header as *mut cap_user_header,
+            data.as_ptr(),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
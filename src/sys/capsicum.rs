@@ -0,0 +1,411 @@
+//! Capsicum, the capability and sandbox framework of FreeBSD.
+//!
+//! A process enters capability mode with [`cap_enter`], after which it can
+//! no longer access global namespaces, such as the file system or the network,
+//! and can only use the file descriptors it already holds.  Before doing so,
+//! the rights of each file descriptor can be restricted with
+//! [`cap_rights_limit`], [`cap_ioctls_limit`] and [`cap_fcntls_limit`].  None
+//! of this can be undone.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::capsicum::*;
+//! # use std::fs::File;
+//! let file = File::open("/etc/passwd").unwrap();
+//! let mut rights = CapRights::new();
+//! rights.set(CapRight::CAP_READ).set(CapRight::CAP_FSTAT);
+//! cap_rights_limit(&file, &rights).unwrap();
+//! cap_enter().unwrap();
+//! ```
+//!
+//! # See Also
+//! [capsicum(4)](https://man.freebsd.org/cgi/man.cgi?query=capsicum&sektion=4)
+
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::os::fd::{AsFd, AsRawFd};
+
+use crate::errno::Errno;
+use crate::Result;
+
+/// A right that can be granted to a file descriptor, see [`CapRights`].
+///
+/// Some rights are combinations of others, e.g. [`CAP_PREAD`](Self::CAP_PREAD)
+/// includes [`CAP_READ`](Self::CAP_READ) and [`CAP_SEEK`](Self::CAP_SEEK).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CapRight(u64);
+
+impl CapRight {
+    /// Read or receive from the file descriptor.
+    pub const CAP_READ: Self = CapRight(libc::CAP_READ);
+    /// Write or send to the file descriptor.
+    pub const CAP_WRITE: Self = CapRight(libc::CAP_WRITE);
+    /// Get the current offset of the file descriptor.
+    pub const CAP_SEEK_TELL: Self = CapRight(libc::CAP_SEEK_TELL);
+    /// Change the offset of the file descriptor.
+    pub const CAP_SEEK: Self = CapRight(libc::CAP_SEEK);
+    /// Read at an offset, with `pread(2)`.
+    pub const CAP_PREAD: Self = CapRight(libc::CAP_PREAD);
+    /// Write at an offset, with `pwrite(2)`.
+    pub const CAP_PWRITE: Self = CapRight(libc::CAP_PWRITE);
+    /// Map the file descriptor without any access.
+    pub const CAP_MMAP: Self = CapRight(libc::CAP_MMAP);
+    /// Map the file descriptor for reading.
+    pub const CAP_MMAP_R: Self = CapRight(libc::CAP_MMAP_R);
+    /// Map the file descriptor for writing.
+    pub const CAP_MMAP_W: Self = CapRight(libc::CAP_MMAP_W);
+    /// Map the file descriptor for execution.
+    pub const CAP_MMAP_X: Self = CapRight(libc::CAP_MMAP_X);
+    /// Map the file descriptor for reading and writing.
+    pub const CAP_MMAP_RW: Self = CapRight(libc::CAP_MMAP_RW);
+    /// Map the file descriptor for reading and execution.
+    pub const CAP_MMAP_RX: Self = CapRight(libc::CAP_MMAP_RX);
+    /// Map the file descriptor for writing and execution.
+    pub const CAP_MMAP_WX: Self = CapRight(libc::CAP_MMAP_WX);
+    /// Map the file descriptor for reading, writing and execution.
+    pub const CAP_MMAP_RWX: Self = CapRight(libc::CAP_MMAP_RWX);
+    /// Create files with `openat(2)` and `O_CREAT`.
+    pub const CAP_CREATE: Self = CapRight(libc::CAP_CREATE);
+    /// Execute the file with `fexecve(2)`.
+    pub const CAP_FEXECVE: Self = CapRight(libc::CAP_FEXECVE);
+    /// Synchronize the file with `fsync(2)`, or use `O_SYNC`.
+    pub const CAP_FSYNC: Self = CapRight(libc::CAP_FSYNC);
+    /// Truncate the file with `ftruncate(2)`, or use `O_TRUNC`.
+    pub const CAP_FTRUNCATE: Self = CapRight(libc::CAP_FTRUNCATE);
+    /// Use the directory file descriptor with the `*at` functions.
+    pub const CAP_LOOKUP: Self = CapRight(libc::CAP_LOOKUP);
+    /// Change the working directory with `fchdir(2)`.
+    pub const CAP_FCHDIR: Self = CapRight(libc::CAP_FCHDIR);
+    /// Change the file flags with `fchflags(2)`.
+    pub const CAP_FCHFLAGS: Self = CapRight(libc::CAP_FCHFLAGS);
+    /// Change the file flags with `chflagsat(2)`.
+    pub const CAP_CHFLAGSAT: Self = CapRight(libc::CAP_CHFLAGSAT);
+    /// Change the mode with `fchmod(2)`.
+    pub const CAP_FCHMOD: Self = CapRight(libc::CAP_FCHMOD);
+    /// Change the mode with `fchmodat(2)`.
+    pub const CAP_FCHMODAT: Self = CapRight(libc::CAP_FCHMODAT);
+    /// Change the owner with `fchown(2)`.
+    pub const CAP_FCHOWN: Self = CapRight(libc::CAP_FCHOWN);
+    /// Change the owner with `fchownat(2)`.
+    pub const CAP_FCHOWNAT: Self = CapRight(libc::CAP_FCHOWNAT);
+    /// Use `fcntl(2)`, see [`cap_fcntls_limit`].
+    pub const CAP_FCNTL: Self = CapRight(libc::CAP_FCNTL);
+    /// Lock the file with `flock(2)` or `fcntl(2)`.
+    pub const CAP_FLOCK: Self = CapRight(libc::CAP_FLOCK);
+    /// Use `fpathconf(2)`.
+    pub const CAP_FPATHCONF: Self = CapRight(libc::CAP_FPATHCONF);
+    /// Check the file system with UFS background-fsck operations.
+    pub const CAP_FSCK: Self = CapRight(libc::CAP_FSCK);
+    /// Get the status of the file with `fstat(2)`.
+    pub const CAP_FSTAT: Self = CapRight(libc::CAP_FSTAT);
+    /// Get the status of files with `fstatat(2)`.
+    pub const CAP_FSTATAT: Self = CapRight(libc::CAP_FSTATAT);
+    /// Get the status of the file system with `fstatfs(2)`.
+    pub const CAP_FSTATFS: Self = CapRight(libc::CAP_FSTATFS);
+    /// Change the timestamps with `futimes(2)`.
+    pub const CAP_FUTIMES: Self = CapRight(libc::CAP_FUTIMES);
+    /// Change the timestamps with `futimesat(2)`.
+    pub const CAP_FUTIMESAT: Self = CapRight(libc::CAP_FUTIMESAT);
+    /// Create links in the directory with `linkat(2)`.
+    pub const CAP_LINKAT_TARGET: Self = CapRight(libc::CAP_LINKAT_TARGET);
+    /// Create directories with `mkdirat(2)`.
+    pub const CAP_MKDIRAT: Self = CapRight(libc::CAP_MKDIRAT);
+    /// Create named pipes with `mkfifoat(2)`.
+    pub const CAP_MKFIFOAT: Self = CapRight(libc::CAP_MKFIFOAT);
+    /// Create special files with `mknodat(2)`.
+    pub const CAP_MKNODAT: Self = CapRight(libc::CAP_MKNODAT);
+    /// Rename files from the directory with `renameat(2)`.
+    pub const CAP_RENAMEAT_SOURCE: Self = CapRight(libc::CAP_RENAMEAT_SOURCE);
+    /// Create symbolic links with `symlinkat(2)`.
+    pub const CAP_SYMLINKAT: Self = CapRight(libc::CAP_SYMLINKAT);
+    /// Remove files with `unlinkat(2)`.
+    pub const CAP_UNLINKAT: Self = CapRight(libc::CAP_UNLINKAT);
+    /// Accept connections with `accept(2)`.
+    pub const CAP_ACCEPT: Self = CapRight(libc::CAP_ACCEPT);
+    /// Bind the socket with `bind(2)`.
+    pub const CAP_BIND: Self = CapRight(libc::CAP_BIND);
+    /// Connect the socket with `connect(2)`.
+    pub const CAP_CONNECT: Self = CapRight(libc::CAP_CONNECT);
+    /// Get the peer address with `getpeername(2)`.
+    pub const CAP_GETPEERNAME: Self = CapRight(libc::CAP_GETPEERNAME);
+    /// Get the local address with `getsockname(2)`.
+    pub const CAP_GETSOCKNAME: Self = CapRight(libc::CAP_GETSOCKNAME);
+    /// Get socket options with `getsockopt(2)`.
+    pub const CAP_GETSOCKOPT: Self = CapRight(libc::CAP_GETSOCKOPT);
+    /// Listen for connections with `listen(2)`.
+    pub const CAP_LISTEN: Self = CapRight(libc::CAP_LISTEN);
+    /// Branch off an SCTP association with `sctp_peeloff(2)`.
+    pub const CAP_PEELOFF: Self = CapRight(libc::CAP_PEELOFF);
+    /// Receive from the socket, same as [`CAP_READ`](Self::CAP_READ).
+    pub const CAP_RECV: Self = CapRight(libc::CAP_RECV);
+    /// Send to the socket, same as [`CAP_WRITE`](Self::CAP_WRITE).
+    pub const CAP_SEND: Self = CapRight(libc::CAP_SEND);
+    /// Set socket options with `setsockopt(2)`.
+    pub const CAP_SETSOCKOPT: Self = CapRight(libc::CAP_SETSOCKOPT);
+    /// Shut the socket down with `shutdown(2)`.
+    pub const CAP_SHUTDOWN: Self = CapRight(libc::CAP_SHUTDOWN);
+    /// Bind sockets relative to the directory with `bindat(2)`.
+    pub const CAP_BINDAT: Self = CapRight(libc::CAP_BINDAT);
+    /// Connect sockets relative to the directory with `connectat(2)`.
+    pub const CAP_CONNECTAT: Self = CapRight(libc::CAP_CONNECTAT);
+    /// Create links to files in the directory with `linkat(2)`.
+    pub const CAP_LINKAT_SOURCE: Self = CapRight(libc::CAP_LINKAT_SOURCE);
+    /// Rename files into the directory with `renameat(2)`.
+    pub const CAP_RENAMEAT_TARGET: Self = CapRight(libc::CAP_RENAMEAT_TARGET);
+    /// All of the rights of a client socket.
+    pub const CAP_SOCK_CLIENT: Self = CapRight(libc::CAP_SOCK_CLIENT);
+    /// All of the rights of a server socket.
+    pub const CAP_SOCK_SERVER: Self = CapRight(libc::CAP_SOCK_SERVER);
+    /// Get the MAC label with `mac_get_fd(3)`.
+    pub const CAP_MAC_GET: Self = CapRight(libc::CAP_MAC_GET);
+    /// Set the MAC label with `mac_set_fd(3)`.
+    pub const CAP_MAC_SET: Self = CapRight(libc::CAP_MAC_SET);
+    /// Get the value of a semaphore.
+    pub const CAP_SEM_GETVALUE: Self = CapRight(libc::CAP_SEM_GETVALUE);
+    /// Post a semaphore.
+    pub const CAP_SEM_POST: Self = CapRight(libc::CAP_SEM_POST);
+    /// Wait on a semaphore.
+    pub const CAP_SEM_WAIT: Self = CapRight(libc::CAP_SEM_WAIT);
+    /// Wait for events with `select(2)`, `poll(2)` or `kevent(2)`.
+    pub const CAP_EVENT: Self = CapRight(libc::CAP_EVENT);
+    /// Wait for events on the kqueue with `kevent(2)`.
+    pub const CAP_KQUEUE_EVENT: Self = CapRight(libc::CAP_KQUEUE_EVENT);
+    /// Use `ioctl(2)`, see [`cap_ioctls_limit`].
+    pub const CAP_IOCTL: Self = CapRight(libc::CAP_IOCTL);
+    /// Use the file descriptor as a TTY hook.
+    pub const CAP_TTYHOOK: Self = CapRight(libc::CAP_TTYHOOK);
+    /// Get the pid of the process descriptor with `pdgetpid(2)`.
+    pub const CAP_PDGETPID: Self = CapRight(libc::CAP_PDGETPID);
+    /// Wait for the process descriptor.
+    pub const CAP_PDWAIT: Self = CapRight(libc::CAP_PDWAIT);
+    /// Send signals with `pdkill(2)`.
+    pub const CAP_PDKILL: Self = CapRight(libc::CAP_PDKILL);
+    /// Delete extended attributes.
+    pub const CAP_EXTATTR_DELETE: Self = CapRight(libc::CAP_EXTATTR_DELETE);
+    /// Get extended attributes.
+    pub const CAP_EXTATTR_GET: Self = CapRight(libc::CAP_EXTATTR_GET);
+    /// List extended attributes.
+    pub const CAP_EXTATTR_LIST: Self = CapRight(libc::CAP_EXTATTR_LIST);
+    /// Set extended attributes.
+    pub const CAP_EXTATTR_SET: Self = CapRight(libc::CAP_EXTATTR_SET);
+    /// Check an ACL with `acl_valid_fd_np(3)`.
+    pub const CAP_ACL_CHECK: Self = CapRight(libc::CAP_ACL_CHECK);
+    /// Delete the ACL with `acl_delete_fd_np(3)`.
+    pub const CAP_ACL_DELETE: Self = CapRight(libc::CAP_ACL_DELETE);
+    /// Get the ACL with `acl_get_fd(3)`.
+    pub const CAP_ACL_GET: Self = CapRight(libc::CAP_ACL_GET);
+    /// Set the ACL with `acl_set_fd(3)`.
+    pub const CAP_ACL_SET: Self = CapRight(libc::CAP_ACL_SET);
+    /// Register changes on the kqueue with `kevent(2)`.
+    pub const CAP_KQUEUE_CHANGE: Self = CapRight(libc::CAP_KQUEUE_CHANGE);
+    /// Both [`CAP_KQUEUE_EVENT`](Self::CAP_KQUEUE_EVENT) and
+    /// [`CAP_KQUEUE_CHANGE`](Self::CAP_KQUEUE_CHANGE).
+    pub const CAP_KQUEUE: Self = CapRight(libc::CAP_KQUEUE);
+}
+
+/// A set of rights, see [`cap_rights_limit`].
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct CapRights(libc::cap_rights_t);
+
+impl CapRights {
+    /// Create an empty set of rights.
+    pub fn new() -> Self {
+        let mut rights = MaybeUninit::uninit();
+        // SAFETY: the list of rights is terminated by 0
+        unsafe {
+            libc::__cap_rights_init(
+                libc::CAP_RIGHTS_VERSION,
+                rights.as_mut_ptr(),
+                0u64,
+            );
+            CapRights(rights.assume_init())
+        }
+    }
+
+    /// Add `right` to the set.
+    pub fn set(&mut self, right: CapRight) -> &mut Self {
+        // SAFETY: the list of rights is terminated by 0
+        unsafe {
+            libc::__cap_rights_set(&mut self.0, right.0, 0u64);
+        }
+        self
+    }
+
+    /// Remove `right` from the set.
+    pub fn clear(&mut self, right: CapRight) -> &mut Self {
+        // SAFETY: the list of rights is terminated by 0
+        unsafe {
+            libc::__cap_rights_clear(&mut self.0, right.0, 0u64);
+        }
+        self
+    }
+
+    /// Whether the set contains `right`.
+    pub fn is_set(&self, right: CapRight) -> bool {
+        // SAFETY: the list of rights is terminated by 0
+        unsafe { libc::__cap_rights_is_set(&self.0, right.0, 0u64) }
+    }
+
+    /// Add all of the rights of `other` to the set.
+    pub fn merge(&mut self, other: &CapRights) -> &mut Self {
+        unsafe {
+            libc::cap_rights_merge(&mut self.0, &other.0);
+        }
+        self
+    }
+
+    /// Remove all of the rights of `other` from the set.
+    pub fn remove(&mut self, other: &CapRights) -> &mut Self {
+        unsafe {
+            libc::cap_rights_remove(&mut self.0, &other.0);
+        }
+        self
+    }
+
+    /// Whether the set contains all of the rights of `other`.
+    pub fn contains(&self, other: &CapRights) -> bool {
+        unsafe { libc::cap_rights_contains(&self.0, &other.0) }
+    }
+}
+
+impl Default for CapRights {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CapRights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `cap_rights_t` has no public fields.
+        let words: [u64; 2] = unsafe { std::mem::transmute(self.0) };
+        f.debug_tuple("CapRights").field(&words).finish()
+    }
+}
+
+libc_bitflags! {
+    /// The `fcntl(2)` commands allowed by [`cap_fcntls_limit`].
+    pub struct FcntlRights: u32 {
+        /// `F_GETFL`
+        CAP_FCNTL_GETFL;
+        /// `F_SETFL`
+        CAP_FCNTL_SETFL;
+        /// `F_GETOWN`
+        CAP_FCNTL_GETOWN;
+        /// `F_SETOWN`
+        CAP_FCNTL_SETOWN;
+    }
+}
+
+/// Enter capability mode.
+///
+/// # See Also
+/// [cap_enter(2)](https://man.freebsd.org/cgi/man.cgi?query=cap_enter&sektion=2)
+pub fn cap_enter() -> Result<()> {
+    let res = unsafe { libc::cap_enter() };
+
+    Errno::result(res).map(drop)
+}
+
+/// Whether the calling process is in capability mode.
+///
+/// Fails with `ENOSYS` if the kernel does not support Capsicum.
+pub fn cap_getmode() -> Result<bool> {
+    let mut mode = 0;
+    let res = unsafe { libc::cap_getmode(&mut mode) };
+
+    Errno::result(res).map(|_| mode != 0)
+}
+
+/// Limit the rights of `fd` to `rights`.
+///
+/// Rights can only be removed: fails with `ENOTCAPABLE` if `rights` contains
+/// a right that `fd` does not have.  Using `fd` in a way that requires a
+/// removed right then fails with `ENOTCAPABLE`.
+///
+/// # See Also
+/// [cap_rights_limit(2)](https://man.freebsd.org/cgi/man.cgi?query=cap_rights_limit&sektion=2)
+pub fn cap_rights_limit<Fd: AsFd>(fd: Fd, rights: &CapRights) -> Result<()> {
+    let res =
+        unsafe { libc::cap_rights_limit(fd.as_fd().as_raw_fd(), &rights.0) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the rights of `fd`.
+pub fn cap_rights_get<Fd: AsFd>(fd: Fd) -> Result<CapRights> {
+    let mut rights = MaybeUninit::uninit();
+    let res = unsafe {
+        libc::__cap_rights_get(
+            libc::CAP_RIGHTS_VERSION,
+            fd.as_fd().as_raw_fd(),
+            rights.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+
+    Ok(CapRights(unsafe { rights.assume_init() }))
+}
+
+/// Limit the `ioctl(2)` commands allowed on `fd` to `cmds`.
+///
+/// This only matters if `fd` has the [`CAP_IOCTL`](CapRight::CAP_IOCTL)
+/// right.
+///
+/// # See Also
+/// [cap_ioctls_limit(2)](https://man.freebsd.org/cgi/man.cgi?query=cap_ioctls_limit&sektion=2)
+pub fn cap_ioctls_limit<Fd: AsFd>(fd: Fd, cmds: &[libc::u_long]) -> Result<()> {
+    let res = unsafe {
+        libc::cap_ioctls_limit(
+            fd.as_fd().as_raw_fd(),
+            cmds.as_ptr(),
+            cmds.len(),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the `ioctl(2)` commands allowed on `fd`, or `None` if all of them are.
+pub fn cap_ioctls_get<Fd: AsFd>(fd: Fd) -> Result<Option<Vec<libc::u_long>>> {
+    let fd = fd.as_fd().as_raw_fd();
+    let mut cmds = Vec::new();
+    loop {
+        let res = unsafe {
+            libc::cap_ioctls_get(fd, cmds.as_mut_ptr(), cmds.capacity())
+        };
+        // `CAP_IOCTLS_ALL`
+        if res == isize::MAX {
+            return Ok(None);
+        }
+        let n = Errno::result(res)? as usize;
+        if n <= cmds.capacity() {
+            // SAFETY: the first `n` commands were initialized
+            unsafe { cmds.set_len(n) };
+            return Ok(Some(cmds));
+        }
+        cmds.reserve(n);
+    }
+}
+
+/// Limit the `fcntl(2)` commands allowed on `fd` to `rights`.
+///
+/// This only matters if `fd` has the [`CAP_FCNTL`](CapRight::CAP_FCNTL)
+/// right.
+pub fn cap_fcntls_limit<Fd: AsFd>(fd: Fd, rights: FcntlRights) -> Result<()> {
+    let res = unsafe {
+        libc::cap_fcntls_limit(fd.as_fd().as_raw_fd(), rights.bits())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the `fcntl(2)` commands allowed on `fd`.
+pub fn cap_fcntls_get<Fd: AsFd>(fd: Fd) -> Result<FcntlRights> {
+    let mut rights = 0;
+    let res =
+        unsafe { libc::cap_fcntls_get(fd.as_fd().as_raw_fd(), &mut rights) };
+
+    Errno::result(res).map(|_| FcntlRights::from_bits_retain(rights))
+}
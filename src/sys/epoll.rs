@@ -1,5 +1,6 @@
 use crate::errno::Errno;
 pub use crate::poll_timeout::PollTimeout as EpollTimeout;
+pub use crate::poll_timeout::Timeout;
 use crate::Result;
 use libc::{self, c_int};
 use std::mem;
@@ -68,6 +69,45 @@ impl EpollEvent {
     pub fn data(&self) -> u64 {
         self.event.u64
     }
+
+    /// Create an event whose user data is a typed token, rather than a raw
+    /// `u64`.
+    ///
+    /// The token is converted back with [`EpollEvent::token`].  Using the
+    /// same token type for all the registrations of an [`Epoll`] instance
+    /// avoids casting file descriptors or indices by hand.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nix::sys::epoll::{EpollEvent, EpollFlags};
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// struct Token(u32);
+    ///
+    /// impl From<Token> for u64 {
+    ///     fn from(token: Token) -> u64 {
+    ///         token.0.into()
+    ///     }
+    /// }
+    ///
+    /// impl From<u64> for Token {
+    ///     fn from(data: u64) -> Token {
+    ///         Token(data as u32)
+    ///     }
+    /// }
+    ///
+    /// let event = EpollEvent::with_token(EpollFlags::EPOLLIN, Token(7));
+    /// assert_eq!(event.token::<Token>(), Token(7));
+    /// ```
+    pub fn with_token<T: Into<u64>>(events: EpollFlags, token: T) -> Self {
+        Self::new(events, token.into())
+    }
+
+    /// Get the user data of the event as a typed token, see
+    /// [`EpollEvent::with_token`].
+    pub fn token<T: From<u64>>(&self) -> T {
+        T::from(self.data())
+    }
 }
 
 /// A safe wrapper around [`epoll`](https://man7.org/linux/man-pages/man7/epoll.7.html).
@@ -157,6 +197,86 @@ impl Epoll {
 
         Errno::result(res).map(|r| r as usize)
     }
+    /// Like [`Epoll::wait`], but with a nanosecond precision `timeout`, and
+    /// with the signal mask of the calling thread atomically replaced by
+    /// `sigmask`, if given, for the duration of the wait.
+    ///
+    /// On kernels older than 5.11, where `epoll_pwait2` is unavailable, this
+    /// falls back to `epoll_pwait`, and `timeout` is rounded up to the next
+    /// millisecond.
+    ///
+    /// [`epoll_pwait2`](https://man7.org/linux/man-pages/man2/epoll_pwait2.2.html)
+    #[cfg(feature = "signal")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn wait_with_sigmask<T: Into<Timeout>>(
+        &self,
+        events: &mut [EpollEvent],
+        timeout: T,
+        sigmask: Option<&crate::sys::signal::SigSet>,
+    ) -> Result<usize> {
+        let timeout = timeout.into();
+
+        // The kernel's `sigset_t` is smaller than the C library's one, and
+        // its `timespec` always has a 64-bit `tv_sec`.
+        #[cfg(any(
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6"
+        ))]
+        const SIGSET_SIZE: usize = 128 / 8;
+        #[cfg(not(any(
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6"
+        )))]
+        const SIGSET_SIZE: usize = 64 / 8;
+        #[repr(C)]
+        struct KernelTimespec {
+            tv_sec: i64,
+            tv_nsec: i64,
+        }
+
+        let sigmask = sigmask
+            .map_or(std::ptr::null(), |s| s.as_ref() as *const libc::sigset_t);
+        let kernel_timeout = timeout.as_timespec().map(|t| KernelTimespec {
+            tv_sec: t.tv_sec() as i64,
+            tv_nsec: t.tv_nsec() as i64,
+        });
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_epoll_pwait2,
+                self.0.as_raw_fd(),
+                events.as_mut_ptr(),
+                events.len() as c_int,
+                kernel_timeout
+                    .as_ref()
+                    .map_or(std::ptr::null(), |t| t as *const KernelTimespec),
+                sigmask,
+                SIGSET_SIZE,
+            )
+        };
+        match Errno::result(res) {
+            Err(Errno::ENOSYS) => (),
+            res => return res.map(|r| r as usize),
+        }
+
+        let timeout_ms = c_int::from(EpollTimeout::from(timeout));
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_epoll_pwait,
+                self.0.as_raw_fd(),
+                events.as_mut_ptr(),
+                events.len() as c_int,
+                timeout_ms,
+                sigmask,
+                SIGSET_SIZE,
+            )
+        };
+        Errno::result(res).map(|r| r as usize)
+    }
     /// This system call is used to add, modify, or remove entries in the interest list of the epoll
     /// instance referred to by `self`. It requests that the operation `op` be performed for the
     /// target file descriptor, `fd`.
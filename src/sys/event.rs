@@ -3,6 +3,8 @@
 //! # See Also
 //! [kqueue(2)](https://www.freebsd.org/cgi/man.cgi?query=kqueue)
 
+pub use crate::poll_timeout::Timeout;
+use crate::sys::time::TimeSpec;
 use crate::{Errno, Result};
 #[cfg(not(target_os = "netbsd"))]
 use libc::{c_int, c_long, intptr_t, time_t, timespec, uintptr_t};
@@ -13,6 +15,9 @@ use std::mem;
 use std::os::fd::{AsFd, BorrowedFd};
 use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
 use std::ptr;
+use std::time::Duration;
+#[cfg(any(apple_targets, target_os = "freebsd", target_os = "netbsd"))]
+use std::time::SystemTime;
 
 /// A kernel event queue.  Used to notify a process of various asynchronous
 /// events.
@@ -59,7 +64,8 @@ impl Kqueue {
     /// # Arguments
     /// - `changelist` - Any new kevents to register for notifications.
     /// - `eventlist` - Storage space for the kernel to return notifications.
-    /// - `timeout` - An optional timeout.
+    /// - `timeout` - The maximum time to wait, [`Timeout::Infinite`] to block
+    ///   until an event triggers.
     ///
     /// # Returns
     /// Returns the number of events placed in the `eventlist`.  If an error
@@ -67,12 +73,13 @@ impl Kqueue {
     /// enough room in the `eventlist`, then the event will be placed in the
     /// `eventlist` with `EV_ERROR` set in `flags` and the system error in
     /// `data`.
-    pub fn kevent(
+    pub fn kevent<T: Into<Timeout>>(
         &self,
         changelist: &[KEvent],
         eventlist: &mut [KEvent],
-        timeout_opt: Option<timespec>,
+        timeout: T,
     ) -> Result<usize> {
+        let timeout = timeout.into().as_timespec();
         let res = unsafe {
             libc::kevent(
                 self.0.as_raw_fd(),
@@ -80,21 +87,43 @@ impl Kqueue {
                 changelist.len() as type_of_nchanges,
                 eventlist.as_mut_ptr().cast(),
                 eventlist.len() as type_of_nchanges,
-                if let Some(ref timeout) = timeout_opt {
-                    timeout as *const timespec
-                } else {
-                    ptr::null()
-                },
+                timeout
+                    .as_ref()
+                    .map_or(ptr::null(), |t| t.as_ref() as *const timespec),
             )
         };
         Errno::result(res).map(|r| r as usize)
     }
+
+    /// Trigger the user event `ident`, registered with [`KEvent::user`],
+    /// ORing `data` into its filter flags.
+    ///
+    /// This allows another thread to wake up a thread waiting in
+    /// [`Kqueue::kevent`], without using a pipe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nix::sys::event::{EventFlag, KEvent, Kqueue, Timeout};
+    /// let kq = Kqueue::new().unwrap();
+    /// kq.kevent(&[KEvent::user(1, EventFlag::EV_CLEAR, 0)], &mut [], Timeout::ZeroPoll)
+    ///     .unwrap();
+    ///
+    /// kq.trigger_user(1, 0x42).unwrap();
+    ///
+    /// let mut events = [KEvent::user(0, EventFlag::empty(), 0)];
+    /// assert_eq!(kq.kevent(&[], &mut events, Timeout::Infinite), Ok(1));
+    /// assert_eq!(events[0].ident(), 1);
+    /// assert_eq!(events[0].user_fflags(), 0x42);
+    /// ```
+    #[cfg(any(freebsdlike, apple_targets))]
+    pub fn trigger_user(&self, ident: uintptr_t, data: u32) -> Result<()> {
+        let change = KEvent::user_trigger(ident, UserFflagsOp::Or(data));
+        self.kevent(&[change], &mut [], Timeout::ZeroPoll).map(drop)
+    }
 }
 
-#[cfg(any(freebsdlike, apple_targets, target_os = "openbsd"))]
 type type_of_udata = *mut libc::c_void;
-#[cfg(target_os = "netbsd")]
-type type_of_udata = intptr_t;
 
 #[cfg(target_os = "netbsd")]
 type type_of_event_filter = u32;
@@ -225,10 +254,19 @@ libc_bitflags!(
         #[cfg(apple_targets)]
         #[allow(missing_docs)]
         NOTE_ABSOLUTE;
+        #[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+        #[allow(missing_docs)]
+        NOTE_ABSTIME;
         #[allow(missing_docs)]
         NOTE_ATTRIB;
+        #[cfg(apple_targets)]
+        #[allow(missing_docs)]
+        NOTE_BACKGROUND;
         #[allow(missing_docs)]
         NOTE_CHILD;
+        #[cfg(apple_targets)]
+        #[allow(missing_docs)]
+        NOTE_CRITICAL;
         #[allow(missing_docs)]
         NOTE_DELETE;
         #[cfg(target_os = "openbsd")]
@@ -267,6 +305,9 @@ libc_bitflags!(
         NOTE_LINK;
         #[allow(missing_docs)]
         NOTE_LOWAT;
+        #[cfg(apple_targets)]
+        #[allow(missing_docs)]
+        NOTE_MACHTIME;
         #[cfg(target_os = "freebsd")]
         #[allow(missing_docs)]
         NOTE_MSECONDS;
@@ -275,12 +316,14 @@ libc_bitflags!(
         NOTE_NONE;
         #[cfg(any(
             apple_targets,
-            target_os = "freebsd"))]
+            target_os = "freebsd",
+            target_os = "netbsd"))]
         #[allow(missing_docs)]
         NOTE_NSECONDS;
         #[cfg(target_os = "dragonfly")]
         #[allow(missing_docs)]
         NOTE_OOB;
+        #[cfg(not(target_os = "freebsd"))]
         #[allow(missing_docs)]
         NOTE_PCTRLMASK;
         #[allow(missing_docs)]
@@ -291,7 +334,8 @@ libc_bitflags!(
         NOTE_REVOKE;
         #[cfg(any(
             apple_targets,
-            target_os = "freebsd"))]
+            target_os = "freebsd",
+            target_os = "netbsd"))]
         #[allow(missing_docs)]
         NOTE_SECONDS;
         #[cfg(apple_targets)]
@@ -309,7 +353,8 @@ libc_bitflags!(
         NOTE_TRUNCATE;
         #[cfg(any(
             apple_targets,
-            target_os = "freebsd"))]
+            target_os = "freebsd",
+            target_os = "netbsd"))]
         #[allow(missing_docs)]
         NOTE_USECONDS;
         #[cfg(apple_targets)]
@@ -400,6 +445,198 @@ impl KEvent {
     pub fn udata(&self) -> intptr_t {
         self.kevent.udata as intptr_t
     }
+
+    /// Set the user-defined value to a pointer, to be retrieved with
+    /// [`udata_ptr`](Self::udata_ptr) from the events returned by the
+    /// kernel.
+    ///
+    /// The pointer is passed through the kernel unchanged, so it is up to the
+    /// caller to keep the pointee alive while the event is registered.
+    pub fn with_udata_ptr<T>(mut self, ptr: *mut T) -> Self {
+        self.kevent.udata = ptr as type_of_udata;
+        self
+    }
+
+    /// The user-defined value, as a pointer set with
+    /// [`with_udata_ptr`](Self::with_udata_ptr).
+    pub fn udata_ptr<T>(&self) -> *mut T {
+        self.kevent.udata as *mut T
+    }
+}
+
+/// Convert a timer duration to the `data` of an `EVFILT_TIMER` event, in the
+/// finest unit that fits, rounding up.
+#[cfg(any(apple_targets, target_os = "freebsd", target_os = "netbsd"))]
+fn timer_data(duration: Duration) -> Result<(FilterFlag, intptr_t)> {
+    let nanos = duration.as_nanos();
+    let units = [
+        (FilterFlag::NOTE_NSECONDS, 1),
+        (FilterFlag::NOTE_USECONDS, 1_000),
+        (FilterFlag::NOTE_SECONDS, 1_000_000_000),
+    ];
+    units
+        .into_iter()
+        .find_map(|(unit, scale)| {
+            let value = (nanos + scale - 1) / scale;
+            intptr_t::try_from(value).ok().map(|value| (unit, value))
+        })
+        .ok_or(Errno::EINVAL)
+}
+
+/// Convert a timer duration to the `data` of an `EVFILT_TIMER` event, in
+/// milliseconds, rounding up.
+#[cfg(any(target_os = "dragonfly", target_os = "openbsd"))]
+fn timer_data(duration: Duration) -> Result<(FilterFlag, intptr_t)> {
+    let millis = (duration.as_nanos() + 999_999) / 1_000_000;
+    let millis = intptr_t::try_from(millis).map_err(|_| Errno::EINVAL)?;
+    Ok((FilterFlag::empty(), millis))
+}
+
+impl KEvent {
+    /// Construct a `KEvent` registering the timer `ident`, that expires every
+    /// `period`, or once with `EV_ONESHOT`.
+    ///
+    /// `flags` are added to `EV_ADD`, and `fflags` may contain the
+    /// platform-specific timer flags, like `NOTE_CRITICAL` or
+    /// `NOTE_BACKGROUND` on Apple platforms, and the unit flags are chosen
+    /// from `period`.  The `data` of the returned events is the number of
+    /// expirations since the last time the event was received.
+    ///
+    /// Fails with `EINVAL` if `period` can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use nix::sys::event::{EventFlag, FilterFlag, KEvent, Kqueue, Timeout};
+    /// let kq = Kqueue::new().unwrap();
+    /// let timer = KEvent::timer(
+    ///     1,
+    ///     Duration::from_millis(10),
+    ///     EventFlag::EV_ONESHOT,
+    ///     FilterFlag::empty(),
+    ///     0,
+    /// )
+    /// .unwrap();
+    /// let mut events = [timer];
+    /// assert_eq!(kq.kevent(&[timer], &mut events, Timeout::Infinite), Ok(1));
+    /// assert_eq!(events[0].data(), 1);
+    /// ```
+    pub fn timer(
+        ident: uintptr_t,
+        period: Duration,
+        flags: EventFlag,
+        fflags: FilterFlag,
+        udata: intptr_t,
+    ) -> Result<KEvent> {
+        let (unit, data) = timer_data(period)?;
+        Ok(KEvent::new(
+            ident,
+            EventFilter::EVFILT_TIMER,
+            EventFlag::EV_ADD | flags,
+            fflags | unit,
+            data,
+            udata,
+        ))
+    }
+
+    /// Construct a `KEvent` registering the one-shot timer `ident`, that
+    /// expires at the wall clock time `deadline` (`NOTE_ABSTIME`, or
+    /// `NOTE_ABSOLUTE` on Apple platforms).
+    ///
+    /// See [`KEvent::timer`] for the other arguments.  Fails with `EINVAL`
+    /// if `deadline` is before the Unix epoch or can't be represented.
+    #[cfg(any(apple_targets, target_os = "freebsd", target_os = "netbsd"))]
+    pub fn timer_at(
+        ident: uintptr_t,
+        deadline: SystemTime,
+        flags: EventFlag,
+        fflags: FilterFlag,
+        udata: intptr_t,
+    ) -> Result<KEvent> {
+        #[cfg(apple_targets)]
+        let absolute = FilterFlag::NOTE_ABSOLUTE;
+        #[cfg(not(apple_targets))]
+        let absolute = FilterFlag::NOTE_ABSTIME;
+
+        let since_epoch = deadline
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| Errno::EINVAL)?;
+        KEvent::timer(
+            ident,
+            since_epoch,
+            EventFlag::EV_ONESHOT | flags,
+            absolute | fflags,
+            udata,
+        )
+    }
+}
+
+/// How the trigger of a user event changes its filter flags.
+///
+/// The low 24 bits of the filter flags of an `EVFILT_USER` event, see
+/// [`KEvent::user_fflags`], can be used to pass a value to the waiter.
+#[cfg(any(freebsdlike, apple_targets))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UserFflagsOp {
+    /// Leave the flags unchanged (`NOTE_FFNOP`).
+    Nop,
+    /// AND the flags with the value (`NOTE_FFAND`).
+    And(u32),
+    /// OR the flags with the value (`NOTE_FFOR`).
+    Or(u32),
+    /// Replace the flags with the value (`NOTE_FFCOPY`).
+    Copy(u32),
+}
+
+#[cfg(any(freebsdlike, apple_targets))]
+impl UserFflagsOp {
+    fn bits(self) -> u32 {
+        let (ctrl, value) = match self {
+            UserFflagsOp::Nop => (libc::NOTE_FFNOP, 0),
+            UserFflagsOp::And(value) => (libc::NOTE_FFAND, value),
+            UserFflagsOp::Or(value) => (libc::NOTE_FFOR, value),
+            UserFflagsOp::Copy(value) => (libc::NOTE_FFCOPY, value),
+        };
+        ctrl | (value & libc::NOTE_FFLAGSMASK)
+    }
+}
+
+#[cfg(any(freebsdlike, apple_targets))]
+impl KEvent {
+    /// Construct a `KEvent` registering the user event `ident`, that is only
+    /// triggered with [`KEvent::user_trigger`].
+    ///
+    /// `flags` are added to `EV_ADD`, `EV_CLEAR` is usually wanted, so that
+    /// the event is reset once received.
+    pub fn user(ident: uintptr_t, flags: EventFlag, udata: intptr_t) -> KEvent {
+        KEvent::new(
+            ident,
+            EventFilter::EVFILT_USER,
+            EventFlag::EV_ADD | flags,
+            FilterFlag::empty(),
+            0,
+            udata,
+        )
+    }
+
+    /// Construct a `KEvent` triggering the user event `ident`, and updating
+    /// its filter flags with `op`.
+    pub fn user_trigger(ident: uintptr_t, op: UserFflagsOp) -> KEvent {
+        KEvent::new(
+            ident,
+            EventFilter::EVFILT_USER,
+            EventFlag::empty(),
+            FilterFlag::from_bits_retain(libc::NOTE_TRIGGER | op.bits()),
+            0,
+            0,
+        )
+    }
+
+    /// The value carried in the filter flags of a user event.
+    pub fn user_fflags(&self) -> u32 {
+        self.kevent.fflags & libc::NOTE_FFLAGSMASK
+    }
 }
 
 #[allow(missing_docs)]
@@ -416,7 +653,7 @@ pub fn kevent(
         tv_nsec: ((timeout_ms % 1000) * 1_000_000) as c_long,
     };
 
-    kq.kevent(changelist, eventlist, Some(timeout))
+    kq.kevent(changelist, eventlist, Some(TimeSpec::from(timeout)))
 }
 
 #[cfg(any(apple_targets, freebsdlike, target_os = "openbsd"))]
@@ -432,7 +669,7 @@ pub fn kevent_ts(
     eventlist: &mut [KEvent],
     timeout_opt: Option<timespec>,
 ) -> Result<usize> {
-    kq.kevent(changelist, eventlist, timeout_opt)
+    kq.kevent(changelist, eventlist, timeout_opt.map(TimeSpec::from))
 }
 
 /// Modify an existing [`KEvent`].
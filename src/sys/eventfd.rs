@@ -90,6 +90,71 @@ impl EventFd {
         unistd::read(&self.0, &mut arr)?;
         Ok(u64::from_ne_bytes(arr))
     }
+
+    /// Adds `value` to the counter, like [`EventFd::write`], but fails with
+    /// `EIO` if the counter was not written in full.
+    ///
+    /// Blocks if the counter would overflow, or fails with `EAGAIN` if the
+    /// file descriptor is nonblocking.  `u64::MAX` is rejected with `EINVAL`.
+    pub fn write_value(&self, value: u64) -> Result<()> {
+        match self.write(value)? {
+            8 => Ok(()),
+            _ => Err(Errno::EIO),
+        }
+    }
+
+    /// Reads the counter, like [`EventFd::read`], but fails with `EIO` if it
+    /// was not read in full.
+    pub fn read_value(&self) -> Result<u64> {
+        let mut arr = [0; std::mem::size_of::<u64>()];
+        match unistd::read(&self.0, &mut arr)? {
+            8 => Ok(u64::from_ne_bytes(arr)),
+            _ => Err(Errno::EIO),
+        }
+    }
+
+    /// Reads the counter like [`EventFd::read_value`], but returns `None`
+    /// instead of blocking if it is zero, even if the file descriptor is
+    /// blocking.
+    ///
+    /// For a blocking file descriptor, this checks for readiness before
+    /// reading, so it may still block if another thread or process reads the
+    /// counter in between.
+    pub fn try_read(&self) -> Result<Option<u64>> {
+        let mut pfd = libc::pollfd {
+            fd: self.0.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if Errno::result(unsafe { libc::poll(&mut pfd, 1, 0) })? == 0 {
+            return Ok(None);
+        }
+        match self.read_value() {
+            Ok(value) => Ok(Some(value)),
+            Err(Errno::EAGAIN) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decrements the counter of a semaphore-like eventfd, created with
+    /// [`EFD_SEMAPHORE`](EfdFlags::EFD_SEMAPHORE), blocking while it is zero.
+    ///
+    /// Without `EFD_SEMAPHORE`, this resets the counter to zero instead.
+    pub fn acquire(&self) -> Result<()> {
+        self.read_value().map(drop)
+    }
+
+    /// Like [`EventFd::acquire`], but returns `false` instead of blocking if
+    /// the counter is zero, see [`EventFd::try_read`].
+    pub fn try_acquire(&self) -> Result<bool> {
+        self.try_read().map(|value| value.is_some())
+    }
+
+    /// Increments the counter of a semaphore-like eventfd, waking up one
+    /// waiter of [`EventFd::acquire`].
+    pub fn post(&self) -> Result<()> {
+        self.write_value(1)
+    }
 }
 impl AsFd for EventFd {
     fn as_fd(&self) -> BorrowedFd {
@@ -0,0 +1,408 @@
+//! Extended attributes on FreeBSD and NetBSD, name-value pairs associated
+//! with files.
+//!
+//! Unlike the `xattr` functions of Linux, the namespace of an attribute is
+//! not part of its name, but a separate [`ExtattrNamespace`] argument.
+//!
+//! Each operation comes in three variants: one suffixed by `_file` taking a
+//! path and following symbolic links, one suffixed by `_link` taking a path
+//! and operating on the symbolic link itself, and one suffixed by `_fd`
+//! taking a file descriptor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::extattr::*;
+//! let ns = ExtattrNamespace::EXTATTR_NAMESPACE_USER;
+//! extattr_set_file("file", ns, "checksum", b"1234").unwrap();
+//! assert_eq!(extattr_get_file("file", ns, "checksum").unwrap(), b"1234");
+//! for name in extattr_list_file("file", ns).unwrap().iter() {
+//!     println!("{:?}", name);
+//! }
+//! ```
+//!
+//! # See Also
+//! * [`extattr(2)`](https://man.freebsd.org/cgi/man.cgi?query=extattr&sektion=2)
+
+use std::ffi::{CStr, OsStr};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::ptr;
+
+use libc::{c_int, c_void, size_t, ssize_t};
+
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+
+libc_enum! {
+    /// The namespace of an extended attribute.
+    #[repr(i32)]
+    #[non_exhaustive]
+    pub enum ExtattrNamespace {
+        /// For arbitrary attributes, subject to the permissions of the file.
+        EXTATTR_NAMESPACE_USER,
+        /// For attributes of the system, only accessible with privileges.
+        EXTATTR_NAMESPACE_SYSTEM,
+    }
+}
+
+/// The list of the names of the extended attributes of a file in one
+/// namespace, as returned by [`extattr_list_file`] and its variants.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExtattrList {
+    buf: Vec<u8>,
+}
+
+impl ExtattrList {
+    /// Iterate over the names of the attributes.
+    pub fn iter(&self) -> ExtattrListIter<'_> {
+        ExtattrListIter { rest: &self.buf }
+    }
+
+    /// Does the file have no extended attributes in the namespace?
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a ExtattrList {
+    type Item = &'a OsStr;
+    type IntoIter = ExtattrListIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the names of an [`ExtattrList`].
+#[derive(Clone, Debug)]
+pub struct ExtattrListIter<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for ExtattrListIter<'a> {
+    type Item = &'a OsStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Each name is preceded by a byte holding its length, and is not
+        // NUL-terminated.
+        let (&len, rest) = self.rest.split_first()?;
+        let len = (len as usize).min(rest.len());
+        let (name, rest) = rest.split_at(len);
+        self.rest = rest;
+        Some(OsStr::from_bytes(name))
+    }
+}
+
+/// The file whose attributes are operated on.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    File(&'a CStr),
+    Link(&'a CStr),
+    Fd(BorrowedFd<'a>),
+}
+
+impl Target<'_> {
+    unsafe fn get(
+        self,
+        ns: c_int,
+        name: &CStr,
+        data: *mut c_void,
+        size: size_t,
+    ) -> ssize_t {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::File(p) => {
+                    libc::extattr_get_file(p.as_ptr(), ns, name, data, size)
+                }
+                Target::Link(p) => {
+                    libc::extattr_get_link(p.as_ptr(), ns, name, data, size)
+                }
+                Target::Fd(fd) => {
+                    libc::extattr_get_fd(fd.as_raw_fd(), ns, name, data, size)
+                }
+            }
+        }
+    }
+
+    unsafe fn set(self, ns: c_int, name: &CStr, data: &[u8]) -> Result<()> {
+        let name = name.as_ptr();
+        let (d, len) = (data.as_ptr().cast(), data.len());
+        // The return types differ between platforms and functions.
+        unsafe {
+            match self {
+                Target::File(p) => Errno::result(libc::extattr_set_file(
+                    p.as_ptr(),
+                    ns,
+                    name,
+                    d,
+                    len,
+                ))
+                .map(drop),
+                Target::Link(p) => Errno::result(libc::extattr_set_link(
+                    p.as_ptr(),
+                    ns,
+                    name,
+                    d,
+                    len,
+                ))
+                .map(drop),
+                Target::Fd(fd) => Errno::result(libc::extattr_set_fd(
+                    fd.as_raw_fd(),
+                    ns,
+                    name,
+                    d,
+                    len,
+                ))
+                .map(drop),
+            }
+        }
+    }
+
+    unsafe fn list(
+        self,
+        ns: c_int,
+        data: *mut c_void,
+        size: size_t,
+    ) -> ssize_t {
+        unsafe {
+            match self {
+                Target::File(p) => {
+                    libc::extattr_list_file(p.as_ptr(), ns, data, size)
+                }
+                Target::Link(p) => {
+                    libc::extattr_list_link(p.as_ptr(), ns, data, size)
+                }
+                Target::Fd(fd) => {
+                    libc::extattr_list_fd(fd.as_raw_fd(), ns, data, size)
+                }
+            }
+        }
+    }
+
+    unsafe fn delete(self, ns: c_int, name: &CStr) -> c_int {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::File(p) => {
+                    libc::extattr_delete_file(p.as_ptr(), ns, name)
+                }
+                Target::Link(p) => {
+                    libc::extattr_delete_link(p.as_ptr(), ns, name)
+                }
+                Target::Fd(fd) => {
+                    libc::extattr_delete_fd(fd.as_raw_fd(), ns, name)
+                }
+            }
+        }
+    }
+}
+
+/// Call `f` with a buffer large enough for the data it returns, retrying if
+/// the data grows in the meantime.
+///
+/// The `extattr` functions silently truncate the data to the size of the
+/// buffer, so one extra byte is allocated to detect growth.
+fn read_data<F>(f: F) -> Result<Vec<u8>>
+where
+    F: Fn(*mut c_void, size_t) -> ssize_t,
+{
+    loop {
+        let size = Errno::result(f(ptr::null_mut(), 0))? as usize;
+        let mut buf = Vec::<u8>::with_capacity(size + 1);
+        let len =
+            Errno::result(f(buf.as_mut_ptr().cast(), buf.capacity()))? as usize;
+        if len < buf.capacity() {
+            unsafe { buf.set_len(len) };
+            return Ok(buf);
+        }
+    }
+}
+
+fn get<N: ?Sized + NixPath>(
+    target: Target<'_>,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<Vec<u8>> {
+    name.with_nix_path(|name| {
+        read_data(|data, size| unsafe {
+            target.get(ns as c_int, name, data, size)
+        })
+    })?
+}
+
+fn set<N: ?Sized + NixPath>(
+    target: Target<'_>,
+    ns: ExtattrNamespace,
+    name: &N,
+    data: &[u8],
+) -> Result<()> {
+    name.with_nix_path(|name| unsafe { target.set(ns as c_int, name, data) })?
+}
+
+fn list(target: Target<'_>, ns: ExtattrNamespace) -> Result<ExtattrList> {
+    read_data(|data, size| unsafe { target.list(ns as c_int, data, size) })
+        .map(|buf| ExtattrList { buf })
+}
+
+fn delete<N: ?Sized + NixPath>(
+    target: Target<'_>,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<()> {
+    let res =
+        name.with_nix_path(|name| unsafe { target.delete(ns as c_int, name) })?;
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the value of the extended attribute `name` in the namespace `ns` of
+/// the file at `path`, following symbolic links.
+pub fn extattr_get_file<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<Vec<u8>>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| get(Target::File(path), ns, name))?
+}
+
+/// Get the value of the extended attribute `name` in the namespace `ns` of
+/// the file at `path`, without following symbolic links.
+pub fn extattr_get_link<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<Vec<u8>>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| get(Target::Link(path), ns, name))?
+}
+
+/// Get the value of the extended attribute `name` in the namespace `ns` of
+/// the open file `fd`.
+pub fn extattr_get_fd<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<Vec<u8>> {
+    get(Target::Fd(fd.as_fd()), ns, name)
+}
+
+/// Set the value of the extended attribute `name` in the namespace `ns` of
+/// the file at `path`, following symbolic links.
+///
+/// The attribute is created if it doesn't exist yet.
+pub fn extattr_set_file<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+    value: &[u8],
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| set(Target::File(path), ns, name, value))?
+}
+
+/// Set the value of the extended attribute `name` in the namespace `ns` of
+/// the file at `path`, without following symbolic links.
+///
+/// The attribute is created if it doesn't exist yet.
+pub fn extattr_set_link<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+    value: &[u8],
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| set(Target::Link(path), ns, name, value))?
+}
+
+/// Set the value of the extended attribute `name` in the namespace `ns` of
+/// the open file `fd`.
+///
+/// The attribute is created if it doesn't exist yet.
+pub fn extattr_set_fd<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    ns: ExtattrNamespace,
+    name: &N,
+    value: &[u8],
+) -> Result<()> {
+    set(Target::Fd(fd.as_fd()), ns, name, value)
+}
+
+/// List the names of the extended attributes in the namespace `ns` of the
+/// file at `path`, following symbolic links.
+pub fn extattr_list_file<P: ?Sized + NixPath>(
+    path: &P,
+    ns: ExtattrNamespace,
+) -> Result<ExtattrList> {
+    path.with_nix_path(|path| list(Target::File(path), ns))?
+}
+
+/// List the names of the extended attributes in the namespace `ns` of the
+/// file at `path`, without following symbolic links.
+pub fn extattr_list_link<P: ?Sized + NixPath>(
+    path: &P,
+    ns: ExtattrNamespace,
+) -> Result<ExtattrList> {
+    path.with_nix_path(|path| list(Target::Link(path), ns))?
+}
+
+/// List the names of the extended attributes in the namespace `ns` of the
+/// open file `fd`.
+pub fn extattr_list_fd<Fd: AsFd>(
+    fd: Fd,
+    ns: ExtattrNamespace,
+) -> Result<ExtattrList> {
+    list(Target::Fd(fd.as_fd()), ns)
+}
+
+/// Delete the extended attribute `name` in the namespace `ns` of the file at
+/// `path`, following symbolic links.
+pub fn extattr_delete_file<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| delete(Target::File(path), ns, name))?
+}
+
+/// Delete the extended attribute `name` in the namespace `ns` of the file at
+/// `path`, without following symbolic links.
+pub fn extattr_delete_link<P, N>(
+    path: &P,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| delete(Target::Link(path), ns, name))?
+}
+
+/// Delete the extended attribute `name` in the namespace `ns` of the open
+/// file `fd`.
+pub fn extattr_delete_fd<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    ns: ExtattrNamespace,
+    name: &N,
+) -> Result<()> {
+    delete(Target::Fd(fd.as_fd()), ns, name)
+}
@@ -14,8 +14,10 @@ use crate::errno::Errno;
 use crate::fcntl::OFlag;
 use crate::unistd::{close, read, write};
 use crate::{NixPath, Result};
+use std::ffi::{OsStr, OsString};
 use std::marker::PhantomData;
 use std::mem::{size_of, MaybeUninit};
+use std::os::unix::ffi::OsStrExt;
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 use std::ptr;
 
@@ -113,11 +115,33 @@ libc_bitflags! {
         /// See `fanotify(7)` for details about this limit. Use of this flag
         /// requires the `CAP_SYS_ADMIN` capability.
         FAN_UNLIMITED_MARKS;
+        /// Allow responses to permission events to request auditing with
+        /// `FAN_AUDIT`. Requires the `CAP_AUDIT_WRITE` capability. Since
+        /// Linux 4.15.
+        FAN_ENABLE_AUDIT;
 
         /// Make `FanotifyEvent::pid` return pidfd. Since Linux 5.15.
         FAN_REPORT_PIDFD;
         /// Make `FanotifyEvent::pid` return thread id. Since Linux 4.20.
         FAN_REPORT_TID;
+        /// Identify the object of events by file handle, see
+        /// [`FanotifyEvent::fid_records`], instead of by file descriptor.
+        /// Since Linux 5.1.
+        FAN_REPORT_FID;
+        /// Identify the directory of dirent events by file handle. Since
+        /// Linux 5.9.
+        FAN_REPORT_DIR_FID;
+        /// Report the name of the entry of dirent events, along with the
+        /// directory. Requires `FAN_REPORT_DIR_FID`. Since Linux 5.9.
+        FAN_REPORT_NAME;
+        /// Also identify the child of dirent events by file handle. Since
+        /// Linux 5.17.
+        FAN_REPORT_TARGET_FID;
+        /// Combination of `FAN_REPORT_DIR_FID` and `FAN_REPORT_NAME`.
+        FAN_REPORT_DFID_NAME;
+        /// Combination of `FAN_REPORT_DFID_NAME`, `FAN_REPORT_FID` and
+        /// `FAN_REPORT_TARGET_FID`.
+        FAN_REPORT_DFID_NAME_TARGET;
     }
 }
 
@@ -185,9 +209,13 @@ libc_bitflags! {
 
         /// Default flag.
         FAN_MARK_INODE;
-        /// Mark the mount specified by pathname.
+        /// Mark the mount specified by pathname, so that events for all of
+        /// the objects of the mount are reported. Requires the
+        /// `CAP_SYS_ADMIN` capability.
         FAN_MARK_MOUNT;
-        /// Mark the filesystem specified by pathname. Since Linux 4.20.
+        /// Mark the filesystem specified by pathname, so that events for all
+        /// of the objects of the filesystem are reported, regardless of the
+        /// mount. Requires the `CAP_SYS_ADMIN` capability. Since Linux 4.20.
         FAN_MARK_FILESYSTEM;
 
         /// Combination of `FAN_MARK_IGNORE` and `FAN_MARK_IGNORED_SURV_MODIFY`.
@@ -198,13 +226,92 @@ libc_bitflags! {
 /// Compile version number of fanotify API.
 pub const FANOTIFY_METADATA_VERSION: u8 = libc::FANOTIFY_METADATA_VERSION;
 
+/// The kind of object identified by a [`FanotifyFidRecord`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum FidInfoType {
+    /// The object of the event (`FAN_EVENT_INFO_TYPE_FID`).
+    Fid,
+    /// The directory of a dirent event (`FAN_EVENT_INFO_TYPE_DFID`).
+    Dfid,
+    /// The directory and the name of the entry of a dirent event
+    /// (`FAN_EVENT_INFO_TYPE_DFID_NAME`).
+    DfidName,
+    /// The old directory and name of a `FAN_RENAME` event
+    /// (`FAN_EVENT_INFO_TYPE_OLD_DFID_NAME`).
+    OldDfidName,
+    /// The new directory and name of a `FAN_RENAME` event
+    /// (`FAN_EVENT_INFO_TYPE_NEW_DFID_NAME`).
+    NewDfidName,
+}
+
+impl FidInfoType {
+    fn from_raw(info_type: u8) -> Option<Self> {
+        match info_type {
+            libc::FAN_EVENT_INFO_TYPE_FID => Some(Self::Fid),
+            libc::FAN_EVENT_INFO_TYPE_DFID => Some(Self::Dfid),
+            libc::FAN_EVENT_INFO_TYPE_DFID_NAME => Some(Self::DfidName),
+            libc::FAN_EVENT_INFO_TYPE_OLD_DFID_NAME => Some(Self::OldDfidName),
+            libc::FAN_EVENT_INFO_TYPE_NEW_DFID_NAME => Some(Self::NewDfidName),
+            _ => None,
+        }
+    }
+
+    fn has_name(self) -> bool {
+        matches!(self, Self::DfidName | Self::OldDfidName | Self::NewDfidName)
+    }
+}
+
+/// An object identified by file handle, as reported in groups initialized
+/// with `FAN_REPORT_FID` or `FAN_REPORT_DIR_FID`.
+///
+/// The file handle can be opened with `open_by_handle_at(2)`, on a file
+/// descriptor of the filesystem identified by [`fsid`](Self::fsid).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FanotifyFidRecord {
+    info_type: FidInfoType,
+    fsid: [libc::c_int; 2],
+    handle_type: libc::c_int,
+    handle: Vec<u8>,
+    name: Option<OsString>,
+}
+
+impl FanotifyFidRecord {
+    /// The kind of object identified.
+    pub fn info_type(&self) -> FidInfoType {
+        self.info_type
+    }
+
+    /// The identifier of the filesystem of the object, as in the `f_fsid`
+    /// field of `statfs(2)`.
+    pub fn fsid(&self) -> [libc::c_int; 2] {
+        self.fsid
+    }
+
+    /// The type of the file handle.
+    pub fn handle_type(&self) -> libc::c_int {
+        self.handle_type
+    }
+
+    /// The opaque bytes of the file handle.
+    pub fn handle(&self) -> &[u8] {
+        &self.handle
+    }
+
+    /// The name of the directory entry, for the `*DfidName` types.
+    pub fn name(&self) -> Option<&OsStr> {
+        self.name.as_deref()
+    }
+}
+
 /// Abstract over `libc::fanotify_event_metadata`, which represents an event
 /// received via `Fanotify::read_events`.
 // Is not Clone due to fd field, to avoid use-after-close scenarios.
 #[derive(Debug, Eq, Hash, PartialEq)]
-#[repr(transparent)]
-#[allow(missing_copy_implementations)]
-pub struct FanotifyEvent(libc::fanotify_event_metadata);
+pub struct FanotifyEvent {
+    metadata: libc::fanotify_event_metadata,
+    fid_records: Vec<FanotifyFidRecord>,
+}
 
 impl FanotifyEvent {
     /// Version number for the structure. It must be compared to
@@ -212,7 +319,7 @@ impl FanotifyEvent {
     /// version does match. It can be done with the
     /// `FanotifyEvent::check_version` method.
     pub fn version(&self) -> u8 {
-        self.0.vers
+        self.metadata.vers
     }
 
     /// Checks that compile fanotify API version is equal to the version of the
@@ -223,40 +330,47 @@ impl FanotifyEvent {
 
     /// Mask flags of the events.
     pub fn mask(&self) -> MaskFlags {
-        MaskFlags::from_bits_truncate(self.0.mask)
+        MaskFlags::from_bits_truncate(self.metadata.mask)
     }
 
     /// The file descriptor of the event. If the value is `None` when reading
     /// from the fanotify group, this event is to notify that a group queue
     /// overflow occured.
     pub fn fd(&self) -> Option<BorrowedFd> {
-        if self.0.fd == libc::FAN_NOFD {
+        if self.metadata.fd == libc::FAN_NOFD {
             None
         } else {
-            // SAFETY: self.0.fd will be opened for the lifetime of `Self`,
+            // SAFETY: self.metadata.fd will be opened for the lifetime of `Self`,
             // which is longer than the lifetime of the returned BorrowedFd, so
             // it is safe.
-            Some(unsafe { BorrowedFd::borrow_raw(self.0.fd) })
+            Some(unsafe { BorrowedFd::borrow_raw(self.metadata.fd) })
         }
     }
 
     /// PID of the process that caused the event. TID in case flag
     /// `FAN_REPORT_TID` was set at group initialization.
     pub fn pid(&self) -> i32 {
-        self.0.pid
+        self.metadata.pid
+    }
+
+    /// The objects identified by file handle, in groups initialized with
+    /// `FAN_REPORT_FID` or `FAN_REPORT_DIR_FID`.  Such groups report
+    /// events without a file descriptor.
+    pub fn fid_records(&self) -> &[FanotifyFidRecord] {
+        &self.fid_records
     }
 }
 
 impl Drop for FanotifyEvent {
     fn drop(&mut self) {
-        if self.0.fd == libc::FAN_NOFD {
+        if self.metadata.fd == libc::FAN_NOFD {
             return;
         }
         // SAFETY:
         //
         // If this fd is not `FAN_NOFD`, then it should be a valid, owned file
         // descriptor, which means we can safely close it.
-        let e = unsafe { close(self.0.fd) };
+        let e = unsafe { close(self.metadata.fd) };
         if !std::thread::panicking() && e == Err(Errno::EBADF) {
             panic!("Closing an invalid file descriptor!");
         };
@@ -292,6 +406,9 @@ libc_bitflags! {
         FAN_ALLOW;
         /// Deny the event.
         FAN_DENY;
+        /// Audit the decision. Requires `FAN_ENABLE_AUDIT` at group
+        /// initialization. Since Linux 4.15.
+        FAN_AUDIT;
     }
 }
 
@@ -379,13 +496,46 @@ impl Fanotify {
                 metadata.assume_init()
             };
 
-            events.push(FanotifyEvent(metadata));
+            let start = offset + metadata.metadata_len as usize;
+            let end = (offset + metadata.event_len as usize).min(nread);
+            let fid_records = parse_fid_records(&buffer[start..end]);
+
+            events.push(FanotifyEvent {
+                metadata,
+                fid_records,
+            });
             offset += metadata.event_len as usize;
         }
 
         Ok(events)
     }
 
+    /// Allow or deny the permission event `event`, which must have been
+    /// read from this group.
+    ///
+    /// This is a shorthand for [`write_response`](Self::write_response), that
+    /// fails with `EINVAL` for events without a file descriptor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use nix::fcntl::AT_FDCWD;
+    /// # use nix::sys::fanotify::{EventFFlags, Fanotify, InitFlags, MarkFlags, MaskFlags, Response};
+    /// let group = Fanotify::init(InitFlags::FAN_CLASS_CONTENT, EventFFlags::O_RDONLY).unwrap();
+    /// group.mark(MarkFlags::FAN_MARK_ADD, MaskFlags::FAN_OPEN_PERM, AT_FDCWD, Some("/etc")).unwrap();
+    /// for event in group.read_events().unwrap() {
+    ///     group.respond(&event, Response::FAN_ALLOW).unwrap();
+    /// }
+    /// ```
+    pub fn respond(
+        &self,
+        event: &FanotifyEvent,
+        response: Response,
+    ) -> Result<()> {
+        let fd = event.fd().ok_or(Errno::EINVAL)?;
+        self.write_response(FanotifyResponse::new(fd, response))
+    }
+
     /// Write an event response on the fanotify group.
     ///
     /// Returns a Result containing either `()` on success or errno otherwise.
@@ -409,6 +559,64 @@ impl Fanotify {
     }
 }
 
+/// Parse the information records of an event, keeping the file handles.
+fn parse_fid_records(mut buf: &[u8]) -> Vec<FanotifyFidRecord> {
+    let header_size = size_of::<libc::fanotify_event_info_header>();
+    let fid_size = size_of::<libc::fanotify_event_info_fid>();
+    let handle_size = size_of::<libc::file_handle>();
+    let mut records = Vec::new();
+
+    while buf.len() >= header_size {
+        let header = unsafe {
+            ptr::read_unaligned(
+                buf.as_ptr().cast::<libc::fanotify_event_info_header>(),
+            )
+        };
+        let len = (header.len as usize).min(buf.len());
+        if len < header_size {
+            break;
+        }
+        let record = &buf[..len];
+        buf = &buf[len..];
+
+        let Some(info_type) = FidInfoType::from_raw(header.info_type) else {
+            continue;
+        };
+        if record.len() < fid_size + handle_size {
+            continue;
+        }
+        let fid = unsafe {
+            ptr::read_unaligned(
+                record.as_ptr().cast::<libc::fanotify_event_info_fid>(),
+            )
+        };
+        let handle = unsafe {
+            ptr::read_unaligned(
+                record[fid_size..].as_ptr().cast::<libc::file_handle>(),
+            )
+        };
+        let handle_start = fid_size + handle_size;
+        let handle_end =
+            (handle_start + handle.handle_bytes as usize).min(record.len());
+        let name = info_type.has_name().then(|| {
+            let name = &record[handle_end..];
+            let name_len =
+                name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            OsStr::from_bytes(&name[..name_len]).to_owned()
+        });
+
+        records.push(FanotifyFidRecord {
+            info_type,
+            fsid: fid.fsid.val,
+            handle_type: handle.handle_type,
+            handle: record[handle_start..handle_end].to_vec(),
+            name,
+        });
+    }
+
+    records
+}
+
 impl FromRawFd for Fanotify {
     unsafe fn from_raw_fd(fd: RawFd) -> Self {
         Fanotify {
@@ -0,0 +1,206 @@
+//! fs-verity, transparent integrity protection of read-only files.
+//!
+//! Once fs-verity is enabled on a file, the file becomes read-only, and every
+//! read of its data is checked against a Merkle tree of hashes.  The root hash
+//! of that tree, the file's "digest", can be retrieved with
+//! [`measure_verity`] and compared to a trusted value.
+//!
+//! See the [kernel documentation](https://www.kernel.org/doc/html/latest/filesystems/fsverity.html)
+//! for details.
+
+use std::marker::PhantomData;
+use std::os::fd::{AsFd, AsRawFd};
+
+use crate::errno::Errno;
+use crate::Result;
+
+// `struct fsverity_enable_arg` and `struct fsverity_digest` from
+// `linux/fsverity.h`, which are not yet provided by libc.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct fsverity_enable_arg {
+    version: u32,
+    hash_algorithm: u32,
+    block_size: u32,
+    salt_size: u32,
+    salt_ptr: u64,
+    sig_size: u32,
+    __reserved1: u32,
+    sig_ptr: u64,
+    __reserved2: [u64; 11],
+}
+
+#[repr(C)]
+struct fsverity_digest {
+    digest_algorithm: u16,
+    digest_size: u16,
+    // Followed by `digest_size` bytes of digest.
+}
+
+const FS_IOC_ENABLE_VERITY: libc::Ioctl =
+    request_code_write!(b'f', 133, std::mem::size_of::<fsverity_enable_arg>());
+const FS_IOC_MEASURE_VERITY: libc::Ioctl =
+    request_code_readwrite!(b'f', 134, std::mem::size_of::<fsverity_digest>());
+
+/// The size of the largest digest supported by the kernel.
+const FS_VERITY_MAX_DIGEST_SIZE: usize = 64;
+
+/// Hash algorithm used to build the Merkle tree of a file.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    /// SHA-256, with a 32-byte digest.
+    Sha256 = 1,
+    /// SHA-512, with a 64-byte digest.
+    Sha512 = 2,
+}
+
+impl TryFrom<u16> for HashAlgorithm {
+    type Error = Errno;
+
+    fn try_from(value: u16) -> Result<Self> {
+        match value {
+            1 => Ok(HashAlgorithm::Sha256),
+            2 => Ok(HashAlgorithm::Sha512),
+            _ => Err(Errno::EINVAL),
+        }
+    }
+}
+
+/// Parameters for [`enable_verity`].
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::fsverity::{EnableVerityArg, HashAlgorithm};
+/// let arg = EnableVerityArg::new(HashAlgorithm::Sha256)
+///     .block_size(4096)
+///     .salt(b"salt");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct EnableVerityArg<'a> {
+    arg: fsverity_enable_arg,
+    _buffers: PhantomData<&'a [u8]>,
+}
+
+impl<'a> EnableVerityArg<'a> {
+    /// Create the parameters for building the Merkle tree with the given hash
+    /// algorithm, the page size as block size, and no salt or signature.
+    pub fn new(hash_algorithm: HashAlgorithm) -> Self {
+        let block_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        Self {
+            arg: fsverity_enable_arg {
+                version: 1,
+                hash_algorithm: hash_algorithm as u32,
+                block_size: block_size as u32,
+                salt_size: 0,
+                salt_ptr: 0,
+                sig_size: 0,
+                __reserved1: 0,
+                sig_ptr: 0,
+                __reserved2: [0; 11],
+            },
+            _buffers: PhantomData,
+        }
+    }
+
+    /// Set the Merkle tree block size, in bytes.
+    ///
+    /// It must be a power of 2, and at least 1024.  Most filesystems only
+    /// support the page size.
+    pub fn block_size(mut self, block_size: u32) -> Self {
+        self.arg.block_size = block_size;
+        self
+    }
+
+    /// Set a salt that is prepended to every hashed block.  It can be at most
+    /// 32 bytes long.
+    pub fn salt(mut self, salt: &'a [u8]) -> Self {
+        self.arg.salt_size = salt.len() as u32;
+        self.arg.salt_ptr = salt.as_ptr() as u64;
+        self
+    }
+
+    /// Set a PKCS#7 signature of the file digest, to be verified against the
+    /// `.fs-verity` kernel keyring.
+    pub fn signature(mut self, signature: &'a [u8]) -> Self {
+        self.arg.sig_size = signature.len() as u32;
+        self.arg.sig_ptr = signature.as_ptr() as u64;
+        self
+    }
+}
+
+/// Enable fs-verity on a file (`ioctl(FS_IOC_ENABLE_VERITY)`).
+///
+/// `fd` must be open for reading only, and no other file descriptor may have
+/// the file open for writing.  This reads the whole file to build its Merkle
+/// tree, so it may take a while for large files.  Afterwards, the file can no
+/// longer be written to.
+///
+/// # See Also
+/// * [`FS_IOC_ENABLE_VERITY`](https://www.kernel.org/doc/html/latest/filesystems/fsverity.html#fs-ioc-enable-verity)
+pub fn enable_verity<Fd: AsFd>(
+    fd: Fd,
+    arg: &EnableVerityArg<'_>,
+) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(fd.as_fd().as_raw_fd(), FS_IOC_ENABLE_VERITY, &arg.arg)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// The digest of a file with fs-verity enabled, returned by
+/// [`measure_verity`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VerityDigest {
+    algorithm: u16,
+    size: u16,
+    digest: [u8; FS_VERITY_MAX_DIGEST_SIZE],
+}
+
+impl VerityDigest {
+    /// The hash algorithm of the digest, `EINVAL` is returned if it is not
+    /// known to Nix.
+    pub fn algorithm(&self) -> Result<HashAlgorithm> {
+        HashAlgorithm::try_from(self.algorithm)
+    }
+
+    /// The bytes of the digest.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest[..self.size as usize]
+    }
+}
+
+/// Get the digest of a file with fs-verity enabled
+/// (`ioctl(FS_IOC_MEASURE_VERITY)`).
+///
+/// `ENODATA` is returned if fs-verity is not enabled on the file.
+///
+/// # See Also
+/// * [`FS_IOC_MEASURE_VERITY`](https://www.kernel.org/doc/html/latest/filesystems/fsverity.html#fs-ioc-measure-verity)
+pub fn measure_verity<Fd: AsFd>(fd: Fd) -> Result<VerityDigest> {
+    #[repr(C)]
+    struct Buffer {
+        header: fsverity_digest,
+        digest: [u8; FS_VERITY_MAX_DIGEST_SIZE],
+    }
+
+    let mut buf = Buffer {
+        header: fsverity_digest {
+            digest_algorithm: 0,
+            digest_size: FS_VERITY_MAX_DIGEST_SIZE as u16,
+        },
+        digest: [0; FS_VERITY_MAX_DIGEST_SIZE],
+    };
+    let res = unsafe {
+        libc::ioctl(fd.as_fd().as_raw_fd(), FS_IOC_MEASURE_VERITY, &mut buf)
+    };
+    Errno::result(res)?;
+
+    Ok(VerityDigest {
+        algorithm: buf.header.digest_algorithm,
+        size: buf.header.digest_size.min(FS_VERITY_MAX_DIGEST_SIZE as u16),
+        digest: buf.digest,
+    })
+}
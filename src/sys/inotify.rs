@@ -79,6 +79,15 @@ libc_bitflags! {
         IN_ONLYDIR;
         /// Don't follow symlinks.
         IN_DONT_FOLLOW;
+        /// Don't generate events for children after they are unlinked from
+        /// the watched directory.
+        IN_EXCL_UNLINK;
+        /// Fail with `EEXIST` if the path is already watched, instead of
+        /// replacing the existing watch.
+        IN_MASK_CREATE;
+        /// Add the events to the mask of an existing watch of the path,
+        /// instead of replacing it.
+        IN_MASK_ADD;
 
         /// Event occurred against directory.
         IN_ISDIR;
@@ -195,13 +204,26 @@ impl Inotify {
     /// Returns as many events as available. If the call was non blocking and no
     /// events could be read then the EAGAIN error is returned.
     pub fn read_events(&self) -> Result<Vec<InotifyEvent>> {
+        let mut buffer = [0u8; 4096];
+        self.read_events_with_buffer(&mut buffer)
+    }
+
+    /// Like [`read_events`](Self::read_events), but read the events into
+    /// `buffer`, which allows to reuse it between calls, or to read larger
+    /// bursts of events at once.
+    ///
+    /// The buffer must be able to hold at least one event with its name,
+    /// that is `size_of::<libc::inotify_event>() + NAME_MAX + 1` bytes, or the
+    /// read may fail with `EINVAL`.
+    pub fn read_events_with_buffer(
+        &self,
+        buffer: &mut [u8],
+    ) -> Result<Vec<InotifyEvent>> {
         let header_size = size_of::<libc::inotify_event>();
-        const BUFSIZ: usize = 4096;
-        let mut buffer = [0u8; BUFSIZ];
         let mut events = Vec::new();
         let mut offset = 0;
 
-        let nread = read(&self.fd, &mut buffer)?;
+        let nread = read(&self.fd, buffer)?;
 
         while (nread - offset) >= header_size {
             let event = unsafe {
@@ -209,7 +231,7 @@ impl Inotify {
                 ptr::copy_nonoverlapping(
                     buffer.as_ptr().add(offset),
                     event.as_mut_ptr().cast(),
-                    (BUFSIZ - offset).min(header_size),
+                    (buffer.len() - offset).min(header_size),
                 );
                 event.assume_init()
             };
@@ -241,6 +263,71 @@ impl Inotify {
     }
 }
 
+/// A rename, as reported by a pair of `IN_MOVED_FROM` and `IN_MOVED_TO` events
+/// with the same cookie.  See [`pair_moves`].
+#[derive(Clone, Copy, Debug)]
+pub struct InotifyMove<'a> {
+    /// The `IN_MOVED_FROM` event, or `None` if the file was moved from an
+    /// unwatched directory.
+    pub from: Option<&'a InotifyEvent>,
+    /// The `IN_MOVED_TO` event, or `None` if the file was moved to an
+    /// unwatched directory, or the matching event is not part of the batch.
+    pub to: Option<&'a InotifyEvent>,
+}
+
+/// Pair the `IN_MOVED_FROM` and `IN_MOVED_TO` events of `events` by cookie,
+/// in the order of their first event.
+///
+/// The events of a rename are usually consecutive, but are not guaranteed
+/// to be read in the same batch, so an event may be left unpaired.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::inotify::{pair_moves, AddWatchFlags, InitFlags, Inotify};
+/// let instance = Inotify::init(InitFlags::empty()).unwrap();
+/// instance.add_watch("test", AddWatchFlags::IN_MOVE).unwrap();
+/// let events = instance.read_events().unwrap();
+/// for m in pair_moves(&events) {
+///     if let (Some(from), Some(to)) = (m.from, m.to) {
+///         println!("{:?} was renamed to {:?}", from.name, to.name);
+///     }
+/// }
+/// ```
+pub fn pair_moves(events: &[InotifyEvent]) -> Vec<InotifyMove<'_>> {
+    let mut moves: Vec<(u32, InotifyMove<'_>)> = Vec::new();
+
+    for event in events {
+        let from = event.mask.contains(AddWatchFlags::IN_MOVED_FROM);
+        let to = event.mask.contains(AddWatchFlags::IN_MOVED_TO);
+        if !from && !to {
+            continue;
+        }
+
+        let pending = moves.iter_mut().find(|(cookie, m)| {
+            *cookie == event.cookie
+                && if from {
+                    m.from.is_none()
+                } else {
+                    m.to.is_none()
+                }
+        });
+        match pending {
+            Some((_, m)) if from => m.from = Some(event),
+            Some((_, m)) => m.to = Some(event),
+            None => {
+                let m = InotifyMove {
+                    from: from.then_some(event),
+                    to: to.then_some(event),
+                };
+                moves.push((event.cookie, m));
+            }
+        }
+    }
+
+    moves.into_iter().map(|(_, m)| m).collect()
+}
+
 impl FromRawFd for Inotify {
     unsafe fn from_raw_fd(fd: RawFd) -> Self {
         Inotify {
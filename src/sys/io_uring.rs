@@ -0,0 +1,602 @@
+//! A thin layer over the `io_uring` system calls.
+//!
+//! An io_uring instance consists of a submission queue (SQ) and a completion
+//! queue (CQ), shared between the kernel and user space.  Requests are
+//! submitted by writing [`IoUringSqe`] entries and advancing the tail of the
+//! SQ ring, and [`IoUring::enter`] tells the kernel to consume them, and
+//! optionally waits for [`IoUringCqe`] completions.
+//!
+//! This module only wraps the system calls and maps the rings, accessing the
+//! rings with the proper atomic operations is up to the caller.  See the
+//! [`io_uring(7)`](https://man7.org/linux/man-pages/man7/io_uring.7.html)
+//! manual page for the protocol.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::io_uring::{IoUring, IoUringParams};
+//! let ring = IoUring::setup(8, IoUringParams::new()).unwrap();
+//! let sq_ring = ring.map_sq_ring().unwrap();
+//! let cq_ring = ring.map_cq_ring().unwrap();
+//! let sqes = ring.map_sqes().unwrap();
+//! // The SQ tail is at `sq_ring.as_ptr() + ring.params().sq_off().tail`...
+//! ```
+
+use std::mem;
+use std::num::NonZeroUsize;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::ptr::NonNull;
+
+use libc::{c_uint, c_void};
+
+use crate::errno::Errno;
+use crate::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
+use crate::sys::signal::SigSet;
+use crate::Result;
+
+bitflags::bitflags! {
+    /// Flags for [`IoUringParams::set_flags`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct SetupFlags: u32 {
+        /// Busy-poll for I/O completions, for `O_DIRECT` files.
+        const IORING_SETUP_IOPOLL = 1 << 0;
+        /// Poll the SQ from a kernel thread.
+        const IORING_SETUP_SQPOLL = 1 << 1;
+        /// Bind the SQ polling thread to [`IoUringParams::set_sq_thread_cpu`].
+        const IORING_SETUP_SQ_AFF = 1 << 2;
+        /// Use [`IoUringParams::set_cq_entries`] as the size of the CQ.
+        const IORING_SETUP_CQSIZE = 1 << 3;
+        /// Clamp the sizes of the rings to their maximum.
+        const IORING_SETUP_CLAMP = 1 << 4;
+        /// Share the asynchronous worker threads of another ring, see
+        /// [`IoUringParams::set_attach_wq`].
+        const IORING_SETUP_ATTACH_WQ = 1 << 5;
+        /// Start the ring disabled.
+        const IORING_SETUP_R_DISABLED = 1 << 6;
+        /// Continue submitting requests after an error.
+        const IORING_SETUP_SUBMIT_ALL = 1 << 7;
+        /// Only run completion work when the task enters the kernel.
+        const IORING_SETUP_COOP_TASKRUN = 1 << 8;
+        /// Set `IORING_SQ_TASKRUN` in the SQ flags when completion work is
+        /// pending.
+        const IORING_SETUP_TASKRUN_FLAG = 1 << 9;
+        /// Use 128-byte SQEs.
+        const IORING_SETUP_SQE128 = 1 << 10;
+        /// Use 32-byte CQEs.
+        const IORING_SETUP_CQE32 = 1 << 11;
+        /// Only a single task submits requests.
+        const IORING_SETUP_SINGLE_ISSUER = 1 << 12;
+        /// Defer completion work until [`IoUring::enter`] waits for events.
+        const IORING_SETUP_DEFER_TASKRUN = 1 << 13;
+    }
+}
+
+bitflags::bitflags! {
+    /// Features supported by the kernel, see [`IoUringParams::features`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct Features: u32 {
+        /// The SQ and CQ rings can be mapped with a single mmap.
+        const IORING_FEAT_SINGLE_MMAP = 1 << 0;
+        /// Completions are never dropped when the CQ is full.
+        const IORING_FEAT_NODROP = 1 << 1;
+        /// Submitted data does not need to remain stable.
+        const IORING_FEAT_SUBMIT_STABLE = 1 << 2;
+        /// An offset of -1 means the current file position.
+        const IORING_FEAT_RW_CUR_POS = 1 << 3;
+        /// Requests use the credentials of the task that set up the ring.
+        const IORING_FEAT_CUR_PERSONALITY = 1 << 4;
+        /// Internal polling is used for requests that would block.
+        const IORING_FEAT_FAST_POLL = 1 << 5;
+        /// Poll requests accept 32-bit event masks.
+        const IORING_FEAT_POLL_32BITS = 1 << 6;
+        /// SQ polling does not require registered files.
+        const IORING_FEAT_SQPOLL_NONFIXED = 1 << 7;
+        /// [`IoUring::enter`] supports `IORING_ENTER_EXT_ARG`.
+        const IORING_FEAT_EXT_ARG = 1 << 8;
+        /// Asynchronous workers are native threads.
+        const IORING_FEAT_NATIVE_WORKERS = 1 << 9;
+        /// Registered resources can be tagged.
+        const IORING_FEAT_RSRC_TAGS = 1 << 10;
+        /// Completions can be skipped with `IOSQE_CQE_SKIP_SUCCESS`.
+        const IORING_FEAT_CQE_SKIP = 1 << 11;
+        /// Linked requests resolve their files at execution time.
+        const IORING_FEAT_LINKED_FILE = 1 << 12;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`IoUring::enter`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct EnterFlags: u32 {
+        /// Wait for `min_complete` completions.
+        const IORING_ENTER_GETEVENTS = 1 << 0;
+        /// Wake up the SQ polling thread.
+        const IORING_ENTER_SQ_WAKEUP = 1 << 1;
+        /// Wait for free space in the SQ, when SQ polling is used.
+        const IORING_ENTER_SQ_WAIT = 1 << 2;
+    }
+}
+
+/// Offsets of the fields of the SQ ring, relative to the start of
+/// [`IoUring::map_sq_ring`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SqringOffsets {
+    /// The head index, advanced by the kernel as it consumes entries.
+    pub head: u32,
+    /// The tail index, advanced by user space as it submits entries.
+    pub tail: u32,
+    /// The mask to apply to indices.
+    pub ring_mask: u32,
+    /// The number of entries of the ring.
+    pub ring_entries: u32,
+    /// The `IORING_SQ_*` flags.
+    pub flags: u32,
+    /// The number of invalid entries that were dropped.
+    pub dropped: u32,
+    /// The array of indices of the entries returned by
+    /// [`IoUring::map_sqes`].
+    pub array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+/// Offsets of the fields of the CQ ring, relative to the start of
+/// [`IoUring::map_cq_ring`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CqringOffsets {
+    /// The head index, advanced by user space as it consumes completions.
+    pub head: u32,
+    /// The tail index, advanced by the kernel as it posts completions.
+    pub tail: u32,
+    /// The mask to apply to indices.
+    pub ring_mask: u32,
+    /// The number of entries of the ring.
+    pub ring_entries: u32,
+    /// The number of completions dropped because the ring was full.
+    pub overflow: u32,
+    /// The array of [`IoUringCqe`]s.
+    pub cqes: u32,
+    /// The `IORING_CQ_*` flags.
+    pub flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+/// Parameters of [`IoUring::setup`], filled in by the kernel.
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::io_uring::{IoUringParams, SetupFlags};
+/// let params = IoUringParams::new()
+///     .set_flags(SetupFlags::IORING_SETUP_CLAMP)
+///     .set_cq_entries(64);
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IoUringParams {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+impl IoUringParams {
+    /// Create the default parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the setup flags.
+    pub fn set_flags(mut self, flags: SetupFlags) -> Self {
+        self.flags |= flags.bits();
+        self
+    }
+
+    /// Set the number of entries of the CQ, which defaults to twice the
+    /// number of entries of the SQ.
+    pub fn set_cq_entries(mut self, entries: u32) -> Self {
+        self.flags |= SetupFlags::IORING_SETUP_CQSIZE.bits();
+        self.cq_entries = entries;
+        self
+    }
+
+    /// Set the CPU of the SQ polling thread.
+    pub fn set_sq_thread_cpu(mut self, cpu: u32) -> Self {
+        self.flags |= SetupFlags::IORING_SETUP_SQ_AFF.bits();
+        self.sq_thread_cpu = cpu;
+        self
+    }
+
+    /// Set the time, in milliseconds, after which an idle SQ polling thread
+    /// goes to sleep.
+    pub fn set_sq_thread_idle(mut self, idle_ms: u32) -> Self {
+        self.sq_thread_idle = idle_ms;
+        self
+    }
+
+    /// Share the asynchronous worker threads of the ring `fd`.
+    pub fn set_attach_wq<Fd: AsFd>(mut self, fd: Fd) -> Self {
+        self.flags |= SetupFlags::IORING_SETUP_ATTACH_WQ.bits();
+        self.wq_fd = fd.as_fd().as_raw_fd() as u32;
+        self
+    }
+
+    /// The number of entries of the SQ.
+    pub fn sq_entries(&self) -> u32 {
+        self.sq_entries
+    }
+
+    /// The number of entries of the CQ.
+    pub fn cq_entries(&self) -> u32 {
+        self.cq_entries
+    }
+
+    /// The setup flags.
+    pub fn flags(&self) -> SetupFlags {
+        SetupFlags::from_bits_retain(self.flags)
+    }
+
+    /// The CPU of the SQ polling thread.
+    pub fn sq_thread_cpu(&self) -> u32 {
+        self.sq_thread_cpu
+    }
+
+    /// The time, in milliseconds, after which an idle SQ polling thread goes
+    /// to sleep.
+    pub fn sq_thread_idle(&self) -> u32 {
+        self.sq_thread_idle
+    }
+
+    /// The features supported by the kernel.
+    pub fn features(&self) -> Features {
+        Features::from_bits_retain(self.features)
+    }
+
+    /// The offsets of the fields of the SQ ring.
+    pub fn sq_off(&self) -> &SqringOffsets {
+        &self.sq_off
+    }
+
+    /// The offsets of the fields of the CQ ring.
+    pub fn cq_off(&self) -> &CqringOffsets {
+        &self.cq_off
+    }
+}
+
+/// A submission queue entry, as found in [`IoUring::map_sqes`].
+///
+/// With `IORING_SETUP_SQE128`, each entry is followed by 64 bytes of extra
+/// data.
+///
+/// The meaning of the fields depends on the `opcode`, see
+/// [`io_uring_enter(2)`](https://man7.org/linux/man-pages/man2/io_uring_enter.2.html).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub struct IoUringSqe {
+    pub opcode: u8,
+    pub flags: u8,
+    pub ioprio: u16,
+    pub fd: i32,
+    pub off: u64,
+    pub addr: u64,
+    pub len: u32,
+    /// `rw_flags`, `poll32_events`, etc., depending on the opcode.
+    pub op_flags: u32,
+    /// Passed back in the [`IoUringCqe`] of the request.
+    pub user_data: u64,
+    pub buf_index: u16,
+    pub personality: u16,
+    pub splice_fd_in: i32,
+    pub addr3: u64,
+    __pad2: [u64; 1],
+}
+
+/// A completion queue entry, as found in [`IoUring::map_cq_ring`].
+///
+/// With `IORING_SETUP_CQE32`, each entry is followed by 16 bytes of extra
+/// data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IoUringCqe {
+    /// The `user_data` of the request.
+    pub user_data: u64,
+    /// The result of the request, a negative errno on failure.
+    pub res: i32,
+    /// The `IORING_CQE_F_*` flags.
+    pub flags: u32,
+}
+
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_CQ_RING: libc::off_t = 0x8000000;
+const IORING_OFF_SQES: libc::off_t = 0x10000000;
+
+const IORING_REGISTER_BUFFERS: c_uint = 0;
+const IORING_UNREGISTER_BUFFERS: c_uint = 1;
+const IORING_REGISTER_FILES: c_uint = 2;
+const IORING_UNREGISTER_FILES: c_uint = 3;
+const IORING_REGISTER_EVENTFD: c_uint = 4;
+const IORING_UNREGISTER_EVENTFD: c_uint = 5;
+
+/// A mapping of one of the rings of an [`IoUring`], which is unmapped when
+/// dropped.
+///
+/// The kernel accesses the memory concurrently, so it is only exposed through
+/// a raw pointer.
+#[derive(Debug)]
+pub struct RingMapping {
+    ptr: NonNull<c_void>,
+    len: NonZeroUsize,
+}
+
+// SAFETY: the mapping is only accessed through raw pointers, whose use is
+// already unsafe.
+unsafe impl Send for RingMapping {}
+unsafe impl Sync for RingMapping {}
+
+impl RingMapping {
+    /// The address of the mapping.
+    pub fn as_ptr(&self) -> NonNull<c_void> {
+        self.ptr
+    }
+
+    /// The length of the mapping, in bytes.
+    // The mapping is never empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+}
+
+impl Drop for RingMapping {
+    fn drop(&mut self) {
+        // SAFETY: the mapping is owned by `self`.
+        let res = unsafe { munmap(self.ptr, self.len()) };
+        debug_assert!(res.is_ok(), "munmap failed: {res:?}");
+    }
+}
+
+/// An io_uring instance, created with [`IoUring::setup`].
+#[derive(Debug)]
+pub struct IoUring {
+    fd: OwnedFd,
+    params: IoUringParams,
+}
+
+impl IoUring {
+    /// Create an io_uring instance with at least `entries` SQ entries
+    /// (`io_uring_setup(2)`).
+    ///
+    /// # See Also
+    /// * [`io_uring_setup(2)`](https://man7.org/linux/man-pages/man2/io_uring_setup.2.html)
+    pub fn setup(entries: u32, mut params: IoUringParams) -> Result<Self> {
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_setup,
+                entries,
+                &mut params as *mut IoUringParams,
+            )
+        };
+        let fd = Errno::result(res)? as RawFd;
+        Ok(IoUring {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            params,
+        })
+    }
+
+    /// The parameters of the instance, as filled in by the kernel.
+    pub fn params(&self) -> &IoUringParams {
+        &self.params
+    }
+
+    fn map(&self, len: usize, offset: libc::off_t) -> Result<RingMapping> {
+        let len = NonZeroUsize::new(len).ok_or(Errno::EINVAL)?;
+        // The mapping is backed by memory owned by the instance, which stays
+        // alive until the mapping is removed.
+        let ptr = unsafe {
+            mmap(
+                None,
+                len,
+                ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                MapFlags::MAP_SHARED | MapFlags::MAP_POPULATE,
+                &self.fd,
+                offset,
+            )?
+        };
+        Ok(RingMapping { ptr, len })
+    }
+
+    /// Map the SQ ring, whose layout is given by
+    /// [`IoUringParams::sq_off`].
+    ///
+    /// Its fields are shared with the kernel, so they must only be accessed
+    /// through raw pointers and, for the head and tail, atomic operations.
+    pub fn map_sq_ring(&self) -> Result<RingMapping> {
+        let len = self.params.sq_off.array as usize
+            + self.params.sq_entries as usize * mem::size_of::<u32>();
+        self.map(len, IORING_OFF_SQ_RING)
+    }
+
+    /// Map the CQ ring, whose layout is given by
+    /// [`IoUringParams::cq_off`].
+    ///
+    /// Its fields are shared with the kernel, so they must only be accessed
+    /// through raw pointers and, for the head and tail, atomic operations.
+    /// With `IORING_SETUP_CQE32`, the entries are 32 bytes long.
+    pub fn map_cq_ring(&self) -> Result<RingMapping> {
+        let mut cqe_size = mem::size_of::<IoUringCqe>();
+        if self.params.flags().contains(SetupFlags::IORING_SETUP_CQE32) {
+            cqe_size *= 2;
+        }
+        let len = self.params.cq_off.cqes as usize
+            + self.params.cq_entries as usize * cqe_size;
+        self.map(len, IORING_OFF_CQ_RING)
+    }
+
+    /// Map the array of [`IoUringSqe`]s, indexed by the array of the SQ ring.
+    /// With `IORING_SETUP_SQE128`, the entries are 128 bytes long.
+    pub fn map_sqes(&self) -> Result<RingMapping> {
+        let mut sqe_size = mem::size_of::<IoUringSqe>();
+        if self
+            .params
+            .flags()
+            .contains(SetupFlags::IORING_SETUP_SQE128)
+        {
+            sqe_size *= 2;
+        }
+        let len = self.params.sq_entries as usize * sqe_size;
+        self.map(len, IORING_OFF_SQES)
+    }
+
+    /// Submit `to_submit` entries of the SQ and, with
+    /// `IORING_ENTER_GETEVENTS`, wait for `min_complete` completions, with
+    /// the signal mask of the calling thread replaced by `sigmask`, if given,
+    /// while waiting.  Returns the number of entries consumed from the SQ.
+    ///
+    /// # See Also
+    /// * [`io_uring_enter(2)`](https://man7.org/linux/man-pages/man2/io_uring_enter.2.html)
+    pub fn enter(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        flags: EnterFlags,
+        sigmask: Option<&SigSet>,
+    ) -> Result<u32> {
+        // The kernel's `sigset_t` is smaller than the C library's one.
+        #[cfg(any(
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6"
+        ))]
+        const SIGSET_SIZE: usize = 128 / 8;
+        #[cfg(not(any(
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6"
+        )))]
+        const SIGSET_SIZE: usize = 64 / 8;
+
+        let sigmask = sigmask
+            .map_or(std::ptr::null(), |s| s.as_ref() as *const libc::sigset_t);
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_enter,
+                self.fd.as_raw_fd(),
+                to_submit,
+                min_complete,
+                flags.bits(),
+                sigmask,
+                SIGSET_SIZE,
+            )
+        };
+        Errno::result(res).map(|r| r as u32)
+    }
+
+    fn register(
+        &self,
+        opcode: c_uint,
+        arg: *const c_void,
+        nr_args: c_uint,
+    ) -> Result<()> {
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_register,
+                self.fd.as_raw_fd(),
+                opcode,
+                arg,
+                nr_args,
+            )
+        };
+        Errno::result(res).map(drop)
+    }
+
+    /// Register fixed buffers, for the `*_FIXED` operations
+    /// (`IORING_REGISTER_BUFFERS`).
+    ///
+    /// # Safety
+    ///
+    /// The kernel may write to the buffers until they are unregistered, or
+    /// the instance is closed, so they must remain valid and not be otherwise
+    /// accessed while I/O is in flight.
+    ///
+    /// # See Also
+    /// * [`io_uring_register(2)`](https://man7.org/linux/man-pages/man2/io_uring_register.2.html)
+    pub unsafe fn register_buffers(
+        &self,
+        iovecs: &[libc::iovec],
+    ) -> Result<()> {
+        self.register(
+            IORING_REGISTER_BUFFERS,
+            iovecs.as_ptr().cast(),
+            iovecs.len() as c_uint,
+        )
+    }
+
+    /// Unregister all the fixed buffers (`IORING_UNREGISTER_BUFFERS`).
+    pub fn unregister_buffers(&self) -> Result<()> {
+        self.register(IORING_UNREGISTER_BUFFERS, std::ptr::null(), 0)
+    }
+
+    /// Register fixed files, used with `IOSQE_FIXED_FILE` by their index in
+    /// `fds` (`IORING_REGISTER_FILES`).  `-1` leaves a slot empty.
+    ///
+    /// The instance holds references to the files, so the file descriptors
+    /// can be closed afterwards.
+    pub fn register_files(&self, fds: &[RawFd]) -> Result<()> {
+        self.register(
+            IORING_REGISTER_FILES,
+            fds.as_ptr().cast(),
+            fds.len() as c_uint,
+        )
+    }
+
+    /// Unregister all the fixed files (`IORING_UNREGISTER_FILES`).
+    pub fn unregister_files(&self) -> Result<()> {
+        self.register(IORING_UNREGISTER_FILES, std::ptr::null(), 0)
+    }
+
+    /// Notify the eventfd `fd` of completions (`IORING_REGISTER_EVENTFD`).
+    pub fn register_eventfd<Fd: AsFd>(&self, fd: Fd) -> Result<()> {
+        let fd = fd.as_fd().as_raw_fd();
+        self.register(
+            IORING_REGISTER_EVENTFD,
+            &fd as *const RawFd as *const c_void,
+            1,
+        )
+    }
+
+    /// Stop notifying the registered eventfd (`IORING_UNREGISTER_EVENTFD`).
+    pub fn unregister_eventfd(&self) -> Result<()> {
+        self.register(IORING_UNREGISTER_EVENTFD, std::ptr::null(), 0)
+    }
+}
+
+impl AsFd for IoUring {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for IoUring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+const _: () = assert!(mem::size_of::<IoUringSqe>() == 64);
+const _: () = assert!(mem::size_of::<IoUringCqe>() == 16);
+const _: () = assert!(mem::size_of::<IoUringParams>() == 120);
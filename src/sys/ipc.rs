@@ -0,0 +1,78 @@
+//! Definitions shared by the System V IPC APIs.
+//!
+//! System V IPC objects, such as [semaphore sets](crate::sys::sem) and
+//! message queues, are identified by a [`key_t`] that is agreed upon by all
+//! users of the object, usually derived from a path with [`ftok`], or
+//! [`IPC_PRIVATE`] to always create a new object.
+//!
+//! Unlike file descriptors, these objects persist until they are explicitly
+//! removed, or the system is rebooted.
+
+use crate::errno::Errno;
+use crate::sys::stat::Mode;
+use crate::unistd::{Gid, Uid};
+use crate::{NixPath, Result};
+
+pub use libc::key_t;
+
+/// A key that always creates a new IPC object, which can only be shared by
+/// passing its identifier to other processes.
+pub const IPC_PRIVATE: key_t = libc::IPC_PRIVATE;
+
+libc_bitflags! {
+    /// Flags for getting an IPC object, such as with
+    /// [`semget`](crate::sys::sem::semget).
+    pub struct IpcGetFlags: libc::c_int {
+        /// Create the object if it does not exist.
+        IPC_CREAT;
+        /// With `IPC_CREAT`, fail with `EEXIST` if the object already exists.
+        IPC_EXCL;
+    }
+}
+
+/// Generate an IPC key from the path of an existing, accessible file and a
+/// non-zero project identifier.
+///
+/// The same path and identifier generate the same key, as long as the file is
+/// not recreated.
+///
+/// # See Also
+/// * [`ftok(3)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/ftok.html)
+pub fn ftok<P: ?Sized + NixPath>(path: &P, proj_id: u8) -> Result<key_t> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::ftok(cstr.as_ptr(), proj_id.into())
+    })?;
+    Errno::result(res)
+}
+
+/// The ownership and permissions of an IPC object.
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct IpcPerm(pub(crate) libc::ipc_perm);
+
+impl IpcPerm {
+    /// The user ID of the owner.
+    pub fn uid(&self) -> Uid {
+        Uid::from_raw(self.0.uid)
+    }
+
+    /// The group ID of the owner.
+    pub fn gid(&self) -> Gid {
+        Gid::from_raw(self.0.gid)
+    }
+
+    /// The user ID of the creator.
+    pub fn cuid(&self) -> Uid {
+        Uid::from_raw(self.0.cuid)
+    }
+
+    /// The group ID of the creator.
+    pub fn cgid(&self) -> Gid {
+        Gid::from_raw(self.0.cgid)
+    }
+
+    /// The access permissions.
+    pub fn mode(&self) -> Mode {
+        Mode::from_bits_truncate(self.0.mode.into())
+    }
+}
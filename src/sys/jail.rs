@@ -0,0 +1,303 @@
+//! FreeBSD jails.
+//!
+//! A jail is a partition of the system with its own file system root,
+//! hostname, network addresses and users.  Jails are created, modified and
+//! queried with [`jail_set`] and [`jail_get`], which take their parameters as
+//! a list of name-value pairs built with [`JailParams`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::jail::*;
+//! let jid = jail_set(
+//!     JailParams::new()
+//!         .str_param("name", "example")
+//!         .str_param("path", "/")
+//!         .null_param("persist"),
+//!     JailFlags::JAIL_CREATE,
+//! )
+//! .unwrap();
+//! jail_remove(jid).unwrap();
+//! ```
+//!
+//! # See Also
+//! * [`jail(2)`](https://man.freebsd.org/cgi/man.cgi?query=jail&sektion=2)
+//! * [`jail(8)`](https://man.freebsd.org/cgi/man.cgi?query=jail&sektion=8),
+//!   for the list of parameters
+
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::{fmt, io, ptr};
+
+use libc::{c_int, c_uint};
+
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+
+libc_bitflags! {
+    /// Flags for [`jail_set`] and [`jail_get`].
+    pub struct JailFlags: c_int {
+        /// Create a new jail, with [`jail_set`].
+        JAIL_CREATE;
+        /// Modify an existing jail, with [`jail_set`].  It can be combined
+        /// with `JAIL_CREATE` to create the jail only if needed.
+        JAIL_UPDATE;
+        /// Attach the calling process to the jail, with [`jail_set`].
+        JAIL_ATTACH;
+        /// Allow the jail to be in the process of being removed.
+        JAIL_DYING;
+    }
+}
+
+/// The Error type of [`jail_set`] and [`jail_get`].
+///
+/// It wraps an [`Errno`], but also may contain an additional message returned
+/// by the kernel.
+#[derive(Debug)]
+pub struct JailError {
+    errno: Errno,
+    errmsg: Option<String>,
+}
+
+impl JailError {
+    /// Returns the additional error string sometimes generated by the
+    /// kernel.
+    pub fn errmsg(&self) -> Option<&str> {
+        self.errmsg.as_deref()
+    }
+
+    /// Returns the inner [`Errno`]
+    pub const fn error(&self) -> Errno {
+        self.errno
+    }
+}
+
+impl std::error::Error for JailError {}
+
+impl fmt::Display for JailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(errmsg) = &self.errmsg {
+            write!(f, "{:?}: {}: {}", self.errno, errmsg, self.errno.desc())
+        } else {
+            write!(f, "{:?}: {}", self.errno, self.errno.desc())
+        }
+    }
+}
+
+impl From<JailError> for io::Error {
+    fn from(err: JailError) -> Self {
+        err.errno.into()
+    }
+}
+
+/// Result type of [`jail_set`] and [`jail_get`].
+pub type JailResult<T> = std::result::Result<T, JailError>;
+
+/// The parameters of [`jail_set`] and [`jail_get`], as name-value pairs.
+///
+/// None of the values are interpreted by Nix: see `jail(8)` for the names of
+/// the parameters and the types of their values.  With [`jail_get`], the
+/// parameters to be retrieved need a buffer of the right size for their
+/// value, which is overwritten with the value of the jail.
+///
+/// # Example
+///
+/// Get the name of the jail with id 1:
+///
+/// ```no_run
+/// # use nix::sys::jail::*;
+/// let mut params = JailParams::new();
+/// params.int_param("jid", 1).buf_param("name", 256);
+/// jail_get(&mut params, JailFlags::empty()).unwrap();
+/// println!("{:?}", params.str_value("name"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JailParams {
+    params: Vec<(CString, Option<Vec<u8>>)>,
+}
+
+impl JailParams {
+    /// Create an empty list of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push<P: ?Sized + NixPath>(&mut self, name: &P, value: Option<Vec<u8>>) {
+        let name = name.with_nix_path(CStr::to_owned).unwrap();
+        self.params.push((name, value));
+    }
+
+    /// Add a parameter without value, such as a boolean parameter like
+    /// `persist`, or its negation like `nopersist`.
+    pub fn null_param<P: ?Sized + NixPath>(&mut self, name: &P) -> &mut Self {
+        self.push(name, None);
+        self
+    }
+
+    /// Add a string parameter.
+    pub fn str_param<P1, P2>(&mut self, name: &P1, value: &P2) -> &mut Self
+    where
+        P1: ?Sized + NixPath,
+        P2: ?Sized + NixPath,
+    {
+        let value = value
+            .with_nix_path(|s| s.to_bytes_with_nul().to_vec())
+            .unwrap();
+        self.push(name, Some(value));
+        self
+    }
+
+    /// Add an integer parameter, such as `jid`.
+    pub fn int_param<P: ?Sized + NixPath>(
+        &mut self,
+        name: &P,
+        value: c_int,
+    ) -> &mut Self {
+        self.push(name, Some(value.to_ne_bytes().to_vec()));
+        self
+    }
+
+    /// Add a parameter with an opaque value, such as a list of addresses.
+    pub fn bytes_param<P: ?Sized + NixPath>(
+        &mut self,
+        name: &P,
+        value: &[u8],
+    ) -> &mut Self {
+        self.push(name, Some(value.to_vec()));
+        self
+    }
+
+    /// Add a parameter with a zeroed value of `len` bytes, to be retrieved
+    /// by [`jail_get`].
+    pub fn buf_param<P: ?Sized + NixPath>(
+        &mut self,
+        name: &P,
+        len: usize,
+    ) -> &mut Self {
+        self.push(name, Some(vec![0; len]));
+        self
+    }
+
+    /// The value of the last parameter named `name`.
+    pub fn value<P: ?Sized + NixPath>(&self, name: &P) -> Option<&[u8]> {
+        name.with_nix_path(|name| {
+            self.params
+                .iter()
+                .rev()
+                .find(|(n, _)| n.as_c_str() == name)
+                .and_then(|(_, v)| v.as_deref())
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// The value of the last parameter named `name`, as a string.
+    pub fn str_value<P: ?Sized + NixPath>(&self, name: &P) -> Option<&CStr> {
+        self.value(name)
+            .and_then(|v| CStr::from_bytes_until_nul(v).ok())
+    }
+
+    /// The value of the last parameter named `name`, as an integer.
+    pub fn int_value<P: ?Sized + NixPath>(&self, name: &P) -> Option<c_int> {
+        self.value(name)
+            .and_then(|v| v.try_into().ok())
+            .map(c_int::from_ne_bytes)
+    }
+
+    fn call(
+        &mut self,
+        f: unsafe extern "C" fn(*mut libc::iovec, c_uint, c_int) -> c_int,
+        flags: JailFlags,
+    ) -> JailResult<c_int> {
+        const ERRMSG_NAME: &[u8] = b"errmsg\0";
+        let mut errmsg = vec![0u8; 256];
+
+        let mut iov = Vec::with_capacity(2 * self.params.len() + 2);
+        for (name, value) in self.params.iter_mut() {
+            iov.push(libc::iovec {
+                iov_base: name.as_ptr().cast_mut().cast(),
+                iov_len: name.as_bytes_with_nul().len(),
+            });
+            iov.push(match value {
+                Some(value) => libc::iovec {
+                    iov_base: value.as_mut_ptr().cast(),
+                    iov_len: value.len(),
+                },
+                None => libc::iovec {
+                    iov_base: ptr::null_mut(),
+                    iov_len: 0,
+                },
+            });
+        }
+        // The kernel can return extra error information via a "errmsg"
+        // parameter.
+        iov.push(libc::iovec {
+            iov_base: ERRMSG_NAME.as_ptr().cast_mut().cast(),
+            iov_len: ERRMSG_NAME.len(),
+        });
+        iov.push(libc::iovec {
+            iov_base: errmsg.as_mut_ptr().cast(),
+            iov_len: errmsg.len(),
+        });
+
+        let res =
+            unsafe { f(iov.as_mut_ptr(), iov.len() as c_uint, flags.bits()) };
+        Errno::result(res).map_err(|errno| JailError {
+            errno,
+            errmsg: CStr::from_bytes_until_nul(&errmsg)
+                .ok()
+                .filter(|msg| !msg.to_bytes().is_empty())
+                .map(CStr::to_string_lossy)
+                .map(Cow::into_owned),
+        })
+    }
+}
+
+/// Create or modify a jail.
+///
+/// Returns the id of the jail.
+///
+/// # See Also
+/// [`jail_set(2)`](https://man.freebsd.org/cgi/man.cgi?query=jail_set&sektion=2)
+pub fn jail_set(
+    params: &mut JailParams,
+    flags: JailFlags,
+) -> JailResult<c_int> {
+    params.call(libc::jail_set, flags)
+}
+
+/// Retrieve the parameters of a jail.
+///
+/// The jail is selected by the `jid` or `name` parameter, or by the
+/// `lastjid` parameter to iterate over the jails whose id is greater.  The
+/// values of the other parameters are overwritten.  Returns the id of the
+/// jail.
+///
+/// # See Also
+/// [`jail_get(2)`](https://man.freebsd.org/cgi/man.cgi?query=jail_get&sektion=2)
+pub fn jail_get(
+    params: &mut JailParams,
+    flags: JailFlags,
+) -> JailResult<c_int> {
+    params.call(libc::jail_get, flags)
+}
+
+/// Attach the calling process to the jail `jid`.
+///
+/// # See Also
+/// [`jail_attach(2)`](https://man.freebsd.org/cgi/man.cgi?query=jail_attach&sektion=2)
+pub fn jail_attach(jid: c_int) -> Result<()> {
+    let res = unsafe { libc::jail_attach(jid) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove the jail `jid`, killing all of its processes.
+///
+/// # See Also
+/// [`jail_remove(2)`](https://man.freebsd.org/cgi/man.cgi?query=jail_remove&sektion=2)
+pub fn jail_remove(jid: c_int) -> Result<()> {
+    let res = unsafe { libc::jail_remove(jid) };
+
+    Errno::result(res).map(drop)
+}
@@ -0,0 +1,366 @@
+//! The kernel key retention service.
+//!
+//! Keys hold secrets, such as passwords or cryptographic keys, in the
+//! kernel.  Each key has a type, a description, a payload and permissions,
+//! and is identified by a [`KeySerial`].  Keys are attached to keyrings,
+//! which are themselves keys, and a process can reach the keys linked from
+//! its thread, process, session and user keyrings.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::sys::key::*;
+//! let key = add_key(
+//!     "user",
+//!     "nix:example",
+//!     b"secret",
+//!     KeySerial::KEY_SPEC_THREAD_KEYRING,
+//! )
+//! .unwrap();
+//! assert_eq!(keyctl_read(key).unwrap(), b"secret");
+//! keyctl_revoke(key).unwrap();
+//! ```
+//!
+//! # See Also
+//! [keyrings(7)](https://man7.org/linux/man-pages/man7/keyrings.7.html)
+
+use std::ffi::CStr;
+use std::fmt;
+use std::ptr;
+
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+
+const KEYCTL_GET_KEYRING_ID: libc::c_int = 0;
+const KEYCTL_JOIN_SESSION_KEYRING: libc::c_int = 1;
+const KEYCTL_UPDATE: libc::c_int = 2;
+const KEYCTL_REVOKE: libc::c_int = 3;
+const KEYCTL_SETPERM: libc::c_int = 5;
+const KEYCTL_LINK: libc::c_int = 8;
+const KEYCTL_UNLINK: libc::c_int = 9;
+const KEYCTL_SEARCH: libc::c_int = 10;
+const KEYCTL_READ: libc::c_int = 11;
+
+/// The serial number of a key.
+///
+/// Besides the serial numbers of actual keys, the special `KEY_SPEC_*`
+/// constants refer to the keyrings of the calling thread.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct KeySerial(i32);
+
+impl KeySerial {
+    /// The keyring of the calling thread.
+    pub const KEY_SPEC_THREAD_KEYRING: Self = KeySerial(-1);
+    /// The keyring of the calling process.
+    pub const KEY_SPEC_PROCESS_KEYRING: Self = KeySerial(-2);
+    /// The session keyring of the calling process.
+    pub const KEY_SPEC_SESSION_KEYRING: Self = KeySerial(-3);
+    /// The keyring of the real user id of the calling process.
+    pub const KEY_SPEC_USER_KEYRING: Self = KeySerial(-4);
+    /// The default session keyring of the real user id of the calling
+    /// process.
+    pub const KEY_SPEC_USER_SESSION_KEYRING: Self = KeySerial(-5);
+    /// The authorization key of a `request_key(2)` upcall.
+    pub const KEY_SPEC_REQKEY_AUTH_KEY: Self = KeySerial(-7);
+    /// The destination keyring of a `request_key(2)` upcall.
+    pub const KEY_SPEC_REQUESTOR_KEYRING: Self = KeySerial(-8);
+
+    /// Creates a `KeySerial` from a raw serial number.
+    pub const fn from_raw(serial: i32) -> Self {
+        KeySerial(serial)
+    }
+
+    /// Get the raw serial number wrapped by `self`.
+    pub const fn as_raw(self) -> i32 {
+        self.0
+    }
+}
+
+impl From<KeySerial> for i32 {
+    fn from(key: KeySerial) -> Self {
+        key.0
+    }
+}
+
+impl fmt::Display for KeySerial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+bitflags::bitflags! {
+    /// The permissions of a key, see [`keyctl_setperm`].
+    ///
+    /// The possessor permissions apply to the processes that can reach the
+    /// key through their keyrings, and are granted in addition to the
+    /// user, group or other permissions.
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct KeyPerm: u32 {
+        /// The possessor can view the attributes of the key.
+        const KEY_POS_VIEW = 0x0100_0000;
+        /// The possessor can read the payload of the key.
+        const KEY_POS_READ = 0x0200_0000;
+        /// The possessor can update the key, or add links to the keyring.
+        const KEY_POS_WRITE = 0x0400_0000;
+        /// The possessor can find the key in searches.
+        const KEY_POS_SEARCH = 0x0800_0000;
+        /// The possessor can link the key to a keyring.
+        const KEY_POS_LINK = 0x1000_0000;
+        /// The possessor can change the attributes of the key.
+        const KEY_POS_SETATTR = 0x2000_0000;
+        /// All of the possessor permissions.
+        const KEY_POS_ALL = 0x3f00_0000;
+        /// The owner can view the attributes of the key.
+        const KEY_USR_VIEW = 0x0001_0000;
+        /// The owner can read the payload of the key.
+        const KEY_USR_READ = 0x0002_0000;
+        /// The owner can update the key, or add links to the keyring.
+        const KEY_USR_WRITE = 0x0004_0000;
+        /// The owner can find the key in searches.
+        const KEY_USR_SEARCH = 0x0008_0000;
+        /// The owner can link the key to a keyring.
+        const KEY_USR_LINK = 0x0010_0000;
+        /// The owner can change the attributes of the key.
+        const KEY_USR_SETATTR = 0x0020_0000;
+        /// All of the owner permissions.
+        const KEY_USR_ALL = 0x003f_0000;
+        /// The group can view the attributes of the key.
+        const KEY_GRP_VIEW = 0x0000_0100;
+        /// The group can read the payload of the key.
+        const KEY_GRP_READ = 0x0000_0200;
+        /// The group can update the key, or add links to the keyring.
+        const KEY_GRP_WRITE = 0x0000_0400;
+        /// The group can find the key in searches.
+        const KEY_GRP_SEARCH = 0x0000_0800;
+        /// The group can link the key to a keyring.
+        const KEY_GRP_LINK = 0x0000_1000;
+        /// The group can change the attributes of the key.
+        const KEY_GRP_SETATTR = 0x0000_2000;
+        /// All of the group permissions.
+        const KEY_GRP_ALL = 0x0000_3f00;
+        /// Others can view the attributes of the key.
+        const KEY_OTH_VIEW = 0x0000_0001;
+        /// Others can read the payload of the key.
+        const KEY_OTH_READ = 0x0000_0002;
+        /// Others can update the key, or add links to the keyring.
+        const KEY_OTH_WRITE = 0x0000_0004;
+        /// Others can find the key in searches.
+        const KEY_OTH_SEARCH = 0x0000_0008;
+        /// Others can link the key to a keyring.
+        const KEY_OTH_LINK = 0x0000_0010;
+        /// Others can change the attributes of the key.
+        const KEY_OTH_SETATTR = 0x0000_0020;
+        /// All of the permissions of others.
+        const KEY_OTH_ALL = 0x0000_003f;
+    }
+}
+
+fn key_result(res: libc::c_long) -> Result<KeySerial> {
+    Errno::result(res).map(|serial| KeySerial(serial as i32))
+}
+
+/// Create a key of type `key_type` with the description `description` and
+/// the payload `payload`, and link it to `keyring`.
+///
+/// If `keyring` already holds a key with the same type and description, that
+/// key is updated instead.  Keyrings are created with the type `"keyring"`
+/// and an empty payload.
+///
+/// # See Also
+/// [add_key(2)](https://man7.org/linux/man-pages/man2/add_key.2.html)
+pub fn add_key<T: ?Sized + NixPath, D: ?Sized + NixPath>(
+    key_type: &T,
+    description: &D,
+    payload: &[u8],
+    keyring: KeySerial,
+) -> Result<KeySerial> {
+    let res = key_type.with_nix_path(|t| {
+        description.with_nix_path(|d| unsafe {
+            libc::syscall(
+                libc::SYS_add_key,
+                t.as_ptr(),
+                d.as_ptr(),
+                payload.as_ptr(),
+                payload.len(),
+                keyring.0,
+            )
+        })
+    })??;
+
+    key_result(res)
+}
+
+/// Find the key of type `key_type` with the description `description` in
+/// the keyrings of the calling thread.
+///
+/// If there is no such key and `callout_info` is given, the kernel asks
+/// `/sbin/request-key` to create it.  The key found or created is linked to
+/// `dest_keyring`, if any.  Fails with `ENOKEY` if the key cannot be
+/// found.
+///
+/// # See Also
+/// [request_key(2)](https://man7.org/linux/man-pages/man2/request_key.2.html)
+pub fn request_key<T: ?Sized + NixPath, D: ?Sized + NixPath>(
+    key_type: &T,
+    description: &D,
+    callout_info: Option<&CStr>,
+    dest_keyring: Option<KeySerial>,
+) -> Result<KeySerial> {
+    let res = key_type.with_nix_path(|t| {
+        description.with_nix_path(|d| unsafe {
+            libc::syscall(
+                libc::SYS_request_key,
+                t.as_ptr(),
+                d.as_ptr(),
+                callout_info.map_or(ptr::null(), CStr::as_ptr),
+                dest_keyring.map_or(0, KeySerial::as_raw),
+            )
+        })
+    })??;
+
+    key_result(res)
+}
+
+/// Get the serial number of the actual keyring referred to by the special
+/// serial number `key`.
+///
+/// If the keyring does not exist yet, it is created if `create` is true,
+/// otherwise this fails with `ENOKEY`.
+pub fn keyctl_get_keyring_id(
+    key: KeySerial,
+    create: bool,
+) -> Result<KeySerial> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_keyctl,
+            KEYCTL_GET_KEYRING_ID,
+            key.0,
+            libc::c_int::from(create),
+        )
+    };
+
+    key_result(res)
+}
+
+/// Replace the session keyring of the calling process with the keyring
+/// named `name`, which is created if needed, or with a new anonymous keyring
+/// if `None`.
+///
+/// Returns the serial number of the new session keyring.
+pub fn keyctl_join_session_keyring(name: Option<&CStr>) -> Result<KeySerial> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_keyctl,
+            KEYCTL_JOIN_SESSION_KEYRING,
+            name.map_or(ptr::null(), CStr::as_ptr),
+        )
+    };
+
+    key_result(res)
+}
+
+/// Replace the payload of `key` with `payload`.
+pub fn keyctl_update(key: KeySerial, payload: &[u8]) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_keyctl,
+            KEYCTL_UPDATE,
+            key.0,
+            payload.as_ptr(),
+            payload.len(),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Revoke `key`, so that any further operation on it fails with
+/// `EKEYREVOKED`.
+pub fn keyctl_revoke(key: KeySerial) -> Result<()> {
+    let res = unsafe { libc::syscall(libc::SYS_keyctl, KEYCTL_REVOKE, key.0) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the permissions of `key`.
+pub fn keyctl_setperm(key: KeySerial, perm: KeyPerm) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_keyctl, KEYCTL_SETPERM, key.0, perm.bits())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Link `key` to `keyring`.
+pub fn keyctl_link(key: KeySerial, keyring: KeySerial) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_keyctl, KEYCTL_LINK, key.0, keyring.0)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove the link from `keyring` to `key`.
+pub fn keyctl_unlink(key: KeySerial, keyring: KeySerial) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_keyctl, KEYCTL_UNLINK, key.0, keyring.0)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Find the key of type `key_type` with the description `description` in
+/// `keyring` and the keyrings linked from it, and link it to `dest_keyring`,
+/// if any.
+///
+/// Fails with `ENOKEY` if the key cannot be found.
+pub fn keyctl_search<T: ?Sized + NixPath, D: ?Sized + NixPath>(
+    keyring: KeySerial,
+    key_type: &T,
+    description: &D,
+    dest_keyring: Option<KeySerial>,
+) -> Result<KeySerial> {
+    let res = key_type.with_nix_path(|t| {
+        description.with_nix_path(|d| unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                KEYCTL_SEARCH,
+                keyring.0,
+                t.as_ptr(),
+                d.as_ptr(),
+                dest_keyring.map_or(0, KeySerial::as_raw),
+            )
+        })
+    })??;
+
+    key_result(res)
+}
+
+/// Read the payload of `key`.
+///
+/// For a keyring, the payload is the array of the serial numbers of the
+/// keys linked from it, as `i32`s in native byte order.
+pub fn keyctl_read(key: KeySerial) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    loop {
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                KEYCTL_READ,
+                key.0,
+                buf.as_mut_ptr(),
+                buf.capacity(),
+            )
+        };
+        let len = Errno::result(res)? as usize;
+        // The payload may change between the calls, so retry until it fits.
+        if len <= buf.capacity() {
+            // SAFETY: the kernel initialized the first `len` bytes
+            unsafe { buf.set_len(len) };
+            return Ok(buf);
+        }
+        buf.reserve(len);
+    }
+}
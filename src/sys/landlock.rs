@@ -0,0 +1,264 @@
+//! Unprivileged sandboxing with Landlock.
+//!
+//! A process creates a ruleset declaring the kinds of accesses it wants to
+//! restrict, adds rules allowing some of those accesses to chosen file
+//! hierarchies or network ports, and finally enforces the ruleset on itself
+//! with [`landlock_restrict_self`].  All of the accesses that are handled by
+//! the ruleset, but not allowed by any rule, are then denied to the process
+//! and to its future children.  This cannot be undone.
+//!
+//! Newer kernels support more access rights, see [`landlock_abi_version`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::landlock::*;
+//! # use nix::sys::prctl::set_no_new_privs;
+//! # use std::fs::File;
+//! # use std::os::fd::AsFd;
+//! let ruleset = landlock_create_ruleset(
+//!     AccessFs::LANDLOCK_ACCESS_FS_READ_FILE
+//!         | AccessFs::LANDLOCK_ACCESS_FS_WRITE_FILE,
+//!     AccessNet::empty(),
+//! )
+//! .unwrap();
+//! // Only allow reading files below /usr.
+//! let usr = File::open("/usr").unwrap();
+//! landlock_add_rule(
+//!     &ruleset,
+//!     &Rule::PathBeneath {
+//!         allowed_access: AccessFs::LANDLOCK_ACCESS_FS_READ_FILE,
+//!         parent_fd: usr.as_fd(),
+//!     },
+//! )
+//! .unwrap();
+//! set_no_new_privs().unwrap();
+//! landlock_restrict_self(&ruleset).unwrap();
+//! ```
+//!
+//! # See Also
+//! [landlock(7)](https://man7.org/linux/man-pages/man7/landlock.7.html)
+
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::ptr;
+
+use crate::errno::Errno;
+use crate::Result;
+
+bitflags::bitflags! {
+    /// File system access rights, see [`landlock_create_ruleset`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct AccessFs: u64 {
+        /// Execute a file.
+        const LANDLOCK_ACCESS_FS_EXECUTE = 1 << 0;
+        /// Open a file for writing.
+        const LANDLOCK_ACCESS_FS_WRITE_FILE = 1 << 1;
+        /// Open a file for reading.
+        const LANDLOCK_ACCESS_FS_READ_FILE = 1 << 2;
+        /// Open or list a directory.
+        const LANDLOCK_ACCESS_FS_READ_DIR = 1 << 3;
+        /// Remove an empty directory, or rename one.
+        const LANDLOCK_ACCESS_FS_REMOVE_DIR = 1 << 4;
+        /// Unlink or rename a file.
+        const LANDLOCK_ACCESS_FS_REMOVE_FILE = 1 << 5;
+        /// Create, rename or link a character device.
+        const LANDLOCK_ACCESS_FS_MAKE_CHAR = 1 << 6;
+        /// Create or rename a directory.
+        const LANDLOCK_ACCESS_FS_MAKE_DIR = 1 << 7;
+        /// Create, rename or link a regular file.
+        const LANDLOCK_ACCESS_FS_MAKE_REG = 1 << 8;
+        /// Create, rename or link a UNIX domain socket.
+        const LANDLOCK_ACCESS_FS_MAKE_SOCK = 1 << 9;
+        /// Create, rename or link a named pipe.
+        const LANDLOCK_ACCESS_FS_MAKE_FIFO = 1 << 10;
+        /// Create, rename or link a block device.
+        const LANDLOCK_ACCESS_FS_MAKE_BLOCK = 1 << 11;
+        /// Create, rename or link a symbolic link.
+        const LANDLOCK_ACCESS_FS_MAKE_SYM = 1 << 12;
+        /// Link or rename a file from or to a different directory.  Since
+        /// ABI version 2.
+        const LANDLOCK_ACCESS_FS_REFER = 1 << 13;
+        /// Truncate a file.  Since ABI version 3.
+        const LANDLOCK_ACCESS_FS_TRUNCATE = 1 << 14;
+        /// Use `ioctl(2)` on a character or block device.  Since ABI
+        /// version 5.
+        const LANDLOCK_ACCESS_FS_IOCTL_DEV = 1 << 15;
+    }
+}
+
+bitflags::bitflags! {
+    /// Network access rights, see [`landlock_create_ruleset`].  Since ABI
+    /// version 4.
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct AccessNet: u64 {
+        /// Bind a TCP socket to a local port.
+        const LANDLOCK_ACCESS_NET_BIND_TCP = 1 << 0;
+        /// Connect a TCP socket to a remote port.
+        const LANDLOCK_ACCESS_NET_CONNECT_TCP = 1 << 1;
+    }
+}
+
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+const LANDLOCK_RULE_NET_PORT: libc::c_int = 2;
+
+#[repr(C)]
+struct landlock_ruleset_attr {
+    handled_access_fs: u64,
+    handled_access_net: u64,
+}
+
+#[repr(C, packed)]
+struct landlock_path_beneath_attr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+#[repr(C)]
+struct landlock_net_port_attr {
+    allowed_access: u64,
+    port: u64,
+}
+
+/// A rule allowing some accesses, see [`landlock_add_rule`].
+#[derive(Clone, Copy, Debug)]
+pub enum Rule<'fd> {
+    /// Allow `allowed_access` to the file hierarchy below `parent_fd`,
+    /// which may be opened with `O_PATH`.
+    PathBeneath {
+        /// The allowed accesses.
+        allowed_access: AccessFs,
+        /// The file or directory at the root of the hierarchy.
+        parent_fd: BorrowedFd<'fd>,
+    },
+    /// Allow `allowed_access` to the TCP port `port`.  Since ABI version 4.
+    NetPort {
+        /// The allowed accesses.
+        allowed_access: AccessNet,
+        /// The port number, in host byte order.
+        port: u16,
+    },
+}
+
+/// Get the highest Landlock ABI version supported by the kernel.
+///
+/// Fails with `EOPNOTSUPP` if Landlock is supported but disabled, and with
+/// `ENOSYS` if it is not supported at all.
+pub fn landlock_abi_version() -> Result<u32> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            ptr::null::<landlock_ruleset_attr>(),
+            0usize,
+            LANDLOCK_CREATE_RULESET_VERSION,
+        )
+    };
+    Errno::result(res).map(|v| v as u32)
+}
+
+/// Create a ruleset restricting the accesses `handled_access_fs` and
+/// `handled_access_net`.
+///
+/// Fails with `EINVAL` if the kernel does not support some of the accesses.
+pub fn landlock_create_ruleset(
+    handled_access_fs: AccessFs,
+    handled_access_net: AccessNet,
+) -> Result<OwnedFd> {
+    let attr = landlock_ruleset_attr {
+        handled_access_fs: handled_access_fs.bits(),
+        handled_access_net: handled_access_net.bits(),
+    };
+    // Older kernels only know about the first field, and reject the larger
+    // structure unless the rest of it is zero.
+    let size = if handled_access_net.is_empty() {
+        mem::size_of::<u64>()
+    } else {
+        mem::size_of::<landlock_ruleset_attr>()
+    };
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const landlock_ruleset_attr,
+            size,
+            0u32,
+        )
+    };
+    let fd = Errno::result(res)? as RawFd;
+
+    // SAFETY: `landlock_create_ruleset(2)` returns a valid owned fd on
+    // success
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Add `rule` to the ruleset `ruleset_fd`.
+///
+/// The allowed accesses must be handled by the ruleset.
+pub fn landlock_add_rule<Fd: AsFd>(
+    ruleset_fd: Fd,
+    rule: &Rule<'_>,
+) -> Result<()> {
+    let ruleset_fd = ruleset_fd.as_fd().as_raw_fd();
+    let res = match *rule {
+        Rule::PathBeneath {
+            allowed_access,
+            parent_fd,
+        } => {
+            let attr = landlock_path_beneath_attr {
+                allowed_access: allowed_access.bits(),
+                parent_fd: parent_fd.as_raw_fd(),
+            };
+            unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset_fd,
+                    LANDLOCK_RULE_PATH_BENEATH,
+                    &attr as *const landlock_path_beneath_attr,
+                    0u32,
+                )
+            }
+        }
+        Rule::NetPort {
+            allowed_access,
+            port,
+        } => {
+            let attr = landlock_net_port_attr {
+                allowed_access: allowed_access.bits(),
+                port: port.into(),
+            };
+            unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset_fd,
+                    LANDLOCK_RULE_NET_PORT,
+                    &attr as *const landlock_net_port_attr,
+                    0u32,
+                )
+            }
+        }
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Enforce the ruleset `ruleset_fd` on the calling thread.
+///
+/// This requires the thread to have set `no_new_privs`, see
+/// [`set_no_new_privs`](crate::sys::prctl::set_no_new_privs), or to have
+/// `CAP_SYS_ADMIN` in its user namespace.  Other threads of the process are
+/// not restricted, so this should be called before spawning any thread.
+pub fn landlock_restrict_self<Fd: AsFd>(ruleset_fd: Fd) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_restrict_self,
+            ruleset_fd.as_fd().as_raw_fd(),
+            0u32,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+const _: () = assert!(mem::size_of::<landlock_path_beneath_attr>() == 12);
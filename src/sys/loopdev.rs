@@ -0,0 +1,344 @@
+//! Loop devices, block devices backed by regular files.
+//!
+//! A free loop device is found with [`loop_ctl_get_free`] on
+//! `/dev/loop-control`, and a backing file is then attached to it with
+//! [`loop_configure`].  The block device can afterwards be used like any
+//! other, for instance to mount the file system image in the backing file.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::loopdev::*;
+//! # use std::fs::{File, OpenOptions};
+//! # use std::os::fd::AsFd;
+//! let ctl = File::open("/dev/loop-control").unwrap();
+//! let n = loop_ctl_get_free(&ctl).unwrap();
+//! let dev = OpenOptions::new()
+//!     .read(true)
+//!     .write(true)
+//!     .open(format!("/dev/loop{n}"))
+//!     .unwrap();
+//! let image = File::open("disk.img").unwrap();
+//! let info = LoopInfo64::new().set_flags(LoopFlags::LO_FLAGS_READ_ONLY);
+//! let config = LoopConfig::new(image.as_fd()).info(info);
+//! loop_configure(&dev, &config).unwrap();
+//! ```
+//!
+//! # See Also
+//! [loop(4)](https://man7.org/linux/man-pages/man4/loop.4.html)
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd};
+use std::os::unix::ffi::OsStrExt;
+
+use crate::errno::Errno;
+use crate::Result;
+
+const LO_NAME_SIZE: usize = 64;
+const LO_KEY_SIZE: usize = 32;
+
+// `struct loop_info64` and `struct loop_config` from `linux/loop.h`, which
+// are not yet provided by libc.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct loop_info64 {
+    lo_device: u64,
+    lo_inode: u64,
+    lo_rdevice: u64,
+    lo_offset: u64,
+    lo_sizelimit: u64,
+    lo_number: u32,
+    lo_encrypt_type: u32,
+    lo_encrypt_key_size: u32,
+    lo_flags: u32,
+    lo_file_name: [u8; LO_NAME_SIZE],
+    lo_crypt_name: [u8; LO_NAME_SIZE],
+    lo_encrypt_key: [u8; LO_KEY_SIZE],
+    lo_init: [u64; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct loop_config {
+    fd: u32,
+    block_size: u32,
+    info: loop_info64,
+    __reserved: [u64; 8],
+}
+
+const LOOP_SET_FD: libc::Ioctl = 0x4C00 as libc::Ioctl;
+const LOOP_CLR_FD: libc::Ioctl = 0x4C01 as libc::Ioctl;
+const LOOP_GET_STATUS64: libc::Ioctl = 0x4C05 as libc::Ioctl;
+const LOOP_SET_DIRECT_IO: libc::Ioctl = 0x4C08 as libc::Ioctl;
+const LOOP_CONFIGURE: libc::Ioctl = 0x4C0A as libc::Ioctl;
+const LOOP_CTL_GET_FREE: libc::Ioctl = 0x4C82 as libc::Ioctl;
+
+bitflags::bitflags! {
+    /// Flags of a loop device, see [`LoopInfo64::set_flags`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct LoopFlags: u32 {
+        /// The loop device is read-only.
+        const LO_FLAGS_READ_ONLY = 1;
+        /// Detach the backing file when the loop device is last closed.
+        const LO_FLAGS_AUTOCLEAR = 4;
+        /// Scan the loop device for partitions.
+        const LO_FLAGS_PARTSCAN = 8;
+        /// Access the backing file with direct I/O.
+        const LO_FLAGS_DIRECT_IO = 16;
+    }
+}
+
+/// The status of a loop device, see [`loop_configure`] and
+/// [`loop_get_status64`].
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct LoopInfo64(loop_info64);
+
+impl LoopInfo64 {
+    /// Create a status with no offset, size limit, flags or file name.
+    pub fn new() -> Self {
+        // SAFETY: all of the fields are integers
+        LoopInfo64(unsafe { mem::zeroed() })
+    }
+
+    /// Start the loop device at `offset` bytes in the backing file.
+    pub fn set_offset(mut self, offset: u64) -> Self {
+        self.0.lo_offset = offset;
+        self
+    }
+
+    /// Limit the size of the loop device to `sizelimit` bytes, or use the
+    /// whole backing file if 0.
+    pub fn set_sizelimit(mut self, sizelimit: u64) -> Self {
+        self.0.lo_sizelimit = sizelimit;
+        self
+    }
+
+    /// Set the flags of the loop device.
+    pub fn set_flags(mut self, flags: LoopFlags) -> Self {
+        self.0.lo_flags = flags.bits();
+        self
+    }
+
+    /// Set the name of the backing file reported by the loop device, which
+    /// is truncated to 63 bytes.
+    pub fn set_file_name<S: AsRef<OsStr> + ?Sized>(mut self, name: &S) -> Self {
+        let name = name.as_ref().as_bytes();
+        let len = name.len().min(LO_NAME_SIZE - 1);
+        self.0.lo_file_name = [0; LO_NAME_SIZE];
+        self.0.lo_file_name[..len].copy_from_slice(&name[..len]);
+        self
+    }
+
+    /// The device number of the file system holding the backing file.
+    pub fn device(&self) -> u64 {
+        self.0.lo_device
+    }
+
+    /// The inode number of the backing file.
+    pub fn inode(&self) -> u64 {
+        self.0.lo_inode
+    }
+
+    /// The device number of the backing file, if it is a device.
+    pub fn rdevice(&self) -> u64 {
+        self.0.lo_rdevice
+    }
+
+    /// The offset of the loop device in the backing file, in bytes.
+    pub fn offset(&self) -> u64 {
+        self.0.lo_offset
+    }
+
+    /// The size limit of the loop device, in bytes.
+    pub fn sizelimit(&self) -> u64 {
+        self.0.lo_sizelimit
+    }
+
+    /// The number of the loop device.
+    pub fn number(&self) -> u32 {
+        self.0.lo_number
+    }
+
+    /// The flags of the loop device.
+    pub fn flags(&self) -> LoopFlags {
+        LoopFlags::from_bits_retain(self.0.lo_flags)
+    }
+
+    /// The name of the backing file.
+    pub fn file_name(&self) -> &OsStr {
+        let name = &self.0.lo_file_name;
+        let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        OsStr::from_bytes(&name[..len])
+    }
+}
+
+impl Default for LoopInfo64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LoopInfo64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LoopInfo64")
+            .field("device", &self.device())
+            .field("inode", &self.inode())
+            .field("rdevice", &self.rdevice())
+            .field("offset", &self.offset())
+            .field("sizelimit", &self.sizelimit())
+            .field("number", &self.number())
+            .field("flags", &self.flags())
+            .field("file_name", &self.file_name())
+            .finish()
+    }
+}
+
+/// The configuration of a loop device, see [`loop_configure`].
+#[derive(Clone, Copy)]
+pub struct LoopConfig<'fd> {
+    config: loop_config,
+    _fd: PhantomData<BorrowedFd<'fd>>,
+}
+
+impl<'fd> LoopConfig<'fd> {
+    /// Create a configuration attaching the backing file `fd`, with the
+    /// default block size and status.
+    pub fn new(fd: BorrowedFd<'fd>) -> Self {
+        // SAFETY: all of the fields are integers
+        let mut config: loop_config = unsafe { mem::zeroed() };
+        config.fd = fd.as_raw_fd() as u32;
+        LoopConfig {
+            config,
+            _fd: PhantomData,
+        }
+    }
+
+    /// Set the logical block size of the loop device, which must be a power
+    /// of 2 between 512 and the page size.
+    pub fn block_size(mut self, block_size: u32) -> Self {
+        self.config.block_size = block_size;
+        self
+    }
+
+    /// Set the status of the loop device.
+    pub fn info(mut self, info: LoopInfo64) -> Self {
+        self.config.info = info.0;
+        self
+    }
+}
+
+impl fmt::Debug for LoopConfig<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LoopConfig")
+            .field("fd", &self.config.fd)
+            .field("block_size", &self.config.block_size)
+            .field("info", &LoopInfo64(self.config.info))
+            .finish()
+    }
+}
+
+/// Find a free loop device, allocating one if needed
+/// (`ioctl(LOOP_CTL_GET_FREE)`).
+///
+/// `ctl_fd` must be `/dev/loop-control`.  Returns the number `N` of the
+/// device `/dev/loopN`.
+pub fn loop_ctl_get_free<Fd: AsFd>(ctl_fd: Fd) -> Result<u32> {
+    let res =
+        unsafe { libc::ioctl(ctl_fd.as_fd().as_raw_fd(), LOOP_CTL_GET_FREE) };
+
+    Errno::result(res).map(|n| n as u32)
+}
+
+/// Attach a backing file to the loop device `loop_fd` and configure it at
+/// once (`ioctl(LOOP_CONFIGURE)`).
+///
+/// Fails with `EBUSY` if the loop device is already in use.  Since
+/// Linux 5.8.
+pub fn loop_configure<Fd: AsFd>(
+    loop_fd: Fd,
+    config: &LoopConfig<'_>,
+) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            loop_fd.as_fd().as_raw_fd(),
+            LOOP_CONFIGURE,
+            &config.config as *const loop_config,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Attach the backing file `backing_fd` to the loop device `loop_fd`
+/// (`ioctl(LOOP_SET_FD)`).
+///
+/// The loop device is read-only if `backing_fd` is.
+pub fn loop_set_fd<Fd1: AsFd, Fd2: AsFd>(
+    loop_fd: Fd1,
+    backing_fd: Fd2,
+) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            loop_fd.as_fd().as_raw_fd(),
+            LOOP_SET_FD,
+            backing_fd.as_fd().as_raw_fd() as libc::c_ulong,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Detach the backing file from the loop device `loop_fd`
+/// (`ioctl(LOOP_CLR_FD)`).
+///
+/// If the loop device is still open elsewhere, it is only detached once it
+/// is last closed.
+pub fn loop_clr_fd<Fd: AsFd>(loop_fd: Fd) -> Result<()> {
+    let res = unsafe { libc::ioctl(loop_fd.as_fd().as_raw_fd(), LOOP_CLR_FD) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Enable or disable direct I/O on the backing file of the loop device
+/// `loop_fd` (`ioctl(LOOP_SET_DIRECT_IO)`).
+///
+/// Fails with `EINVAL` if the backing file does not support direct I/O.
+pub fn loop_set_direct_io<Fd: AsFd>(
+    loop_fd: Fd,
+    direct_io: bool,
+) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            loop_fd.as_fd().as_raw_fd(),
+            LOOP_SET_DIRECT_IO,
+            libc::c_ulong::from(direct_io),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the status of the loop device `loop_fd`
+/// (`ioctl(LOOP_GET_STATUS64)`).
+///
+/// Fails with `ENXIO` if no backing file is attached.
+pub fn loop_get_status64<Fd: AsFd>(loop_fd: Fd) -> Result<LoopInfo64> {
+    let mut info = LoopInfo64::new();
+    let res = unsafe {
+        libc::ioctl(
+            loop_fd.as_fd().as_raw_fd(),
+            LOOP_GET_STATUS64,
+            &mut info.0 as *mut loop_info64,
+        )
+    };
+
+    Errno::result(res).map(|_| info)
+}
+
+const _: () = assert!(mem::size_of::<loop_info64>() == 232);
+const _: () = assert!(mem::size_of::<loop_config>() == 304);
@@ -1,9 +1,10 @@
 //! Interfaces for managing memory-backed files.
 
 use cfg_if::cfg_if;
-use std::os::unix::io::{FromRawFd, OwnedFd, RawFd};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 
 use crate::errno::Errno;
+use crate::fcntl::SealFlag;
 use crate::{NixPath, Result};
 
 libc_bitflags!(
@@ -110,3 +111,160 @@ pub fn memfd_create<P: NixPath + ?Sized>(
 
     Errno::result(res).map(|r| unsafe { OwnedFd::from_raw_fd(r as RawFd) })
 }
+
+/// An anonymous memory file that is being filled, before it is sealed.
+///
+/// Handing out a memory file, e.g. over a Unix socket, is only safe if the
+/// receiver can rely on its contents not changing under it.  `SealedMemFd`
+/// performs the steps needed for this in the right order: it creates the file
+/// with sealing allowed, sets its size, lets the caller fill it, and finally
+/// [seals](SealedMemFd::seal) it, returning the file descriptor to share.
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::memfd::SealedMemFd;
+/// let mut memfd = SealedMemFd::create("config", 5).unwrap();
+/// memfd.write_all_at(b"hello", 0).unwrap();
+/// let fd = memfd.seal_all().unwrap();
+/// // `fd` can now be sent to another process.
+/// ```
+#[derive(Debug)]
+pub struct SealedMemFd {
+    fd: OwnedFd,
+    len: usize,
+}
+
+impl SealedMemFd {
+    /// Create a new memory file of `len` bytes, initially filled with zeros.
+    ///
+    /// The file is created with `MFD_CLOEXEC` and `MFD_ALLOW_SEALING`.
+    pub fn create<P: NixPath + ?Sized>(name: &P, len: usize) -> Result<Self> {
+        let fd = memfd_create(
+            name,
+            MemFdCreateFlag::MFD_CLOEXEC | MemFdCreateFlag::MFD_ALLOW_SEALING,
+        )?;
+        let size = libc::off_t::try_from(len).map_err(|_| Errno::EFBIG)?;
+        crate::unistd::ftruncate(&fd, size)?;
+
+        Ok(Self { fd, len })
+    }
+
+    /// Create a sealed memory file with the given contents.
+    ///
+    /// This is a shorthand for [`create`](SealedMemFd::create),
+    /// [`write_all_at`](SealedMemFd::write_all_at) and
+    /// [`seal_all`](SealedMemFd::seal_all).
+    pub fn from_bytes<P: NixPath + ?Sized>(
+        name: &P,
+        contents: &[u8],
+    ) -> Result<OwnedFd> {
+        let memfd = Self::create(name, contents.len())?;
+        memfd.write_all_at(contents, 0)?;
+        memfd.seal_all()
+    }
+
+    /// The size of the file, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the file empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Write `buf` to the file at `offset`.
+    ///
+    /// `EFBIG` is returned if this would write past the size of the file.
+    pub fn write_all_at(&self, mut buf: &[u8], offset: usize) -> Result<()> {
+        if offset
+            .checked_add(buf.len())
+            .map_or(true, |end| end > self.len)
+        {
+            return Err(Errno::EFBIG);
+        }
+
+        let mut offset = offset;
+        while !buf.is_empty() {
+            let res = unsafe {
+                libc::pwrite(
+                    self.fd.as_raw_fd(),
+                    buf.as_ptr().cast(),
+                    buf.len(),
+                    offset as libc::off_t,
+                )
+            };
+            match Errno::result(res) {
+                Ok(n) => {
+                    buf = &buf[n as usize..];
+                    offset += n as usize;
+                }
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Map the whole file into memory, and let `f` fill it.
+    ///
+    /// The mapping is removed before this returns, as the file could not be
+    /// sealed against writes while a writable shared mapping exists.
+    #[cfg(feature = "mman")]
+    pub fn fill_with<F: FnOnce(&mut [u8])>(&mut self, f: F) -> Result<()> {
+        use crate::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
+
+        let Some(len) = std::num::NonZeroUsize::new(self.len) else {
+            f(&mut []);
+            return Ok(());
+        };
+        // SAFETY: the mapping is only accessed through the slice given to
+        // `f`, and removed afterwards.
+        unsafe {
+            let addr = mmap(
+                None,
+                len,
+                ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                MapFlags::MAP_SHARED,
+                &self.fd,
+                0,
+            )?;
+            f(std::slice::from_raw_parts_mut(
+                addr.as_ptr().cast(),
+                self.len,
+            ));
+            munmap(addr, self.len)
+        }
+    }
+
+    /// Seal the file, and return its file descriptor.
+    ///
+    /// The file offset is reset to the start of the file.  To prevent any
+    /// later modification, `seals` should include `F_SEAL_SHRINK`,
+    /// `F_SEAL_GROW` and `F_SEAL_WRITE`, and `F_SEAL_SEAL` to prevent the
+    /// seals from being changed; [`seal_all`](SealedMemFd::seal_all) does
+    /// that.
+    pub fn seal(self, seals: SealFlag) -> Result<OwnedFd> {
+        crate::fcntl::fcntl(&self.fd, crate::fcntl::F_ADD_SEALS(seals))?;
+        crate::unistd::lseek(&self.fd, 0, crate::unistd::Whence::SeekSet)?;
+        Ok(self.fd)
+    }
+
+    /// Seal the file against any modification, and return its file
+    /// descriptor.
+    pub fn seal_all(self) -> Result<OwnedFd> {
+        self.seal(
+            SealFlag::F_SEAL_SHRINK
+                | SealFlag::F_SEAL_GROW
+                | SealFlag::F_SEAL_WRITE
+                | SealFlag::F_SEAL_SEAL,
+        )
+    }
+}
+
+impl AsFd for SealedMemFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
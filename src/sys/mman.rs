@@ -46,6 +46,11 @@ libc_bitflags! {
         /// Place the mapping at exactly the address specified in `addr`.
         MAP_FIXED;
         /// Place the mapping at exactly the address specified in `addr`, but never clobber an existing range.
+        ///
+        /// If the range overlaps an existing mapping, `mmap` fails with `EEXIST` instead of
+        /// replacing it like `MAP_FIXED` would.  Kernels older than 4.17 ignore this flag and
+        /// treat `addr` as a hint, so callers should check that the returned address is the
+        /// requested one.
         #[cfg(target_os = "linux")]
         MAP_FIXED_NOREPLACE;
         /// To be used with `MAP_FIXED`, to forbid the system
@@ -161,7 +166,83 @@ libc_bitflags! {
     }
 }
 
+/// Huge page sizes, for [`MapFlags::huge_page_size`].
+///
+/// Which sizes are available depends on the architecture and the kernel
+/// configuration, see `/sys/kernel/mm/hugepages`.
+#[cfg(any(linux_android, target_os = "fuchsia"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HugePageSize {
+    /// 64 KiB pages
+    Kb64,
+    /// 512 KiB pages
+    Kb512,
+    /// 1 MiB pages
+    Mb1,
+    /// 2 MiB pages
+    Mb2,
+    /// 8 MiB pages
+    Mb8,
+    /// 16 MiB pages
+    Mb16,
+    /// 32 MiB pages
+    Mb32,
+    /// 256 MiB pages
+    Mb256,
+    /// 512 MiB pages
+    Mb512,
+    /// 1 GiB pages
+    Gb1,
+    /// 2 GiB pages
+    Gb2,
+    /// 16 GiB pages
+    Gb16,
+}
+
+#[cfg(any(linux_android, target_os = "fuchsia"))]
+impl HugePageSize {
+    /// The page size, in bytes.
+    pub const fn bytes(self) -> u64 {
+        1 << self.log2()
+    }
+
+    const fn log2(self) -> u32 {
+        match self {
+            HugePageSize::Kb64 => 16,
+            HugePageSize::Kb512 => 19,
+            HugePageSize::Mb1 => 20,
+            HugePageSize::Mb2 => 21,
+            HugePageSize::Mb8 => 23,
+            HugePageSize::Mb16 => 24,
+            HugePageSize::Mb32 => 25,
+            HugePageSize::Mb256 => 28,
+            HugePageSize::Mb512 => 29,
+            HugePageSize::Gb1 => 30,
+            HugePageSize::Gb2 => 31,
+            HugePageSize::Gb16 => 34,
+        }
+    }
+}
+
 impl MapFlags {
+    /// Create `MAP_HUGETLB` with the given huge page size.
+    ///
+    /// `mmap` fails with `EINVAL` if the system does not support that size,
+    /// and with `ENOMEM` if no page of that size is available.
+    ///
+    /// ```
+    /// # use nix::sys::mman::{HugePageSize, MapFlags};
+    /// let f = MapFlags::huge_page_size(HugePageSize::Gb1);
+    /// assert_eq!(f, MapFlags::MAP_HUGETLB | MapFlags::MAP_HUGE_1GB);
+    /// ```
+    #[cfg(any(linux_android, target_os = "fuchsia"))]
+    pub fn huge_page_size(size: HugePageSize) -> Self {
+        // All the sizes are in the range accepted by
+        // `map_hugetlb_with_size_log2`.
+        Self::map_hugetlb_with_size_log2(size.log2()).unwrap()
+    }
+
     /// Create `MAP_HUGETLB` with provided size of huge page.
     ///
     /// Under the hood it computes `MAP_HUGETLB | (huge_page_size_log2 << libc::MAP_HUGE_SHIFT`).
@@ -194,9 +275,16 @@ libc_bitflags! {
         /// Permit the kernel to relocate the mapping to a new virtual address, if necessary.
         #[cfg(target_os = "linux")]
         MREMAP_MAYMOVE;
-        /// Place the mapping at exactly the address specified in `new_address`.
+        /// Place the mapping at exactly the address specified in `new_address`, unmapping any
+        /// existing mapping there.  Requires `MREMAP_MAYMOVE`.
         #[cfg(target_os = "linux")]
         MREMAP_FIXED;
+        /// Move the mapping, but leave the old range mapped: the old range is emptied, so
+        /// accessing it faults, which can be handled with `userfaultfd(2)`.  Requires
+        /// `MREMAP_MAYMOVE`, and `old_size` equal to `new_size`.  Since Linux 5.7, and only for
+        /// private anonymous mappings before Linux 5.13.
+        #[cfg(target_os = "linux")]
+        MREMAP_DONTUNMAP;
         /// Place the mapping at exactly the address specified in `new_address`.
         #[cfg(target_os = "netbsd")]
         MAP_FIXED;
@@ -268,8 +356,37 @@ libc_enum! {
         #[cfg(linux_android)]
         MADV_DODUMP,
         /// Specify that the application no longer needs the pages in the given range.
+        ///
+        /// Unlike `MADV_DONTNEED`, the pages are only freed lazily, under memory pressure, and
+        /// writing to a page before that cancels its freeing.  On Linux, this is only supported
+        /// for private anonymous pages, since Linux 4.5.
         #[cfg(not(any(target_os = "aix", target_os = "hurd")))]
         MADV_FREE,
+        /// Zero-fill the pages in this range in the child after a `fork(2)`.  Since Linux 4.14.
+        #[cfg(linux_android)]
+        MADV_WIPEONFORK,
+        /// Undo the effect of `MADV_WIPEONFORK`.  Since Linux 4.14.
+        #[cfg(linux_android)]
+        MADV_KEEPONFORK,
+        /// Deactivate the pages in this range, making them a more likely target for reclaim.
+        /// Since Linux 5.4.
+        #[cfg(linux_android)]
+        MADV_COLD,
+        /// Reclaim the pages in this range immediately, writing them to swap if needed.  Since
+        /// Linux 5.4.
+        #[cfg(linux_android)]
+        MADV_PAGEOUT,
+        /// Populate (prefault) the page tables for this range readable, without writing to the
+        /// pages.  Since Linux 5.14.
+        #[cfg(linux_android)]
+        MADV_POPULATE_READ,
+        /// Populate (prefault) the page tables for this range writable.  Since Linux 5.14.
+        #[cfg(linux_android)]
+        MADV_POPULATE_WRITE,
+        /// Synchronously collapse the pages in this range into Transparent Huge Pages.  Since
+        /// Linux 6.1.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        MADV_COLLAPSE,
         /// Request that the system not flush the current range to disk unless it needs to.
         #[cfg(freebsdlike)]
         MADV_NOSYNC,
@@ -333,6 +450,23 @@ libc_bitflags! {
         MCL_CURRENT;
         /// Lock pages which will become mapped into the address space of the process in the future.
         MCL_FUTURE;
+        /// Together with `MCL_CURRENT` and/or `MCL_FUTURE`, only lock pages once they are faulted
+        /// in, instead of populating them all at once.  Since Linux 4.4.
+        #[cfg(linux_android)]
+        MCL_ONFAULT;
+    }
+}
+
+#[cfg(any(
+    target_os = "android",
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+))]
+libc_bitflags! {
+    /// Flags for [`mlock2`].
+    pub struct MlockFlags: libc::c_uint {
+        /// Lock pages once they are faulted in, instead of populating and locking them all at
+        /// once.
+        MLOCK_ONFAULT as libc::c_uint;
     }
 }
 
@@ -350,6 +484,32 @@ pub unsafe fn mlock(addr: NonNull<c_void>, length: size_t) -> Result<()> {
     unsafe { Errno::result(libc::mlock(addr.as_ptr(), length)).map(drop) }
 }
 
+/// Like [`mlock`], but with additional `flags`.
+///
+/// With [`MlockFlags::MLOCK_ONFAULT`], pages are only locked once they are
+/// faulted in, so a large range can be locked without populating it first.
+/// Requires Linux 4.4 or later.
+///
+/// # Safety
+///
+/// `addr` must meet all the requirements described in the [`mlock2(2)`] man page.
+///
+/// [`mlock2(2)`]: https://man7.org/linux/man-pages/man2/mlock2.2.html
+#[cfg(any(
+    target_os = "android",
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+))]
+pub unsafe fn mlock2(
+    addr: NonNull<c_void>,
+    length: size_t,
+    flags: MlockFlags,
+) -> Result<()> {
+    unsafe {
+        Errno::result(libc::mlock2(addr.as_ptr(), length, flags.bits() as _))
+            .map(drop)
+    }
+}
+
 /// Unlocks all memory pages that contain part of the address range with
 /// `length` bytes starting at `addr`.
 ///
@@ -451,6 +611,10 @@ pub unsafe fn mmap_anonymous(
 /// Expands (or shrinks) an existing memory mapping, potentially moving it at
 /// the same time.
 ///
+/// On Linux, `new_address` is only used with `MREMAP_FIXED`, in which case it
+/// must be `Some`.  With `MREMAP_DONTUNMAP`, the mapping is moved to a new
+/// address while the old range stays mapped, but empty.
+///
 /// # Safety
 ///
 /// See the `mremap(2)` [man page](https://man7.org/linux/man-pages/man2/mremap.2.html) for
@@ -511,6 +675,9 @@ pub unsafe fn munmap(addr: NonNull<c_void>, len: size_t) -> Result<()> {
 
 /// give advice about use of memory
 ///
+/// Kernels that do not know some advice return `EINVAL`, so callers using
+/// recent advice values should be ready to handle it.
+///
 /// # Safety
 ///
 /// See the [`madvise(2)`] man page.  Take special care when using
@@ -567,6 +734,32 @@ pub unsafe fn mprotect(
     }
 }
 
+/// Determine which pages of a memory range are resident in memory.
+///
+/// `addr` must be aligned to the page size, and the whole range must be
+/// mapped, `ENOMEM` is returned otherwise.  `EINVAL` is returned if `length`
+/// is too large for its number of pages to be computed.  The result holds
+/// one element per page of the range, which is `true` if the page is
+/// resident.  As pages can be evicted or faulted in at any time, it may
+/// already be outdated when this returns.
+///
+/// # See Also
+/// * [`mincore(2)`](https://man7.org/linux/man-pages/man2/mincore.2.html)
+#[cfg(any(linux_android, freebsdlike, apple_targets, target_os = "netbsd"))]
+pub fn mincore(addr: NonNull<c_void>, length: size_t) -> Result<Vec<bool>> {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    // `div_ceil` is not available on our MSRV.
+    let pages =
+        length.checked_add(page_size - 1).ok_or(Errno::EINVAL)? / page_size;
+    let mut vec = vec![0u8; pages];
+    let res = unsafe {
+        libc::mincore(addr.as_ptr(), length, vec.as_mut_ptr().cast())
+    };
+    Errno::result(res)?;
+
+    Ok(vec.into_iter().map(|page| page & 1 != 0).collect())
+}
+
 /// synchronize a mapped region
 ///
 /// # Safety
@@ -586,6 +779,205 @@ pub unsafe fn msync(
     }
 }
 
+/// An owned memory mapping, which is unmapped when dropped.
+///
+/// The contents of the mapping can be accessed as a byte slice through
+/// [`Deref`](std::ops::Deref), and through [`DerefMut`](std::ops::DerefMut)
+/// if it is also writable.  The mapping is always readable: `EINVAL` is
+/// returned when creating it, or changing its protection, without
+/// `PROT_READ`.  The raw
+/// [`mmap`] and [`munmap`] functions remain available for mappings that need
+/// to be managed by hand.
+///
+/// # Example
+///
+/// ```
+/// # use std::num::NonZeroUsize;
+/// # use nix::sys::mman::{MapFlags, MmapRegion, ProtFlags};
+/// let mut region = MmapRegion::anonymous(
+///     NonZeroUsize::new(4096).unwrap(),
+///     ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+///     MapFlags::MAP_PRIVATE,
+/// )
+/// .unwrap();
+/// region[0] = 0xFF;
+/// assert_eq!(region[0], 0xFF);
+/// region.mprotect(ProtFlags::PROT_READ).unwrap();
+/// assert_eq!(region[0], 0xFF);
+/// ```
+#[derive(Debug)]
+pub struct MmapRegion {
+    ptr: NonNull<c_void>,
+    len: NonZeroUsize,
+    prot: ProtFlags,
+}
+
+// SAFETY: `MmapRegion` owns its mapping exclusively, like a `Box<[u8]>`.
+unsafe impl Send for MmapRegion {}
+unsafe impl Sync for MmapRegion {}
+
+impl MmapRegion {
+    /// Create an anonymous mapping of `length` bytes, initialized to zero.
+    ///
+    /// `MAP_FIXED` is not allowed in `flags`, `EINVAL` is returned if it is
+    /// given, as it could replace existing mappings.
+    pub fn anonymous(
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+    ) -> Result<Self> {
+        if flags.contains(MapFlags::MAP_FIXED)
+            || !prot.contains(ProtFlags::PROT_READ)
+        {
+            return Err(Errno::EINVAL);
+        }
+        // SAFETY: no address is requested, so no existing mapping is
+        // affected.
+        let ptr = unsafe { mmap_anonymous(None, length, prot, flags)? };
+        Ok(Self {
+            ptr,
+            len: length,
+            prot,
+        })
+    }
+
+    /// Map `length` bytes of the file `f`, starting at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// The requirements of [`mmap`] apply.  Additionally, if the mapping is
+    /// shared, the file must not be modified through other means while the
+    /// contents of the mapping are borrowed, e.g. truncated by another
+    /// process, which would lead to `SIGBUS` or data races.
+    pub unsafe fn map<F: AsFd>(
+        addr: Option<NonZeroUsize>,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+        f: F,
+        offset: off_t,
+    ) -> Result<Self> {
+        if !prot.contains(ProtFlags::PROT_READ) {
+            return Err(Errno::EINVAL);
+        }
+        let ptr = unsafe { mmap(addr, length, prot, flags, f, offset)? };
+        Ok(Self {
+            ptr,
+            len: length,
+            prot,
+        })
+    }
+
+    /// Take ownership of an existing mapping.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `length` must describe a mapping that is not owned by
+    /// anything else, and whose protection is `prot`, which must contain
+    /// `PROT_READ`.
+    pub unsafe fn from_raw(
+        ptr: NonNull<c_void>,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+    ) -> Self {
+        Self {
+            ptr,
+            len: length,
+            prot,
+        }
+    }
+
+    /// Give up ownership of the mapping, without unmapping it, and return
+    /// its address and length.
+    pub fn into_raw(self) -> (NonNull<c_void>, NonZeroUsize) {
+        let region = std::mem::ManuallyDrop::new(self);
+        (region.ptr, region.len)
+    }
+
+    /// The address of the mapping.
+    pub fn as_ptr(&self) -> NonNull<c_void> {
+        self.ptr
+    }
+
+    /// The length of the mapping, in bytes.
+    // The mapping is never empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// The current protection of the mapping.
+    pub fn prot(&self) -> ProtFlags {
+        self.prot
+    }
+
+    /// Change the protection of the mapping, see [`mprotect`].
+    ///
+    /// `EINVAL` is returned if `prot` does not contain `PROT_READ`.
+    pub fn mprotect(&mut self, prot: ProtFlags) -> Result<()> {
+        if !prot.contains(ProtFlags::PROT_READ) {
+            return Err(Errno::EINVAL);
+        }
+        // SAFETY: the mapping is exclusively borrowed, so no reference to
+        // its contents exists, and the protection is tracked for later
+        // accesses.
+        unsafe { mprotect(self.ptr, self.len(), prot)? };
+        self.prot = prot;
+        Ok(())
+    }
+
+    /// Give advice about the use of the mapping, see [`madvise`].
+    ///
+    /// # Safety
+    ///
+    /// See [`madvise`].
+    pub unsafe fn madvise(&mut self, advise: MmapAdvise) -> Result<()> {
+        unsafe { madvise(self.ptr, self.len(), advise) }
+    }
+
+    /// Synchronize the mapping with the file it maps, see [`msync`].
+    pub fn msync(&self, flags: MsFlags) -> Result<()> {
+        // SAFETY: the whole range is owned by `self`.
+        unsafe { msync(self.ptr, self.len(), flags) }
+    }
+}
+
+impl std::ops::Deref for MmapRegion {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the mapping is readable, and owned by `self`.
+        unsafe {
+            std::slice::from_raw_parts(self.ptr.as_ptr().cast(), self.len())
+        }
+    }
+}
+
+impl std::ops::DerefMut for MmapRegion {
+    /// # Panics
+    ///
+    /// Panics if the mapping is not writable.
+    fn deref_mut(&mut self) -> &mut [u8] {
+        assert!(
+            self.prot.contains(ProtFlags::PROT_WRITE),
+            "the mapping is not writable"
+        );
+        // SAFETY: the mapping is readable and writable, and exclusively
+        // borrowed.
+        unsafe {
+            std::slice::from_raw_parts_mut(self.ptr.as_ptr().cast(), self.len())
+        }
+    }
+}
+
+impl Drop for MmapRegion {
+    fn drop(&mut self) {
+        // SAFETY: the mapping is owned by `self`, and no longer borrowed.
+        let res = unsafe { munmap(self.ptr, self.len()) };
+        debug_assert!(res.is_ok(), "munmap failed: {res:?}");
+    }
+}
+
 #[cfg(not(target_os = "android"))]
 feature! {
 #![feature = "fs"]
@@ -633,3 +1025,341 @@ pub fn shm_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
 
     Errno::result(ret).map(drop)
 }
+
+#[cfg(not(target_os = "android"))]
+feature! {
+#![feature = "fs"]
+/// A mapped POSIX shared memory object.
+///
+/// This performs the usual sequence of [`shm_open`], `ftruncate` and [`mmap`],
+/// and undoes it when dropped: the mapping is removed, and, if the object was
+/// created by [`SharedMem::create`], the object is unlinked.  Whether the
+/// object is unlinked can be changed with [`SharedMem::set_unlink_on_drop`].
+///
+/// The contents of the mapping can be accessed through [`SharedMem::region`].
+/// Note that other processes may modify them concurrently.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::num::NonZeroUsize;
+/// # use nix::sys::mman::{ProtFlags, SharedMem};
+/// let mut shm = SharedMem::create(
+///     "/my-shm",
+///     NonZeroUsize::new(4096).unwrap(),
+///     ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+/// )
+/// .unwrap();
+/// let region = unsafe { shm.region_mut() };
+/// region[..5].copy_from_slice(b"hello");
+/// // Another process can now open "/my-shm" with `SharedMem::open`.
+/// ```
+#[derive(Debug)]
+pub struct SharedMem {
+    region: MmapRegion,
+    fd: std::os::unix::io::OwnedFd,
+    name: std::ffi::CString,
+    unlink_on_drop: bool,
+}
+
+impl SharedMem {
+    /// Create a new shared memory object of `length` bytes, and map it.
+    ///
+    /// The object is created with mode `0600`, and `EEXIST` is returned if it
+    /// already exists.  It is unlinked on drop, unless
+    /// [`set_unlink_on_drop`](SharedMem::set_unlink_on_drop) is used.
+    pub fn create<P: ?Sized + NixPath>(
+        name: &P,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+    ) -> Result<Self> {
+        let name = name.with_nix_path(|cstr| cstr.to_owned())?;
+        let fd = shm_open(
+            name.as_c_str(),
+            OFlag::O_CREAT | OFlag::O_EXCL | OFlag::O_RDWR,
+            Mode::S_IRUSR | Mode::S_IWUSR,
+        )?;
+        let res = off_t::try_from(length.get())
+            .map_err(|_| Errno::EFBIG)
+            .and_then(|len| crate::unistd::ftruncate(&fd, len))
+            .and_then(|_| unsafe {
+                MmapRegion::map(None, length, prot, MapFlags::MAP_SHARED, &fd, 0)
+            });
+        match res {
+            Ok(region) => Ok(Self { region, fd, name, unlink_on_drop: true }),
+            Err(e) => {
+                let _ = shm_unlink(name.as_c_str());
+                Err(e)
+            }
+        }
+    }
+
+    /// Open an existing shared memory object, and map it entirely.
+    ///
+    /// The object is opened read-only, unless `prot` contains `PROT_WRITE`.
+    /// `EINVAL` is returned if it is empty.  It is not unlinked on drop,
+    /// unless [`set_unlink_on_drop`](SharedMem::set_unlink_on_drop) is used.
+    pub fn open<P: ?Sized + NixPath>(name: &P, prot: ProtFlags) -> Result<Self> {
+        let name = name.with_nix_path(|cstr| cstr.to_owned())?;
+        let oflag = if prot.contains(ProtFlags::PROT_WRITE) {
+            OFlag::O_RDWR
+        } else {
+            OFlag::O_RDONLY
+        };
+        let fd = shm_open(name.as_c_str(), oflag, Mode::empty())?;
+        let size = crate::sys::stat::fstat(&fd)?.st_size;
+        let length = usize::try_from(size)
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .ok_or(Errno::EINVAL)?;
+        let region = unsafe {
+            MmapRegion::map(None, length, prot, MapFlags::MAP_SHARED, &fd, 0)?
+        };
+        Ok(Self { region, fd, name, unlink_on_drop: false })
+    }
+
+    /// Choose whether the object is unlinked when this is dropped.
+    pub fn set_unlink_on_drop(&mut self, unlink: bool) {
+        self.unlink_on_drop = unlink;
+    }
+
+    /// The name of the object.
+    pub fn name(&self) -> &std::ffi::CStr {
+        &self.name
+    }
+
+    /// The mapping of the object.
+    ///
+    /// # Safety
+    ///
+    /// No other process or mapping may modify the object while the contents
+    /// of the mapping are borrowed.
+    pub unsafe fn region(&self) -> &MmapRegion {
+        &self.region
+    }
+
+    /// The mapping of the object, mutably.
+    ///
+    /// # Safety
+    ///
+    /// No other process or mapping may access the object while the contents
+    /// of the mapping are borrowed.
+    pub unsafe fn region_mut(&mut self) -> &mut MmapRegion {
+        &mut self.region
+    }
+}
+
+impl AsFd for SharedMem {
+    fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl Drop for SharedMem {
+    fn drop(&mut self) {
+        if self.unlink_on_drop {
+            let _ = shm_unlink(self.name.as_c_str());
+        }
+    }
+}
+}
+
+/// The range of a file to query with [`cachestat`] (`struct cachestat_range`).
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CachestatRange {
+    /// Offset of the start of the range, in bytes.
+    pub off: u64,
+    /// Length of the range, in bytes.  `0` means up to the end of the file.
+    pub len: u64,
+}
+
+/// Page cache statistics of a file range, returned by [`cachestat`]
+/// (`struct cachestat`).
+///
+/// All values are numbers of pages.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cachestat {
+    /// Pages that are in the page cache.
+    pub nr_cache: u64,
+    /// Cached pages that are dirty.
+    pub nr_dirty: u64,
+    /// Cached pages that are being written back.
+    pub nr_writeback: u64,
+    /// Pages that have been evicted from the page cache.
+    pub nr_evicted: u64,
+    /// Evicted pages that would still be in the page cache, had the cache
+    /// been somewhat larger, i.e. pages that were recently evicted.
+    pub nr_recently_evicted: u64,
+}
+
+// The number of `cachestat`, which libc doesn't export on every architecture.
+// Like other recent syscalls, it has the same number everywhere, plus the
+// per-ABI offset on mips and x32.
+#[cfg(target_os = "linux")]
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "mips")] {
+        const SYS_CACHESTAT: libc::c_long = 4000 + 451;
+    } else if #[cfg(all(target_arch = "mips64", target_pointer_width = "64"))] {
+        const SYS_CACHESTAT: libc::c_long = 5000 + 451;
+    } else if #[cfg(target_arch = "mips64")] {
+        const SYS_CACHESTAT: libc::c_long = 6000 + 451;
+    } else if #[cfg(all(target_arch = "x86_64", target_pointer_width = "32"))] {
+        const SYS_CACHESTAT: libc::c_long = 0x4000_0000 + 451;
+    } else {
+        const SYS_CACHESTAT: libc::c_long = 451;
+    }
+}
+
+/// Query the page cache state of a range of a file.
+///
+/// Unlike [`mincore`](https://man7.org/linux/man-pages/man2/mincore.2.html),
+/// this does not require the file to be mapped, and also reports dirty, under
+/// writeback and evicted pages.  Requires Linux 6.5 or later, `ENOSYS` is
+/// returned otherwise.
+///
+/// # See Also
+/// * [`cachestat(2)`](https://man7.org/linux/man-pages/man2/cachestat.2.html)
+#[cfg(target_os = "linux")]
+pub fn cachestat<Fd: AsFd>(fd: Fd, range: CachestatRange) -> Result<Cachestat> {
+    let mut cstat = Cachestat::default();
+    let res = unsafe {
+        libc::syscall(
+            SYS_CACHESTAT,
+            fd.as_fd().as_raw_fd(),
+            &range as *const CachestatRange,
+            &mut cstat as *mut Cachestat,
+            0 as libc::c_uint,
+        )
+    };
+    Errno::result(res).map(|_| cstat)
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+// libc does not export the `PKEY_DISABLE_*` constants, so `libc_bitflags!`
+// can't be used.  The values are from `<asm-generic/mman-common.h>`.
+::bitflags::bitflags! {
+    /// Access restrictions of a memory protection key, for [`pkey_alloc`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct PkeyAccessRights: libc::c_uint {
+        /// Disable all data access to memory covered by the key.
+        const PKEY_DISABLE_ACCESS = 0x1;
+        /// Disable write access to memory covered by the key.
+        const PKEY_DISABLE_WRITE = 0x2;
+    }
+}
+
+/// A memory protection key, allocated with [`pkey_alloc`].
+///
+/// The key is freed when this is dropped.  Memory assigned to it with
+/// [`pkey_mprotect`] should be unmapped, or assigned to another key, before
+/// that.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Pkey(c_int);
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+impl Pkey {
+    /// The raw key number.
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+impl Drop for Pkey {
+    fn drop(&mut self) {
+        let res = unsafe { libc::syscall(libc::SYS_pkey_free, self.0) };
+        debug_assert_eq!(res, 0, "pkey_free failed: {}", Errno::last());
+    }
+}
+
+/// Allocate a memory protection key, with the given initial access rights
+/// for the calling thread.
+///
+/// Other threads get the default access rights for the key, which usually
+/// disable all access.  `ENOSPC` is returned if the hardware or kernel does
+/// not support protection keys, or if all keys are in use.
+///
+/// # See Also
+/// * [`pkey_alloc(2)`](https://man7.org/linux/man-pages/man2/pkey_alloc.2.html)
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn pkey_alloc(access_rights: PkeyAccessRights) -> Result<Pkey> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_pkey_alloc,
+            0 as libc::c_uint,
+            access_rights.bits(),
+        )
+    };
+    Errno::result(res).map(|pkey| Pkey(pkey as c_int))
+}
+
+/// Free a memory protection key, reporting any error.
+///
+/// This is the same as dropping `pkey`, except that errors are returned.
+///
+/// # See Also
+/// * [`pkey_free(2)`](https://man7.org/linux/man-pages/man2/pkey_free.2.html)
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn pkey_free(pkey: Pkey) -> Result<()> {
+    let pkey = std::mem::ManuallyDrop::new(pkey);
+    let res = unsafe { libc::syscall(libc::SYS_pkey_free, pkey.0) };
+    Errno::result(res).map(drop)
+}
+
+/// Set protection of memory mapping, and assign it to a memory protection
+/// key.
+///
+/// Access to the memory is then restricted by both `prot`, and by the access
+/// rights of the calling thread for `pkey`.
+///
+/// # Safety
+///
+/// See [`mprotect`].
+///
+/// # See Also
+/// * [`pkey_mprotect(2)`](https://man7.org/linux/man-pages/man2/pkey_mprotect.2.html)
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub unsafe fn pkey_mprotect(
+    addr: NonNull<c_void>,
+    length: size_t,
+    prot: ProtFlags,
+    pkey: &Pkey,
+) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_pkey_mprotect,
+            addr.as_ptr(),
+            length,
+            prot.bits(),
+            pkey.0,
+        )
+    };
+    Errno::result(res).map(drop)
+}
@@ -10,6 +10,24 @@ feature! {
     pub mod aio;
 }
 
+#[cfg(apple_targets)]
+feature! {
+    #![feature = "fs"]
+    pub mod attrlist;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "caps"]
+    pub mod caps;
+}
+
+#[cfg(target_os = "freebsd")]
+feature! {
+    #![feature = "capsicum"]
+    pub mod capsicum;
+}
+
 feature! {
     #![feature = "event"]
 
@@ -20,11 +38,20 @@ feature! {
     #[cfg(bsd)]
     pub mod event;
 
+    #[cfg(solarish)]
+    pub mod port;
+
     /// Event file descriptor.
     #[cfg(any(linux_android, target_os = "freebsd"))]
     pub mod eventfd;
 }
 
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+feature! {
+    #![feature = "fs"]
+    pub mod extattr;
+}
+
 #[cfg(target_os = "linux")]
 feature! {
     #![feature = "fanotify"]
@@ -37,6 +64,24 @@ feature! {
 #[macro_use]
 pub mod ioctl;
 
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "fs"]
+    pub mod fsverity;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "fs"]
+    pub mod loopdev;
+}
+
+#[cfg(any(target_os = "linux", apple_targets))]
+feature! {
+    #![feature = "ipc"]
+    pub mod ipc;
+}
+
 #[cfg(any(linux_android, target_os = "freebsd"))]
 feature! {
     #![feature = "fs"]
@@ -49,6 +94,12 @@ feature! {
     pub mod mman;
 }
 
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "ipc"]
+    pub mod msg;
+}
+
 #[cfg(target_os = "linux")]
 feature! {
     #![feature = "personality"]
@@ -101,6 +152,12 @@ feature! {
     pub mod select;
 }
 
+#[cfg(any(target_os = "linux", apple_targets))]
+feature! {
+    #![feature = "ipc"]
+    pub mod sem;
+}
+
 #[cfg(any(linux_android, freebsdlike, apple_targets, solarish))]
 feature! {
     #![feature = "zerocopy"]
@@ -139,6 +196,24 @@ feature! {
     pub mod statvfs;
 }
 
+#[cfg(any(
+    target_os = "android",
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+))]
+feature! {
+    #![feature = "fs"]
+    pub mod statx;
+}
+
+#[cfg(linux_android)]
+feature! {
+    #![feature = "fs"]
+    pub mod swap;
+}
+
+#[cfg(bsd)]
+pub mod sysctl;
+
 #[cfg(linux_android)]
 #[allow(missing_docs)]
 pub mod sysinfo;
@@ -173,6 +248,42 @@ feature! {
     pub mod inotify;
 }
 
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "io_uring"]
+    pub mod io_uring;
+}
+
+#[cfg(target_os = "freebsd")]
+feature! {
+    #![feature = "jail"]
+    pub mod jail;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "key"]
+    pub mod key;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "landlock"]
+    pub mod landlock;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "perf_event"]
+    pub mod perf_event;
+}
+
+#[cfg(target_os = "linux")]
+feature! {
+    #![feature = "seccomp"]
+    pub mod seccomp;
+}
+
 #[cfg(linux_android)]
 feature! {
     #![feature = "time"]
@@ -193,3 +304,9 @@ feature! {
     #![feature = "time"]
     pub mod timer;
 }
+
+#[cfg(any(linux_android, apple_targets))]
+feature! {
+    #![feature = "fs"]
+    pub mod xattr;
+}
@@ -0,0 +1,259 @@
+//! System V message queues.
+//!
+//! A message queue holds messages made of a positive type and a payload of
+//! bytes.  Receivers can select messages by type, so a single queue can be
+//! used for several channels.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::sys::ipc::{IpcGetFlags, IPC_PRIVATE};
+//! # use nix::sys::msg::{msgctl_rmid, msgget, msgrcv, msgsnd, MsgBuf, MsgFlags};
+//! # use nix::sys::stat::Mode;
+//! let id = msgget(IPC_PRIVATE, IpcGetFlags::IPC_CREAT, Mode::S_IRWXU).unwrap();
+//! msgsnd(id, &MsgBuf::new(1, b"hello"), MsgFlags::empty()).unwrap();
+//!
+//! let mut msg = MsgBuf::with_capacity(64);
+//! msgrcv(id, &mut msg, 0, MsgFlags::empty()).unwrap();
+//! assert_eq!(msg.mtype(), 1);
+//! assert_eq!(msg.payload(), b"hello");
+//! msgctl_rmid(id).unwrap();
+//! ```
+
+use std::mem;
+
+use libc::{c_int, c_long};
+
+use crate::errno::Errno;
+use crate::sys::ipc::{key_t, IpcGetFlags, IpcPerm};
+use crate::sys::stat::Mode;
+use crate::unistd::Pid;
+use crate::Result;
+
+/// Identifier of a message queue, returned by [`msgget`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MsgId(c_int);
+
+impl MsgId {
+    /// Create a `MsgId` from a raw identifier, as obtained from another
+    /// process.
+    pub const fn from_raw(id: c_int) -> Self {
+        MsgId(id)
+    }
+
+    /// Get the raw identifier.
+    pub const fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+/// Get the identifier of the message queue associated with `key`, creating
+/// it with permissions `mode` if requested by `flags`.
+///
+/// # See Also
+/// * [`msgget(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/msgget.html)
+pub fn msgget(key: key_t, flags: IpcGetFlags, mode: Mode) -> Result<MsgId> {
+    let res = unsafe { libc::msgget(key, flags.bits() | mode.bits() as c_int) };
+    Errno::result(res).map(MsgId)
+}
+
+libc_bitflags! {
+    /// Flags for [`msgsnd`] and [`msgrcv`].
+    pub struct MsgFlags: c_int {
+        /// Fail with `EAGAIN` when sending to a full queue, or `ENOMSG` when
+        /// no matching message is queued, instead of blocking.
+        IPC_NOWAIT;
+        /// Truncate received messages that are larger than the buffer,
+        /// instead of failing with `E2BIG`.
+        MSG_NOERROR;
+        /// Receive the first message whose type is not `msgtyp`.
+        MSG_EXCEPT;
+    }
+}
+
+const MTYPE_SIZE: usize = mem::size_of::<c_long>();
+
+/// A message, as sent with [`msgsnd`] or received with [`msgrcv`].
+///
+/// This has the layout expected by the kernel: a `long` message type,
+/// followed by the payload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MsgBuf {
+    // Stored as `c_long`s so that `mtype` is properly aligned.
+    buf: Vec<c_long>,
+    len: usize,
+}
+
+impl MsgBuf {
+    /// Create a message of type `mtype`, which must be positive, with the
+    /// given payload.
+    pub fn new(mtype: c_long, payload: &[u8]) -> Self {
+        let mut msg = Self::with_capacity(payload.len());
+        msg.buf[0] = mtype;
+        msg.len = payload.len();
+        msg.payload_mut().copy_from_slice(payload);
+        msg
+    }
+
+    /// Create an empty message that can receive payloads of up to `capacity`
+    /// bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let words = (capacity + MTYPE_SIZE - 1) / MTYPE_SIZE;
+        MsgBuf {
+            buf: vec![0; 1 + words],
+            len: 0,
+        }
+    }
+
+    /// The type of the message.
+    pub fn mtype(&self) -> c_long {
+        self.buf[0]
+    }
+
+    /// The payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(self.buf[1..].as_ptr().cast(), self.len)
+        }
+    }
+
+    /// The payload of the message, mutably.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.buf[1..].as_mut_ptr().cast(),
+                self.len,
+            )
+        }
+    }
+
+    /// The largest payload that can be stored in this message.
+    pub fn capacity(&self) -> usize {
+        (self.buf.len() - 1) * MTYPE_SIZE
+    }
+}
+
+/// Send the message `msg` to the queue `id`, blocking while the queue is full
+/// unless `IPC_NOWAIT` is given.
+///
+/// # See Also
+/// * [`msgsnd(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/msgsnd.html)
+pub fn msgsnd(id: MsgId, msg: &MsgBuf, flags: MsgFlags) -> Result<()> {
+    let res = unsafe {
+        libc::msgsnd(id.0, msg.buf.as_ptr().cast(), msg.len, flags.bits())
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Receive a message from the queue `id` into `msg`, returning the length of
+/// its payload.
+///
+/// `msgtyp` selects the message to receive:
+/// * 0 receives the first message of the queue.
+/// * A positive value receives the first message of that type, or of any
+///   other type with `MSG_EXCEPT`.
+/// * A negative value receives the first message with the lowest type that is
+///   at most the absolute value of `msgtyp`.
+///
+/// Messages larger than the capacity of `msg` fail with `E2BIG`, and stay
+/// queued, unless `MSG_NOERROR` is given.
+///
+/// # See Also
+/// * [`msgrcv(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/msgrcv.html)
+pub fn msgrcv(
+    id: MsgId,
+    msg: &mut MsgBuf,
+    msgtyp: c_long,
+    flags: MsgFlags,
+) -> Result<usize> {
+    let res = unsafe {
+        libc::msgrcv(
+            id.0,
+            msg.buf.as_mut_ptr().cast(),
+            msg.capacity(),
+            msgtyp,
+            flags.bits(),
+        )
+    };
+    let len = Errno::result(res)? as usize;
+    msg.len = len;
+    Ok(len)
+}
+
+/// Remove the message queue `id` (`msgctl(IPC_RMID)`), waking up all
+/// processes blocked on it with `EIDRM`.
+///
+/// # See Also
+/// * [`msgctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/msgctl.html)
+pub fn msgctl_rmid(id: MsgId) -> Result<()> {
+    let res =
+        unsafe { libc::msgctl(id.0, libc::IPC_RMID, std::ptr::null_mut()) };
+    Errno::result(res).map(drop)
+}
+
+/// The status of a message queue, returned by [`msgctl_stat`].
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct MsqidDs(libc::msqid_ds);
+
+impl MsqidDs {
+    /// The ownership and permissions of the queue.
+    pub fn perm(&self) -> IpcPerm {
+        IpcPerm(self.0.msg_perm)
+    }
+
+    /// The number of messages in the queue.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn qnum(&self) -> u64 {
+        self.0.msg_qnum as u64
+    }
+
+    /// The maximum number of bytes allowed in the queue.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn qbytes(&self) -> u64 {
+        self.0.msg_qbytes as u64
+    }
+
+    /// The process that sent the last message, if any.
+    pub fn lspid(&self) -> Option<Pid> {
+        (self.0.msg_lspid != 0).then(|| Pid::from_raw(self.0.msg_lspid))
+    }
+
+    /// The process that received the last message, if any.
+    pub fn lrpid(&self) -> Option<Pid> {
+        (self.0.msg_lrpid != 0).then(|| Pid::from_raw(self.0.msg_lrpid))
+    }
+
+    /// The time of the last [`msgsnd`], or 0.
+    pub fn stime(&self) -> libc::time_t {
+        self.0.msg_stime
+    }
+
+    /// The time of the last [`msgrcv`], or 0.
+    pub fn rtime(&self) -> libc::time_t {
+        self.0.msg_rtime
+    }
+
+    /// The time of the creation or last change of the queue.
+    pub fn ctime(&self) -> libc::time_t {
+        self.0.msg_ctime
+    }
+}
+
+impl AsRef<libc::msqid_ds> for MsqidDs {
+    fn as_ref(&self) -> &libc::msqid_ds {
+        &self.0
+    }
+}
+
+/// Get the status of the message queue `id` (`msgctl(IPC_STAT)`).
+///
+/// # See Also
+/// * [`msgctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/msgctl.html)
+pub fn msgctl_stat(id: MsgId) -> Result<MsqidDs> {
+    let mut buf = mem::MaybeUninit::<libc::msqid_ds>::uninit();
+    let res = unsafe { libc::msgctl(id.0, libc::IPC_STAT, buf.as_mut_ptr()) };
+    Errno::result(res)?;
+
+    Ok(MsqidDs(unsafe { buf.assume_init() }))
+}
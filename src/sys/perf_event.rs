@@ -0,0 +1,538 @@
+//! Performance monitoring counters.
+//!
+//! A counter is described by a [`PerfEventAttr`], such as the number of
+//! instructions executed or of page faults, and opened with
+//! [`perf_event_open`] for a process, a CPU or both.  It is then controlled
+//! with [`perf_event_enable`], [`perf_event_disable`] and
+//! [`perf_event_reset`], and read with [`perf_event_read`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::perf_event::*;
+//! # use nix::unistd::Pid;
+//! let attr = PerfEventAttr::hardware(PerfHwId::PERF_COUNT_HW_INSTRUCTIONS)
+//!     .flags(PerfEventFlags::DISABLED | PerfEventFlags::EXCLUDE_KERNEL);
+//! let fd = perf_event_open(
+//!     &attr,
+//!     Some(Pid::from_raw(0)),
+//!     None,
+//!     None,
+//!     PerfFlags::PERF_FLAG_FD_CLOEXEC,
+//! )
+//! .unwrap();
+//! perf_event_enable(&fd, false).unwrap();
+//! // ...
+//! perf_event_disable(&fd, false).unwrap();
+//! let count = perf_event_read(&fd, ReadFormat::empty()).unwrap();
+//! println!("{} instructions", count.values[0].value);
+//! ```
+//!
+//! # See Also
+//! [perf_event_open(2)](https://man7.org/linux/man-pages/man2/perf_event_open.2.html)
+
+use std::fmt;
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+
+use libc::c_int;
+
+use crate::errno::Errno;
+use crate::unistd::Pid;
+use crate::Result;
+
+// `struct perf_event_attr` from `linux/perf_event.h`, version 8, which is
+// not yet provided by libc.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct perf_event_attr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period_or_freq: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events_or_watermark: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    __reserved_2: u16,
+    aux_sample_size: u32,
+    __reserved_3: u32,
+    sig_data: u64,
+    config3: u64,
+}
+
+const PERF_EVENT_IOC_ENABLE: libc::Ioctl = request_code_none!(b'$', 0);
+const PERF_EVENT_IOC_DISABLE: libc::Ioctl = request_code_none!(b'$', 1);
+const PERF_EVENT_IOC_RESET: libc::Ioctl = request_code_none!(b'$', 3);
+const PERF_IOC_FLAG_GROUP: libc::c_ulong = 1;
+
+// The `freq` bit of `perf_event_attr::flags`.
+const FREQ: u64 = 1 << 10;
+
+/// The type of a counter, see [`PerfEventAttr::new`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct PerfType(u32);
+
+impl PerfType {
+    /// A generalized hardware event, see [`PerfHwId`].
+    pub const PERF_TYPE_HARDWARE: Self = Self(0);
+    /// A software event provided by the kernel, see [`PerfSwId`].
+    pub const PERF_TYPE_SOFTWARE: Self = Self(1);
+    /// A tracepoint, whose id is found in `tracing/events/*/*/id` in
+    /// debugfs.
+    pub const PERF_TYPE_TRACEPOINT: Self = Self(2);
+    /// A hardware cache event.
+    pub const PERF_TYPE_HW_CACHE: Self = Self(3);
+    /// A raw, implementation-specific, hardware event.
+    pub const PERF_TYPE_RAW: Self = Self(4);
+    /// A hardware breakpoint.
+    pub const PERF_TYPE_BREAKPOINT: Self = Self(5);
+
+    /// Create a type from its raw value, such as the type of a dynamic PMU
+    /// found in `/sys/bus/event_source/devices/*/type`.
+    pub const fn from_raw(type_: u32) -> Self {
+        Self(type_)
+    }
+
+    /// Get the raw value of the type.
+    pub const fn as_raw(self) -> u32 {
+        self.0
+    }
+}
+
+/// The generalized hardware events, see [`PerfEventAttr::hardware`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u64)]
+#[non_exhaustive]
+pub enum PerfHwId {
+    /// The CPU cycles, which may be affected by frequency scaling.
+    PERF_COUNT_HW_CPU_CYCLES = 0,
+    /// The retired instructions.
+    PERF_COUNT_HW_INSTRUCTIONS = 1,
+    /// The accesses to the last level cache.
+    PERF_COUNT_HW_CACHE_REFERENCES = 2,
+    /// The misses of the last level cache.
+    PERF_COUNT_HW_CACHE_MISSES = 3,
+    /// The retired branch instructions.
+    PERF_COUNT_HW_BRANCH_INSTRUCTIONS = 4,
+    /// The mispredicted branch instructions.
+    PERF_COUNT_HW_BRANCH_MISSES = 5,
+    /// The bus cycles.
+    PERF_COUNT_HW_BUS_CYCLES = 6,
+    /// The stalled cycles during issue.
+    PERF_COUNT_HW_STALLED_CYCLES_FRONTEND = 7,
+    /// The stalled cycles during retirement.
+    PERF_COUNT_HW_STALLED_CYCLES_BACKEND = 8,
+    /// The CPU cycles, not affected by frequency scaling.
+    PERF_COUNT_HW_REF_CPU_CYCLES = 9,
+}
+
+/// The software events, see [`PerfEventAttr::software`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u64)]
+#[non_exhaustive]
+pub enum PerfSwId {
+    /// The CPU clock, in nanoseconds.
+    PERF_COUNT_SW_CPU_CLOCK = 0,
+    /// The clock of the task, in nanoseconds.
+    PERF_COUNT_SW_TASK_CLOCK = 1,
+    /// The page faults.
+    PERF_COUNT_SW_PAGE_FAULTS = 2,
+    /// The context switches.
+    PERF_COUNT_SW_CONTEXT_SWITCHES = 3,
+    /// The migrations of the process to another CPU.
+    PERF_COUNT_SW_CPU_MIGRATIONS = 4,
+    /// The minor page faults, which did not require I/O.
+    PERF_COUNT_SW_PAGE_FAULTS_MIN = 5,
+    /// The major page faults, which required I/O.
+    PERF_COUNT_SW_PAGE_FAULTS_MAJ = 6,
+    /// The alignment faults.
+    PERF_COUNT_SW_ALIGNMENT_FAULTS = 7,
+    /// The emulation faults.
+    PERF_COUNT_SW_EMULATION_FAULTS = 8,
+    /// A placeholder event that counts nothing.
+    PERF_COUNT_SW_DUMMY = 9,
+}
+
+bitflags::bitflags! {
+    /// Flags of a counter, see [`PerfEventAttr::flags`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct PerfEventFlags: u64 {
+        /// The counter starts disabled, see [`perf_event_enable`].
+        const DISABLED = 1 << 0;
+        /// The counter also counts the children created afterwards.
+        const INHERIT = 1 << 1;
+        /// The counter, if a group leader, is always on the CPU.
+        const PINNED = 1 << 2;
+        /// The group of the counter, if a group leader, is the only one on
+        /// the CPU.
+        const EXCLUSIVE = 1 << 3;
+        /// Do not count events in user space.
+        const EXCLUDE_USER = 1 << 4;
+        /// Do not count events in the kernel.
+        const EXCLUDE_KERNEL = 1 << 5;
+        /// Do not count events in the hypervisor.
+        const EXCLUDE_HV = 1 << 6;
+        /// Do not count events while the CPU is idle.
+        const EXCLUDE_IDLE = 1 << 7;
+        /// Save the counts of the inherited counters separately.
+        const INHERIT_STAT = 1 << 11;
+        /// Enable the counter on the next `execve`.
+        const ENABLE_ON_EXEC = 1 << 12;
+        /// Do not count events in the host, when running a guest.
+        const EXCLUDE_HOST = 1 << 19;
+        /// Do not count events in guests.
+        const EXCLUDE_GUEST = 1 << 20;
+        /// Close the counter on the next `execve`.
+        const REMOVE_ON_EXEC = 1 << 36;
+    }
+}
+
+bitflags::bitflags! {
+    /// The values recorded in a sample, see [`PerfEventAttr::sample_type`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct SampleFormat: u64 {
+        /// The instruction pointer.
+        const PERF_SAMPLE_IP = 1 << 0;
+        /// The process and thread ids.
+        const PERF_SAMPLE_TID = 1 << 1;
+        /// A timestamp.
+        const PERF_SAMPLE_TIME = 1 << 2;
+        /// An address, for some events.
+        const PERF_SAMPLE_ADDR = 1 << 3;
+        /// The values of the counters, in the read format.
+        const PERF_SAMPLE_READ = 1 << 4;
+        /// A call chain.
+        const PERF_SAMPLE_CALLCHAIN = 1 << 5;
+        /// The id of the group leader.
+        const PERF_SAMPLE_ID = 1 << 6;
+        /// The CPU number.
+        const PERF_SAMPLE_CPU = 1 << 7;
+        /// The sampling period.
+        const PERF_SAMPLE_PERIOD = 1 << 8;
+        /// The id of the counter.
+        const PERF_SAMPLE_STREAM_ID = 1 << 9;
+        /// Raw data.
+        const PERF_SAMPLE_RAW = 1 << 10;
+        /// The branch stack.
+        const PERF_SAMPLE_BRANCH_STACK = 1 << 11;
+        /// The user space registers.
+        const PERF_SAMPLE_REGS_USER = 1 << 12;
+        /// The user space stack.
+        const PERF_SAMPLE_STACK_USER = 1 << 13;
+        /// The cost of the event.
+        const PERF_SAMPLE_WEIGHT = 1 << 14;
+        /// The source of the data, for memory accesses.
+        const PERF_SAMPLE_DATA_SRC = 1 << 15;
+        /// The id of the counter, at a fixed position.
+        const PERF_SAMPLE_IDENTIFIER = 1 << 16;
+        /// The source of a transactional memory abort.
+        const PERF_SAMPLE_TRANSACTION = 1 << 17;
+        /// The registers at the time of the interrupt.
+        const PERF_SAMPLE_REGS_INTR = 1 << 18;
+        /// The physical address of the data, for memory accesses.
+        const PERF_SAMPLE_PHYS_ADDR = 1 << 19;
+    }
+}
+
+bitflags::bitflags! {
+    /// The values read from a counter, see [`PerfEventAttr::read_format`]
+    /// and [`perf_event_read`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct ReadFormat: u64 {
+        /// The time during which the counter was enabled, in nanoseconds.
+        const PERF_FORMAT_TOTAL_TIME_ENABLED = 1 << 0;
+        /// The time during which the counter was running on the CPU, in
+        /// nanoseconds.
+        const PERF_FORMAT_TOTAL_TIME_RUNNING = 1 << 1;
+        /// The id of the counter.
+        const PERF_FORMAT_ID = 1 << 2;
+        /// The values of all of the counters of the group, if a group
+        /// leader.
+        const PERF_FORMAT_GROUP = 1 << 3;
+        /// The number of lost samples.  Since Linux 6.0.
+        const PERF_FORMAT_LOST = 1 << 4;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`perf_event_open`].
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct PerfFlags: libc::c_ulong {
+        /// Do not redirect the output of the counter to the group leader.
+        const PERF_FLAG_FD_NO_GROUP = 1 << 0;
+        /// Redirect the output of the counter to the group leader.
+        const PERF_FLAG_FD_OUTPUT = 1 << 1;
+        /// `pid` is a file descriptor of a cgroup directory, to count the
+        /// processes of the cgroup on a CPU.
+        const PERF_FLAG_PID_CGROUP = 1 << 2;
+        /// Set the close-on-exec flag on the file descriptor.
+        const PERF_FLAG_FD_CLOEXEC = 1 << 3;
+    }
+}
+
+/// The description of a counter, see [`perf_event_open`].
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct PerfEventAttr(perf_event_attr);
+
+impl PerfEventAttr {
+    /// Create a counter for the event `config` of type `type_`, with no flags
+    /// and no sampling.
+    pub fn new(type_: PerfType, config: u64) -> Self {
+        // SAFETY: all of the fields are integers
+        let mut attr: perf_event_attr = unsafe { mem::zeroed() };
+        attr.type_ = type_.0;
+        attr.size = mem::size_of::<perf_event_attr>() as u32;
+        attr.config = config;
+        PerfEventAttr(attr)
+    }
+
+    /// Create a counter for a generalized hardware event.
+    pub fn hardware(id: PerfHwId) -> Self {
+        Self::new(PerfType::PERF_TYPE_HARDWARE, id as u64)
+    }
+
+    /// Create a counter for a software event.
+    pub fn software(id: PerfSwId) -> Self {
+        Self::new(PerfType::PERF_TYPE_SOFTWARE, id as u64)
+    }
+
+    /// Take a sample every `period` events.
+    pub fn sample_period(mut self, period: u64) -> Self {
+        self.0.sample_period_or_freq = period;
+        self.0.flags &= !raw_flags(FREQ);
+        self
+    }
+
+    /// Take `freq` samples per second, adjusting the period as needed.
+    pub fn sample_freq(mut self, freq: u64) -> Self {
+        self.0.sample_period_or_freq = freq;
+        self.0.flags |= raw_flags(FREQ);
+        self
+    }
+
+    /// Set the values recorded in the samples.
+    pub fn sample_type(mut self, sample_type: SampleFormat) -> Self {
+        self.0.sample_type = sample_type.bits();
+        self
+    }
+
+    /// Set the values read from the counter, see [`perf_event_read`].
+    pub fn read_format(mut self, read_format: ReadFormat) -> Self {
+        self.0.read_format = read_format.bits();
+        self
+    }
+
+    /// Set the flags of the counter.
+    pub fn flags(mut self, flags: PerfEventFlags) -> Self {
+        let freq = self.0.flags & raw_flags(FREQ);
+        self.0.flags = raw_flags(flags.bits()) | freq;
+        self
+    }
+
+    /// The type of the counter.
+    pub fn type_(&self) -> PerfType {
+        PerfType(self.0.type_)
+    }
+
+    /// The event counted.
+    pub fn config(&self) -> u64 {
+        self.0.config
+    }
+
+    /// The values read from the counter.
+    pub fn get_read_format(&self) -> ReadFormat {
+        ReadFormat::from_bits_retain(self.0.read_format)
+    }
+
+    /// The flags of the counter.
+    pub fn get_flags(&self) -> PerfEventFlags {
+        PerfEventFlags::from_bits_truncate(raw_flags(self.0.flags))
+    }
+}
+
+impl fmt::Debug for PerfEventAttr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PerfEventAttr")
+            .field("type_", &self.type_())
+            .field("config", &self.config())
+            .field("read_format", &self.get_read_format())
+            .field("flags", &self.get_flags())
+            .finish()
+    }
+}
+
+// The flags are bit-fields, which are allocated from the most significant
+// bit on big-endian targets.
+fn raw_flags(flags: u64) -> u64 {
+    if cfg!(target_endian = "big") {
+        flags.reverse_bits()
+    } else {
+        flags
+    }
+}
+
+/// Open a counter (`perf_event_open(2)`).
+///
+/// The counter counts the events of the process `pid`, or of all of the
+/// processes if `None`, on the CPU `cpu`, or on any CPU if `None`.  A pid of
+/// 0 is the calling process, and `pid` and `cpu` can't be both `None`.  The
+/// counter is added to the group of `group_fd`, or is the leader of a new
+/// group if `None`.
+///
+/// Fails with `EACCES` if not allowed by `/proc/sys/kernel/perf_event_paranoid`
+/// and with `ENOENT` if the event is not supported by the CPU.
+pub fn perf_event_open(
+    attr: &PerfEventAttr,
+    pid: Option<Pid>,
+    cpu: Option<c_int>,
+    group_fd: Option<BorrowedFd<'_>>,
+    flags: PerfFlags,
+) -> Result<OwnedFd> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            &attr.0 as *const perf_event_attr,
+            pid.map_or(-1, Pid::as_raw),
+            cpu.unwrap_or(-1),
+            group_fd.map_or(-1, |fd| fd.as_raw_fd()),
+            flags.bits(),
+        )
+    };
+
+    Errno::result(res).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+fn perf_event_ioctl<Fd: AsFd>(
+    fd: Fd,
+    request: libc::Ioctl,
+    group: bool,
+) -> Result<()> {
+    let arg = if group { PERF_IOC_FLAG_GROUP } else { 0 };
+    let res = unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), request, arg) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Enable a counter, or all of the counters of its group if `group`
+/// (`ioctl(PERF_EVENT_IOC_ENABLE)`).
+pub fn perf_event_enable<Fd: AsFd>(fd: Fd, group: bool) -> Result<()> {
+    perf_event_ioctl(fd, PERF_EVENT_IOC_ENABLE, group)
+}
+
+/// Disable a counter, or all of the counters of its group if `group`
+/// (`ioctl(PERF_EVENT_IOC_DISABLE)`).
+pub fn perf_event_disable<Fd: AsFd>(fd: Fd, group: bool) -> Result<()> {
+    perf_event_ioctl(fd, PERF_EVENT_IOC_DISABLE, group)
+}
+
+/// Reset the value of a counter to 0, or of all of the counters of its
+/// group if `group` (`ioctl(PERF_EVENT_IOC_RESET)`).
+pub fn perf_event_reset<Fd: AsFd>(fd: Fd, group: bool) -> Result<()> {
+    perf_event_ioctl(fd, PERF_EVENT_IOC_RESET, group)
+}
+
+/// The value of a counter, see [`PerfEventCount`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PerfEventValue {
+    /// The value of the counter.
+    pub value: u64,
+    /// The id of the counter, with `PERF_FORMAT_ID`.
+    pub id: Option<u64>,
+    /// The number of lost samples, with `PERF_FORMAT_LOST`.
+    pub lost: Option<u64>,
+}
+
+/// The values read from a counter, as returned by [`perf_event_read`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PerfEventCount {
+    /// The time during which the counter was enabled, with
+    /// `PERF_FORMAT_TOTAL_TIME_ENABLED`.
+    pub time_enabled: Option<u64>,
+    /// The time during which the counter was running on the CPU, with
+    /// `PERF_FORMAT_TOTAL_TIME_RUNNING`.
+    pub time_running: Option<u64>,
+    /// The value of the counter, or with `PERF_FORMAT_GROUP` the values of
+    /// all of the counters of the group, starting with the leader.
+    pub values: Vec<PerfEventValue>,
+}
+
+/// Read the values of a counter.
+///
+/// `read_format` must be the read format the counter was opened with.
+pub fn perf_event_read<Fd: AsFd>(
+    fd: Fd,
+    read_format: ReadFormat,
+) -> Result<PerfEventCount> {
+    let fd = fd.as_fd();
+    let group = read_format.contains(ReadFormat::PERF_FORMAT_GROUP);
+    let mut buf = vec![0u64; if group { 64 } else { 5 }];
+    let len = loop {
+        let res = unsafe {
+            libc::read(
+                fd.as_raw_fd(),
+                buf.as_mut_ptr().cast(),
+                buf.len() * mem::size_of::<u64>(),
+            )
+        };
+        match Errno::result(res) {
+            // The buffer is too small for the values of the group.
+            Err(Errno::ENOSPC) if group => {
+                let len = buf.len() * 2;
+                buf.resize(len, 0);
+            }
+            res => break res? as usize / mem::size_of::<u64>(),
+        }
+    };
+    let mut words = buf[..len].iter().copied();
+    let mut next = |present: bool| if present { words.next() } else { None };
+    let time_enabled =
+        read_format.contains(ReadFormat::PERF_FORMAT_TOTAL_TIME_ENABLED);
+    let time_running =
+        read_format.contains(ReadFormat::PERF_FORMAT_TOTAL_TIME_RUNNING);
+    let id = read_format.contains(ReadFormat::PERF_FORMAT_ID);
+    let lost = read_format.contains(ReadFormat::PERF_FORMAT_LOST);
+
+    // With `PERF_FORMAT_GROUP`, the number of values and the times come
+    // first, and the values of each counter follow.  Otherwise, the times
+    // follow the value of the counter.
+    let mut count = PerfEventCount::default();
+    let nr = if group { next(true).unwrap_or(0) } else { 1 };
+    if group {
+        count.time_enabled = next(time_enabled);
+        count.time_running = next(time_running);
+    }
+    for _ in 0..nr {
+        let Some(value) = next(true) else {
+            break;
+        };
+        if !group {
+            count.time_enabled = next(time_enabled);
+            count.time_running = next(time_running);
+        }
+        count.values.push(PerfEventValue {
+            value,
+            id: next(id),
+            lost: next(lost),
+        });
+    }
+    Ok(count)
+}
+
+const _: () = assert!(mem::size_of::<perf_event_attr>() == 136);
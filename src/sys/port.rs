@@ -0,0 +1,250 @@
+//! Event ports, the event notification mechanism of illumos and Solaris.
+//!
+//! Objects of various sources are associated with a port, which then
+//! delivers one event for each of them once they fire.  Associations are
+//! one-shot: a file descriptor must be associated again after each event.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::sys::port::{Port, PortEvent, PortSource, Timeout};
+//! let port = Port::new().unwrap();
+//! port.send(1, 42).unwrap();
+//!
+//! let mut events = [PortEvent::default(); 4];
+//! assert_eq!(port.get(&mut events, 1, Timeout::Infinite), Ok(1));
+//! assert_eq!(events[0].source(), Ok(PortSource::PORT_SOURCE_USER));
+//! assert_eq!(events[0].user(), 42);
+//! ```
+//!
+//! # See Also
+//! [port_create(3C)](https://illumos.org/man/3C/port_create)
+
+use crate::errno::Errno;
+pub use crate::poll_timeout::Timeout;
+use crate::Result;
+use libc::{c_int, c_uint, intptr_t, uintptr_t};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::ptr;
+
+#[cfg(all(feature = "signal", feature = "time"))]
+use crate::{sys::timer::Timer, time::ClockId};
+
+libc_enum! {
+    /// The kind of object an event was delivered for.
+    #[repr(i32)]
+    #[non_exhaustive]
+    pub enum PortSource {
+        /// Completion of an asynchronous I/O operation.
+        PORT_SOURCE_AIO,
+        /// Expiration of a timer, see [`Port::create_timer`].
+        PORT_SOURCE_TIMER,
+        /// An event sent with [`Port::send`].
+        PORT_SOURCE_USER,
+        /// Readiness of a file descriptor, see [`Port::associate_fd`].
+        PORT_SOURCE_FD,
+        /// An alert set with `port_alert(3C)`.
+        PORT_SOURCE_ALERT,
+        /// A message arriving on a POSIX message queue.
+        PORT_SOURCE_MQ,
+        /// A change to a file.
+        PORT_SOURCE_FILE,
+    }
+    impl TryFrom<i32>
+}
+
+/// An event retrieved with [`Port::get`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PortEvent(libc::port_event);
+
+// PortEvent can't derive Send because `portev_user` is a void*.  However, the
+// public API always treats it as an intptr_t, which is safe to Send.
+unsafe impl Send for PortEvent {}
+
+impl Default for PortEvent {
+    fn default() -> Self {
+        PortEvent(libc::port_event {
+            portev_events: 0,
+            portev_source: 0,
+            portev_pad: 0,
+            portev_object: 0,
+            portev_user: ptr::null_mut(),
+        })
+    }
+}
+
+impl PortEvent {
+    /// The kind of object the event was delivered for.
+    ///
+    /// Will only return an error if the source is unknown to Nix.
+    pub fn source(&self) -> Result<PortSource> {
+        PortSource::try_from(c_int::from(self.0.portev_source))
+    }
+
+    /// The source-specific events, like the `POLL*` flags of a file
+    /// descriptor.
+    pub fn events(&self) -> c_int {
+        self.0.portev_events
+    }
+
+    /// The poll events of a `PORT_SOURCE_FD` event.
+    #[cfg(feature = "poll")]
+    pub fn poll_events(&self) -> crate::poll::PollFlags {
+        crate::poll::PollFlags::from_bits_truncate(self.0.portev_events as _)
+    }
+
+    /// The object the event was delivered for, like the file descriptor of
+    /// a `PORT_SOURCE_FD` event, or the timer ID of a `PORT_SOURCE_TIMER`
+    /// event.
+    pub fn object(&self) -> uintptr_t {
+        self.0.portev_object
+    }
+
+    /// The user-defined value given when the object was associated.
+    pub fn user(&self) -> intptr_t {
+        self.0.portev_user as intptr_t
+    }
+}
+
+/// An event port.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct Port(OwnedFd);
+
+impl AsFd for Port {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl From<Port> for OwnedFd {
+    fn from(value: Port) -> Self {
+        value.0
+    }
+}
+
+impl Port {
+    /// Create a new event port.
+    pub fn new() -> Result<Self> {
+        let res = unsafe { libc::port_create() };
+
+        Errno::result(res).map(|fd| unsafe { Self(OwnedFd::from_raw_fd(fd)) })
+    }
+
+    /// Associate the file descriptor `fd` with the port, which will deliver
+    /// a single `PORT_SOURCE_FD` event once one of `events` is pending on
+    /// it.
+    ///
+    /// Associating a file descriptor again replaces the events and the user
+    /// value.
+    #[cfg(feature = "poll")]
+    pub fn associate_fd<Fd: AsFd>(
+        &self,
+        fd: Fd,
+        events: crate::poll::PollFlags,
+        user: intptr_t,
+    ) -> Result<()> {
+        let res = unsafe {
+            libc::port_associate(
+                self.0.as_raw_fd(),
+                libc::PORT_SOURCE_FD,
+                fd.as_fd().as_raw_fd() as uintptr_t,
+                c_int::from(events.bits()),
+                user as *mut libc::c_void,
+            )
+        };
+        Errno::result(res).map(drop)
+    }
+
+    /// Remove the association of the file descriptor `fd` with the port.
+    pub fn dissociate_fd<Fd: AsFd>(&self, fd: Fd) -> Result<()> {
+        let res = unsafe {
+            libc::port_dissociate(
+                self.0.as_raw_fd(),
+                libc::PORT_SOURCE_FD,
+                fd.as_fd().as_raw_fd() as uintptr_t,
+            )
+        };
+        Errno::result(res).map(drop)
+    }
+
+    /// Send a `PORT_SOURCE_USER` event, with the given events and user value,
+    /// to the port.
+    pub fn send(&self, events: c_int, user: intptr_t) -> Result<()> {
+        let res = unsafe {
+            libc::port_send(
+                self.0.as_raw_fd(),
+                events,
+                user as *mut libc::c_void,
+            )
+        };
+        Errno::result(res).map(drop)
+    }
+
+    /// Create a timer, that delivers a `PORT_SOURCE_TIMER` event to the port
+    /// each time it expires, once armed with [`Timer::set`].
+    ///
+    /// The `object` of the events is the timer ID.
+    #[cfg(all(feature = "signal", feature = "time"))]
+    pub fn create_timer(
+        &self,
+        clockid: ClockId,
+        user: intptr_t,
+    ) -> Result<Timer> {
+        // The notification is copied by timer_create.
+        let mut notify = libc::port_notify {
+            portnfy_port: self.0.as_raw_fd(),
+            portnfy_user: user as *mut libc::c_void,
+        };
+        let mut sev: libc::sigevent = unsafe { std::mem::zeroed() };
+        sev.sigev_notify = libc::SIGEV_PORT;
+        sev.sigev_value.sival_ptr = ptr::addr_of_mut!(notify).cast();
+
+        let mut timer_id = std::mem::MaybeUninit::<libc::timer_t>::uninit();
+        let res = unsafe {
+            libc::timer_create(
+                clockid.as_raw(),
+                &mut sev,
+                timer_id.as_mut_ptr(),
+            )
+        };
+        Errno::result(res)?;
+
+        Ok(Timer(unsafe { timer_id.assume_init() }))
+    }
+
+    /// Retrieve up to `events.len()` events, waiting until at least `min`
+    /// are available, or `timeout` expires.
+    ///
+    /// Returns the number of events placed in `events`, which may be less
+    /// than `min` if the timeout expired.
+    ///
+    /// # See Also
+    /// [port_getn(3C)](https://illumos.org/man/3C/port_getn)
+    pub fn get<T: Into<Timeout>>(
+        &self,
+        events: &mut [PortEvent],
+        min: u32,
+        timeout: T,
+    ) -> Result<usize> {
+        let max = c_uint::try_from(events.len()).unwrap_or(c_uint::MAX);
+        let mut nget: c_uint = min.min(max);
+        let mut timeout = timeout.into().as_timespec().map(|t| *t.as_ref());
+        let res = unsafe {
+            libc::port_getn(
+                self.0.as_raw_fd(),
+                events.as_mut_ptr().cast(),
+                max,
+                &mut nget,
+                timeout.as_mut().map_or(ptr::null_mut(), |t| t as *mut _),
+            )
+        };
+        match Errno::result(res) {
+            Ok(_) => Ok(nget as usize),
+            // Some events may have been retrieved before the timeout.
+            Err(Errno::ETIME) => Ok(nget as usize),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -32,6 +32,95 @@ libc_enum! {
     impl TryFrom<i32>
 }
 
+libc_bitflags! {
+    /// The "securebits" flags of the thread, which control how root is
+    /// treated with respect to capabilities.
+    ///
+    /// Each `_LOCKED` flag prevents further changes to the corresponding
+    /// flag.
+    pub struct SecureBits: c_int {
+        /// Don't grant capabilities when a thread with a zero user id
+        /// executes a program, or when a set-user-ID-root program is executed.
+        SECBIT_NOROOT;
+        /// Lock [`SECBIT_NOROOT`](Self::SECBIT_NOROOT).
+        SECBIT_NOROOT_LOCKED;
+        /// Don't adjust the capability sets when the user ids of the thread
+        /// change from or to zero.
+        SECBIT_NO_SETUID_FIXUP;
+        /// Lock [`SECBIT_NO_SETUID_FIXUP`](Self::SECBIT_NO_SETUID_FIXUP).
+        SECBIT_NO_SETUID_FIXUP_LOCKED;
+        /// Keep the permitted capabilities when all user ids of the thread
+        /// switch to nonzero values, see [`set_keepcaps`].
+        SECBIT_KEEP_CAPS;
+        /// Lock [`SECBIT_KEEP_CAPS`](Self::SECBIT_KEEP_CAPS).
+        SECBIT_KEEP_CAPS_LOCKED;
+        /// Disallow raising ambient capabilities.
+        SECBIT_NO_CAP_AMBIENT_RAISE;
+        /// Lock
+        /// [`SECBIT_NO_CAP_AMBIENT_RAISE`](Self::SECBIT_NO_CAP_AMBIENT_RAISE).
+        SECBIT_NO_CAP_AMBIENT_RAISE_LOCKED;
+    }
+}
+
+const PR_GET_SPECULATION_CTRL: c_int = 52;
+const PR_SET_SPECULATION_CTRL: c_int = 53;
+
+/// A speculation misfeature of the CPU, see [`get_speculation_ctrl`].
+#[repr(i32)]
+#[non_exhaustive]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PrctlSpeculationFeature {
+    /// Speculative store bypass.
+    PR_SPEC_STORE_BYPASS = 0,
+    /// Indirect branch speculation.
+    PR_SPEC_INDIRECT_BRANCH = 1,
+    /// Flushing of the L1D cache on context switch out of the task.
+    PR_SPEC_L1D_FLUSH = 2,
+}
+
+bitflags::bitflags! {
+    /// The state of the mitigation of a speculation misfeature, see
+    /// [`get_speculation_ctrl`].
+    ///
+    /// No flags means that the CPU is not affected by the misfeature.
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct PrctlSpeculationCtrl: c_ulong {
+        /// The mitigation can be controlled per thread with
+        /// [`set_speculation_ctrl`].
+        const PR_SPEC_PRCTL = 1 << 0;
+        /// The speculation feature is enabled, the mitigation is disabled.
+        const PR_SPEC_ENABLE = 1 << 1;
+        /// The speculation feature is disabled, the mitigation is enabled.
+        const PR_SPEC_DISABLE = 1 << 2;
+        /// Same as [`PR_SPEC_DISABLE`](Self::PR_SPEC_DISABLE), but cannot
+        /// be undone.
+        const PR_SPEC_FORCE_DISABLE = 1 << 3;
+        /// Same as [`PR_SPEC_DISABLE`](Self::PR_SPEC_DISABLE), but the state
+        /// is reset to the default on `execve(2)`.
+        const PR_SPEC_DISABLE_NOEXEC = 1 << 4;
+    }
+}
+
+/// How to mitigate a speculation misfeature, see [`set_speculation_ctrl`].
+#[repr(i32)]
+#[non_exhaustive]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PrctlSpeculationMode {
+    /// Enable the speculation feature, disabling the mitigation.
+    PR_SPEC_ENABLE = 1 << 1,
+    /// Disable the speculation feature, enabling the mitigation.
+    PR_SPEC_DISABLE = 1 << 2,
+    /// Same as [`PR_SPEC_DISABLE`](Self::PR_SPEC_DISABLE), but cannot be
+    /// undone.
+    PR_SPEC_FORCE_DISABLE = 1 << 3,
+    /// Same as [`PR_SPEC_DISABLE`](Self::PR_SPEC_DISABLE), but the state is
+    /// reset to the default on `execve(2)`.
+    PR_SPEC_DISABLE_NOEXEC = 1 << 4,
+}
+
 fn prctl_set_bool(option: c_int, status: bool) -> Result<()> {
     let res = unsafe { libc::prctl(option, status as c_ulong, 0, 0, 0) };
     Errno::result(res).map(drop)
@@ -217,6 +306,12 @@ pub fn get_thp_disable() -> Result<bool> {
 }
 
 /// Set an identifier (or reset it) to the address memory range.
+///
+/// The name is shown in `/proc/<pid>/maps` as `[anon:<name>]` for anonymous
+/// mappings in the range.  It can hold at most 79 bytes, and may not contain
+/// any of `\`, `` ` ``, `$`, `[`, `]` or a non printable character, otherwise
+/// this fails with `EINVAL`.  The kernel must be built with
+/// `CONFIG_ANON_VMA_NAME`.
 pub fn set_vma_anon_name(addr: NonNull<c_void>, length: NonZeroUsize, name: Option<&CStr>) -> Result<()> {
     let nameref = match name {
         Some(n) => n.as_ptr(),
@@ -226,3 +321,109 @@ pub fn set_vma_anon_name(addr: NonNull<c_void>, length: NonZeroUsize, name: Opti
 
     Errno::result(res).map(drop)
 }
+
+/// Set the "securebits" flags of the calling thread.
+///
+/// This requires `CAP_SETPCAP`.
+pub fn set_securebits(bits: SecureBits) -> Result<()> {
+    let res = unsafe {
+        libc::prctl(libc::PR_SET_SECUREBITS, bits.bits() as c_ulong, 0, 0, 0)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the "securebits" flags of the calling thread.
+pub fn get_securebits() -> Result<SecureBits> {
+    let res = unsafe { libc::prctl(libc::PR_GET_SECUREBITS, 0, 0, 0, 0) };
+
+    Errno::result(res).map(SecureBits::from_bits_retain)
+}
+
+#[cfg(feature = "caps")]
+fn prctl_cap_ambient(
+    option: c_int,
+    cap: crate::sys::caps::Capability,
+) -> Result<c_int> {
+    let res = unsafe {
+        libc::prctl(libc::PR_CAP_AMBIENT, option, cap as c_ulong, 0, 0)
+    };
+
+    Errno::result(res)
+}
+
+/// Add `cap` to the ambient capabilities of the calling thread, which are
+/// preserved across the execution of a program that is not privileged.
+///
+/// The capability must already be both permitted and inheritable.
+#[cfg(feature = "caps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "caps")))]
+pub fn cap_ambient_raise(cap: crate::sys::caps::Capability) -> Result<()> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_RAISE, cap).map(drop)
+}
+
+/// Remove `cap` from the ambient capabilities of the calling thread.
+#[cfg(feature = "caps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "caps")))]
+pub fn cap_ambient_lower(cap: crate::sys::caps::Capability) -> Result<()> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_LOWER, cap).map(drop)
+}
+
+/// Check whether `cap` is in the ambient capabilities of the calling thread.
+#[cfg(feature = "caps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "caps")))]
+pub fn cap_ambient_is_set(cap: crate::sys::caps::Capability) -> Result<bool> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_IS_SET, cap).map(|res| res != 0)
+}
+
+/// Remove all of the ambient capabilities of the calling thread.
+pub fn cap_ambient_clear_all() -> Result<()> {
+    let res = unsafe {
+        libc::prctl(
+            libc::PR_CAP_AMBIENT,
+            libc::PR_CAP_AMBIENT_CLEAR_ALL,
+            0,
+            0,
+            0,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the state of the mitigation of the speculation misfeature `feature`
+/// for the calling thread.
+///
+/// Fails with `ENODEV` if the kernel or the CPU does not support `feature`.
+pub fn get_speculation_ctrl(
+    feature: PrctlSpeculationFeature,
+) -> Result<PrctlSpeculationCtrl> {
+    let res = unsafe {
+        libc::prctl(PR_GET_SPECULATION_CTRL, feature as c_ulong, 0, 0, 0)
+    };
+
+    Errno::result(res)
+        .map(|res| PrctlSpeculationCtrl::from_bits_retain(res as c_ulong))
+}
+
+/// Set the state of the mitigation of the speculation misfeature `feature`
+/// for the calling thread.
+///
+/// This fails with `ENXIO` if the mitigation cannot be controlled per
+/// thread, and with `EPERM` if it was previously force disabled.
+pub fn set_speculation_ctrl(
+    feature: PrctlSpeculationFeature,
+    mode: PrctlSpeculationMode,
+) -> Result<()> {
+    let res = unsafe {
+        libc::prctl(
+            PR_SET_SPECULATION_CTRL,
+            feature as c_ulong,
+            mode as c_ulong,
+            0,
+            0,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
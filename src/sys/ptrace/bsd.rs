@@ -5,6 +5,8 @@ use crate::Result;
 use cfg_if::cfg_if;
 use libc::{self, c_int};
 use std::ptr;
+#[cfg(target_os = "freebsd")]
+use std::{ffi::CStr, mem};
 
 pub type RequestType = c_int;
 
@@ -46,7 +48,57 @@ libc_enum! {
         #[cfg(apple_targets)]
         PT_THUPDATE,
         #[cfg(apple_targets)]
-        PT_ATTACHEXC
+        PT_ATTACHEXC,
+        #[cfg(any(freebsdlike, netbsdlike))]
+        PT_IO,
+        #[cfg(target_os = "freebsd")]
+        PT_LWPINFO,
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+libc_enum! {
+    #[repr(i32)]
+    /// The reason a LWP is stopped, see [`LwpInfo::event`].
+    #[non_exhaustive]
+    pub enum LwpEvent {
+        /// The LWP is not stopped by a signal, such as after `PT_ATTACH`.
+        PL_EVENT_NONE,
+        /// The LWP is stopped by a signal.
+        PL_EVENT_SIGNAL,
+    }
+    impl TryFrom<i32>
+}
+
+#[cfg(target_os = "freebsd")]
+libc_bitflags! {
+    /// The details of the stop of a LWP, see [`LwpInfo::flags`].
+    pub struct LwpFlags: c_int {
+        /// The LWP is a scheduler activation.
+        PL_FLAG_SA;
+        /// The LWP is bound.
+        PL_FLAG_BOUND;
+        /// The LWP is stopped at a system call entry.
+        PL_FLAG_SCE;
+        /// The LWP is stopped at a system call exit.
+        PL_FLAG_SCX;
+        /// The LWP is stopped after a successful `execve`.
+        PL_FLAG_EXEC;
+        /// The signal information is valid, see [`LwpInfo::siginfo`].
+        PL_FLAG_SI;
+        /// The LWP is stopped after a fork, see [`LwpInfo::child_pid`].
+        PL_FLAG_FORKED;
+        /// The LWP is the first one of a new child, stopped after a fork.
+        PL_FLAG_CHILD;
+        /// The LWP was just created.
+        PL_FLAG_BORN;
+        /// The LWP is exiting.
+        PL_FLAG_EXITED;
+        /// The LWP is stopped after a `vfork`.
+        PL_FLAG_VFORKED;
+        /// The LWP is stopped after the child of a `vfork` exited or
+        /// called `execve`.
+        PL_FLAG_VFORK_DONE;
     }
 }
 
@@ -185,3 +237,168 @@ pub fn read(pid: Pid, addr: AddressType) -> Result<c_int> {
 pub fn write(pid: Pid, addr: AddressType, data: c_int) -> Result<()> {
     unsafe { ptrace_other(Request::PT_WRITE_D, pid, addr, data).map(drop) }
 }
+
+/// Transfers `len` bytes between `buf` and the memory of the tracee at `addr`
+/// with `PT_IO`, which is repeated until everything is transferred.
+#[cfg(any(freebsdlike, netbsdlike))]
+fn ptrace_io(
+    pid: Pid,
+    op: c_int,
+    addr: AddressType,
+    buf: *mut libc::c_void,
+    len: usize,
+) -> Result<()> {
+    let mut done = 0;
+    while done < len {
+        let mut desc = libc::ptrace_io_desc {
+            piod_op: op,
+            piod_offs: addr.cast::<u8>().wrapping_add(done).cast(),
+            piod_addr: buf.cast::<u8>().wrapping_add(done).cast(),
+            piod_len: len - done,
+        };
+        unsafe {
+            ptrace_other(
+                Request::PT_IO,
+                pid,
+                &mut desc as *mut libc::ptrace_io_desc as AddressType,
+                0,
+            )?;
+        }
+        // Nothing more can be transferred, like at the end of a mapping.
+        if desc.piod_len == 0 {
+            return Err(Errno::EFAULT);
+        }
+        done += desc.piod_len;
+    }
+    Ok(())
+}
+
+/// Reads `buf.len()` bytes from the processes memory at the given address,
+/// as with `ptrace(PT_IO, ...)` and `PIOD_READ_D`
+///
+/// `EFAULT` is returned if the end of a mapping of the tracee is reached
+/// before the whole buffer is read.
+#[cfg(any(freebsdlike, netbsdlike))]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn read_bytes(pid: Pid, addr: AddressType, buf: &mut [u8]) -> Result<()> {
+    ptrace_io(
+        pid,
+        libc::PIOD_READ_D,
+        addr,
+        buf.as_mut_ptr().cast(),
+        buf.len(),
+    )
+}
+
+/// Writes `buf` into the processes memory at the given address, as with
+/// `ptrace(PT_IO, ...)` and `PIOD_WRITE_D`
+///
+/// `EFAULT` is returned if the end of a mapping of the tracee is reached
+/// before the whole buffer is written.
+#[cfg(any(freebsdlike, netbsdlike))]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn write_bytes(pid: Pid, addr: AddressType, buf: &[u8]) -> Result<()> {
+    ptrace_io(
+        pid,
+        libc::PIOD_WRITE_D,
+        addr,
+        buf.as_ptr().cast_mut().cast(),
+        buf.len(),
+    )
+}
+
+/// The state of a stopped LWP, as returned by [`lwpinfo`].
+#[cfg(target_os = "freebsd")]
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct LwpInfo(libc::ptrace_lwpinfo);
+
+#[cfg(target_os = "freebsd")]
+impl LwpInfo {
+    /// The id of the LWP.
+    pub fn lwpid(&self) -> libc::lwpid_t {
+        self.0.pl_lwpid
+    }
+
+    /// The reason the LWP is stopped.
+    pub fn event(&self) -> Result<LwpEvent> {
+        LwpEvent::try_from(self.0.pl_event)
+    }
+
+    /// The details of the stop.
+    pub fn flags(&self) -> LwpFlags {
+        LwpFlags::from_bits_retain(self.0.pl_flags)
+    }
+
+    /// The signal mask of the LWP.
+    #[cfg(feature = "signal")]
+    pub fn sigmask(&self) -> crate::sys::signal::SigSet {
+        // SAFETY: the kernel returns a valid signal set
+        unsafe {
+            crate::sys::signal::SigSet::from_sigset_t_unchecked(
+                self.0.pl_sigmask,
+            )
+        }
+    }
+
+    /// The signals pending for the LWP.
+    #[cfg(feature = "signal")]
+    pub fn siglist(&self) -> crate::sys::signal::SigSet {
+        // SAFETY: the kernel returns a valid signal set
+        unsafe {
+            crate::sys::signal::SigSet::from_sigset_t_unchecked(
+                self.0.pl_siglist,
+            )
+        }
+    }
+
+    /// The signal that stopped the LWP, if `PL_FLAG_SI` is set.
+    pub fn siginfo(&self) -> Option<&libc::siginfo_t> {
+        self.flags()
+            .contains(LwpFlags::PL_FLAG_SI)
+            .then_some(&self.0.pl_siginfo)
+    }
+
+    /// The name of the LWP.
+    pub fn tdname(&self) -> &CStr {
+        // SAFETY: the kernel returns a nul-terminated name
+        unsafe { CStr::from_ptr(self.0.pl_tdname.as_ptr()) }
+    }
+
+    /// The new child, if `PL_FLAG_FORKED` is set.
+    pub fn child_pid(&self) -> Option<Pid> {
+        self.flags()
+            .contains(LwpFlags::PL_FLAG_FORKED)
+            .then(|| Pid::from_raw(self.0.pl_child_pid))
+    }
+
+    /// The number of the system call, if stopped at a system call entry or
+    /// exit.
+    pub fn syscall_code(&self) -> libc::c_uint {
+        self.0.pl_syscall_code
+    }
+
+    /// The number of arguments of the system call, if stopped at a system
+    /// call entry or exit.
+    pub fn syscall_narg(&self) -> libc::c_uint {
+        self.0.pl_syscall_narg
+    }
+}
+
+/// Get the state of a stopped LWP, as with `ptrace(PT_LWPINFO, ...)`
+///
+/// `pid` is either a process, for the LWP that caused the last stop, or a
+/// LWP id.
+#[cfg(target_os = "freebsd")]
+pub fn lwpinfo(pid: Pid) -> Result<LwpInfo> {
+    let mut info = mem::MaybeUninit::<libc::ptrace_lwpinfo>::zeroed();
+    unsafe {
+        ptrace_other(
+            Request::PT_LWPINFO,
+            pid,
+            info.as_mut_ptr().cast(),
+            mem::size_of::<libc::ptrace_lwpinfo>() as c_int,
+        )?;
+        Ok(LwpInfo(info.assume_init()))
+    }
+}
@@ -6,6 +6,7 @@ use crate::unistd::Pid;
 use crate::Result;
 use cfg_if::cfg_if;
 use libc::{self, c_long, c_void, siginfo_t};
+use std::ffi::CString;
 use std::{mem, ptr};
 
 pub type AddressType = *mut ::libc::c_void;
@@ -135,12 +136,18 @@ libc_enum! {
                                                target_arch = "mips64",
                                                target_arch = "mips64r6"))))]
         PTRACE_PEEKSIGINFO,
-        #[cfg(all(target_os = "linux", target_env = "gnu",
+        #[cfg(all(target_os = "linux",
+                  any(target_env = "gnu", target_env = "musl"),
                   any(target_arch = "x86", target_arch = "x86_64")))]
         PTRACE_SYSEMU,
-        #[cfg(all(target_os = "linux", target_env = "gnu",
+        #[cfg(all(target_os = "linux",
+                  any(target_env = "gnu", target_env = "musl"),
                   any(target_arch = "x86", target_arch = "x86_64")))]
         PTRACE_SYSEMU_SINGLESTEP,
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        PTRACE_GET_SYSCALL_INFO,
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        PTRACE_GET_SYSCALL_USER_DISPATCH_CONFIG,
     }
 }
 
@@ -182,17 +189,19 @@ libc_enum! {
         target_arch = "riscv64",
     )
 ))]
-libc_enum! {
-    #[repr(i32)]
-    /// Defines a specific register set, as used in `PTRACE_GETREGSET` and `PTRACE_SETREGSET`.
-    #[non_exhaustive]
-    pub enum RegisterSetValue {
-        NT_PRSTATUS,
-        NT_PRFPREG,
-        NT_PRPSINFO,
-        NT_TASKSTRUCT,
-        NT_AUXV,
-    }
+#[repr(i32)]
+/// Defines a specific register set, as used in `PTRACE_GETREGSET` and `PTRACE_SETREGSET`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RegisterSetValue {
+    NT_PRSTATUS = libc::NT_PRSTATUS,
+    NT_PRFPREG = libc::NT_PRFPREG,
+    NT_PRPSINFO = libc::NT_PRPSINFO,
+    NT_TASKSTRUCT = libc::NT_TASKSTRUCT,
+    NT_AUXV = libc::NT_AUXV,
+    /// The system call number, on aarch64.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_SYSTEM_CALL = 0x404,
 }
 
 #[cfg(all(
@@ -256,6 +265,19 @@ pub mod regset {
         #[cfg(target_arch = "riscv64")]
         type Regs = libc::__riscv_mc_d_ext_state;
     }
+
+    #[cfg(target_arch = "aarch64")]
+    #[derive(Debug, Clone, Copy)]
+    /// The number of the system call that the tracee is stopped at, which
+    /// can be changed on syscall-enter-stop, or set to -1 to skip the
+    /// system call.  aarch64 counterpart of `orig_rax` on x86_64.
+    pub enum NT_ARM_SYSTEM_CALL {}
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe impl RegisterSet for NT_ARM_SYSTEM_CALL {
+        const VALUE: RegisterSetValue = RegisterSetValue::NT_ARM_SYSTEM_CALL;
+        type Regs = libc::c_int;
+    }
 }
 
 libc_bitflags! {
@@ -500,6 +522,201 @@ pub fn getsiginfo(pid: Pid) -> Result<siginfo_t> {
     ptrace_get_data::<siginfo_t>(Request::PTRACE_GETSIGINFO, pid)
 }
 
+/// The system call a tracee is stopped at, as returned by [`get_syscall_info`].
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SyscallInfo {
+    /// The `AUDIT_ARCH_*` value of the system call, which tells apart the
+    /// native and compat system calls.
+    pub arch: u32,
+    /// The instruction pointer of the tracee.
+    pub instruction_pointer: u64,
+    /// The stack pointer of the tracee.
+    pub stack_pointer: u64,
+    /// The kind of stop, with the details of the system call.
+    pub op: SyscallInfoOp,
+}
+
+/// The kind of stop of a [`SyscallInfo`].
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SyscallInfoOp {
+    /// The tracee is not stopped at a system call.
+    None,
+    /// A syscall-enter-stop.
+    Entry {
+        /// The system call number.
+        nr: u64,
+        /// The arguments of the system call.
+        args: [u64; 6],
+    },
+    /// A syscall-exit-stop.
+    Exit {
+        /// The return value of the system call, or the negated error number
+        /// if `is_error` is true.
+        rval: i64,
+        /// Whether the system call failed.
+        is_error: bool,
+    },
+    /// A `PTRACE_EVENT_SECCOMP` stop.
+    Seccomp {
+        /// The system call number.
+        nr: u64,
+        /// The arguments of the system call.
+        args: [u64; 6],
+        /// The `SECCOMP_RET_DATA` part of the return value of the filter.
+        ret_data: u32,
+    },
+}
+
+/// Get the system call a tracee is stopped at, as with
+/// `ptrace(PTRACE_GET_SYSCALL_INFO, ...)`
+///
+/// Syscall-enter-stops and syscall-exit-stops are only told apart when the
+/// tracee was traced with `PTRACE_O_TRACESYSGOOD`.  Since Linux 5.3.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn get_syscall_info(pid: Pid) -> Result<SyscallInfo> {
+    let mut data = mem::MaybeUninit::<libc::ptrace_syscall_info>::zeroed();
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_GET_SYSCALL_INFO as RequestType,
+            libc::pid_t::from(pid),
+            mem::size_of::<libc::ptrace_syscall_info>(),
+            data.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+    // SAFETY: the struct was zero-initialized, and is at most partially
+    // overwritten by the kernel
+    let data = unsafe { data.assume_init() };
+
+    let op = match data.op {
+        libc::PTRACE_SYSCALL_INFO_ENTRY => {
+            let entry = unsafe { data.u.entry };
+            SyscallInfoOp::Entry {
+                nr: entry.nr,
+                args: entry.args,
+            }
+        }
+        libc::PTRACE_SYSCALL_INFO_EXIT => {
+            let exit = unsafe { data.u.exit };
+            SyscallInfoOp::Exit {
+                rval: exit.sval,
+                is_error: exit.is_error != 0,
+            }
+        }
+        libc::PTRACE_SYSCALL_INFO_SECCOMP => {
+            let seccomp = unsafe { data.u.seccomp };
+            SyscallInfoOp::Seccomp {
+                nr: seccomp.nr,
+                args: seccomp.args,
+                ret_data: seccomp.ret_data,
+            }
+        }
+        _ => SyscallInfoOp::None,
+    };
+    Ok(SyscallInfo {
+        arch: data.arch,
+        instruction_pointer: data.instruction_pointer,
+        stack_pointer: data.stack_pointer,
+        op,
+    })
+}
+
+// Not yet provided by libc.
+const PTRACE_SECCOMP_GET_FILTER: RequestType = 0x420c;
+
+/// Get the instructions of a seccomp filter of a tracee, as with
+/// `ptrace(PTRACE_SECCOMP_GET_FILTER, ...)`
+///
+/// The filters are numbered from 0 for the most recently installed one, and
+/// `ENOENT` is returned past the oldest one.  Requires `CAP_SYS_ADMIN`, and a
+/// kernel built with `CONFIG_CHECKPOINT_RESTORE`.
+pub fn seccomp_get_filter(
+    pid: Pid,
+    index: usize,
+) -> Result<Vec<libc::sock_filter>> {
+    let len = unsafe {
+        libc::ptrace(
+            PTRACE_SECCOMP_GET_FILTER,
+            libc::pid_t::from(pid),
+            index,
+            ptr::null_mut::<libc::sock_filter>(),
+        )
+    };
+    let len = Errno::result(len)? as usize;
+
+    let mut filter = Vec::with_capacity(len);
+    let res = unsafe {
+        libc::ptrace(
+            PTRACE_SECCOMP_GET_FILTER,
+            libc::pid_t::from(pid),
+            index,
+            filter.as_mut_ptr(),
+        )
+    };
+    let len = Errno::result(res)? as usize;
+    // SAFETY: the kernel wrote `len` instructions, which is the length of
+    // the filter and thus at most its capacity
+    unsafe { filter.set_len(len) };
+    Ok(filter)
+}
+
+/// The syscall user dispatch configuration of a tracee, as returned by
+/// [`get_syscall_user_dispatch_config`].
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct SyscallUserDispatchConfig(libc::ptrace_sud_config);
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+impl SyscallUserDispatchConfig {
+    /// The dispatch mode, `PR_SYS_DISPATCH_OFF` (0) if syscall user dispatch
+    /// is disabled.
+    pub fn mode(&self) -> u64 {
+        self.0.mode
+    }
+
+    /// The address of the selector byte in the tracee, if any.
+    pub fn selector(&self) -> u64 {
+        self.0.selector
+    }
+
+    /// The start of the memory region whose system calls are never
+    /// dispatched.
+    pub fn offset(&self) -> u64 {
+        self.0.offset
+    }
+
+    /// The length of the memory region whose system calls are never
+    /// dispatched.
+    pub fn length(&self) -> u64 {
+        self.0.len
+    }
+}
+
+/// Get the syscall user dispatch configuration of a tracee, as with
+/// `ptrace(PTRACE_GET_SYSCALL_USER_DISPATCH_CONFIG, ...)`
+///
+/// Since Linux 6.4.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn get_syscall_user_dispatch_config(
+    pid: Pid,
+) -> Result<SyscallUserDispatchConfig> {
+    let mut data = mem::MaybeUninit::<libc::ptrace_sud_config>::uninit();
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_GET_SYSCALL_USER_DISPATCH_CONFIG as RequestType,
+            libc::pid_t::from(pid),
+            mem::size_of::<libc::ptrace_sud_config>(),
+            data.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+    Ok(SyscallUserDispatchConfig(unsafe { data.assume_init() }))
+}
+
 /// Set siginfo as with `ptrace(PTRACE_SETSIGINFO, ...)`
 pub fn setsiginfo(pid: Pid, sig: &siginfo_t) -> Result<()> {
     let ret = unsafe {
@@ -555,7 +772,7 @@ pub fn syscall<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
 /// optionally delivering a signal specified by `sig`.
 #[cfg(all(
     target_os = "linux",
-    target_env = "gnu",
+    any(target_env = "gnu", target_env = "musl"),
     any(target_arch = "x86", target_arch = "x86_64")
 ))]
 pub fn sysemu<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
@@ -647,6 +864,33 @@ pub fn interrupt(pid: Pid) -> Result<()> {
     }
 }
 
+/// Restart a tracee in a group-stop without resuming it, as with
+/// `ptrace(PTRACE_LISTEN, ...)`
+///
+/// The tracee stays stopped, but is no longer in a ptrace-stop, so that the
+/// tracer is notified with a `PTRACE_EVENT_STOP` when it is resumed by a
+/// `SIGCONT`. Only works on a tracee attached with [`seize`].
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6"
+    ))
+))]
+pub fn listen(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(
+            Request::PTRACE_LISTEN,
+            pid,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+        .map(drop)
+    }
+}
+
 /// Issues a kill request as with `ptrace(PTRACE_KILL, ...)`
 ///
 /// This request is equivalent to `ptrace(PTRACE_CONT, ..., SIGKILL);`
@@ -704,7 +948,7 @@ pub fn step<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
 /// Optionally, the signal specified by `sig` is delivered to the tracee upon continuation.
 #[cfg(all(
     target_os = "linux",
-    target_env = "gnu",
+    any(target_env = "gnu", target_env = "musl"),
     any(target_arch = "x86", target_arch = "x86_64")
 ))]
 pub fn sysemu_step<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
@@ -742,6 +986,115 @@ pub fn write(pid: Pid, addr: AddressType, data: c_long) -> Result<()> {
     }
 }
 
+/// Reads `buf.len()` bytes from the processes memory at the given address.
+///
+/// The memory is read with `process_vm_readv` when possible, and word by word
+/// with [`read`] otherwise, such as for pages that are not readable by the
+/// tracee.  The address and length need not be aligned.
+pub fn read_bytes(pid: Pid, addr: AddressType, buf: &mut [u8]) -> Result<()> {
+    let local = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let remote = libc::iovec {
+        iov_base: addr,
+        iov_len: buf.len(),
+    };
+    let res =
+        unsafe { libc::process_vm_readv(pid.into(), &local, 1, &remote, 1, 0) };
+    // Read whatever is left after a failure or a partial read.
+    let mut done = if res > 0 { res as usize } else { 0 };
+
+    const WORD: usize = mem::size_of::<c_long>();
+    while done < buf.len() {
+        let start = addr as usize + done;
+        let aligned = start & !(WORD - 1);
+        let skip = start - aligned;
+        let n = (WORD - skip).min(buf.len() - done);
+        let word = read(pid, aligned as AddressType)?.to_ne_bytes();
+        buf[done..done + n].copy_from_slice(&word[skip..skip + n]);
+        done += n;
+    }
+    Ok(())
+}
+
+/// Writes `buf` into the processes memory at the given address.
+///
+/// The memory is written word by word with [`write`], so that read-only pages
+/// such as the code of the tracee can be written too.  The words only
+/// partially overwritten at the edges of an unaligned buffer are read first,
+/// to preserve the bytes around the buffer.
+pub fn write_bytes(pid: Pid, addr: AddressType, buf: &[u8]) -> Result<()> {
+    const WORD: usize = mem::size_of::<c_long>();
+    let mut done = 0;
+    while done < buf.len() {
+        let start = addr as usize + done;
+        let aligned = start & !(WORD - 1);
+        let skip = start - aligned;
+        let n = (WORD - skip).min(buf.len() - done);
+        let mut word = if n == WORD {
+            [0; WORD]
+        } else {
+            read(pid, aligned as AddressType)?.to_ne_bytes()
+        };
+        word[skip..skip + n].copy_from_slice(&buf[done..done + n]);
+        write(pid, aligned as AddressType, c_long::from_ne_bytes(word))?;
+        done += n;
+    }
+    Ok(())
+}
+
+/// Reads a nul-terminated string from the processes memory at the given
+/// address, with [`read_bytes`].
+///
+/// The string is read page by page, so that it can end right before an
+/// unmapped page.  Fails with `ERANGE` if there is no nul byte within the
+/// first `max_len` bytes.
+pub fn read_cstring(
+    pid: Pid,
+    addr: AddressType,
+    max_len: usize,
+) -> Result<CString> {
+    // The smallest page size, to never read across a page boundary.
+    const PAGE: usize = 4096;
+    let mut bytes = Vec::new();
+    while bytes.len() < max_len {
+        let start = addr as usize + bytes.len();
+        let n = (PAGE - start % PAGE).min(max_len - bytes.len());
+        let mut chunk = [0u8; PAGE];
+        let chunk = &mut chunk[..n];
+        read_bytes(pid, start as AddressType, chunk)?;
+        if let Some(nul) = chunk.iter().position(|&c| c == 0) {
+            bytes.extend_from_slice(&chunk[..nul]);
+            // SAFETY: `bytes` contains no nul byte
+            return Ok(unsafe { CString::from_vec_unchecked(bytes) });
+        }
+        bytes.extend_from_slice(chunk);
+    }
+    Err(Errno::ERANGE)
+}
+
+/// Reads a value of type `T` from the processes memory at the given
+/// address, with [`read_bytes`].
+///
+/// # Safety
+///
+/// The bytes read must be a valid value of type `T`, which is always the
+/// case for a `#[repr(C)]` struct of integers.  The address need not be
+/// aligned.
+pub unsafe fn read_struct<T: Copy>(pid: Pid, addr: AddressType) -> Result<T> {
+    let mut data = mem::MaybeUninit::<T>::zeroed();
+    // SAFETY: the zeroed bytes of `data` are initialized
+    let buf = unsafe {
+        std::slice::from_raw_parts_mut(
+            data.as_mut_ptr().cast::<u8>(),
+            mem::size_of::<T>(),
+        )
+    };
+    read_bytes(pid, addr, buf)?;
+    Ok(unsafe { data.assume_init() })
+}
+
 /// Reads a word from a user area at `offset`, as with ptrace(PTRACE_PEEKUSER, ...).
 /// The user struct definition can be found in `/usr/include/sys/user.h`.
 pub fn read_user(pid: Pid, offset: AddressType) -> Result<c_long> {
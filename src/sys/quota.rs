@@ -16,6 +16,7 @@ use crate::errno::Errno;
 use crate::{NixPath, Result};
 use libc::{self, c_char, c_int};
 use std::default::Default;
+use std::os::unix::io::{AsFd, AsRawFd};
 use std::{mem, ptr};
 
 struct QuotaCmd(QuotaSubCmd, QuotaType);
@@ -27,27 +28,29 @@ impl QuotaCmd {
 }
 
 // linux quota version >= 2
-libc_enum! {
-    #[repr(i32)]
-    enum QuotaSubCmd {
-        Q_SYNC,
-        Q_QUOTAON,
-        Q_QUOTAOFF,
-        Q_GETQUOTA,
-        Q_SETQUOTA,
-    }
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum QuotaSubCmd {
+    Q_SYNC = libc::Q_SYNC,
+    Q_QUOTAON = libc::Q_QUOTAON,
+    Q_QUOTAOFF = libc::Q_QUOTAOFF,
+    Q_GETQUOTA = libc::Q_GETQUOTA,
+    Q_SETQUOTA = libc::Q_SETQUOTA,
+    // Not yet exported by libc.
+    Q_GETNEXTQUOTA = 0x800009,
 }
 
-libc_enum! {
-    /// The scope of the quota.
-    #[repr(i32)]
-    #[non_exhaustive]
-    pub enum QuotaType {
-        /// Specify a user quota
-        USRQUOTA,
-        /// Specify a group quota
-        GRPQUOTA,
-    }
+/// The scope of the quota.
+#[repr(i32)]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum QuotaType {
+    /// Specify a user quota
+    USRQUOTA = libc::USRQUOTA,
+    /// Specify a group quota
+    GRPQUOTA = libc::GRPQUOTA,
+    /// Specify a project quota
+    PRJQUOTA = 2,
 }
 
 libc_enum! {
@@ -335,3 +338,177 @@ pub fn quotactl_set<P: ?Sized + NixPath>(
         &mut dqblk_copy as *mut _ as *mut c_char,
     )
 }
+
+// `struct if_nextdqblk` from `<linux/quota.h>`, not yet exported by libc.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct NextDqblk {
+    dqb_bhardlimit: u64,
+    dqb_bsoftlimit: u64,
+    dqb_curspace: u64,
+    dqb_ihardlimit: u64,
+    dqb_isoftlimit: u64,
+    dqb_curinodes: u64,
+    dqb_btime: u64,
+    dqb_itime: u64,
+    dqb_valid: u32,
+    dqb_id: u32,
+}
+
+const _: () = assert!(mem::size_of::<NextDqblk>() == 72);
+
+impl NextDqblk {
+    fn into_parts(self) -> (c_int, Dqblk) {
+        let dqblk = Dqblk(libc::dqblk {
+            dqb_bhardlimit: self.dqb_bhardlimit,
+            dqb_bsoftlimit: self.dqb_bsoftlimit,
+            dqb_curspace: self.dqb_curspace,
+            dqb_ihardlimit: self.dqb_ihardlimit,
+            dqb_isoftlimit: self.dqb_isoftlimit,
+            dqb_curinodes: self.dqb_curinodes,
+            dqb_btime: self.dqb_btime,
+            dqb_itime: self.dqb_itime,
+            dqb_valid: self.dqb_valid,
+        });
+        (self.dqb_id as c_int, dqblk)
+    }
+}
+
+/// Get disk quota limits and current usage for the first user/group/project
+/// id that is greater than or equal to `id` and has a quota.
+///
+/// Returns the id that was found along with its quota.  Iterating over all
+/// ids with quotas can be done by repeatedly calling this function with the
+/// returned id plus one, until it fails with `ESRCH`.
+pub fn quotactl_get_next<P: ?Sized + NixPath>(
+    which: QuotaType,
+    special: &P,
+    id: c_int,
+) -> Result<(c_int, Dqblk)> {
+    let mut next = mem::MaybeUninit::<NextDqblk>::uninit();
+    quotactl(
+        QuotaCmd(QuotaSubCmd::Q_GETNEXTQUOTA, which),
+        Some(special),
+        id,
+        next.as_mut_ptr().cast(),
+    )?;
+    Ok(unsafe { next.assume_init() }.into_parts())
+}
+
+fn quotactl_fd<Fd: AsFd>(
+    fd: Fd,
+    cmd: QuotaCmd,
+    id: c_int,
+    addr: *mut c_char,
+) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_quotactl_fd,
+            fd.as_fd().as_raw_fd(),
+            cmd.as_int(),
+            id,
+            addr,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Turn on disk quotas for the filesystem containing the open file `fd`.
+///
+/// Unlike [`quotactl_on`], no quota file can be specified, so this only works
+/// on filesystems that store quotas in hidden system inodes, such as ext4
+/// with the `quota` feature or XFS.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_on<Fd: AsFd>(
+    fd: Fd,
+    which: QuotaType,
+    format: QuotaFmt,
+) -> Result<()> {
+    quotactl_fd(
+        fd,
+        QuotaCmd(QuotaSubCmd::Q_QUOTAON, which),
+        format as c_int,
+        ptr::null_mut(),
+    )
+}
+
+/// Disable disk quotas for the filesystem containing the open file `fd`.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_off<Fd: AsFd>(fd: Fd, which: QuotaType) -> Result<()> {
+    quotactl_fd(
+        fd,
+        QuotaCmd(QuotaSubCmd::Q_QUOTAOFF, which),
+        0,
+        ptr::null_mut(),
+    )
+}
+
+/// Update the on-disk copy of quota usages for the filesystem containing the
+/// open file `fd`.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_sync<Fd: AsFd>(fd: Fd, which: QuotaType) -> Result<()> {
+    quotactl_fd(fd, QuotaCmd(QuotaSubCmd::Q_SYNC, which), 0, ptr::null_mut())
+}
+
+/// Get disk quota limits and current usage for the given user/group id, on
+/// the filesystem containing the open file `fd`.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_get<Fd: AsFd>(
+    fd: Fd,
+    which: QuotaType,
+    id: c_int,
+) -> Result<Dqblk> {
+    let mut dqblk = mem::MaybeUninit::<libc::dqblk>::uninit();
+    quotactl_fd(
+        fd,
+        QuotaCmd(QuotaSubCmd::Q_GETQUOTA, which),
+        id,
+        dqblk.as_mut_ptr().cast(),
+    )?;
+    Ok(unsafe { Dqblk(dqblk.assume_init()) })
+}
+
+/// Configure quota values for the specified fields for a given user/group id,
+/// on the filesystem containing the open file `fd`.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_set<Fd: AsFd>(
+    fd: Fd,
+    which: QuotaType,
+    id: c_int,
+    dqblk: &Dqblk,
+    fields: QuotaValidFlags,
+) -> Result<()> {
+    let mut dqblk_copy = *dqblk;
+    dqblk_copy.0.dqb_valid = fields.bits();
+    quotactl_fd(
+        fd,
+        QuotaCmd(QuotaSubCmd::Q_SETQUOTA, which),
+        id,
+        &mut dqblk_copy as *mut _ as *mut c_char,
+    )
+}
+
+/// Like [`quotactl_get_next`], but operates on the filesystem containing the
+/// open file `fd`.
+///
+/// Requires Linux 5.14 or later.
+pub fn quotactl_fd_get_next<Fd: AsFd>(
+    fd: Fd,
+    which: QuotaType,
+    id: c_int,
+) -> Result<(c_int, Dqblk)> {
+    let mut next = mem::MaybeUninit::<NextDqblk>::uninit();
+    quotactl_fd(
+        fd,
+        QuotaCmd(QuotaSubCmd::Q_GETNEXTQUOTA, which),
+        id,
+        next.as_mut_ptr().cast(),
+    )?;
+    Ok(unsafe { next.assume_init() }.into_parts())
+}
@@ -0,0 +1,364 @@
+//! Filter the system calls of a thread with seccomp.
+//!
+//! Filters are classic BPF programs, see [`SockFprog`], that are run against
+//! the [`seccomp_data`](libc::seccomp_data) of each system call and decide
+//! what to do with it: allow it, fail it with an error, kill the thread, or
+//! with `SECCOMP_RET_USER_NOTIF` let a supervisor process handle it through
+//! the notification file descriptor returned by
+//! [`seccomp_set_mode_filter_listener`].
+//!
+//! # See Also
+//! [seccomp(2)](https://man7.org/linux/man-pages/man2/seccomp.2.html),
+//! [seccomp_unotify(2)](https://man7.org/linux/man-pages/man2/seccomp_unotify.2.html)
+
+use std::marker::PhantomData;
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+
+use libc::{c_int, c_uint, c_ulong};
+
+use crate::errno::Errno;
+use crate::unistd::Pid;
+use crate::Result;
+
+libc_bitflags! {
+    /// Flags for [`seccomp_set_mode_filter`].
+    pub struct FilterFlags: c_ulong {
+        /// Synchronize all of the threads of the process to the same filter
+        /// tree.
+        SECCOMP_FILTER_FLAG_TSYNC;
+        /// Log all of the actions, except `SECCOMP_RET_ALLOW`.
+        SECCOMP_FILTER_FLAG_LOG;
+        /// Disable the Speculative Store Bypass mitigation.
+        SECCOMP_FILTER_FLAG_SPEC_ALLOW;
+        /// With `SECCOMP_FILTER_FLAG_TSYNC`, report a thread that could not
+        /// be synchronized with `ESRCH`, which is required to also get a
+        /// listener with [`seccomp_set_mode_filter_listener`].
+        SECCOMP_FILTER_FLAG_TSYNC_ESRCH;
+        /// Ignore non-fatal signals while the notified thread waits for the
+        /// supervisor to receive the notification.
+        SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV;
+    }
+}
+
+/// A classic BPF program, for [`seccomp_set_mode_filter`].
+///
+/// # Example
+///
+/// Fail `getppid` with `EPERM`, and allow anything else:
+///
+/// ```
+/// # use nix::sys::seccomp::SockFprog;
+/// # use libc::*;
+/// let filter = unsafe {
+///     [
+///         BPF_STMT((BPF_LD | BPF_W | BPF_ABS) as u16, 0),
+///         BPF_JUMP((BPF_JMP | BPF_JEQ | BPF_K) as u16, SYS_getppid as u32, 0, 1),
+///         BPF_STMT((BPF_RET | BPF_K) as u16, SECCOMP_RET_ERRNO | EPERM as u32),
+///         BPF_STMT((BPF_RET | BPF_K) as u16, SECCOMP_RET_ALLOW),
+///     ]
+/// };
+/// let prog = SockFprog::new(&filter).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SockFprog<'a> {
+    prog: libc::sock_fprog,
+    _filter: PhantomData<&'a [libc::sock_filter]>,
+}
+
+impl<'a> SockFprog<'a> {
+    /// Create a program from its instructions.
+    ///
+    /// Fails with `EINVAL` if there are more than `u16::MAX` instructions.
+    pub fn new(filter: &'a [libc::sock_filter]) -> Result<Self> {
+        let len = u16::try_from(filter.len()).map_err(|_| Errno::EINVAL)?;
+        Ok(SockFprog {
+            prog: libc::sock_fprog {
+                len,
+                // The kernel does not modify the program.
+                filter: filter.as_ptr() as *mut libc::sock_filter,
+            },
+            _filter: PhantomData,
+        })
+    }
+
+    /// The instructions of the program.
+    pub fn filter(&self) -> &'a [libc::sock_filter] {
+        unsafe {
+            std::slice::from_raw_parts(self.prog.filter, self.prog.len.into())
+        }
+    }
+}
+
+// SAFETY: `SockFprog` is a shared borrow of the instructions.
+unsafe impl Send for SockFprog<'_> {}
+unsafe impl Sync for SockFprog<'_> {}
+
+fn seccomp(
+    operation: c_uint,
+    flags: c_ulong,
+    args: *const libc::c_void,
+) -> Result<c_int> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_seccomp, operation, flags as c_uint, args)
+    };
+    Errno::result(res).map(|r| r as c_int)
+}
+
+/// Only allow the calling thread to call `read(2)`, `write(2)`, `_exit(2)`
+/// (but not `exit_group(2)`) and `sigreturn(2)`.  Any other system call
+/// kills it with `SIGKILL`.
+pub fn seccomp_set_mode_strict() -> Result<()> {
+    seccomp(libc::SECCOMP_SET_MODE_STRICT, 0, std::ptr::null()).map(drop)
+}
+
+/// Install the filter `prog` for the calling thread, or for all of the
+/// threads of the process with `SECCOMP_FILTER_FLAG_TSYNC`.
+///
+/// This requires the thread to have set `no_new_privs`, see
+/// [`set_no_new_privs`](crate::sys::prctl::set_no_new_privs), or to have
+/// `CAP_SYS_ADMIN` in its user namespace.  Filters can't be removed, and are
+/// inherited by children.
+///
+/// Fails with `ESRCH` if another thread could not be synchronized with
+/// `SECCOMP_FILTER_FLAG_TSYNC`.
+pub fn seccomp_set_mode_filter(
+    flags: FilterFlags,
+    prog: &SockFprog<'_>,
+) -> Result<()> {
+    let res = seccomp(
+        libc::SECCOMP_SET_MODE_FILTER,
+        flags.bits(),
+        &prog.prog as *const libc::sock_fprog as *const libc::c_void,
+    )?;
+    // The id of the thread that could not be synchronized.
+    if res > 0 {
+        return Err(Errno::ESRCH);
+    }
+    Ok(())
+}
+
+/// Like [`seccomp_set_mode_filter`], but also return a notification file
+/// descriptor, for the system calls on which the filter returns
+/// `SECCOMP_RET_USER_NOTIF` (`SECCOMP_FILTER_FLAG_NEW_LISTENER`).
+///
+/// The file descriptor is typically passed to a supervisor process, which
+/// handles the notifications with [`seccomp_notif_recv`] and
+/// [`seccomp_notif_send`].
+pub fn seccomp_set_mode_filter_listener(
+    flags: FilterFlags,
+    prog: &SockFprog<'_>,
+) -> Result<OwnedFd> {
+    let fd = seccomp(
+        libc::SECCOMP_SET_MODE_FILTER,
+        flags.bits() | libc::SECCOMP_FILTER_FLAG_NEW_LISTENER,
+        &prog.prog as *const libc::sock_fprog as *const libc::c_void,
+    )?;
+
+    // SAFETY: `seccomp(2)` returns a valid owned fd with
+    // `SECCOMP_FILTER_FLAG_NEW_LISTENER`
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+/// A system call notification, received with [`seccomp_notif_recv`].
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct SeccompNotif(libc::seccomp_notif);
+
+impl SeccompNotif {
+    /// The id of the notification, to respond to it.
+    pub fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    /// The thread that made the system call, in the PID namespace of the
+    /// supervisor, or 0 if it is not visible there.
+    pub fn pid(&self) -> Pid {
+        Pid::from_raw(self.0.pid as libc::pid_t)
+    }
+
+    /// The number of the system call.
+    pub fn syscall(&self) -> c_int {
+        self.0.data.nr
+    }
+
+    /// The `AUDIT_ARCH_*` architecture of the system call.
+    pub fn arch(&self) -> u32 {
+        self.0.data.arch
+    }
+
+    /// The instruction pointer of the thread at the system call.
+    pub fn instruction_pointer(&self) -> u64 {
+        self.0.data.instruction_pointer
+    }
+
+    /// The arguments of the system call.
+    ///
+    /// Pointers point into the memory of the notified process, and may be
+    /// read through `/proc/[pid]/mem`, after which [`seccomp_notif_id_valid`]
+    /// should be called to check that the process did not die meanwhile.
+    pub fn args(&self) -> [u64; 6] {
+        self.0.data.args
+    }
+}
+
+impl AsRef<libc::seccomp_notif> for SeccompNotif {
+    fn as_ref(&self) -> &libc::seccomp_notif {
+        &self.0
+    }
+}
+
+/// The response to a [`SeccompNotif`], sent with [`seccomp_notif_send`].
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct SeccompNotifResp(libc::seccomp_notif_resp);
+
+impl SeccompNotifResp {
+    /// Make the system call of the notification `id` return `val`, without
+    /// executing it.
+    pub fn new_val(id: u64, val: i64) -> Self {
+        SeccompNotifResp(libc::seccomp_notif_resp {
+            id,
+            val,
+            error: 0,
+            flags: 0,
+        })
+    }
+
+    /// Make the system call of the notification `id` fail with `errno`,
+    /// without executing it.
+    pub fn new_error(id: u64, errno: Errno) -> Self {
+        SeccompNotifResp(libc::seccomp_notif_resp {
+            id,
+            val: 0,
+            error: -(errno as i32),
+            flags: 0,
+        })
+    }
+
+    /// Let the kernel execute the system call of the notification `id`.
+    ///
+    /// This must not be used to implement security policies, since the
+    /// arguments may have been changed since the notification was received.
+    pub fn new_continue(id: u64) -> Self {
+        SeccompNotifResp(libc::seccomp_notif_resp {
+            id,
+            val: 0,
+            error: 0,
+            flags: libc::SECCOMP_USER_NOTIF_FLAG_CONTINUE as u32,
+        })
+    }
+}
+
+/// Wait for a system call notification on the notification file descriptor
+/// `fd` (`SECCOMP_IOCTL_NOTIF_RECV`).
+pub fn seccomp_notif_recv<Fd: AsFd>(fd: Fd) -> Result<SeccompNotif> {
+    // The structure must be zeroed.
+    let mut notif: libc::seccomp_notif = unsafe { mem::zeroed() };
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::SECCOMP_IOCTL_NOTIF_RECV,
+            &mut notif as *mut libc::seccomp_notif,
+        )
+    };
+    Errno::result(res)?;
+
+    Ok(SeccompNotif(notif))
+}
+
+/// Respond to a system call notification (`SECCOMP_IOCTL_NOTIF_SEND`).
+///
+/// Fails with `ENOENT` if the notified thread was interrupted by a signal,
+/// or was killed.
+pub fn seccomp_notif_send<Fd: AsFd>(
+    fd: Fd,
+    resp: &SeccompNotifResp,
+) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::SECCOMP_IOCTL_NOTIF_SEND,
+            &resp.0 as *const libc::seccomp_notif_resp,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Check that the notification `id` is still pending
+/// (`SECCOMP_IOCTL_NOTIF_ID_VALID`), failing with `ENOENT` otherwise.
+pub fn seccomp_notif_id_valid<Fd: AsFd>(fd: Fd, id: u64) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::SECCOMP_IOCTL_NOTIF_ID_VALID,
+            &id as *const u64,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// A file descriptor to install in a notified process, with
+/// [`seccomp_notif_addfd`].
+#[derive(Clone, Copy, Debug)]
+pub struct SeccompNotifAddfd<'fd> {
+    addfd: libc::seccomp_notif_addfd,
+    _srcfd: PhantomData<BorrowedFd<'fd>>,
+}
+
+impl<'fd> SeccompNotifAddfd<'fd> {
+    /// Install a duplicate of `srcfd` in the process of the notification
+    /// `id`, as the lowest available file descriptor.
+    pub fn new(id: u64, srcfd: BorrowedFd<'fd>) -> Self {
+        SeccompNotifAddfd {
+            addfd: libc::seccomp_notif_addfd {
+                id,
+                flags: 0,
+                srcfd: srcfd.as_raw_fd() as u32,
+                newfd: 0,
+                newfd_flags: 0,
+            },
+            _srcfd: PhantomData,
+        }
+    }
+
+    /// Install the file descriptor as `newfd`, replacing any file descriptor
+    /// already open there (`SECCOMP_ADDFD_FLAG_SETFD`).
+    pub fn setfd(mut self, newfd: RawFd) -> Self {
+        self.addfd.flags |= libc::SECCOMP_ADDFD_FLAG_SETFD as u32;
+        self.addfd.newfd = newfd as u32;
+        self
+    }
+
+    /// Also respond to the notification, making the system call return the
+    /// new file descriptor (`SECCOMP_ADDFD_FLAG_SEND`).
+    pub fn send(mut self) -> Self {
+        self.addfd.flags |= libc::SECCOMP_ADDFD_FLAG_SEND as u32;
+        self
+    }
+
+    /// Set the close-on-exec flag on the new file descriptor.
+    pub fn cloexec(mut self) -> Self {
+        self.addfd.newfd_flags = libc::O_CLOEXEC as u32;
+        self
+    }
+}
+
+/// Install a file descriptor in a notified process
+/// (`SECCOMP_IOCTL_NOTIF_ADDFD`), returning its number in that process.
+pub fn seccomp_notif_addfd<Fd: AsFd>(
+    fd: Fd,
+    addfd: &SeccompNotifAddfd<'_>,
+) -> Result<RawFd> {
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::SECCOMP_IOCTL_NOTIF_ADDFD,
+            &addfd.addfd as *const libc::seccomp_notif_addfd,
+        )
+    };
+
+    Errno::result(res)
+}
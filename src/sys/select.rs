@@ -1,6 +1,7 @@
 //! Portably monitor a group of file descriptors for readiness.
 use crate::errno::Errno;
-use crate::sys::time::{TimeSpec, TimeVal};
+pub use crate::poll_timeout::Timeout;
+use crate::sys::time::TimeVal;
 use crate::Result;
 use libc::{self, c_int};
 use std::convert::TryFrom;
@@ -178,8 +179,8 @@ impl<'a, 'fd> FusedIterator for Fds<'a, 'fd> {}
 /// * `readfds`: File descriptors to check for being ready to read.
 /// * `writefds`: File descriptors to check for being ready to write.
 /// * `errorfds`: File descriptors to check for pending error conditions.
-/// * `timeout`: Maximum time to wait for descriptors to become ready (`None` to block
-///   indefinitely).
+/// * `timeout`: Maximum time to wait for descriptors to become ready ([`Timeout::Infinite`]
+///   to block indefinitely).
 ///
 /// # References
 ///
@@ -286,14 +287,14 @@ where
     R: Into<Option<&'a mut FdSet<'fd>>>,
     W: Into<Option<&'a mut FdSet<'fd>>>,
     E: Into<Option<&'a mut FdSet<'fd>>>,
-    T: Into<Option<&'a TimeSpec>>,
+    T: Into<Timeout>,
     S: Into<Option<&'a SigSet>>,
 {
     let mut readfds = readfds.into();
     let mut writefds = writefds.into();
     let mut errorfds = errorfds.into();
     let sigmask = sigmask.into();
-    let timeout = timeout.into();
+    let timeout = timeout.into().as_timespec();
 
     let nfds = nfds.into().unwrap_or_else(|| {
         readfds.iter_mut()
@@ -307,7 +308,7 @@ where
     let readfds = readfds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
     let writefds = writefds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
     let errorfds = errorfds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
-    let timeout = timeout.map(|ts| ts.as_ref() as *const libc::timespec).unwrap_or(null());
+    let timeout = timeout.as_ref().map(|ts| ts.as_ref() as *const libc::timespec).unwrap_or(null());
     let sigmask = sigmask.map(|sm| sm.as_ref() as *const libc::sigset_t).unwrap_or(null());
 
     let res = unsafe {
@@ -0,0 +1,257 @@
+//! System V semaphore sets.
+//!
+//! A semaphore set is an array of counters that can be atomically adjusted
+//! with [`semop`], blocking until the requested operations can all be
+//! performed.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::sys::ipc::{IpcGetFlags, IPC_PRIVATE};
+//! # use nix::sys::sem::{semctl_getval, semctl_rmid, semctl_setval, semget, semop, SemBuf};
+//! # use nix::sys::stat::Mode;
+//! let id = semget(IPC_PRIVATE, 1, IpcGetFlags::IPC_CREAT, Mode::S_IRWXU).unwrap();
+//! semctl_setval(id, 0, 1).unwrap();
+//! // Acquire the semaphore.
+//! semop(id, &[SemBuf::new(0).op(-1)]).unwrap();
+//! assert_eq!(semctl_getval(id, 0).unwrap(), 0);
+//! // Release it.
+//! semop(id, &[SemBuf::new(0).op(1)]).unwrap();
+//! semctl_rmid(id).unwrap();
+//! ```
+
+use libc::{c_int, c_short};
+
+use crate::errno::Errno;
+use crate::sys::ipc::{key_t, IpcGetFlags};
+use crate::sys::stat::Mode;
+use crate::Result;
+
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+use crate::sys::ipc::IpcPerm;
+#[cfg(target_os = "linux")]
+use crate::sys::time::TimeSpec;
+
+/// Identifier of a semaphore set, returned by [`semget`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SemId(c_int);
+
+impl SemId {
+    /// Create a `SemId` from a raw identifier, as obtained from another
+    /// process.
+    pub const fn from_raw(id: c_int) -> Self {
+        SemId(id)
+    }
+
+    /// Get the raw identifier.
+    pub const fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+/// Get the identifier of the semaphore set associated with `key`, creating a
+/// set of `nsems` semaphores, initialized to 0 on Linux but unspecified
+/// elsewhere, with permissions `mode` if requested by `flags`.
+///
+/// # See Also
+/// * [`semget(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semget.html)
+pub fn semget(
+    key: key_t,
+    nsems: c_int,
+    flags: IpcGetFlags,
+    mode: Mode,
+) -> Result<SemId> {
+    let res = unsafe {
+        libc::semget(key, nsems, flags.bits() | mode.bits() as c_int)
+    };
+    Errno::result(res).map(SemId)
+}
+
+libc_bitflags! {
+    /// Flags for a semaphore operation, see [`SemBuf::flags`].
+    pub struct SemOpFlags: c_short {
+        /// Fail with `EAGAIN` instead of blocking.
+        IPC_NOWAIT as c_short;
+        /// Revert the operation when the process exits.
+        SEM_UNDO as c_short;
+    }
+}
+
+/// An operation on a semaphore of a set, for [`semop`].
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::sem::{SemBuf, SemOpFlags};
+/// // Decrement semaphore 2, reverting it on exit.
+/// let op = SemBuf::new(2).op(-1).flags(SemOpFlags::SEM_UNDO);
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct SemBuf(libc::sembuf);
+
+impl SemBuf {
+    /// Create an operation on the semaphore at index `sem_num`, which waits
+    /// for the semaphore to become 0 unless changed with [`op`](Self::op).
+    pub fn new(sem_num: u16) -> Self {
+        SemBuf(libc::sembuf {
+            sem_num,
+            sem_op: 0,
+            sem_flg: 0,
+        })
+    }
+
+    /// Set the value to add to the semaphore.
+    ///
+    /// A negative value blocks until the semaphore is large enough not to
+    /// become negative, and 0 blocks until the semaphore is 0.
+    pub fn op(mut self, sem_op: i16) -> Self {
+        self.0.sem_op = sem_op;
+        self
+    }
+
+    /// Set the flags of the operation.
+    pub fn flags(mut self, flags: SemOpFlags) -> Self {
+        self.0.sem_flg = flags.bits();
+        self
+    }
+
+    /// The index of the semaphore in the set.
+    pub fn sem_num(&self) -> u16 {
+        self.0.sem_num
+    }
+
+    /// The value to add to the semaphore.
+    pub fn sem_op(&self) -> i16 {
+        self.0.sem_op
+    }
+
+    /// The flags of the operation.
+    pub fn sem_flags(&self) -> SemOpFlags {
+        SemOpFlags::from_bits_truncate(self.0.sem_flg)
+    }
+}
+
+/// Atomically perform all of the operations `sops` on the semaphore set `id`.
+///
+/// # See Also
+/// * [`semop(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semop.html)
+pub fn semop(id: SemId, sops: &[SemBuf]) -> Result<()> {
+    // The kernel does not modify the operations, despite the signature.
+    let res = unsafe {
+        libc::semop(id.0, sops.as_ptr() as *mut libc::sembuf, sops.len())
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Like [`semop`], but fail with `EAGAIN` if the operations cannot be
+/// performed before `timeout` elapses.  `None` waits indefinitely.
+///
+/// # See Also
+/// * [`semtimedop(2)`](https://man7.org/linux/man-pages/man2/semtimedop.2.html)
+#[cfg(target_os = "linux")]
+pub fn semtimedop(
+    id: SemId,
+    sops: &[SemBuf],
+    timeout: Option<&TimeSpec>,
+) -> Result<()> {
+    // Provided by all Linux C libraries, but not yet declared by libc.
+    extern "C" {
+        fn semtimedop(
+            semid: c_int,
+            sops: *mut libc::sembuf,
+            nsops: libc::size_t,
+            timeout: *const libc::timespec,
+        ) -> c_int;
+    }
+
+    let timeout = timeout.map_or(std::ptr::null(), |t| t.as_ref() as *const _);
+    let res = unsafe {
+        semtimedop(
+            id.0,
+            sops.as_ptr() as *mut libc::sembuf,
+            sops.len(),
+            timeout,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Get the value of the semaphore at index `semnum` (`semctl(GETVAL)`).
+///
+/// # See Also
+/// * [`semctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)
+pub fn semctl_getval(id: SemId, semnum: c_int) -> Result<c_int> {
+    let res = unsafe { libc::semctl(id.0, semnum, libc::GETVAL) };
+    Errno::result(res)
+}
+
+/// Set the value of the semaphore at index `semnum` (`semctl(SETVAL)`),
+/// clearing the undo entries of all processes for it.
+///
+/// # See Also
+/// * [`semctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)
+pub fn semctl_setval(id: SemId, semnum: c_int, val: c_int) -> Result<()> {
+    let res = unsafe { libc::semctl(id.0, semnum, libc::SETVAL, val) };
+    Errno::result(res).map(drop)
+}
+
+/// Remove the semaphore set `id` (`semctl(IPC_RMID)`), waking up all
+/// processes blocked on it with `EIDRM`.
+///
+/// # See Also
+/// * [`semctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)
+pub fn semctl_rmid(id: SemId) -> Result<()> {
+    let res = unsafe { libc::semctl(id.0, 0, libc::IPC_RMID) };
+    Errno::result(res).map(drop)
+}
+
+/// The status of a semaphore set, returned by [`semctl_stat`].
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct SemidDs(libc::semid_ds);
+
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+impl SemidDs {
+    /// The ownership and permissions of the set.
+    pub fn perm(&self) -> IpcPerm {
+        IpcPerm(self.0.sem_perm)
+    }
+
+    /// The number of semaphores in the set.
+    pub fn nsems(&self) -> usize {
+        self.0.sem_nsems as usize
+    }
+
+    /// The time of the last [`semop`], or 0.
+    pub fn otime(&self) -> libc::time_t {
+        self.0.sem_otime
+    }
+
+    /// The time of the creation or last change of the set.
+    pub fn ctime(&self) -> libc::time_t {
+        self.0.sem_ctime
+    }
+}
+
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+impl AsRef<libc::semid_ds> for SemidDs {
+    fn as_ref(&self) -> &libc::semid_ds {
+        &self.0
+    }
+}
+
+/// Get the status of the semaphore set `id` (`semctl(IPC_STAT)`).
+///
+/// # See Also
+/// * [`semctl(2)`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+pub fn semctl_stat(id: SemId) -> Result<SemidDs> {
+    let mut buf = std::mem::MaybeUninit::<libc::semid_ds>::uninit();
+    let res =
+        unsafe { libc::semctl(id.0, 0, libc::IPC_STAT, buf.as_mut_ptr()) };
+    Errno::result(res)?;
+
+    Ok(SemidDs(unsafe { buf.assume_init() }))
+}
@@ -1376,6 +1376,44 @@ mod sigevent {
             SigEvent{sigevent: sev}
         }
 
+        /// Deliver the realtime signal `SIGRTMIN + offset` instead of the
+        /// signal given to [`SigEvent::new`].
+        ///
+        /// Unlike standard signals, multiple instances of a realtime signal
+        /// can be queued, each carrying its own `si_value` payload.
+        ///
+        /// Fails with `EINVAL` if `SIGRTMIN + offset` is larger than
+        /// `SIGRTMAX`, or if the notification method does not deliver a
+        /// signal.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// # use nix::sys::signal::{SigEvent, SigevNotify, Signal};
+        /// let sigevent = SigEvent::new(SigevNotify::SigevSignal {
+        ///     signal: Signal::SIGALRM,
+        ///     si_value: 42,
+        /// })
+        /// .with_realtime_signal(1)
+        /// .unwrap();
+        /// assert_eq!(sigevent.sigevent().sigev_signo, libc::SIGRTMIN() + 1);
+        /// ```
+        #[cfg(any(linux_android, solarish))]
+        pub fn with_realtime_signal(
+            mut self,
+            offset: libc::c_int,
+        ) -> crate::Result<SigEvent> {
+            let signo = libc::SIGRTMIN().checked_add(offset)
+                .filter(|&signo| offset >= 0 && signo <= libc::SIGRTMAX());
+            match signo {
+                Some(signo) if self.sigevent.sigev_notify != libc::SIGEV_NONE => {
+                    self.sigevent.sigev_signo = signo;
+                    Ok(self)
+                }
+                _ => Err(crate::errno::Errno::EINVAL),
+            }
+        }
+
         /// Return a copy of the inner structure
         #[cfg(target_os = "freebsd")]
         pub fn sigevent(&self) -> libc::sigevent {
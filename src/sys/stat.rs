@@ -281,12 +281,10 @@ pub fn fchmod<Fd: std::os::fd::AsFd>(fd: Fd, mode: Mode) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
-/// Flags for `fchmodat` function.
-#[derive(Clone, Copy, Debug)]
-pub enum FchmodatFlags {
-    FollowSymlink,
-    NoFollowSymlink,
-}
+// Just a wrapper around `AtFlags` so that we can help our users migrate.
+#[allow(missing_docs)]
+#[cfg(not(target_os = "redox"))]
+pub type FchmodatFlags = AtFlags;
 
 /// Change the file permission bits.
 ///
@@ -294,10 +292,12 @@ pub enum FchmodatFlags {
 /// with the file descriptor `dirfd` or the current working directory
 /// if `dirfd` is [`AT_FDCWD`](crate::fcntl::AT_FDCWD).
 ///
-/// If `flag` is `FchmodatFlags::NoFollowSymlink` and `path` names a symbolic link,
-/// then the mode of the symbolic link is changed.
+/// If `flag` is `AtFlags::AT_SYMLINK_NOFOLLOW` and `path` names a symbolic link,
+/// then the mode of the symbolic link is changed.  On Linux, `AT_EMPTY_PATH`
+/// with an empty `path` changes the mode of `dirfd` itself, which requires
+/// Linux 6.6 or later.
 ///
-/// `fchmodat(AT_FDCWD, path, mode, FchmodatFlags::FollowSymlink)` is identical to
+/// `fchmodat(AT_FDCWD, path, mode, AtFlags::empty())` is identical to
 /// a call `libc::chmod(path, mode)`. That's why `chmod` is unimplemented
 /// in the `nix` crate.
 ///
@@ -309,20 +309,94 @@ pub fn fchmodat<Fd: std::os::fd::AsFd, P: ?Sized + NixPath>(
     dirfd: Fd,
     path: &P,
     mode: Mode,
-    flag: FchmodatFlags,
+    flag: AtFlags,
 ) -> Result<()> {
     use std::os::fd::AsRawFd;
 
-    let atflag = match flag {
-        FchmodatFlags::FollowSymlink => AtFlags::empty(),
-        FchmodatFlags::NoFollowSymlink => AtFlags::AT_SYMLINK_NOFOLLOW,
-    };
     let res = path.with_nix_path(|cstr| unsafe {
         libc::fchmodat(
             dirfd.as_fd().as_raw_fd(),
             cstr.as_ptr(),
             mode.bits() as mode_t,
-            atflag.bits() as libc::c_int,
+            flag.bits() as libc::c_int,
+        )
+    })?;
+
+    Errno::result(res).map(drop)
+}
+
+// libc only exports `SYS_fchmodat2` for some architectures and C libraries.
+// Elsewhere, like other recent syscalls, it has the same number everywhere,
+// plus the per-ABI offset on mips.
+#[cfg(target_os = "linux")]
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        all(
+            target_env = "gnu",
+            any(
+                target_arch = "x86",
+                target_arch = "x86_64",
+                target_arch = "m68k"
+            )
+        ),
+        all(
+            any(target_env = "musl", target_env = "ohos"),
+            any(
+                target_arch = "x86",
+                target_arch = "x86_64",
+                target_arch = "s390x",
+                target_arch = "loongarch64"
+            )
+        )
+    ))] {
+        use libc::SYS_fchmodat2 as SYS_FCHMODAT2;
+    } else if #[cfg(target_arch = "mips")] {
+        const SYS_FCHMODAT2: libc::c_long = 4000 + 452;
+    } else if #[cfg(all(target_arch = "mips64", target_pointer_width = "64"))] {
+        const SYS_FCHMODAT2: libc::c_long = 5000 + 452;
+    } else if #[cfg(target_arch = "mips64")] {
+        const SYS_FCHMODAT2: libc::c_long = 6000 + 452;
+    } else {
+        const SYS_FCHMODAT2: libc::c_long = 452;
+    }
+}
+
+/// Change the file permission bits, like [`fchmodat`], but with support for
+/// `AtFlags::AT_SYMLINK_NOFOLLOW` and `AtFlags::AT_EMPTY_PATH`
+/// (`fchmodat2(2)`).
+///
+/// The C library's `fchmodat` either rejects `AT_SYMLINK_NOFOLLOW` or
+/// emulates it through `/proc`, as the `fchmodat` syscall has no flags
+/// argument.  `fchmodat2` handles it in the kernel: if `path` names a
+/// symbolic link, `EOPNOTSUPP` is returned, as Linux doesn't support changing
+/// the mode of symbolic links, and otherwise the file is changed without any
+/// race with a concurrent replacement by a symbolic link.
+///
+/// Requires Linux 6.6 or later.  On older kernels, `ENOSYS` is returned; a
+/// caller that needs to support them can fall back to opening the file with
+/// `O_PATH | O_NOFOLLOW`, checking with [`fstat`] that it isn't a symbolic
+/// link, and calling [`fchmodat`] on `/proc/self/fd/<fd>`, which is what
+/// glibc does.
+///
+/// # References
+///
+/// [fchmodat2(2)](https://man7.org/linux/man-pages/man2/fchmodat2.2.html).
+#[cfg(target_os = "linux")]
+pub fn fchmodat2<Fd: std::os::fd::AsFd, P: ?Sized + NixPath>(
+    dirfd: Fd,
+    path: &P,
+    mode: Mode,
+    flag: AtFlags,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(
+            SYS_FCHMODAT2,
+            dirfd.as_fd().as_raw_fd(),
+            cstr.as_ptr(),
+            mode.bits() as mode_t,
+            flag.bits() as libc::c_int,
         )
     })?;
 
@@ -332,7 +406,7 @@ pub fn fchmodat<Fd: std::os::fd::AsFd, P: ?Sized + NixPath>(
 /// Change the access and modification times of a file.
 ///
 /// `utimes(path, times)` is identical to
-/// `utimensat(None, path, times, UtimensatFlags::FollowSymlink)`. The former
+/// `utimensat(AT_FDCWD, path, times, AtFlags::empty())`. The former
 /// is a deprecated API so prefer using the latter if the platforms you care
 /// about support it.
 ///
@@ -355,7 +429,7 @@ pub fn utimes<P: ?Sized + NixPath>(
 /// Change the access and modification times of a file without following symlinks.
 ///
 /// `lutimes(path, times)` is identical to
-/// `utimensat(None, path, times, UtimensatFlags::NoFollowSymlink)`. The former
+/// `utimensat(AT_FDCWD, path, times, AtFlags::AT_SYMLINK_NOFOLLOW)`. The former
 /// is a deprecated API so prefer using the latter if the platforms you care
 /// about support it.
 ///
@@ -404,13 +478,10 @@ pub fn futimens<Fd: std::os::fd::AsFd>(
     Errno::result(res).map(drop)
 }
 
-/// Flags for `utimensat` function.
-// TODO: replace with fcntl::AtFlags
-#[derive(Clone, Copy, Debug)]
-pub enum UtimensatFlags {
-    FollowSymlink,
-    NoFollowSymlink,
-}
+// Just a wrapper around `AtFlags` so that we can help our users migrate.
+#[allow(missing_docs)]
+#[cfg(not(target_os = "redox"))]
+pub type UtimensatFlags = AtFlags;
 
 /// Change the access and modification times of a file.
 ///
@@ -418,10 +489,12 @@ pub enum UtimensatFlags {
 /// with the file descriptor `dirfd` or the current working directory
 /// if `dirfd` is [`AT_FDCWD`](crate::fcntl::AT_FDCWD).
 ///
-/// If `flag` is `UtimensatFlags::NoFollowSymlink` and `path` names a symbolic link,
-/// then the mode of the symbolic link is changed.
+/// If `flag` is `AtFlags::AT_SYMLINK_NOFOLLOW` and `path` names a symbolic link,
+/// then the times of the symbolic link are changed.  On Linux, `AT_EMPTY_PATH`
+/// with an empty `path` changes the times of `dirfd` itself, which may have
+/// been opened with `O_PATH`.
 ///
-/// `utimensat(AT_FDCWD, path, times, UtimensatFlags::FollowSymlink)` is identical to
+/// `utimensat(AT_FDCWD, path, times, AtFlags::empty())` is identical to
 /// `utimes(path, times)`. The latter is a deprecated API so prefer using the
 /// former if the platforms you care about support it.
 ///
@@ -437,21 +510,17 @@ pub fn utimensat<Fd: std::os::fd::AsFd, P: ?Sized + NixPath>(
     path: &P,
     atime: &TimeSpec,
     mtime: &TimeSpec,
-    flag: UtimensatFlags,
+    flag: AtFlags,
 ) -> Result<()> {
     use std::os::fd::AsRawFd;
 
-    let atflag = match flag {
-        UtimensatFlags::FollowSymlink => AtFlags::empty(),
-        UtimensatFlags::NoFollowSymlink => AtFlags::AT_SYMLINK_NOFOLLOW,
-    };
     let times: [libc::timespec; 2] = [*atime.as_ref(), *mtime.as_ref()];
     let res = path.with_nix_path(|cstr| unsafe {
         libc::utimensat(
             dirfd.as_fd().as_raw_fd(),
             cstr.as_ptr(),
             &times[0],
-            atflag.bits() as libc::c_int,
+            flag.bits() as libc::c_int,
         )
     })?;
 
@@ -286,6 +286,73 @@ pub const NSFS_MAGIC: FsType = FsType(libc::NSFS_MAGIC as fs_type_t);
 #[cfg(all(linux_android, not(target_env = "musl"), not(target_env = "ohos")))]
 #[allow(missing_docs)]
 pub const XFS_SUPER_MAGIC: FsType = FsType(libc::XFS_SUPER_MAGIC as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const BCACHEFS_SUPER_MAGIC: FsType =
+    FsType(libc::BCACHEFS_SUPER_MAGIC as fs_type_t);
+#[cfg(target_os = "linux")]
+#[allow(missing_docs)]
+pub const BINDERFS_SUPER_MAGIC: FsType =
+    FsType(libc::BINDERFS_SUPER_MAGIC as fs_type_t);
+
+// These constants are not provided by libc, `BINDERFS_SUPER_MAGIC` only on
+// Android.  Some of the magic numbers don't fit in a signed 32-bit integer, so
+// they go through `u32` to get the same value as `f_type` on 32-bit
+// platforms.
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const ANON_INODE_FS_MAGIC: FsType = FsType(0x0904_1934_u32 as fs_type_t);
+#[cfg(target_os = "android")]
+#[allow(missing_docs)]
+pub const BINDERFS_SUPER_MAGIC: FsType = FsType(0x6c6f_6f70_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const CEPH_SUPER_MAGIC: FsType = FsType(0x00c3_6400_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const CIFS_SUPER_MAGIC: FsType = FsType(0xff53_4d42_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const CONFIGFS_MAGIC: FsType = FsType(0x6265_6570_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const EFIVARFS_MAGIC: FsType = FsType(0xde5e_81e4_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const EROFS_SUPER_MAGIC_V1: FsType = FsType(0xe0f5_e1e2_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const EXFAT_SUPER_MAGIC: FsType = FsType(0x2011_bab0_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const NTFS3_SUPER_MAGIC: FsType = FsType(0x7366_746e_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const PIPEFS_MAGIC: FsType = FsType(0x5049_5045_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const PSTOREFS_MAGIC: FsType = FsType(0x6165_676c_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const RAMFS_MAGIC: FsType = FsType(0x8584_58f6_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const SMB2_SUPER_MAGIC: FsType = FsType(0xfe53_4d42_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const SOCKFS_MAGIC: FsType = FsType(0x534f_434b_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const SQUASHFS_MAGIC: FsType = FsType(0x7371_7368_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const V9FS_MAGIC: FsType = FsType(0x0102_1997_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const ZFS_SUPER_MAGIC: FsType = FsType(0x2fc1_2fc1_u32 as fs_type_t);
+#[cfg(linux_android)]
+#[allow(missing_docs)]
+pub const ZONEFS_MAGIC: FsType = FsType(0x5a4f_4653_u32 as fs_type_t);
 
 impl Statfs {
     /// Magic code defining system type
@@ -0,0 +1,327 @@
+//! Get extended file status with `statx(2)`.
+//!
+//! Compared to [`fstatat`](crate::sys::stat::fstatat), `statx` lets the
+//! caller choose which fields to retrieve, which may avoid expensive lookups
+//! on network filesystems, and provides information `stat` cannot, such as
+//! the birth time of a file, its mount ID, or its direct I/O alignment
+//! requirements.
+//!
+//! # Example
+//!
+//! ```
+//! # use nix::fcntl::{AtFlags, AT_FDCWD};
+//! # use nix::sys::statx::{statx, StatxMask};
+//! let mask = StatxMask::STATX_BASIC_STATS | StatxMask::STATX_BTIME;
+//! let stx = statx(AT_FDCWD, "/", AtFlags::empty(), mask).unwrap();
+//! assert!(stx.mask().contains(StatxMask::STATX_TYPE));
+//! if let Some(btime) = stx.btime() {
+//!     println!("/ was created at {btime}");
+//! }
+//! ```
+
+use std::mem::MaybeUninit;
+use std::os::fd::{AsFd, AsRawFd};
+
+use libc::c_uint;
+
+use crate::errno::Errno;
+use crate::fcntl::AtFlags;
+use crate::sys::time::TimeSpec;
+use crate::{NixPath, Result};
+
+libc_bitflags! {
+    /// Fields to request from, or returned by, [`statx`].
+    ///
+    /// The kernel may return more fields than requested, and fewer if the
+    /// filesystem cannot provide them, so [`Statx::mask`] should be checked
+    /// before using a field.
+    pub struct StatxMask: c_uint {
+        /// File type, in `stx_mode`.
+        STATX_TYPE;
+        /// File permissions, in `stx_mode`.
+        STATX_MODE;
+        /// Number of hard links.
+        STATX_NLINK;
+        /// Owner user ID.
+        STATX_UID;
+        /// Owner group ID.
+        STATX_GID;
+        /// Last access time.
+        STATX_ATIME;
+        /// Last modification time.
+        STATX_MTIME;
+        /// Last status change time.
+        STATX_CTIME;
+        /// Inode number.
+        STATX_INO;
+        /// File size.
+        STATX_SIZE;
+        /// Number of allocated blocks.
+        STATX_BLOCKS;
+        /// All of the above, the same information as `stat` provides.
+        STATX_BASIC_STATS;
+        /// Birth (creation) time.
+        STATX_BTIME;
+        /// Mount ID.  Since Linux 5.8.
+        STATX_MNT_ID;
+        /// Direct I/O alignment requirements.  Since Linux 6.1.
+        STATX_DIOALIGN;
+        /// Unique, never reused, mount ID.  Since Linux 6.8.
+        STATX_MNT_ID_UNIQUE;
+    }
+}
+
+libc_bitflags! {
+    /// File attributes returned by [`statx`].
+    pub struct StatxAttr: u64 {
+        /// The file is compressed by the filesystem.
+        STATX_ATTR_COMPRESSED as u64;
+        /// The file cannot be modified.
+        STATX_ATTR_IMMUTABLE as u64;
+        /// The file can only be opened in append mode for writing.
+        STATX_ATTR_APPEND as u64;
+        /// The file is not a candidate for backup.
+        STATX_ATTR_NODUMP as u64;
+        /// A key is required for the file to be encrypted by the filesystem.
+        STATX_ATTR_ENCRYPTED as u64;
+        /// The file is an automount trigger.
+        STATX_ATTR_AUTOMOUNT as u64;
+        /// The file is the root of a mount.
+        STATX_ATTR_MOUNT_ROOT as u64;
+        /// The file has fs-verity enabled.
+        STATX_ATTR_VERITY as u64;
+        /// The file is in the DAX (CPU direct access) state.
+        STATX_ATTR_DAX as u64;
+    }
+}
+
+/// Extended file status, returned by [`statx`].
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct Statx(libc::statx);
+
+impl Statx {
+    fn has(&self, mask: StatxMask) -> bool {
+        self.mask().contains(mask)
+    }
+
+    fn timestamp(ts: &libc::statx_timestamp) -> TimeSpec {
+        TimeSpec::new(ts.tv_sec as libc::time_t, ts.tv_nsec as _)
+    }
+
+    /// The fields that were filled in by the kernel.
+    pub fn mask(&self) -> StatxMask {
+        StatxMask::from_bits_truncate(self.0.stx_mask)
+    }
+
+    /// The preferred block size for I/O.
+    pub fn blksize(&self) -> u32 {
+        self.0.stx_blksize
+    }
+
+    /// The attributes of the file.
+    ///
+    /// Only the attributes in [`attributes_mask`](Statx::attributes_mask)
+    /// are supported by the filesystem.
+    pub fn attributes(&self) -> StatxAttr {
+        StatxAttr::from_bits_truncate(self.0.stx_attributes)
+    }
+
+    /// The attributes supported by the filesystem.
+    pub fn attributes_mask(&self) -> StatxAttr {
+        StatxAttr::from_bits_truncate(self.0.stx_attributes_mask)
+    }
+
+    /// The number of hard links.
+    pub fn nlink(&self) -> u32 {
+        self.0.stx_nlink
+    }
+
+    /// The owner user ID.
+    pub fn uid(&self) -> u32 {
+        self.0.stx_uid
+    }
+
+    /// The owner group ID.
+    pub fn gid(&self) -> u32 {
+        self.0.stx_gid
+    }
+
+    /// The file type and permissions, as in `st_mode`.
+    pub fn mode(&self) -> libc::mode_t {
+        self.0.stx_mode.into()
+    }
+
+    /// The inode number.
+    pub fn ino(&self) -> u64 {
+        self.0.stx_ino
+    }
+
+    /// The file size, in bytes.
+    pub fn size(&self) -> u64 {
+        self.0.stx_size
+    }
+
+    /// The number of allocated 512-byte blocks.
+    pub fn blocks(&self) -> u64 {
+        self.0.stx_blocks
+    }
+
+    /// The last access time.
+    pub fn atime(&self) -> TimeSpec {
+        Self::timestamp(&self.0.stx_atime)
+    }
+
+    /// The birth time, if supported by the filesystem.
+    pub fn btime(&self) -> Option<TimeSpec> {
+        self.has(StatxMask::STATX_BTIME)
+            .then(|| Self::timestamp(&self.0.stx_btime))
+    }
+
+    /// The last status change time.
+    pub fn ctime(&self) -> TimeSpec {
+        Self::timestamp(&self.0.stx_ctime)
+    }
+
+    /// The last modification time.
+    pub fn mtime(&self) -> TimeSpec {
+        Self::timestamp(&self.0.stx_mtime)
+    }
+
+    /// The major and minor numbers of the device this file represents, if it
+    /// is a device file.
+    pub fn rdev(&self) -> (u32, u32) {
+        (self.0.stx_rdev_major, self.0.stx_rdev_minor)
+    }
+
+    /// The major and minor numbers of the device containing the file.
+    pub fn dev(&self) -> (u32, u32) {
+        (self.0.stx_dev_major, self.0.stx_dev_minor)
+    }
+
+    /// The ID of the mount containing the file, as in `/proc/self/mountinfo`
+    /// (or the unique mount ID, if `STATX_MNT_ID_UNIQUE` was requested).
+    pub fn mnt_id(&self) -> Option<u64> {
+        (self.has(StatxMask::STATX_MNT_ID)
+            || self.has(StatxMask::STATX_MNT_ID_UNIQUE))
+        .then_some(self.0.stx_mnt_id)
+    }
+
+    /// The alignment, in bytes, required for user memory buffers used for
+    /// direct I/O on the file.
+    ///
+    /// `Some(0)` means that direct I/O is not supported on the file.
+    pub fn dio_mem_align(&self) -> Option<u32> {
+        self.has(StatxMask::STATX_DIOALIGN)
+            .then_some(self.0.stx_dio_mem_align)
+    }
+
+    /// The alignment, in bytes, required for file offsets and I/O sizes used
+    /// for direct I/O on the file.
+    ///
+    /// `Some(0)` means that direct I/O is not supported on the file.
+    pub fn dio_offset_align(&self) -> Option<u32> {
+        self.has(StatxMask::STATX_DIOALIGN)
+            .then_some(self.0.stx_dio_offset_align)
+    }
+}
+
+impl AsRef<libc::statx> for Statx {
+    fn as_ref(&self) -> &libc::statx {
+        &self.0
+    }
+}
+
+/// Get extended file status.
+///
+/// `pathname` is resolved relative to `dirfd`, as with
+/// [`fstatat`](crate::sys::stat::fstatat).  `flags` may contain
+/// `AT_SYMLINK_NOFOLLOW`, `AT_NO_AUTOMOUNT`, `AT_EMPTY_PATH` to get the
+/// status of `dirfd` itself, and `AT_STATX_FORCE_SYNC` or
+/// `AT_STATX_DONT_SYNC` to control synchronization with remote filesystems.
+///
+/// # See Also
+/// * [`statx(2)`](https://man7.org/linux/man-pages/man2/statx.2.html)
+pub fn statx<Fd: AsFd, P: ?Sized + NixPath>(
+    dirfd: Fd,
+    pathname: &P,
+    flags: AtFlags,
+    mask: StatxMask,
+) -> Result<Statx> {
+    let mut dst = MaybeUninit::<libc::statx>::uninit();
+    let res = pathname.with_nix_path(|cstr| unsafe {
+        libc::statx(
+            dirfd.as_fd().as_raw_fd(),
+            cstr.as_ptr(),
+            flags.bits(),
+            mask.bits(),
+            dst.as_mut_ptr(),
+        )
+    })?;
+    Errno::result(res)?;
+
+    Ok(Statx(unsafe { dst.assume_init() }))
+}
+
+/// Alignment requirements for direct I/O (`O_DIRECT`) on a file, returned by
+/// [`dio_alignment`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DioAlignment {
+    /// The alignment, in bytes, of user memory buffers.
+    pub mem_align: u32,
+    /// The alignment, in bytes, of file offsets and I/O sizes.
+    pub offset_align: u32,
+}
+
+/// Get the alignment requirements for direct I/O on the file `fd`.
+///
+/// This uses `statx` with `STATX_DIOALIGN` where supported (Linux 6.1 and
+/// later, and depending on the filesystem).  Otherwise, the logical sector
+/// size of block devices (`ioctl(BLKSSZGET)`), or the filesystem block size
+/// of other files, is returned for both alignments, which is safe but may be
+/// stricter than needed.
+///
+/// `Ok(None)` is returned if direct I/O is known not to be supported on the
+/// file.
+pub fn dio_alignment<Fd: AsFd>(fd: Fd) -> Result<Option<DioAlignment>> {
+    let stx = statx(
+        &fd,
+        "",
+        AtFlags::AT_EMPTY_PATH,
+        StatxMask::STATX_TYPE | StatxMask::STATX_DIOALIGN,
+    )?;
+    if let (Some(mem_align), Some(offset_align)) =
+        (stx.dio_mem_align(), stx.dio_offset_align())
+    {
+        if mem_align == 0 || offset_align == 0 {
+            return Ok(None);
+        }
+        return Ok(Some(DioAlignment {
+            mem_align,
+            offset_align,
+        }));
+    }
+
+    #[cfg(target_os = "linux")]
+    if stx.mode() & libc::S_IFMT == libc::S_IFBLK {
+        let mut sector_size: libc::c_int = 0;
+        let res = unsafe {
+            libc::ioctl(
+                fd.as_fd().as_raw_fd(),
+                libc::BLKSSZGET,
+                &mut sector_size,
+            )
+        };
+        Errno::result(res)?;
+        return Ok(Some(DioAlignment {
+            mem_align: sector_size as u32,
+            offset_align: sector_size as u32,
+        }));
+    }
+
+    let block_size = crate::sys::statvfs::fstatvfs(&fd)?.block_size() as u32;
+    Ok(Some(DioAlignment {
+        mem_align: block_size,
+        offset_align: block_size,
+    }))
+}
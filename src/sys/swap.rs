@@ -0,0 +1,121 @@
+//! Enable and disable devices and files for swapping.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::swap::*;
+//! // Swap to the file with a higher priority than the other swap areas,
+//! // discarding freed pages on devices that support it.
+//! let flags = SwapFlags::with_priority(100) | SwapFlags::SWAP_FLAG_DISCARD;
+//! swapon("/swapfile", flags).unwrap();
+//! // ...
+//! swapoff("/swapfile").unwrap();
+//! ```
+//!
+//! # See Also
+//! [swapon(2)](https://man7.org/linux/man-pages/man2/swapon.2.html)
+
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+use libc::c_int;
+
+// The `SWAP_FLAG_*` constants from `sys/swap.h`, which are not yet provided
+// by libc.
+const SWAP_FLAG_PREFER: c_int = 0x8000;
+const SWAP_FLAG_PRIO_MASK: c_int = 0x7fff;
+const SWAP_FLAG_DISCARD: c_int = 0x10000;
+const SWAP_FLAG_DISCARD_ONCE: c_int = 0x20000;
+const SWAP_FLAG_DISCARD_PAGES: c_int = 0x40000;
+
+bitflags::bitflags! {
+    /// Flags for [`swapon`].
+    ///
+    /// The priority of the swap area is encoded in the low bits of the flags,
+    /// use [`SwapFlags::with_priority`] to set it.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct SwapFlags: c_int {
+        /// Use the priority encoded in the flags rather than the default
+        /// one, which is lower than that of every swap area added before.
+        const SWAP_FLAG_PREFER = SWAP_FLAG_PREFER;
+        /// Discard freed swap pages before they are reused, if the device
+        /// supports it.
+        ///
+        /// Without `SWAP_FLAG_DISCARD_ONCE` or `SWAP_FLAG_DISCARD_PAGES`,
+        /// both kinds of discard are done.
+        const SWAP_FLAG_DISCARD = SWAP_FLAG_DISCARD;
+        /// Discard the entire swap area once, when it is enabled.
+        ///
+        /// Since Linux 3.11; only meaningful with `SWAP_FLAG_DISCARD`.
+        const SWAP_FLAG_DISCARD_ONCE = SWAP_FLAG_DISCARD_ONCE;
+        /// Discard freed swap pages asynchronously, before they are reused.
+        ///
+        /// Since Linux 3.11; only meaningful with `SWAP_FLAG_DISCARD`.
+        const SWAP_FLAG_DISCARD_PAGES = SWAP_FLAG_DISCARD_PAGES;
+        /// The bits holding the priority, which is only used along with
+        /// `SWAP_FLAG_PREFER`.
+        const SWAP_FLAG_PRIO_MASK = SWAP_FLAG_PRIO_MASK;
+    }
+}
+
+impl SwapFlags {
+    /// The highest priority that can be encoded in the flags.
+    pub const MAX_PRIORITY: u16 = SWAP_FLAG_PRIO_MASK as u16;
+
+    /// Returns `SWAP_FLAG_PREFER` along with the given priority, higher
+    /// priority swap areas being used first.
+    ///
+    /// # Panics
+    ///
+    /// If `priority` is greater than [`SwapFlags::MAX_PRIORITY`].
+    pub fn with_priority(priority: u16) -> Self {
+        assert!(
+            priority <= Self::MAX_PRIORITY,
+            "swap priority {priority} is greater than {}",
+            Self::MAX_PRIORITY
+        );
+        Self::SWAP_FLAG_PREFER | Self::from_bits_retain(priority.into())
+    }
+
+    /// Returns the priority encoded in the flags, if `SWAP_FLAG_PREFER` is
+    /// set.
+    pub fn priority(&self) -> Option<u16> {
+        if self.contains(Self::SWAP_FLAG_PREFER) {
+            Some((self.bits() & SWAP_FLAG_PRIO_MASK) as u16)
+        } else {
+            None
+        }
+    }
+}
+
+/// Starts swapping to the file or block device at `path`, which must have
+/// been prepared with `mkswap(8)`.
+///
+/// Requires the `CAP_SYS_ADMIN` capability.
+///
+/// # References
+///
+/// [swapon(2)](https://man7.org/linux/man-pages/man2/swapon.2.html)
+pub fn swapon<P: ?Sized + NixPath>(path: &P, flags: SwapFlags) -> Result<()> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::swapon(cstr.as_ptr(), flags.bits())
+    })?;
+
+    Errno::result(res).map(drop)
+}
+
+/// Stops swapping to the file or block device at `path`.
+///
+/// The pages in the swap area are first moved back to memory, or to other
+/// swap areas, which fails with `ENOMEM` if there is not enough room left.
+///
+/// Requires the `CAP_SYS_ADMIN` capability.
+///
+/// # References
+///
+/// [swapoff(2)](https://man7.org/linux/man-pages/man2/swapoff.2.html)
+pub fn swapoff<P: ?Sized + NixPath>(path: &P) -> Result<()> {
+    let res =
+        path.with_nix_path(|cstr| unsafe { libc::swapoff(cstr.as_ptr()) })?;
+
+    Errno::result(res).map(drop)
+}
@@ -0,0 +1,334 @@
+//! Read and write kernel state with `sysctl(3)`.
+//!
+//! A sysctl is named either by its Management Information Base (MIB), an
+//! array of integers, or, except on OpenBSD, by its dotted string name, like
+//! `kern.ostype`.  Looking a value up by its MIB is faster, and
+//! [`sysctlnametomib`] can translate a name once for repeated lookups.
+//!
+//! Scalar values are read and written with [`sysctl_get`] and [`sysctl_set`],
+//! the type of the value being inferred from the context:
+//!
+//! ```
+//! # use nix::sys::sysctl::*;
+//! # use libc::c_int;
+//! let maxproc: c_int =
+//!     sysctl_get(&[libc::CTL_KERN, libc::KERN_MAXPROC]).unwrap();
+//! assert!(maxproc > 0);
+//! ```
+//!
+//! Other values are read as bytes with [`sysctl_get_bytes`], or as strings
+//! with [`sysctl_get_string`].
+//!
+//! # See Also
+//! [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+
+#[cfg(not(target_os = "openbsd"))]
+use std::ffi::CStr;
+use std::ffi::OsString;
+use std::mem;
+use std::os::unix::ffi::OsStringExt;
+use std::ptr;
+
+use libc::{c_int, c_uint, c_void, size_t};
+
+use crate::errno::Errno;
+#[cfg(not(target_os = "openbsd"))]
+use crate::NixPath;
+use crate::Result;
+
+// The maximum number of components in a MIB, `CTL_MAXNAME`.
+#[cfg(target_os = "freebsd")]
+const CTL_MAXNAME: usize = 24;
+#[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+const CTL_MAXNAME: usize = 12;
+
+mod private {
+    pub trait SysctlValuePriv: Copy + Default {}
+}
+
+/// Scalar types that can be read and written with [`sysctl_get`] and
+/// [`sysctl_set`].
+///
+/// This trait is sealed, and implemented for the fixed-size integer types;
+/// the C types like `c_int` and `c_long` are aliases of them.
+pub trait SysctlValue: private::SysctlValuePriv {}
+
+macro_rules! sysctl_value {
+    ($($ty:ty),*) => {
+        $(
+            impl private::SysctlValuePriv for $ty {}
+            impl SysctlValue for $ty {}
+        )*
+    };
+}
+
+sysctl_value!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+// What a sysctl is named by.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Mib(&'a [c_int]),
+    #[cfg(not(target_os = "openbsd"))]
+    Name(&'a CStr),
+}
+
+impl Target<'_> {
+    /// Calls `sysctl` or `sysctlbyname` with the given old and new values.
+    ///
+    /// # Safety
+    ///
+    /// `oldp` must be null or valid for writes of `*oldlenp` bytes, and
+    /// `newp` must be null or valid for reads of `newlen` bytes.
+    unsafe fn call(
+        self,
+        oldp: *mut c_void,
+        oldlenp: *mut size_t,
+        newp: *const c_void,
+        newlen: size_t,
+    ) -> Result<()> {
+        let res = match self {
+            Target::Mib(mib) => unsafe {
+                libc::sysctl(
+                    mib.as_ptr() as _,
+                    mib.len() as c_uint,
+                    oldp,
+                    oldlenp,
+                    newp as _,
+                    newlen,
+                )
+            },
+            #[cfg(not(target_os = "openbsd"))]
+            Target::Name(name) => unsafe {
+                libc::sysctlbyname(
+                    name.as_ptr(),
+                    oldp,
+                    oldlenp,
+                    newp as _,
+                    newlen,
+                )
+            },
+        };
+
+        Errno::result(res).map(drop)
+    }
+}
+
+fn get<T: SysctlValue>(target: Target<'_>) -> Result<T> {
+    let mut value = T::default();
+    let mut len = mem::size_of::<T>();
+    unsafe {
+        target.call((&mut value as *mut T).cast(), &mut len, ptr::null(), 0)
+    }?;
+    // A shorter value means that the sysctl has another type.
+    if len != mem::size_of::<T>() {
+        return Err(Errno::EINVAL);
+    }
+
+    Ok(value)
+}
+
+fn get_bytes(target: Target<'_>) -> Result<Vec<u8>> {
+    loop {
+        let mut len = 0;
+        unsafe { target.call(ptr::null_mut(), &mut len, ptr::null(), 0) }?;
+        // Leave some room for the value to grow before the second call.
+        let mut buf = Vec::<u8>::with_capacity(len + len / 8);
+        let mut len = buf.capacity();
+        match unsafe {
+            target.call(buf.as_mut_ptr().cast(), &mut len, ptr::null(), 0)
+        } {
+            // The value grew past the extra room, try again.
+            Err(Errno::ENOMEM) => continue,
+            res => res?,
+        }
+        unsafe { buf.set_len(len) };
+        return Ok(buf);
+    }
+}
+
+fn get_string(target: Target<'_>) -> Result<OsString> {
+    let mut buf = get_bytes(target)?;
+    if let Some(nul) = buf.iter().position(|&b| b == 0) {
+        buf.truncate(nul);
+    }
+
+    Ok(OsString::from_vec(buf))
+}
+
+fn set_bytes(target: Target<'_>, value: &[u8]) -> Result<()> {
+    unsafe {
+        target.call(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            value.as_ptr().cast(),
+            value.len(),
+        )
+    }
+}
+
+fn set<T: SysctlValue>(target: Target<'_>, value: T) -> Result<()> {
+    unsafe {
+        target.call(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            (&value as *const T).cast(),
+            mem::size_of::<T>(),
+        )
+    }
+}
+
+#[cfg(not(target_os = "openbsd"))]
+fn with_name<N, T, F>(name: &N, f: F) -> Result<T>
+where
+    N: ?Sized + NixPath,
+    F: FnOnce(Target<'_>) -> Result<T>,
+{
+    name.with_nix_path(|name| f(Target::Name(name)))?
+}
+
+/// Reads the scalar value of the sysctl named by `mib`.
+///
+/// `EINVAL` is returned if the value is smaller than `T`, and `ENOMEM` if it
+/// is larger.
+///
+/// # References
+///
+/// [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+pub fn sysctl_get<T: SysctlValue>(mib: &[c_int]) -> Result<T> {
+    get(Target::Mib(mib))
+}
+
+/// Reads the value of the sysctl named by `mib` as bytes.
+///
+/// As the size of the value may change between the call querying it and the
+/// call reading the value, the value is read again until it fits in the
+/// buffer.
+///
+/// # References
+///
+/// [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+pub fn sysctl_get_bytes(mib: &[c_int]) -> Result<Vec<u8>> {
+    get_bytes(Target::Mib(mib))
+}
+
+/// Reads the string value of the sysctl named by `mib`, without its
+/// terminating nul byte.
+///
+/// # References
+///
+/// [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+pub fn sysctl_get_string(mib: &[c_int]) -> Result<OsString> {
+    get_string(Target::Mib(mib))
+}
+
+/// Writes the scalar value of the sysctl named by `mib`.
+///
+/// Writing most sysctls requires privileges.
+///
+/// # References
+///
+/// [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+pub fn sysctl_set<T: SysctlValue>(mib: &[c_int], value: T) -> Result<()> {
+    set(Target::Mib(mib), value)
+}
+
+/// Writes the value of the sysctl named by `mib` as bytes.
+///
+/// String values must include their terminating nul byte.
+///
+/// # References
+///
+/// [sysctl(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)
+pub fn sysctl_set_bytes(mib: &[c_int], value: &[u8]) -> Result<()> {
+    set_bytes(Target::Mib(mib), value)
+}
+
+/// Reads the scalar value of the sysctl with the given name.
+///
+/// See [`sysctl_get`].
+///
+/// # References
+///
+/// [sysctlbyname(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlbyname&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_get<T, N>(name: &N) -> Result<T>
+where
+    T: SysctlValue,
+    N: ?Sized + NixPath,
+{
+    with_name(name, get)
+}
+
+/// Reads the value of the sysctl with the given name as bytes.
+///
+/// See [`sysctl_get_bytes`].
+///
+/// # References
+///
+/// [sysctlbyname(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlbyname&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_get_bytes<N: ?Sized + NixPath>(
+    name: &N,
+) -> Result<Vec<u8>> {
+    with_name(name, get_bytes)
+}
+
+/// Reads the string value of the sysctl with the given name, without its
+/// terminating nul byte.
+///
+/// # References
+///
+/// [sysctlbyname(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlbyname&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_get_string<N: ?Sized + NixPath>(
+    name: &N,
+) -> Result<OsString> {
+    with_name(name, get_string)
+}
+
+/// Writes the scalar value of the sysctl with the given name.
+///
+/// # References
+///
+/// [sysctlbyname(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlbyname&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_set<T, N>(name: &N, value: T) -> Result<()>
+where
+    T: SysctlValue,
+    N: ?Sized + NixPath,
+{
+    with_name(name, |target| set(target, value))
+}
+
+/// Writes the value of the sysctl with the given name as bytes.
+///
+/// String values must include their terminating nul byte.
+///
+/// # References
+///
+/// [sysctlbyname(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlbyname&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_set_bytes<N: ?Sized + NixPath>(
+    name: &N,
+    value: &[u8],
+) -> Result<()> {
+    with_name(name, |target| set_bytes(target, value))
+}
+
+/// Translates the name of a sysctl to its MIB.
+///
+/// # References
+///
+/// [sysctlnametomib(3)](https://man.freebsd.org/cgi/man.cgi?query=sysctlnametomib&sektion=3)
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlnametomib<N: ?Sized + NixPath>(name: &N) -> Result<Vec<c_int>> {
+    let mut mib = vec![0; CTL_MAXNAME];
+    let mut len = mib.len();
+    let res = name.with_nix_path(|name| unsafe {
+        libc::sysctlnametomib(name.as_ptr(), mib.as_mut_ptr(), &mut len)
+    })?;
+    Errno::result(res)?;
+    mib.truncate(len);
+
+    Ok(mib)
+}
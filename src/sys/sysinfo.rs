@@ -66,6 +66,16 @@ impl SysInfo {
         self.scale_mem(self.0.freeram)
     }
 
+    /// Returns the amount of shared RAM in Bytes.
+    pub fn ram_shared(&self) -> u64 {
+        self.scale_mem(self.0.sharedram)
+    }
+
+    /// Returns the amount of RAM used by buffers in Bytes.
+    pub fn ram_buffer(&self) -> u64 {
+        self.scale_mem(self.0.bufferram)
+    }
+
     // The cast is not unnecessary on all platforms.
     #[allow(clippy::unnecessary_cast)]
     fn scale_mem(&self, units: mem_blocks_t) -> u64 {
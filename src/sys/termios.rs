@@ -147,6 +147,9 @@ use std::os::unix::io::{AsFd, AsRawFd};
 #[cfg(feature = "process")]
 use crate::unistd::Pid;
 
+/// The size of a terminal window, see [`window_size`].
+pub use libc::winsize as Winsize;
+
 /// Stores settings for the termios API
 ///
 /// This is a wrapper around the `libc::termios` struct that provides a safe interface for the
@@ -460,9 +463,9 @@ impl SpecialCharacterIndices {
     pub const VTIME: SpecialCharacterIndices = SpecialCharacterIndices::VEOL;
 }
 
-pub use libc::NCCS;
 #[cfg(any(linux_android, target_os = "aix", bsd))]
 pub use libc::_POSIX_VDISABLE;
+pub use libc::NCCS;
 
 libc_bitflags! {
     /// Flags for configuring the input mode of a terminal
@@ -689,6 +692,31 @@ libc_bitflags! {
     }
 }
 
+#[cfg(any(linux_android, bsd))]
+libc_bitflags! {
+    /// The modem control lines of a serial port, see [`modem_lines`].
+    pub struct ModemLines: c_int {
+        /// Line enable, or data set ready on some platforms.
+        TIOCM_LE;
+        /// Data terminal ready.
+        TIOCM_DTR;
+        /// Request to send.
+        TIOCM_RTS;
+        /// Secondary transmit.
+        TIOCM_ST;
+        /// Secondary receive.
+        TIOCM_SR;
+        /// Clear to send.
+        TIOCM_CTS;
+        /// Carrier detect.
+        TIOCM_CD;
+        /// Ring indicator.
+        TIOCM_RI;
+        /// Data set ready.
+        TIOCM_DSR;
+    }
+}
+
 cfg_if! {
     if #[cfg(bsd)] {
         /// Get input baud rate (see
@@ -818,6 +846,64 @@ pub fn cfmakeraw(termios: &mut Termios) {
     termios.update_wrapper();
 }
 
+/// Puts a terminal in raw mode until dropped, see [`Termios::raw_mode_guard`].
+#[derive(Debug)]
+pub struct RawModeGuard<Fd: AsFd> {
+    fd: Fd,
+    original: Option<Termios>,
+}
+
+impl<Fd: AsFd> RawModeGuard<Fd> {
+    /// The settings of the terminal before it was put in raw mode, which are
+    /// restored on drop.
+    pub fn original(&self) -> &Termios {
+        self.original.as_ref().unwrap()
+    }
+
+    /// Restore the original settings of the terminal now, reporting any
+    /// error instead of ignoring it like `drop` does.
+    pub fn restore(mut self) -> Result<()> {
+        let original = self.original.take().unwrap();
+        tcsetattr(&self.fd, SetArg::TCSANOW, &original)
+    }
+}
+
+impl<Fd: AsFd> Drop for RawModeGuard<Fd> {
+    fn drop(&mut self) {
+        if let Some(original) = self.original.take() {
+            let _ = tcsetattr(&self.fd, SetArg::TCSANOW, &original);
+        }
+    }
+}
+
+impl Termios {
+    /// Put the terminal `fd` in raw mode, as configured by [`cfmakeraw`],
+    /// until the returned guard is dropped.
+    ///
+    /// The original settings are restored when the guard goes out of scope,
+    /// including during unwinding from a panic, so that an interactive
+    /// program doesn't leave the terminal unusable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use nix::sys::termios::Termios;
+    /// let stdin = std::io::stdin();
+    /// let _guard = Termios::raw_mode_guard(stdin.lock()).unwrap();
+    /// // Read keys one at a time, without echo.
+    /// ```
+    pub fn raw_mode_guard<Fd: AsFd>(fd: Fd) -> Result<RawModeGuard<Fd>> {
+        let original = tcgetattr(&fd)?;
+        let mut raw = original.clone();
+        cfmakeraw(&mut raw);
+        tcsetattr(&fd, SetArg::TCSANOW, &raw)?;
+        Ok(RawModeGuard {
+            fd,
+            original: Some(original),
+        })
+    }
+}
+
 /// Configures the port to "sane" mode (like the configuration of a newly created terminal) (see
 /// [tcsetattr(3)](https://www.freebsd.org/cgi/man.cgi?query=tcsetattr)).
 ///
@@ -923,3 +1009,275 @@ pub fn tcgetsid<Fd: AsFd>(fd: Fd) -> Result<Pid> {
     Errno::result(res).map(Pid::from_raw)
 }
 }
+
+/// Make a terminal the controlling terminal of the session of the calling
+/// process (`ioctl(TIOCSCTTY)`).
+///
+/// The calling process must be a session leader without a controlling
+/// terminal.  If the terminal is already the controlling terminal of another
+/// session, this fails with `EPERM` unless `force` is true and the caller has
+/// the `CAP_SYS_ADMIN` capability, in which case the terminal is stolen from
+/// the other session.  `force` is ignored on other platforms than Linux.
+#[cfg(not(target_os = "aix"))]
+pub fn set_controlling_terminal<Fd: AsFd>(fd: Fd, force: bool) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCSCTTY as _,
+            c_int::from(force),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Give up the controlling terminal of the calling process
+/// (`ioctl(TIOCNOTTY)`).
+///
+/// `fd` must refer to the controlling terminal.  If the calling process is
+/// the session leader, the terminal is detached from the whole session, and
+/// its foreground process group receives a `SIGHUP` and a `SIGCONT`.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "aix",
+    apple_targets,
+    freebsdlike,
+    solarish
+))]
+pub fn release_controlling_terminal<Fd: AsFd>(fd: Fd) -> Result<()> {
+    let res = unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), libc::TIOCNOTTY) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the window size of a terminal (`ioctl(TIOCGWINSZ)`).
+pub fn window_size<Fd: AsFd>(fd: Fd) -> Result<Winsize> {
+    let mut ws = mem::MaybeUninit::<Winsize>::uninit();
+    let res = unsafe {
+        libc::ioctl(fd.as_fd().as_raw_fd(), libc::TIOCGWINSZ, ws.as_mut_ptr())
+    };
+
+    Errno::result(res)?;
+
+    unsafe { Ok(ws.assume_init()) }
+}
+
+/// Set the window size of a terminal (`ioctl(TIOCSWINSZ)`).
+///
+/// The foreground process group of the terminal receives a `SIGWINCH` if the
+/// size changes.  On the master side of a pseudoterminal, this sets the size
+/// seen by the programs using the slave side.
+pub fn set_window_size<Fd: AsFd>(fd: Fd, ws: &Winsize) -> Result<()> {
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCSWINSZ,
+            ws as *const Winsize,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Hang up a terminal (`ioctl(TIOCVHANGUP)`).
+///
+/// Like [`vhangup`](crate::unistd::vhangup), but for the terminal `fd`
+/// instead of the controlling terminal of the calling process.  Requires the
+/// `CAP_SYS_ADMIN` capability.
+#[cfg(target_os = "linux")]
+pub fn hangup<Fd: AsFd>(fd: Fd) -> Result<()> {
+    let res = unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), libc::TIOCVHANGUP) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Insert bytes in the input queue of a terminal, as if they had been typed
+/// (`ioctl(TIOCSTI)`).
+///
+/// This is meant for test harnesses simulating input to a program under
+/// test.  Unless the caller has the `CAP_SYS_ADMIN` capability, `fd` must be
+/// its controlling terminal.  Since Linux 6.2, if the kernel is built without
+/// `CONFIG_LEGACY_TIOCSTI` or the `dev.tty.legacy_tiocsti` sysctl is 0, the
+/// ioctl also fails with `EIO` unless the caller has `CAP_SYS_ADMIN`.
+/// OpenBSD has removed `TIOCSTI` altogether.
+///
+/// The bytes are inserted one at a time, so on error some of them may
+/// already have been inserted.
+#[cfg(any(
+    linux_android,
+    target_os = "aix",
+    apple_targets,
+    freebsdlike,
+    solarish
+))]
+pub fn simulate_input<Fd: AsFd>(fd: Fd, bytes: &[u8]) -> Result<()> {
+    let fd = fd.as_fd().as_raw_fd();
+    for byte in bytes {
+        let res = unsafe {
+            libc::ioctl(
+                fd,
+                libc::TIOCSTI,
+                byte as *const u8 as *const libc::c_char,
+            )
+        };
+        Errno::result(res)?;
+    }
+
+    Ok(())
+}
+
+/// Get the state of the modem control lines of a serial port
+/// (`ioctl(TIOCMGET)`).
+#[cfg(any(linux_android, bsd))]
+pub fn modem_lines<Fd: AsFd>(fd: Fd) -> Result<ModemLines> {
+    let mut lines: c_int = 0;
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCMGET,
+            &mut lines as *mut c_int,
+        )
+    };
+
+    Errno::result(res).map(|_| ModemLines::from_bits_retain(lines))
+}
+
+/// Set the state of all the modem control lines of a serial port
+/// (`ioctl(TIOCMSET)`).
+///
+/// Only the output lines, such as `TIOCM_DTR` and `TIOCM_RTS`, can be set.
+#[cfg(any(linux_android, bsd))]
+pub fn set_modem_lines<Fd: AsFd>(fd: Fd, lines: ModemLines) -> Result<()> {
+    let lines = lines.bits();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCMSET,
+            &lines as *const c_int,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Raise the given modem control lines of a serial port, leaving the others
+/// unchanged (`ioctl(TIOCMBIS)`).
+#[cfg(any(linux_android, bsd))]
+pub fn enable_modem_lines<Fd: AsFd>(fd: Fd, lines: ModemLines) -> Result<()> {
+    let lines = lines.bits();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCMBIS,
+            &lines as *const c_int,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Lower the given modem control lines of a serial port, leaving the others
+/// unchanged (`ioctl(TIOCMBIC)`).
+#[cfg(any(linux_android, bsd))]
+pub fn disable_modem_lines<Fd: AsFd>(fd: Fd, lines: ModemLines) -> Result<()> {
+    let lines = lines.bits();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TIOCMBIC,
+            &lines as *const c_int,
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Start sending a break condition, i.e. zero bits, until [`clear_break`] is
+/// called (`ioctl(TIOCSBRK)`).
+///
+/// Unlike [`tcsendbreak`], the duration of the break is controlled by the
+/// caller.
+#[cfg(any(linux_android, bsd))]
+pub fn set_break<Fd: AsFd>(fd: Fd) -> Result<()> {
+    let res =
+        unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), libc::TIOCSBRK as _) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Stop sending a break condition started with [`set_break`]
+/// (`ioctl(TIOCCBRK)`).
+#[cfg(any(linux_android, bsd))]
+pub fn clear_break<Fd: AsFd>(fd: Fd) -> Result<()> {
+    let res =
+        unsafe { libc::ioctl(fd.as_fd().as_raw_fd(), libc::TIOCCBRK as _) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the input and output baud rates of a terminal, in bits per second
+/// (`ioctl(TCGETS2)`).
+///
+/// Unlike [`cfgetispeed`] and [`cfgetospeed`], the baud rates are not limited
+/// to the values of [`BaudRate`].
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "powerpc", target_arch = "powerpc64"))
+))]
+pub fn get_speed<Fd: AsFd>(fd: Fd) -> Result<(u32, u32)> {
+    let mut termios2 = mem::MaybeUninit::<libc::termios2>::uninit();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            libc::TCGETS2,
+            termios2.as_mut_ptr(),
+        )
+    };
+
+    Errno::result(res)?;
+
+    let termios2 = unsafe { termios2.assume_init() };
+    Ok((termios2.c_ispeed, termios2.c_ospeed))
+}
+
+/// Set arbitrary input and output baud rates of a terminal, in bits per
+/// second, such as 250000 (`ioctl(TCSETS2)`).
+///
+/// The change takes effect at a time specified by `actions`, as with
+/// [`tcsetattr`].  The other settings of the terminal are left unchanged.
+/// Unless the driver of the terminal converts them to values of
+/// [`BaudRate`], the baud rates can then only be read with [`get_speed`].
+/// On the BSDs, where the baud rates are integers, use [`cfsetspeed`]
+/// instead.
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "powerpc", target_arch = "powerpc64"))
+))]
+pub fn set_speed<Fd: AsFd>(
+    fd: Fd,
+    actions: SetArg,
+    ispeed: u32,
+    ospeed: u32,
+) -> Result<()> {
+    let fd = fd.as_fd().as_raw_fd();
+    let mut termios2 = mem::MaybeUninit::<libc::termios2>::uninit();
+    let res = unsafe { libc::ioctl(fd, libc::TCGETS2, termios2.as_mut_ptr()) };
+    Errno::result(res)?;
+    let mut termios2 = unsafe { termios2.assume_init() };
+
+    // `BOTHER` means that the speed is in `c_ispeed` or `c_ospeed`.
+    termios2.c_cflag &= !(libc::CBAUD | libc::CBAUD << libc::IBSHIFT);
+    termios2.c_cflag |= libc::BOTHER | libc::BOTHER << libc::IBSHIFT;
+    termios2.c_ispeed = ispeed;
+    termios2.c_ospeed = ospeed;
+
+    let request = match actions {
+        SetArg::TCSANOW => libc::TCSETS2,
+        SetArg::TCSADRAIN => libc::TCSETSW2,
+        SetArg::TCSAFLUSH => libc::TCSETSF2,
+    };
+    let res =
+        unsafe { libc::ioctl(fd, request, &termios2 as *const libc::termios2) };
+
+    Errno::result(res).map(drop)
+}
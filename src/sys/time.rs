@@ -717,3 +717,284 @@ fn mod_floor_64(this: i64, other: i64) -> i64 {
 fn div_rem_64(this: i64, other: i64) -> (i64, i64) {
     (this / other, this % other)
 }
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+feature! {
+#![feature = "time"]
+
+use crate::errno::Errno;
+use crate::time::ClockId;
+use crate::Result;
+
+libc_bitflags! {
+    /// The parameters to change with [`clock_adjtime`], set by the setters of
+    /// [`Timex`].
+    pub struct TimexModes: libc::c_uint {
+        /// Adjust the time offset, see [`Timex::set_offset`].
+        ADJ_OFFSET;
+        /// Set the frequency offset, see [`Timex::set_freq`].
+        ADJ_FREQUENCY;
+        /// Set the maximum time error.
+        ADJ_MAXERROR;
+        /// Set the estimated time error.
+        ADJ_ESTERROR;
+        /// Set the clock status bits, see [`Timex::set_status`].
+        ADJ_STATUS;
+        /// Set the PLL time constant.
+        ADJ_TIMECONST;
+        /// Set the TAI offset.
+        ADJ_TAI;
+        /// Add a time step to the clock, see [`Timex::set_setoffset`].
+        ADJ_SETOFFSET;
+        /// Use microsecond resolution for the time offset.
+        ADJ_MICRO;
+        /// Use nanosecond resolution for the time offset.
+        ADJ_NANO;
+        /// Set the tick value.
+        ADJ_TICK;
+        /// Adjust the time offset gradually, like `adjtime(3)`.
+        ADJ_OFFSET_SINGLESHOT;
+        /// Only read the remaining time offset of `ADJ_OFFSET_SINGLESHOT`.
+        ADJ_OFFSET_SS_READ;
+    }
+}
+
+libc_bitflags! {
+    /// Clock status bits, see [`Timex::status`].
+    pub struct TimexStatus: libc::c_int {
+        /// Enable phase-locked loop updates.
+        STA_PLL;
+        /// Enable PPS frequency discipline.
+        STA_PPSFREQ;
+        /// Enable PPS time discipline.
+        STA_PPSTIME;
+        /// Select frequency-locked loop mode.
+        STA_FLL;
+        /// Insert a leap second after the last second of the UTC day.
+        STA_INS;
+        /// Delete the last second of the UTC day.
+        STA_DEL;
+        /// The clock is not synchronized.
+        STA_UNSYNC;
+        /// Hold the frequency.
+        STA_FREQHOLD;
+        /// A valid PPS signal is present.  Read-only.
+        STA_PPSSIGNAL;
+        /// PPS signal jitter exceeded.  Read-only.
+        STA_PPSJITTER;
+        /// PPS signal wander exceeded.  Read-only.
+        STA_PPSWANDER;
+        /// PPS signal calibration error.  Read-only.
+        STA_PPSERROR;
+        /// Clock hardware fault.  Read-only.
+        STA_CLOCKERR;
+        /// The time offset and `time` field have nanosecond resolution.
+        /// Read-only, set with `ADJ_NANO`.
+        STA_NANO;
+        /// FLL mode is selected.  Read-only.
+        STA_MODE;
+        /// Clock source.  Read-only.
+        STA_CLK;
+    }
+}
+
+libc_enum! {
+    /// The state of the clock, returned by [`clock_adjtime`].
+    #[repr(i32)]
+    #[non_exhaustive]
+    pub enum ClockState {
+        /// The clock is synchronized, no leap second is pending.
+        TIME_OK,
+        /// A leap second will be inserted at the end of the UTC day.
+        TIME_INS,
+        /// A leap second will be deleted at the end of the UTC day.
+        TIME_DEL,
+        /// A leap second insertion is in progress.
+        TIME_OOP,
+        /// A leap second insertion or deletion has completed.
+        TIME_WAIT,
+        /// The clock is not synchronized.
+        TIME_ERROR,
+    }
+    impl TryFrom<i32>
+}
+
+/// The unit of the time offset set with [`Timex::set_offset`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TimexOffsetUnit {
+    /// Microseconds (`ADJ_MICRO`).
+    Microseconds,
+    /// Nanoseconds (`ADJ_NANO`).
+    Nanoseconds,
+}
+
+/// Parameters of the kernel clock discipline, for [`clock_adjtime`].
+///
+/// A `Timex` created with [`Timex::new`] only reads the parameters.  Its
+/// setters also select the parameters to change.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::time::{clock_adjtime, Timex, TimeSpec, TimeValLike};
+/// # use nix::time::ClockId;
+/// // Step the clock forward by 1.5ms.
+/// let mut timex = Timex::new();
+/// timex.set_setoffset(TimeSpec::microseconds(1500));
+/// clock_adjtime(ClockId::CLOCK_REALTIME, &mut timex).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct Timex(libc::timex);
+
+impl Default for Timex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Most fields are `c_long`, or `i64` on x32.
+#[allow(clippy::unnecessary_cast)]
+impl Timex {
+    /// Create a `Timex` that changes nothing.
+    pub fn new() -> Self {
+        Timex(unsafe { std::mem::zeroed() })
+    }
+
+    /// The parameters to change.
+    pub fn modes(&self) -> TimexModes {
+        TimexModes::from_bits_truncate(self.0.modes)
+    }
+
+    /// The time offset, in nanoseconds if [`status`](Self::status) contains
+    /// `STA_NANO`, in microseconds otherwise.
+    pub fn offset(&self) -> i64 {
+        self.0.offset as i64
+    }
+
+    /// Adjust the time offset, in `unit`, using the phase-locked loop.
+    ///
+    /// The unit is also the resolution used by the kernel clock discipline
+    /// from then on, for the whole system, see `STA_NANO`.
+    pub fn set_offset(&mut self, offset: i64, unit: TimexOffsetUnit) {
+        let unit = match unit {
+            TimexOffsetUnit::Microseconds => libc::ADJ_MICRO,
+            TimexOffsetUnit::Nanoseconds => libc::ADJ_NANO,
+        };
+        self.0.modes &= !(libc::ADJ_MICRO | libc::ADJ_NANO);
+        self.0.modes |= libc::ADJ_OFFSET | unit;
+        self.0.offset = offset as _;
+    }
+
+    /// The frequency offset, in parts per million with a 16-bit fractional
+    /// part.
+    pub fn freq(&self) -> i64 {
+        self.0.freq as i64
+    }
+
+    /// Set the frequency offset, in parts per million with a 16-bit
+    /// fractional part.
+    pub fn set_freq(&mut self, freq: i64) {
+        self.0.modes |= libc::ADJ_FREQUENCY;
+        self.0.freq = freq as _;
+    }
+
+    /// The maximum time error, in microseconds.
+    pub fn maxerror(&self) -> i64 {
+        self.0.maxerror as i64
+    }
+
+    /// The estimated time error, in microseconds.
+    pub fn esterror(&self) -> i64 {
+        self.0.esterror as i64
+    }
+
+    /// The clock status bits.
+    pub fn status(&self) -> TimexStatus {
+        TimexStatus::from_bits_truncate(self.0.status)
+    }
+
+    /// Set the clock status bits.  Read-only bits are ignored.
+    pub fn set_status(&mut self, status: TimexStatus) {
+        self.0.modes |= libc::ADJ_STATUS;
+        self.0.status = status.bits();
+    }
+
+    /// The PLL time constant.
+    pub fn constant(&self) -> i64 {
+        self.0.constant as i64
+    }
+
+    /// The clock precision, in microseconds.
+    pub fn precision(&self) -> i64 {
+        self.0.precision as i64
+    }
+
+    /// The maximum frequency error, in parts per million with a 16-bit
+    /// fractional part.
+    pub fn tolerance(&self) -> i64 {
+        self.0.tolerance as i64
+    }
+
+    /// The current time of the clock.
+    pub fn time(&self) -> TimeSpec {
+        let nsec = if self.status().contains(TimexStatus::STA_NANO) {
+            self.0.time.tv_usec as i64
+        } else {
+            self.0.time.tv_usec as i64 * 1000
+        };
+        TimeSpec::new(self.0.time.tv_sec, nsec as _)
+    }
+
+    /// Add `offset`, which may be negative, to the clock at once.
+    pub fn set_setoffset(&mut self, offset: TimeSpec) {
+        self.0.modes |= libc::ADJ_SETOFFSET | libc::ADJ_NANO;
+        self.0.time.tv_sec = offset.tv_sec();
+        self.0.time.tv_usec = offset.tv_nsec() as _;
+    }
+
+    /// The number of microseconds between clock ticks.
+    pub fn tick(&self) -> i64 {
+        self.0.tick as i64
+    }
+
+    /// The offset between TAI and UTC, in seconds.
+    pub fn tai(&self) -> i32 {
+        self.0.tai
+    }
+}
+
+impl AsRef<libc::timex> for Timex {
+    fn as_ref(&self) -> &libc::timex {
+        &self.0
+    }
+}
+
+impl AsMut<libc::timex> for Timex {
+    fn as_mut(&mut self) -> &mut libc::timex {
+        &mut self.0
+    }
+}
+
+/// Read, and change as specified by its [`modes`](Timex::modes), the
+/// parameters of the discipline of the clock `clock_id`.
+///
+/// On success, `timex` is updated to the current parameters, and the state of
+/// the clock is returned.  Changing parameters requires `CAP_SYS_TIME`.
+///
+/// # See Also
+/// * [`clock_adjtime(2)`](https://man7.org/linux/man-pages/man2/clock_adjtime.2.html)
+pub fn clock_adjtime(clock_id: ClockId, timex: &mut Timex) -> Result<ClockState> {
+    let res = unsafe { libc::clock_adjtime(clock_id.as_raw(), &mut timex.0) };
+    ClockState::try_from(Errno::result(res)?)
+}
+
+/// Like [`clock_adjtime`], for `CLOCK_REALTIME`.
+///
+/// # See Also
+/// * [`adjtimex(2)`](https://man7.org/linux/man-pages/man2/adjtimex.2.html)
+pub fn adjtimex(timex: &mut Timex) -> Result<ClockState> {
+    let res = unsafe { libc::adjtimex(&mut timex.0) };
+    ClockState::try_from(Errno::result(res)?)
+}
+}
@@ -65,7 +65,7 @@ use core::mem;
 /// A Unix signal per-process timer.
 #[derive(Debug)]
 #[repr(transparent)]
-pub struct Timer(libc::timer_t);
+pub struct Timer(pub(crate) libc::timer_t);
 
 impl Timer {
     /// Creates a new timer based on the clock defined by `clockid`. The details
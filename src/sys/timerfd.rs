@@ -140,7 +140,10 @@ impl TimerFd {
     /// If you set a `TFD_TIMER_CANCEL_ON_SET` alongside `TFD_TIMER_ABSTIME`
     /// and the clock for this timer is `CLOCK_REALTIME` or `CLOCK_REALTIME_ALARM`,
     /// then this timer is marked as cancelable if the real-time clock undergoes
-    /// a discontinuous change.
+    /// a discontinuous change, for example with `clock_settime` or when the
+    /// system resumes with a different wall-clock time.  The pending
+    /// [`read_expirations`](TimerFd::read_expirations) then fails with
+    /// `ECANCELED`, and the timer must be set again.
     ///
     /// # Disabling alarms
     ///
@@ -204,6 +207,28 @@ impl TimerFd {
         .map(drop)
     }
 
+    /// Wait for the configured alarm to expire, and return the number of
+    /// expirations since the timer was set, or last read.
+    ///
+    /// A count larger than 1 means that periods of an interval timer were
+    /// missed.  This fails with `ECANCELED` if the timer was set with
+    /// `TFD_TIMER_CANCEL_ON_SET` and the real-time clock changed, and with
+    /// `EAGAIN` instead of blocking if the timer was created with
+    /// `TFD_NONBLOCK` and has not expired.
+    ///
+    /// Note: If the alarm is unset, then you will wait forever.
+    pub fn read_expirations(&self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        loop {
+            match read(&self.fd, &mut buf) {
+                Ok(8) => return Ok(u64::from_ne_bytes(buf)),
+                Ok(_) => return Err(Errno::EIO),
+                Err(Errno::EINTR) => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Wait for the configured alarm to expire.
     ///
     /// Note: If the alarm is unset, then you will wait forever.
@@ -231,4 +231,99 @@ pub fn process_vm_readv(
 
     Errno::result(res).map(|r| r as usize)
 }
+
+/// Read exactly `buf.len()` bytes from the memory of another process, starting
+/// at `remote_addr`.
+///
+/// This calls [`process_vm_readv`] until the whole buffer is filled.  If the
+/// remote range is not entirely mapped, `EFAULT` is returned, and the content
+/// of `buf` is unspecified.
+#[cfg(all(linux_android, not(target_env = "uclibc")))]
+pub fn process_vm_read_exact(
+    pid: crate::unistd::Pid,
+    remote_addr: usize,
+    mut buf: &mut [u8]) -> Result<()>
+{
+    let mut remote_addr = remote_addr;
+    while !buf.is_empty() {
+        let remote = RemoteIoVec { base: remote_addr, len: buf.len() };
+        match process_vm_readv(pid, &mut [IoSliceMut::new(buf)], &[remote]) {
+            Ok(0) => return Err(Errno::EFAULT),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                remote_addr += n;
+            }
+            Err(Errno::EINTR) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Write all of `buf` to the memory of another process, starting at
+/// `remote_addr`.
+///
+/// This calls [`process_vm_writev`] until the whole buffer is written.  If the
+/// remote range is not entirely mapped and writable, `EFAULT` is returned,
+/// and only part of `buf` may have been written.
+#[cfg(all(linux_android, not(target_env = "uclibc")))]
+pub fn process_vm_write_all(
+    pid: crate::unistd::Pid,
+    remote_addr: usize,
+    mut buf: &[u8]) -> Result<()>
+{
+    let mut remote_addr = remote_addr;
+    while !buf.is_empty() {
+        let remote = RemoteIoVec { base: remote_addr, len: buf.len() };
+        match process_vm_writev(pid, &[IoSlice::new(buf)], &[remote]) {
+            Ok(0) => return Err(Errno::EFAULT),
+            Ok(n) => {
+                buf = &buf[n..];
+                remote_addr += n;
+            }
+            Err(Errno::EINTR) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Read a NUL-terminated string from the memory of another process, starting
+/// at `remote_addr`.
+///
+/// At most `max_len` bytes, not including the terminating NUL byte, are read:
+/// `ENAMETOOLONG` is returned if no NUL byte is found within that limit.
+/// `usize::MAX` can be used for no limit.  The memory is read one page at a
+/// time, so that a string ending right before an unmapped page can still be
+/// read.
+#[cfg(all(linux_android, not(target_env = "uclibc")))]
+pub fn process_vm_read_c_string(
+    pid: crate::unistd::Pid,
+    remote_addr: usize,
+    max_len: usize) -> Result<std::ffi::CString>
+{
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    // Room for the string and its terminating NUL byte.  A string of
+    // `usize::MAX` bytes can not exist, so saturating is fine.
+    let max_size = max_len.saturating_add(1);
+    let mut string = Vec::new();
+    let mut remote_addr = remote_addr;
+    loop {
+        let to_page_end = page_size - remote_addr % page_size;
+        let chunk_len = to_page_end.min(max_size - string.len());
+        let start = string.len();
+        string.resize(start + chunk_len, 0);
+        process_vm_read_exact(pid, remote_addr, &mut string[start..])?;
+
+        if let Some(nul) = string[start..].iter().position(|&b| b == 0) {
+            string.truncate(start + nul);
+            // SAFETY: `string` contains no NUL byte.
+            return Ok(unsafe { std::ffi::CString::from_vec_unchecked(string) });
+        }
+        if string.len() > max_len {
+            return Err(Errno::ENAMETOOLONG);
+        }
+        remote_addr += chunk_len;
+    }
+}
 }
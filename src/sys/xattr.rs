@@ -0,0 +1,488 @@
+//! Extended attributes, name-value pairs associated with files.
+//!
+//! On Linux and Android, the name of an attribute starts with the prefix of
+//! its namespace, such as `user.`, see [`XattrNamespace`].  On Apple
+//! platforms, names are free-form, but conventionally use reverse-DNS
+//! notation such as `com.apple.quarantine`.
+//!
+//! Each operation comes in three variants: one taking a path and following
+//! symbolic links, one prefixed by `l` taking a path and operating on the
+//! symbolic link itself, and one prefixed by `f` taking a file descriptor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::xattr::*;
+//! setxattr("file", "user.checksum", b"1234", XattrFlags::XATTR_CREATE).unwrap();
+//! assert_eq!(getxattr("file", "user.checksum").unwrap(), b"1234");
+//! for name in listxattr("file").unwrap().iter() {
+//!     println!("{:?}", name);
+//! }
+//! ```
+//!
+//! # See Also
+//! * [`xattr(7)`](https://man7.org/linux/man-pages/man7/xattr.7.html)
+//! * [`getxattr(2)`](https://developer.apple.com/library/archive/documentation/System/Conceptual/ManPages_iPhoneOS/man2/getxattr.2.html)
+//!   on Apple platforms
+
+use std::ffi::{CStr, OsStr};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::ptr;
+
+use libc::{c_char, c_int, c_void, size_t, ssize_t};
+
+use crate::errno::Errno;
+use crate::{NixPath, Result};
+
+libc_bitflags! {
+    /// Flags for [`setxattr`] and its variants.
+    pub struct XattrFlags: c_int {
+        /// Fail with `EEXIST` if the attribute already exists.
+        XATTR_CREATE;
+        /// Fail with `ENODATA` (`ENOATTR` on Apple platforms) if the
+        /// attribute doesn't exist yet.
+        XATTR_REPLACE;
+        /// Bypass the authorization checking.
+        #[cfg(apple_targets)]
+        XATTR_NOSECURITY;
+        /// Bypass the default extended attribute file, `._` prefixed, on
+        /// file systems that don't support extended attributes natively.
+        #[cfg(apple_targets)]
+        XATTR_NODEFAULT;
+    }
+}
+
+/// The namespace of an extended attribute, given by the prefix of its name.
+#[cfg(linux_android)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum XattrNamespace {
+    /// `user.`, for arbitrary attributes set by the owner of the file.
+    User,
+    /// `trusted.`, for attributes only visible to processes with the
+    /// `CAP_SYS_ADMIN` capability.
+    Trusted,
+    /// `security.`, for attributes of security modules, such as SELinux
+    /// labels and file capabilities.
+    Security,
+    /// `system.`, for attributes of the kernel, such as access control
+    /// lists.
+    System,
+}
+
+#[cfg(linux_android)]
+impl XattrNamespace {
+    const ALL: [Self; 4] =
+        [Self::User, Self::Trusted, Self::Security, Self::System];
+
+    /// The prefix of the names in this namespace, including the final dot.
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::User => "user.",
+            Self::Trusted => "trusted.",
+            Self::Security => "security.",
+            Self::System => "system.",
+        }
+    }
+
+    /// Split the name of an attribute into its namespace and the rest of its
+    /// name, or return `None` if the namespace is unknown.
+    pub fn split(name: &OsStr) -> Option<(Self, &OsStr)> {
+        let name = name.as_bytes();
+        Self::ALL.into_iter().find_map(|ns| {
+            name.strip_prefix(ns.prefix().as_bytes())
+                .map(|rest| (ns, OsStr::from_bytes(rest)))
+        })
+    }
+}
+
+/// The list of the names of the extended attributes of a file, as returned
+/// by [`listxattr`] and its variants.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct XattrList {
+    buf: Vec<u8>,
+}
+
+impl XattrList {
+    /// Iterate over the names of the attributes.
+    pub fn iter(&self) -> XattrListIter<'_> {
+        XattrListIter { rest: &self.buf }
+    }
+
+    /// Iterate over the names of the attributes in the namespace `ns`,
+    /// without the prefix of the namespace.
+    #[cfg(linux_android)]
+    pub fn iter_namespace(
+        &self,
+        ns: XattrNamespace,
+    ) -> impl Iterator<Item = &OsStr> + '_ {
+        self.iter()
+            .filter_map(move |name| match XattrNamespace::split(name) {
+                Some((n, rest)) if n == ns => Some(rest),
+                _ => None,
+            })
+    }
+
+    /// Does the file have no extended attributes (that the caller can see)?
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a XattrList {
+    type Item = &'a OsStr;
+    type IntoIter = XattrListIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the names of an [`XattrList`].
+#[derive(Clone, Debug)]
+pub struct XattrListIter<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for XattrListIter<'a> {
+    type Item = &'a OsStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let len = self
+            .rest
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(self.rest.len());
+        let name = &self.rest[..len];
+        self.rest = self.rest.get(len + 1..).unwrap_or_default();
+        Some(OsStr::from_bytes(name))
+    }
+}
+
+/// The file whose attributes are operated on.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Follow(&'a CStr),
+    NoFollow(&'a CStr),
+    Fd(BorrowedFd<'a>),
+}
+
+#[cfg(linux_android)]
+impl Target<'_> {
+    unsafe fn get(
+        self,
+        name: &CStr,
+        value: *mut c_void,
+        size: size_t,
+    ) -> ssize_t {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::Follow(p) => {
+                    libc::getxattr(p.as_ptr(), name, value, size)
+                }
+                Target::NoFollow(p) => {
+                    libc::lgetxattr(p.as_ptr(), name, value, size)
+                }
+                Target::Fd(fd) => {
+                    libc::fgetxattr(fd.as_raw_fd(), name, value, size)
+                }
+            }
+        }
+    }
+
+    unsafe fn set(self, name: &CStr, value: &[u8], flags: c_int) -> c_int {
+        let name = name.as_ptr();
+        let (v, len) = (value.as_ptr().cast(), value.len());
+        unsafe {
+            match self {
+                Target::Follow(p) => {
+                    libc::setxattr(p.as_ptr(), name, v, len, flags)
+                }
+                Target::NoFollow(p) => {
+                    libc::lsetxattr(p.as_ptr(), name, v, len, flags)
+                }
+                Target::Fd(fd) => {
+                    libc::fsetxattr(fd.as_raw_fd(), name, v, len, flags)
+                }
+            }
+        }
+    }
+
+    unsafe fn list(self, list: *mut c_char, size: size_t) -> ssize_t {
+        unsafe {
+            match self {
+                Target::Follow(p) => libc::listxattr(p.as_ptr(), list, size),
+                Target::NoFollow(p) => libc::llistxattr(p.as_ptr(), list, size),
+                Target::Fd(fd) => libc::flistxattr(fd.as_raw_fd(), list, size),
+            }
+        }
+    }
+
+    unsafe fn remove(self, name: &CStr) -> c_int {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::Follow(p) => libc::removexattr(p.as_ptr(), name),
+                Target::NoFollow(p) => libc::lremovexattr(p.as_ptr(), name),
+                Target::Fd(fd) => libc::fremovexattr(fd.as_raw_fd(), name),
+            }
+        }
+    }
+}
+
+// The Apple functions take an offset in the attribute, only supported for
+// resource forks, and options including `XATTR_NOFOLLOW` instead of having
+// `l` variants.
+#[cfg(apple_targets)]
+impl Target<'_> {
+    unsafe fn get(
+        self,
+        name: &CStr,
+        value: *mut c_void,
+        size: size_t,
+    ) -> ssize_t {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::Follow(p) => {
+                    libc::getxattr(p.as_ptr(), name, value, size, 0, 0)
+                }
+                Target::NoFollow(p) => libc::getxattr(
+                    p.as_ptr(),
+                    name,
+                    value,
+                    size,
+                    0,
+                    libc::XATTR_NOFOLLOW,
+                ),
+                Target::Fd(fd) => {
+                    libc::fgetxattr(fd.as_raw_fd(), name, value, size, 0, 0)
+                }
+            }
+        }
+    }
+
+    unsafe fn set(self, name: &CStr, value: &[u8], flags: c_int) -> c_int {
+        let name = name.as_ptr();
+        let (v, len) = (value.as_ptr().cast(), value.len());
+        unsafe {
+            match self {
+                Target::Follow(p) => {
+                    libc::setxattr(p.as_ptr(), name, v, len, 0, flags)
+                }
+                Target::NoFollow(p) => libc::setxattr(
+                    p.as_ptr(),
+                    name,
+                    v,
+                    len,
+                    0,
+                    flags | libc::XATTR_NOFOLLOW,
+                ),
+                Target::Fd(fd) => {
+                    libc::fsetxattr(fd.as_raw_fd(), name, v, len, 0, flags)
+                }
+            }
+        }
+    }
+
+    unsafe fn list(self, list: *mut c_char, size: size_t) -> ssize_t {
+        unsafe {
+            match self {
+                Target::Follow(p) => libc::listxattr(p.as_ptr(), list, size, 0),
+                Target::NoFollow(p) => libc::listxattr(
+                    p.as_ptr(),
+                    list,
+                    size,
+                    libc::XATTR_NOFOLLOW,
+                ),
+                Target::Fd(fd) => {
+                    libc::flistxattr(fd.as_raw_fd(), list, size, 0)
+                }
+            }
+        }
+    }
+
+    unsafe fn remove(self, name: &CStr) -> c_int {
+        let name = name.as_ptr();
+        unsafe {
+            match self {
+                Target::Follow(p) => libc::removexattr(p.as_ptr(), name, 0),
+                Target::NoFollow(p) => {
+                    libc::removexattr(p.as_ptr(), name, libc::XATTR_NOFOLLOW)
+                }
+                Target::Fd(fd) => libc::fremovexattr(fd.as_raw_fd(), name, 0),
+            }
+        }
+    }
+}
+
+/// Call `f` with a buffer large enough for the value it returns, retrying if
+/// the value grows in the meantime.
+fn read_value<F>(f: F) -> Result<Vec<u8>>
+where
+    F: Fn(*mut c_void, size_t) -> ssize_t,
+{
+    loop {
+        let size = Errno::result(f(ptr::null_mut(), 0))? as usize;
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        let mut buf = Vec::<u8>::with_capacity(size);
+        match Errno::result(f(buf.as_mut_ptr().cast(), buf.capacity())) {
+            Ok(len) => {
+                unsafe { buf.set_len(len as usize) };
+                return Ok(buf);
+            }
+            Err(Errno::ERANGE) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn get<N: ?Sized + NixPath>(target: Target<'_>, name: &N) -> Result<Vec<u8>> {
+    name.with_nix_path(|name| {
+        read_value(|value, size| unsafe { target.get(name, value, size) })
+    })?
+}
+
+fn set<N: ?Sized + NixPath>(
+    target: Target<'_>,
+    name: &N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()> {
+    let res = name.with_nix_path(|name| unsafe {
+        target.set(name, value, flags.bits())
+    })?;
+
+    Errno::result(res).map(drop)
+}
+
+fn list(target: Target<'_>) -> Result<XattrList> {
+    read_value(|list, size| unsafe { target.list(list.cast(), size) })
+        .map(|buf| XattrList { buf })
+}
+
+fn remove<N: ?Sized + NixPath>(target: Target<'_>, name: &N) -> Result<()> {
+    let res = name.with_nix_path(|name| unsafe { target.remove(name) })?;
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the value of the extended attribute `name` of the file at `path`,
+/// following symbolic links.
+pub fn getxattr<P, N>(path: &P, name: &N) -> Result<Vec<u8>>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| get(Target::Follow(path), name))?
+}
+
+/// Get the value of the extended attribute `name` of the file at `path`,
+/// without following symbolic links.
+pub fn lgetxattr<P, N>(path: &P, name: &N) -> Result<Vec<u8>>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| get(Target::NoFollow(path), name))?
+}
+
+/// Get the value of the extended attribute `name` of the open file `fd`.
+pub fn fgetxattr<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    name: &N,
+) -> Result<Vec<u8>> {
+    get(Target::Fd(fd.as_fd()), name)
+}
+
+/// Set the value of the extended attribute `name` of the file at `path`,
+/// following symbolic links.
+pub fn setxattr<P, N>(
+    path: &P,
+    name: &N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| set(Target::Follow(path), name, value, flags))?
+}
+
+/// Set the value of the extended attribute `name` of the file at `path`,
+/// without following symbolic links.
+pub fn lsetxattr<P, N>(
+    path: &P,
+    name: &N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| set(Target::NoFollow(path), name, value, flags))?
+}
+
+/// Set the value of the extended attribute `name` of the open file `fd`.
+pub fn fsetxattr<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    name: &N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()> {
+    set(Target::Fd(fd.as_fd()), name, value, flags)
+}
+
+/// List the names of the extended attributes of the file at `path`,
+/// following symbolic links.
+pub fn listxattr<P: ?Sized + NixPath>(path: &P) -> Result<XattrList> {
+    path.with_nix_path(|path| list(Target::Follow(path)))?
+}
+
+/// List the names of the extended attributes of the file at `path`,
+/// without following symbolic links.
+pub fn llistxattr<P: ?Sized + NixPath>(path: &P) -> Result<XattrList> {
+    path.with_nix_path(|path| list(Target::NoFollow(path)))?
+}
+
+/// List the names of the extended attributes of the open file `fd`.
+pub fn flistxattr<Fd: AsFd>(fd: Fd) -> Result<XattrList> {
+    list(Target::Fd(fd.as_fd()))
+}
+
+/// Remove the extended attribute `name` of the file at `path`, following
+/// symbolic links.
+pub fn removexattr<P, N>(path: &P, name: &N) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| remove(Target::Follow(path), name))?
+}
+
+/// Remove the extended attribute `name` of the file at `path`, without
+/// following symbolic links.
+pub fn lremovexattr<P, N>(path: &P, name: &N) -> Result<()>
+where
+    P: ?Sized + NixPath,
+    N: ?Sized + NixPath,
+{
+    path.with_nix_path(|path| remove(Target::NoFollow(path), name))?
+}
+
+/// Remove the extended attribute `name` of the open file `fd`.
+pub fn fremovexattr<Fd: AsFd, N: ?Sized + NixPath>(
+    fd: Fd,
+    name: &N,
+) -> Result<()> {
+    remove(Target::Fd(fd.as_fd()), name)
+}
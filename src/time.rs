@@ -254,6 +254,13 @@ libc_bitflags! {
 /// a signal is caught by a signal-catching function, or a signal causes the process to terminate,
 /// this sleep is interrrupted.
 ///
+/// For a relative sleep, the returned `TimeSpec` is the time that remained to be slept when the
+/// sleep was interrupted by a signal handler, or zero if the sleep completed, so the sleep can be
+/// resumed by calling this function again with it.  An interrupted absolute sleep fails with
+/// `EINTR` instead, and can be resumed with the same `request`.  Periodic loops should prefer
+/// absolute sleeps against `CLOCK_MONOTONIC`, by adding their period to the previous deadline,
+/// since the time spent between relative sleeps accumulates as drift.
+///
 /// see also [man 3 clock_nanosleep](https://pubs.opengroup.org/onlinepubs/009695399/functions/clock_nanosleep.html)
 #[cfg(any(
     linux_android,
@@ -277,9 +284,11 @@ pub fn clock_nanosleep(
             remain.as_mut() as *mut _,
         )
     };
-    if ret == 0 {
-        Ok(remain)
-    } else {
-        Err(Errno::from_raw(ret))
+    match ret {
+        0 => Ok(TimeSpec::new(0, 0)),
+        libc::EINTR if !flags.contains(ClockNanosleepFlags::TIMER_ABSTIME) => {
+            Ok(remain)
+        }
+        _ => Err(Errno::from_raw(ret)),
     }
 }
@@ -375,6 +375,22 @@ pub fn tcsetpgrp<F: std::os::fd::AsFd>(fd: F, pgrp: Pid) -> Result<()> {
 }
 }
 
+feature! {
+#![feature = "term"]
+/// Hang up the controlling terminal of the calling process (see
+/// [vhangup(2)](https://man7.org/linux/man-pages/man2/vhangup.2.html)).
+///
+/// The open file descriptors of the terminal become unusable, so that the
+/// terminal can then be handed over to another user, for instance by a
+/// `getty` or `login` implementation.  Requires the `CAP_SYS_ADMIN`
+/// capability.
+#[cfg(target_os = "linux")]
+pub fn vhangup() -> Result<()> {
+    let res = unsafe { libc::vhangup() };
+    Errno::result(res).map(drop)
+}
+}
+
 feature! {
 #![feature = "process"]
 /// Get the group id of the calling process (see
@@ -1022,16 +1038,6 @@ pub fn fchown<Fd: std::os::fd::AsFd>(fd: Fd, owner: Option<Uid>, group: Option<G
 #[allow(missing_docs)]
 #[cfg(not(target_os = "redox"))]
 pub type FchownatFlags = AtFlags;
-#[allow(missing_docs)]
-#[cfg(not(target_os = "redox"))]
-impl FchownatFlags {
-    #[deprecated(since = "0.28.0", note = "The variant is deprecated, please use `AtFlags` instead")]
-    #[allow(non_upper_case_globals)]
-    pub const FollowSymlink: FchownatFlags = FchownatFlags::empty();
-    #[deprecated(since = "0.28.0", note = "The variant is deprecated, please use `AtFlags` instead")]
-    #[allow(non_upper_case_globals)]
-    pub const NoFollowSymlink: FchownatFlags = FchownatFlags::AT_SYMLINK_NOFOLLOW;
-}
 
 /// Change the ownership of the file at `path` to be owned by the specified
 /// `owner` (user) and `group`.
@@ -1041,7 +1047,9 @@ impl FchownatFlags {
 /// only if `Some` owner/group is provided.
 ///
 /// If `flag` is `AtFlags::AT_SYMLINK_NOFOLLOW` and `path` names a symbolic link,
-/// then the mode of the symbolic link is changed.
+/// then the mode of the symbolic link is changed.  On Linux, `AT_EMPTY_PATH`
+/// with an empty `path` changes the ownership of `dirfd` itself, which may
+/// have been opened with `O_PATH`.
 ///
 /// `fchownat(AT_FDCWD, path, owner, group, AtFlags::AT_SYMLINK_NOFOLLOW)` is identical to
 /// a call `libc::lchown(path, owner, group)`.  That's why `lchown` is unimplemented in
@@ -1428,7 +1436,9 @@ pub enum Whence {
     #[cfg(any(
         freebsdlike,
         solarish,
-        target_os = "linux",
+        linux_android,
+        apple_targets,
+        target_os = "hurd",
     ))]
     SeekData = libc::SEEK_DATA,
     /// Specify an offset relative to the next hole in the file greater than
@@ -1439,7 +1449,9 @@ pub enum Whence {
     #[cfg(any(
         freebsdlike,
         solarish,
-        target_os = "linux",
+        linux_android,
+        apple_targets,
+        target_os = "hurd",
     ))]
     SeekHole = libc::SEEK_HOLE,
 }
@@ -1471,6 +1483,95 @@ pub fn lseek64<Fd: std::os::fd::AsFd>(
 
     Errno::result(res).map(|r| r as libc::off64_t)
 }
+
+/// Iterator over the data regions of a (possibly sparse) file, created by
+/// [`data_extents`].
+#[cfg(any(
+    freebsdlike,
+    solarish,
+    linux_android,
+    apple_targets,
+    target_os = "hurd",
+))]
+#[derive(Debug)]
+pub struct DataExtents<Fd: std::os::fd::AsFd> {
+    fd: Fd,
+    offset: Option<off_t>,
+}
+
+#[cfg(any(
+    freebsdlike,
+    solarish,
+    linux_android,
+    apple_targets,
+    target_os = "hurd",
+))]
+impl<Fd: std::os::fd::AsFd> Iterator for DataExtents<Fd> {
+    type Item = Result<std::ops::Range<off_t>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset?;
+
+        let start = match lseek(&self.fd, offset, Whence::SeekData) {
+            Ok(start) => start,
+            // There is no more data after `offset`.
+            Err(Errno::ENXIO) => {
+                self.offset = None;
+                return None;
+            }
+            Err(e) => {
+                self.offset = None;
+                return Some(Err(e));
+            }
+        };
+        // There is always an implicit hole at the end of the file.
+        match lseek(&self.fd, start, Whence::SeekHole) {
+            Ok(end) => {
+                self.offset = Some(end);
+                Some(Ok(start..end))
+            }
+            Err(e) => {
+                self.offset = None;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Iterate over the data regions of a file, using `SEEK_DATA` and `SEEK_HOLE`.
+///
+/// Each item is the range of bytes of one region containing data; anything
+/// between two consecutive regions is a hole, which reads as zeros.  This
+/// allows copying a sparse file without reading or writing its holes.
+/// Filesystems that do not support sparse files report the whole file as a
+/// single data region.
+///
+/// The iterator moves the file offset of `fd`.
+///
+/// # Example
+///
+/// ```
+/// # use std::os::unix::fs::FileExt;
+/// # use nix::unistd::data_extents;
+/// let f = tempfile::tempfile().unwrap();
+/// f.write_all_at(b"data", 0).unwrap();
+/// let extents = data_extents(&f).collect::<nix::Result<Vec<_>>>().unwrap();
+/// assert_eq!(extents.len(), 1);
+/// assert_eq!(extents[0].start, 0);
+/// ```
+#[cfg(any(
+    freebsdlike,
+    solarish,
+    linux_android,
+    apple_targets,
+    target_os = "hurd",
+))]
+pub fn data_extents<Fd: std::os::fd::AsFd>(fd: Fd) -> DataExtents<Fd> {
+    DataExtents {
+        fd,
+        offset: Some(0),
+    }
+}
 }
 
 /// Create an interprocess channel.
@@ -1595,14 +1696,18 @@ impl LinkatFlags {
 /// In case `flag` is `AtFlags::AT_SYMLINK_FOLLOW` and `oldpath` names a symoblic
 /// link, a new link for the target of the symbolic link is created.
 ///
+/// On Linux, `AtFlags::AT_EMPTY_PATH` with an empty `oldpath` links the file
+/// referred to by `olddirfd` itself, which may have been opened with `O_PATH`
+/// or `O_TMPFILE`.  This requires the `CAP_DAC_READ_SEARCH` capability.
+///
 /// # References
 /// See also [linkat(2)](https://pubs.opengroup.org/onlinepubs/9699919799/functions/linkat.html)
 #[cfg(not(target_os = "redox"))] // Redox does not have this yet
-pub fn linkat<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd, P: ?Sized + NixPath>(
+pub fn linkat<Fd1: std::os::fd::AsFd, Fd2: std::os::fd::AsFd, P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
     olddirfd: Fd1,
-    oldpath: &P,
+    oldpath: &P1,
     newdirfd: Fd2,
-    newpath: &P,
+    newpath: &P2,
     flag: AtFlags,
 ) -> Result<()> {
     use std::os::fd::AsRawFd;
@@ -1694,8 +1799,12 @@ pub fn sync() {
 /// Commit filesystem caches containing file referred to by the open file
 /// descriptor `fd` to disk
 ///
+/// Unlike [`sync`], only the filesystem containing `fd` is flushed.  On Linux
+/// 5.8 and later, errors that occurred while writing back the data of that
+/// filesystem are reported.
+///
 /// See also [syncfs(2)](https://man7.org/linux/man-pages/man2/sync.2.html)
-#[cfg(any(linux_android, target_os = "hurd"))]
+#[cfg(any(linux_android, target_os = "illumos", target_os = "hurd"))]
 pub fn syncfs<Fd: std::os::fd::AsFd>(fd: Fd) -> Result<()> {
     use std::os::fd::AsRawFd;
 
@@ -4001,4 +4110,319 @@ pub fn chflags<P: ?Sized + NixPath>(path: &P, flags: FileFlag) -> Result<()> {
 
     Errno::result(res).map(drop)
 }
+
+/// Set the file flags of an open file.
+///
+/// See also [fchflags(2)](https://www.freebsd.org/cgi/man.cgi?query=fchflags&sektion=2)
+#[cfg(bsd)]
+pub fn fchflags<Fd: std::os::fd::AsFd>(fd: Fd, flags: FileFlag) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let res = unsafe { libc::fchflags(fd.as_fd().as_raw_fd(), flags.bits()) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the file flags, like [`chflags`], but do not follow symbolic links.
+///
+/// If `path` is a symbolic link, the flags of the link itself are changed.
+///
+/// See also [lchflags(2)](https://www.freebsd.org/cgi/man.cgi?query=lchflags&sektion=2)
+#[cfg(all(bsd, not(target_os = "openbsd")))]
+pub fn lchflags<P: ?Sized + NixPath>(path: &P, flags: FileFlag) -> Result<()> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::lchflags(cstr.as_ptr(), flags.bits())
+    })?;
+
+    Errno::result(res).map(drop)
+}
+}
+
+#[cfg(feature = "process")]
+#[cfg(target_os = "openbsd")]
+bitflags::bitflags! {
+    /// Promises for [`pledge`], each allowing a group of system calls.
+    ///
+    /// See [pledge(2)](https://man.openbsd.org/pledge.2) for the exact
+    /// meaning of each promise.
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct Promises: u64 {
+        /// Basic functionality of the C library, memory allocation and I/O
+        /// on open file descriptors.
+        const STDIO = 1 << 0;
+        /// Read-only operations on the file system.
+        const RPATH = 1 << 1;
+        /// Write operations on the file system.
+        const WPATH = 1 << 2;
+        /// Create and remove files and directories.
+        const CPATH = 1 << 3;
+        /// Create special files.
+        const DPATH = 1 << 4;
+        /// Operations on files in `/tmp`.
+        const TMPPATH = 1 << 5;
+        /// Internet sockets.
+        const INET = 1 << 6;
+        /// Multicast socket options.
+        const MCAST = 1 << 7;
+        /// Change the attributes of files.
+        const FATTR = 1 << 8;
+        /// Change the owner of files.
+        const CHOWN = 1 << 9;
+        /// File locks.
+        const FLOCK = 1 << 10;
+        /// UNIX domain sockets.
+        const UNIX = 1 << 11;
+        /// Name resolution.
+        const DNS = 1 << 12;
+        /// Read the password and group databases.
+        const GETPW = 1 << 13;
+        /// Send file descriptors with `sendmsg(2)`.
+        const SENDFD = 1 << 14;
+        /// Receive file descriptors with `recvmsg(2)`.
+        const RECVFD = 1 << 15;
+        /// Tape drive ioctls.
+        const TAPE = 1 << 16;
+        /// Terminal ioctls.
+        const TTY = 1 << 17;
+        /// Process management, like `fork(2)` and `kill(2)`.
+        const PROC = 1 << 18;
+        /// Execute programs with `execve(2)`.
+        const EXEC = 1 << 19;
+        /// Executable mappings with `mmap(2)` and `mprotect(2)`.
+        const PROT_EXEC = 1 << 20;
+        /// Set the system time.
+        const SETTIME = 1 << 21;
+        /// Inspect the processes of the system.
+        const PS = 1 << 22;
+        /// Inspect the virtual memory of the system.
+        const VMINFO = 1 << 23;
+        /// Change the credentials of the process.
+        const ID = 1 << 24;
+        /// Packet filter ioctls.
+        const PF = 1 << 25;
+        /// Inspect the routing table.
+        const ROUTE = 1 << 26;
+        /// Modify the routing table.
+        const WROUTE = 1 << 27;
+        /// Audio ioctls.
+        const AUDIO = 1 << 28;
+        /// Video ioctls.
+        const VIDEO = 1 << 29;
+        /// BPF ioctls.
+        const BPF = 1 << 30;
+        /// Call `unveil(2)`.
+        const UNVEIL = 1 << 31;
+        /// Fail with `ENOSYS` instead of killing the process on a
+        /// violation.
+        const ERROR = 1 << 32;
+    }
+}
+
+#[cfg(feature = "process")]
+#[cfg(target_os = "openbsd")]
+impl Promises {
+    fn to_cstring(self) -> CString {
+        let promises = self
+            .iter_names()
+            .map(|(name, _)| name.to_ascii_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ");
+        // The names of the promises do not contain any nul byte.
+        CString::new(promises).unwrap()
+    }
+}
+
+feature! {
+#![feature = "process"]
+
+/// Restrict the system calls available to the calling process to the groups
+/// allowed by `promises`, and those available after `execve(2)` to
+/// `execpromises`.
+///
+/// `None` leaves the corresponding promises unchanged.  Promises can only
+/// be removed: fails with `EPERM` to add one.  Once pledged, a process using
+/// a system call it did not promise is killed with `SIGABRT`, unless it
+/// promised [`ERROR`](Promises::ERROR).
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::unistd::{pledge, Promises};
+/// pledge(Some(Promises::STDIO | Promises::RPATH), None).unwrap();
+/// ```
+///
+/// See also [pledge(2)](https://man.openbsd.org/pledge.2)
+#[cfg(target_os = "openbsd")]
+pub fn pledge(
+    promises: Option<Promises>,
+    execpromises: Option<Promises>,
+) -> Result<()> {
+    let promises = promises.map(Promises::to_cstring);
+    let execpromises = execpromises.map(Promises::to_cstring);
+    let res = unsafe {
+        libc::pledge(
+            promises.as_deref().map_or(ptr::null(), CStr::as_ptr),
+            execpromises.as_deref().map_or(ptr::null(), CStr::as_ptr),
+        )
+    };
+
+    Errno::result(res).map(drop)
+}
+}
+
+#[cfg(feature = "fs")]
+#[cfg(target_os = "openbsd")]
+bitflags::bitflags! {
+    /// Permissions for [`unveil`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct UnveilPermissions: u8 {
+        /// Read the files.
+        const READ = 1 << 0;
+        /// Write to the files.
+        const WRITE = 1 << 1;
+        /// Execute the files.
+        const EXECUTE = 1 << 2;
+        /// Create and remove the files.
+        const CREATE = 1 << 3;
+    }
+}
+
+feature! {
+#![feature = "fs"]
+
+/// Make `path` visible to the calling process with `permissions`, hiding
+/// the rest of the file system.
+///
+/// The first call hides everything that is not unveiled.  An empty set of
+/// permissions hides `path` again.  Calling it with both `path` and
+/// `permissions` set to `None` prevents any further call.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::unistd::{unveil, UnveilPermissions};
+/// unveil(Some("/etc"), Some(UnveilPermissions::READ)).unwrap();
+/// unveil(None::<&str>, None).unwrap();
+/// ```
+///
+/// See also [unveil(2)](https://man.openbsd.org/unveil.2)
+#[cfg(target_os = "openbsd")]
+pub fn unveil<P: ?Sized + NixPath>(
+    path: Option<&P>,
+    permissions: Option<UnveilPermissions>,
+) -> Result<()> {
+    let permissions = permissions.map(|perms| {
+        let perms = [
+            (UnveilPermissions::READ, b'r'),
+            (UnveilPermissions::WRITE, b'w'),
+            (UnveilPermissions::EXECUTE, b'x'),
+            (UnveilPermissions::CREATE, b'c'),
+        ]
+        .into_iter()
+        .filter(|&(perm, _)| perms.contains(perm))
+        .map(|(_, c)| c)
+        .collect::<Vec<_>>();
+        // The permissions do not contain any nul byte.
+        CString::new(perms).unwrap()
+    });
+    let res = crate::with_opt_nix_path(path, |p| unsafe {
+        libc::unveil(p, permissions.as_deref().map_or(ptr::null(), CStr::as_ptr))
+    })?;
+
+    Errno::result(res).map(drop)
+}
+}
+
+#[cfg(any(linux_android, freebsdlike, target_os = "netbsd", solarish))]
+libc_bitflags! {
+    /// Flags for [`getrandom`].
+    pub struct GetRandomFlags: c_uint {
+        /// Fail with `EAGAIN` instead of blocking when no random bytes are
+        /// available, either because the entropy pool has not been
+        /// initialized yet or, with `GRND_RANDOM`, because it is exhausted.
+        GRND_NONBLOCK;
+        /// Draw from the `/dev/random` source instead of `/dev/urandom`.
+        GRND_RANDOM;
+        /// Return random bytes even if the entropy pool has not been
+        /// initialized yet, so they are not suitable for cryptographic use.
+        ///
+        /// Since Linux 5.6.
+        #[cfg(not(solarish))]
+        GRND_INSECURE;
+    }
+}
+
+/// Fills `buf` with random bytes (see
+/// [getrandom(2)](https://man7.org/linux/man-pages/man2/getrandom.2.html)).
+///
+/// Unlike reading from `/dev/urandom`, this needs no file descriptor, so it
+/// works in programs that may not open files, and blocks until the entropy
+/// pool is initialized, unless `GRND_NONBLOCK` or `GRND_INSECURE` is given.
+///
+/// The kernel may return fewer bytes than requested, when interrupted by a
+/// signal or for requests larger than 32 MiB on Linux, so `getrandom` is
+/// called again until `buf` is full.  An error returned by such a later call
+/// leaves `buf` partially filled.
+///
+/// # Example
+///
+/// ```
+/// # use nix::unistd::{getrandom, GetRandomFlags};
+/// let mut key = [0u8; 32];
+/// getrandom(&mut key, GetRandomFlags::empty()).unwrap();
+/// ```
+#[cfg(any(linux_android, freebsdlike, target_os = "netbsd", solarish))]
+pub fn getrandom(buf: &mut [u8], flags: GetRandomFlags) -> Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let rest = &mut buf[filled..];
+        // Use the syscall directly, as Android only provides `getrandom` since
+        // API level 28 and some Linux C libraries not at all.
+        #[cfg(linux_android)]
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_getrandom,
+                rest.as_mut_ptr(),
+                rest.len(),
+                flags.bits(),
+            )
+        };
+        #[cfg(not(linux_android))]
+        let res = unsafe {
+            libc::getrandom(rest.as_mut_ptr().cast(), rest.len(), flags.bits())
+        };
+        match Errno::result(res) {
+            Ok(n) => filled += n as usize,
+            Err(Errno::EINTR) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Fills `buf`, which must be at most 256 bytes long, with random bytes (see
+/// [getentropy(3)](https://man.openbsd.org/getentropy.3)).
+///
+/// This is the portable interface meant for seeding random number
+/// generators.  It blocks until the entropy pool is initialized, and either
+/// fills the whole buffer or fails; buffers longer than 256 bytes are
+/// rejected with `EIO` or `EINVAL`, depending on the system.
+///
+/// # Example
+///
+/// ```
+/// # use nix::unistd::getentropy;
+/// let mut seed = [0u8; 16];
+/// getentropy(&mut seed).unwrap();
+/// ```
+#[cfg(any(bsd, solarish, all(target_os = "linux", target_env = "gnu")))]
+pub fn getentropy(buf: &mut [u8]) -> Result<()> {
+    let res = unsafe { libc::getentropy(buf.as_mut_ptr().cast(), buf.len()) };
+
+    Errno::result(res).map(drop)
 }
@@ -6,7 +6,14 @@ use std::process::Command;
 
 use libc::{EACCES, EROFS};
 
-use nix::mount::{mount, umount, MsFlags};
+use nix::errno::Errno;
+use nix::mount::{
+    fsconfig, fsmount, fsopen, fspick, listmount, mount, mount_setattr,
+    move_mount, open_tree, statmount, umount, FsconfigCmd, FsmountFlags,
+    FsopenFlags, FspickFlags, ListmountFlags, MountAtime, MountAttr,
+    MountAttrFlags, MountSetattrFlags, MoveMountFlags, MsFlags, OpenTreeFlags,
+    StatmountMask, LSMT_ROOT,
+};
 use nix::sys::stat::{self, Mode};
 
 use crate::*;
@@ -187,3 +194,331 @@ fn test_mount_bind() {
         .unwrap_or_else(|e| panic!("read failed: {e}"));
     assert_eq!(buf, SCRIPT_CONTENTS);
 }
+
+/// Create a detached tmpfs mount with the new mount API, or `None` if the
+/// kernel does not support it.
+fn fsmount_tmpfs(attr_flags: MountAttrFlags) -> Option<std::os::fd::OwnedFd> {
+    let fs_fd = match fsopen("tmpfs", FsopenFlags::FSOPEN_CLOEXEC) {
+        Err(Errno::ENOSYS) => return None,
+        r => r.unwrap_or_else(|e| panic!("fsopen failed: {e}")),
+    };
+    fsconfig(
+        &fs_fd,
+        FsconfigCmd::SetString {
+            key: "size",
+            value: "1M",
+        },
+    )
+    .unwrap_or_else(|e| panic!("fsconfig failed: {e}"));
+    fsconfig(
+        &fs_fd,
+        FsconfigCmd::SetString {
+            key: "mode",
+            value: "0755",
+        },
+    )
+    .unwrap_or_else(|e| panic!("fsconfig failed: {e}"));
+    fsconfig(&fs_fd, FsconfigCmd::Create)
+        .unwrap_or_else(|e| panic!("fsconfig failed: {e}"));
+    let mnt_fd = fsmount(&fs_fd, FsmountFlags::FSMOUNT_CLOEXEC, attr_flags)
+        .unwrap_or_else(|e| panic!("fsmount failed: {e}"));
+    Some(mnt_fd)
+}
+
+#[test]
+fn test_fsmount_move_mount() {
+    require_capability!("test_fsmount_move_mount", CAP_SYS_ADMIN);
+    let Some(mnt_fd) = fsmount_tmpfs(MountAttrFlags::empty()) else {
+        skip!("fsopen is not supported by this kernel. Skipping test.");
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let dir = File::open(tempdir.path()).unwrap();
+
+    move_mount(
+        &mnt_fd,
+        "",
+        &dir,
+        "",
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH
+            | MoveMountFlags::MOVE_MOUNT_T_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    // The mount is busy while referenced by a file descriptor.
+    drop(mnt_fd);
+    drop(dir);
+
+    let test_path = tempdir.path().join("test");
+    fs::write(&test_path, SCRIPT_CONTENTS)
+        .unwrap_or_else(|e| panic!("write failed: {e}"));
+    assert_eq!(fs::read(&test_path).unwrap(), SCRIPT_CONTENTS);
+    // The file lives on the new mount, not in the directory below it.
+    let statfs = nix::sys::statfs::statfs(tempdir.path()).unwrap();
+    assert_eq!(statfs.filesystem_type(), nix::sys::statfs::TMPFS_MAGIC);
+
+    umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+    assert!(!test_path.exists());
+}
+
+#[test]
+fn test_fsmount_rdonly_disallows_write() {
+    require_capability!("test_fsmount_rdonly_disallows_write", CAP_SYS_ADMIN);
+    let Some(mnt_fd) = fsmount_tmpfs(MountAttrFlags::MOUNT_ATTR_RDONLY) else {
+        skip!("fsopen is not supported by this kernel. Skipping test.");
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+
+    move_mount(
+        &mnt_fd,
+        "",
+        File::open("/").unwrap(),
+        tempdir.path(),
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    // The mount is busy while referenced by a file descriptor.
+    drop(mnt_fd);
+
+    assert_eq!(
+        EROFS,
+        File::create(tempdir.path().join("test"))
+            .unwrap_err()
+            .raw_os_error()
+            .unwrap()
+    );
+
+    umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+}
+
+#[test]
+fn test_fsconfig_invalid_parameter() {
+    require_capability!("test_fsconfig_invalid_parameter", CAP_SYS_ADMIN);
+    let fs_fd = match fsopen("tmpfs", FsopenFlags::FSOPEN_CLOEXEC) {
+        Err(Errno::ENOSYS) => {
+            skip!("fsopen is not supported by this kernel. Skipping test.")
+        }
+        r => r.unwrap(),
+    };
+    assert_eq!(
+        fsconfig(&fs_fd, FsconfigCmd::SetFlag("no_such_option")),
+        Err(Errno::EINVAL)
+    );
+}
+
+#[test]
+fn test_open_tree_clone() {
+    require_capability!("test_open_tree_clone", CAP_SYS_ADMIN);
+    let source = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+    let file_name = "test";
+    fs::write(source.path().join(file_name), SCRIPT_CONTENTS).unwrap();
+
+    let tree_fd = match open_tree(
+        File::open("/").unwrap(),
+        source.path(),
+        OpenTreeFlags::OPEN_TREE_CLONE
+            | OpenTreeFlags::OPEN_TREE_CLOEXEC
+            | OpenTreeFlags::AT_RECURSIVE,
+    ) {
+        Err(Errno::ENOSYS) => {
+            skip!("open_tree is not supported by this kernel. Skipping test.")
+        }
+        r => r.unwrap_or_else(|e| panic!("open_tree failed: {e}")),
+    };
+    move_mount(
+        &tree_fd,
+        "",
+        File::open("/").unwrap(),
+        target.path(),
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    drop(tree_fd);
+
+    assert_eq!(
+        fs::read(target.path().join(file_name)).unwrap(),
+        SCRIPT_CONTENTS
+    );
+
+    umount(target.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+    assert!(!target.path().join(file_name).exists());
+}
+
+/// Create a detached clone of `source`, or `None` if the kernel does not
+/// support it.
+fn open_tree_clone(source: &std::path::Path) -> Option<std::os::fd::OwnedFd> {
+    match open_tree(
+        File::open("/").unwrap(),
+        source,
+        OpenTreeFlags::OPEN_TREE_CLONE | OpenTreeFlags::OPEN_TREE_CLOEXEC,
+    ) {
+        Err(Errno::ENOSYS) => None,
+        r => Some(r.unwrap_or_else(|e| panic!("open_tree failed: {e}"))),
+    }
+}
+
+#[test]
+fn test_mount_setattr_rdonly_disallows_write() {
+    require_capability!(
+        "test_mount_setattr_rdonly_disallows_write",
+        CAP_SYS_ADMIN
+    );
+    let source = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+    let Some(tree_fd) = open_tree_clone(source.path()) else {
+        skip!("open_tree is not supported by this kernel. Skipping test.");
+    };
+
+    let attr = MountAttr::new()
+        .set(MountAttrFlags::MOUNT_ATTR_RDONLY)
+        .atime(MountAtime::Noatime);
+    match mount_setattr(&tree_fd, "", MountSetattrFlags::AT_EMPTY_PATH, &attr) {
+        Err(Errno::ENOSYS) => skip!(
+            "mount_setattr is not supported by this kernel. Skipping test."
+        ),
+        r => r.unwrap_or_else(|e| panic!("mount_setattr failed: {e}")),
+    }
+    move_mount(
+        &tree_fd,
+        "",
+        File::open("/").unwrap(),
+        target.path(),
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    drop(tree_fd);
+
+    assert_eq!(
+        EROFS,
+        File::create(target.path().join("test"))
+            .unwrap_err()
+            .raw_os_error()
+            .unwrap()
+    );
+    // The source itself is still writable.
+    File::create(source.path().join("test")).unwrap();
+
+    umount(target.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+}
+
+#[test]
+fn test_mount_setattr_idmap_requires_userns() {
+    require_capability!(
+        "test_mount_setattr_idmap_requires_userns",
+        CAP_SYS_ADMIN
+    );
+    let source = tempfile::tempdir().unwrap();
+    let Some(tree_fd) = open_tree_clone(source.path()) else {
+        skip!("open_tree is not supported by this kernel. Skipping test.");
+    };
+
+    // Only user namespace file descriptors can be used for idmapping.
+    let file = tempfile::tempfile().unwrap();
+    let attr = MountAttr::new().idmap(std::os::fd::AsFd::as_fd(&file));
+    match mount_setattr(&tree_fd, "", MountSetattrFlags::AT_EMPTY_PATH, &attr) {
+        Err(Errno::ENOSYS) => skip!(
+            "mount_setattr is not supported by this kernel. Skipping test."
+        ),
+        r => assert_eq!(r, Err(Errno::EINVAL)),
+    }
+}
+
+#[test]
+fn test_statmount_listmount() {
+    require_capability!("test_statmount_listmount", CAP_SYS_ADMIN);
+    let Some(mnt_fd) = fsmount_tmpfs(MountAttrFlags::MOUNT_ATTR_NOEXEC) else {
+        skip!("fsopen is not supported by this kernel. Skipping test.");
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    move_mount(
+        &mnt_fd,
+        "",
+        File::open("/").unwrap(),
+        tempdir.path(),
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    drop(mnt_fd);
+
+    let ids = match listmount(LSMT_ROOT, ListmountFlags::empty()) {
+        Err(Errno::ENOSYS) => {
+            umount(tempdir.path()).unwrap();
+            skip!("listmount is not supported by this kernel. Skipping test.")
+        }
+        r => r.unwrap_or_else(|e| panic!("listmount failed: {e}")),
+    };
+    let mask = StatmountMask::STATMOUNT_MNT_BASIC
+        | StatmountMask::STATMOUNT_MNT_POINT
+        | StatmountMask::STATMOUNT_FS_TYPE;
+    let sm = ids
+        .iter()
+        .map(|&id| {
+            statmount(id, mask)
+                .unwrap_or_else(|e| panic!("statmount failed: {e}"))
+        })
+        .find(|sm| sm.mnt_point() == Some(tempdir.path().as_os_str()))
+        .expect("the new mount is not listed");
+
+    assert_eq!(sm.fs_type(), Some("tmpfs".as_ref()));
+    assert!(sm
+        .mnt_attr()
+        .unwrap()
+        .contains(MountAttrFlags::MOUNT_ATTR_NOEXEC));
+    assert!(ids.contains(&sm.mnt_parent_id().unwrap()));
+    // Only the requested information is returned.
+    assert_eq!(sm.sb_magic(), None);
+    assert_eq!(sm.mnt_root(), None);
+
+    let mnt_id = sm.mnt_id().unwrap();
+    let sm = statmount(mnt_id, StatmountMask::STATMOUNT_MNT_OPTS).unwrap();
+    if let Some(opts) = sm.mnt_opts() {
+        let opts = opts.to_str().unwrap();
+        assert!(opts.split(',').any(|o| o == "size=1024k"), "{opts}");
+    }
+
+    umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+    assert_eq!(statmount(mnt_id, mask).unwrap_err(), Errno::ENOENT);
+}
+
+#[test]
+fn test_fspick_reconfigure() {
+    require_capability!("test_fspick_reconfigure", CAP_SYS_ADMIN);
+    let Some(mnt_fd) = fsmount_tmpfs(MountAttrFlags::empty()) else {
+        skip!("fsopen is not supported by this kernel. Skipping test.");
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    move_mount(
+        &mnt_fd,
+        "",
+        File::open("/").unwrap(),
+        tempdir.path(),
+        MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH,
+    )
+    .unwrap_or_else(|e| panic!("move_mount failed: {e}"));
+    drop(mnt_fd);
+    let blocks = nix::sys::statfs::statfs(tempdir.path()).unwrap().blocks();
+
+    let fs_fd = fspick(
+        File::open("/").unwrap(),
+        tempdir.path(),
+        FspickFlags::FSPICK_CLOEXEC,
+    )
+    .unwrap_or_else(|e| panic!("fspick failed: {e}"));
+    fsconfig(
+        &fs_fd,
+        FsconfigCmd::SetString {
+            key: "size",
+            value: "2M",
+        },
+    )
+    .unwrap_or_else(|e| panic!("fsconfig failed: {e}"));
+    fsconfig(&fs_fd, FsconfigCmd::Reconfigure)
+        .unwrap_or_else(|e| panic!("fsconfig failed: {e}"));
+    drop(fs_fd);
+
+    assert_eq!(
+        nix::sys::statfs::statfs(tempdir.path()).unwrap().blocks(),
+        2 * blocks
+    );
+
+    umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {e}"));
+}
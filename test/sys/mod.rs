@@ -12,6 +12,8 @@ mod test_signal;
     target_os = "netbsd"
 ))]
 mod test_aio;
+#[cfg(apple_targets)]
+mod test_attrlist;
 #[cfg(not(any(
     target_os = "redox",
     target_os = "fuchsia",
@@ -21,8 +23,12 @@ mod test_aio;
 mod test_ioctl;
 #[cfg(not(target_os = "redox"))]
 mod test_mman;
+#[cfg(target_os = "linux")]
+mod test_msg;
 #[cfg(not(target_os = "redox"))]
 mod test_select;
+#[cfg(any(target_os = "linux", apple_targets))]
+mod test_sem;
 #[cfg(target_os = "linux")]
 mod test_signalfd;
 #[cfg(not(any(target_os = "redox", target_os = "haiku")))]
@@ -30,6 +36,11 @@ mod test_socket;
 #[cfg(not(any(target_os = "redox")))]
 mod test_sockopt;
 mod test_stat;
+#[cfg(any(
+    target_os = "android",
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+))]
+mod test_statx;
 #[cfg(linux_android)]
 mod test_sysinfo;
 #[cfg(not(any(
@@ -41,13 +52,41 @@ mod test_termios;
 mod test_uio;
 mod test_wait;
 
+#[cfg(target_os = "linux")]
+mod test_caps;
+#[cfg(target_os = "freebsd")]
+mod test_capsicum;
 #[cfg(linux_android)]
 mod test_epoll;
+#[cfg(any(linux_android, target_os = "freebsd"))]
+mod test_eventfd;
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+mod test_extattr;
 #[cfg(target_os = "linux")]
 mod test_fanotify;
 #[cfg(target_os = "linux")]
+mod test_fsverity;
+#[cfg(target_os = "linux")]
 mod test_inotify;
+#[cfg(target_os = "linux")]
+mod test_io_uring;
+#[cfg(target_os = "freebsd")]
+mod test_jail;
+#[cfg(target_os = "linux")]
+mod test_key;
+#[cfg(target_os = "linux")]
+mod test_landlock;
+#[cfg(target_os = "linux")]
+mod test_loopdev;
+#[cfg(target_os = "linux")]
+mod test_perf_event;
 mod test_pthread;
+#[cfg(target_os = "linux")]
+mod test_quota;
+#[cfg(target_os = "linux")]
+mod test_seccomp;
+#[cfg(target_os = "linux")]
+mod test_swap;
 
 #[cfg(any(linux_android, freebsdlike, netbsdlike, apple_targets))]
 mod test_ptrace;
@@ -68,7 +107,11 @@ mod test_timer;
 
 #[cfg(bsd)]
 mod test_event;
+#[cfg(solarish)]
+mod test_port;
 mod test_statvfs;
+#[cfg(bsd)]
+mod test_sysctl;
 mod test_time;
 mod test_utsname;
 
@@ -92,3 +135,5 @@ mod test_resource;
 // only enable this for FreeBSD for now.
 #[cfg(target_os = "freebsd")]
 mod test_memfd;
+#[cfg(linux_android)]
+mod test_xattr;
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::Write;
+
+use nix::fcntl::{open, OFlag};
+use nix::sys::attrlist::*;
+use nix::sys::stat::{stat, Mode};
+use nix::sys::time::TimeSpec;
+use tempfile::tempdir;
+
+#[test]
+fn test_getattrlist() {
+    let tmp = tempdir().unwrap();
+    let path = tmp.path().join("file");
+    File::create(&path).unwrap().write_all(b"hello").unwrap();
+    let st = stat(&path).unwrap();
+
+    let attrs = AttrList::new()
+        .common(
+            CommonAttr::ATTR_CMN_NAME
+                | CommonAttr::ATTR_CMN_FILEID
+                | CommonAttr::ATTR_CMN_MODTIME
+                | CommonAttr::ATTR_CMN_OWNERID,
+        )
+        .dir(DirAttr::ATTR_DIR_ENTRYCOUNT)
+        .file(FileAttr::ATTR_FILE_DATALENGTH);
+    let mut buf = [0u8; 1024];
+    let res = getattrlist(&path, &attrs, &mut buf, FsOpt::empty()).unwrap();
+
+    assert_eq!(res.name().unwrap().to_str().unwrap(), "file");
+    assert_eq!(res.file_id(), Some(st.st_ino));
+    assert_eq!(res.owner(), Some(st.st_uid));
+    assert_eq!(
+        res.modification_time(),
+        Some(TimeSpec::new(st.st_mtime, st.st_mtime_nsec))
+    );
+    assert_eq!(res.data_length(), Some(5));
+    // Directory attributes are not returned for regular files.
+    assert_eq!(res.entry_count(), None);
+    assert!(res.dir().is_empty());
+}
+
+#[test]
+fn test_fgetattrlist_truncated() {
+    let tmp = tempdir().unwrap();
+    let file = File::create(tmp.path().join("file")).unwrap();
+    let attrs = AttrList::new()
+        .common(CommonAttr::ATTR_CMN_NAME | CommonAttr::ATTR_CMN_FILEID);
+
+    let mut buf = [0u8; 1024];
+    let res = fgetattrlist(&file, &attrs, &mut buf, FsOpt::empty()).unwrap();
+    assert!(res.file_id().is_some());
+
+    let mut small = [0u8; 16];
+    assert!(fgetattrlist(
+        &file,
+        &attrs,
+        &mut small,
+        FsOpt::FSOPT_REPORT_FULLSIZE
+    )
+    .is_err());
+}
+
+#[test]
+fn test_getattrlistbulk() {
+    let tmp = tempdir().unwrap();
+    for i in 0..50 {
+        File::create(tmp.path().join(format!("file{i:02}")))
+            .unwrap()
+            .write_all(&vec![0; i])
+            .unwrap();
+    }
+    std::fs::create_dir(tmp.path().join("subdir")).unwrap();
+    let dir = open(
+        tmp.path(),
+        OFlag::O_RDONLY | OFlag::O_DIRECTORY,
+        Mode::empty(),
+    )
+    .unwrap();
+
+    let attrs = AttrList::new()
+        .common(CommonAttr::ATTR_CMN_NAME | CommonAttr::ATTR_CMN_OBJTYPE)
+        .file(FileAttr::ATTR_FILE_DATALENGTH);
+    // A small buffer forces several calls.
+    let mut buf = [0u8; 512];
+    let mut entries = Vec::new();
+    loop {
+        let bulk =
+            getattrlistbulk(&dir, &attrs, &mut buf, FsOpt::empty()).unwrap();
+        if bulk.is_empty() {
+            break;
+        }
+        for entry in bulk {
+            let entry = entry.unwrap();
+            let name = entry.name().unwrap().to_str().unwrap().to_owned();
+            entries.push((name, entry.data_length()));
+        }
+    }
+    entries.sort();
+
+    assert_eq!(entries.len(), 51);
+    for (i, (name, len)) in entries[..50].iter().enumerate() {
+        assert_eq!(name, &format!("file{i:02}"));
+        assert_eq!(*len, Some(i as i64));
+    }
+    assert_eq!(entries[50], ("subdir".to_owned(), None));
+}
+
+#[test]
+fn test_setattrlist() {
+    let tmp = tempdir().unwrap();
+    let path = tmp.path().join("file");
+    let file = File::create(&path).unwrap();
+
+    let mtime = TimeSpec::new(1_000_000_000, 0);
+    let values = AttrValues::new()
+        .modification_time(mtime)
+        .access_mask(Mode::from_bits_truncate(0o600));
+    setattrlist(&path, &values, FsOpt::empty()).unwrap();
+
+    let st = stat(&path).unwrap();
+    assert_eq!(st.st_mtime, 1_000_000_000);
+    assert_eq!(st.st_mode & 0o777, 0o600);
+
+    let values = AttrValues::new().access_mask(Mode::from_bits_truncate(0o640));
+    fsetattrlist(&file, &values, FsOpt::empty()).unwrap();
+    assert_eq!(stat(&path).unwrap().st_mode & 0o777, 0o640);
+}
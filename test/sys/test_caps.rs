@@ -0,0 +1,76 @@
+use nix::errno::Errno;
+use nix::sys::caps::{
+    capget, capget_version, capset, Capability, CapabilitySet,
+};
+use nix::unistd::Pid;
+
+#[test]
+fn test_capability_set() {
+    let set = CapabilitySet::CAP_CHOWN | CapabilitySet::CAP_SYS_ADMIN;
+    assert!(set.has(Capability::CAP_SYS_ADMIN));
+    assert!(!set.has(Capability::CAP_KILL));
+    assert_eq!(
+        set.capabilities().collect::<Vec<_>>(),
+        [Capability::CAP_CHOWN, Capability::CAP_SYS_ADMIN]
+    );
+    assert_eq!(
+        Capability::try_from(Capability::CAP_BPF as u32),
+        Ok(Capability::CAP_BPF)
+    );
+    assert_eq!(Capability::try_from(64), Err(Errno::EINVAL));
+}
+
+#[test]
+fn test_capget_version() {
+    // Version 3, with 64-bit sets.
+    assert_eq!(capget_version(), Ok(0x2008_0522));
+}
+
+#[test]
+fn test_capget() {
+    let caps = capget(None).unwrap();
+    assert!(caps.permitted.contains(caps.effective));
+    assert_eq!(
+        caps.effective.has(Capability::CAP_SYS_ADMIN),
+        ::caps::has_cap(
+            None,
+            ::caps::CapSet::Effective,
+            ::caps::Capability::CAP_SYS_ADMIN
+        )
+        .unwrap()
+    );
+    // The capabilities of another thread.
+    assert!(capget(Some(Pid::from_raw(1))).is_ok());
+}
+
+#[test]
+fn test_capset() {
+    require_capability!("test_capset", CAP_SYS_ADMIN);
+    // The capabilities of a thread only apply to it.
+    std::thread::spawn(drop_sys_admin).join().unwrap();
+}
+
+fn drop_sys_admin() {
+    let mut caps = capget(None).unwrap();
+    caps.effective.remove(Capability::CAP_SYS_ADMIN.into());
+    capset(&caps).unwrap();
+    assert!(!capget(None)
+        .unwrap()
+        .effective
+        .has(Capability::CAP_SYS_ADMIN));
+
+    // It is still permitted.
+    caps.effective.insert(Capability::CAP_SYS_ADMIN.into());
+    capset(&caps).unwrap();
+    assert!(capget(None)
+        .unwrap()
+        .effective
+        .has(Capability::CAP_SYS_ADMIN));
+
+    // But can't be raised anymore once it is not.
+    caps.effective.remove(Capability::CAP_SYS_ADMIN.into());
+    caps.permitted.remove(Capability::CAP_SYS_ADMIN.into());
+    capset(&caps).unwrap();
+    caps.effective.insert(Capability::CAP_SYS_ADMIN.into());
+    assert_eq!(capset(&caps), Err(Errno::EPERM));
+}
@@ -0,0 +1,74 @@
+use std::io::{Read, Write};
+
+use nix::errno::Errno;
+use nix::sys::capsicum::*;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, ForkResult};
+
+#[test]
+fn test_cap_rights() {
+    let mut rights = CapRights::new();
+    rights.set(CapRight::CAP_READ).set(CapRight::CAP_FSTAT);
+    assert!(rights.is_set(CapRight::CAP_READ));
+    assert!(!rights.is_set(CapRight::CAP_WRITE));
+    // CAP_PREAD also needs CAP_SEEK.
+    assert!(!rights.is_set(CapRight::CAP_PREAD));
+
+    let mut other = CapRights::new();
+    other.set(CapRight::CAP_READ);
+    assert!(rights.contains(&other));
+    rights.remove(&other);
+    assert!(!rights.is_set(CapRight::CAP_READ));
+    rights.merge(&other);
+    assert!(rights.is_set(CapRight::CAP_READ));
+    rights.clear(CapRight::CAP_FSTAT);
+    assert!(!rights.is_set(CapRight::CAP_FSTAT));
+}
+
+#[test]
+fn test_cap_rights_limit() {
+    let mut file = tempfile::tempfile().unwrap();
+    let mut rights = CapRights::new();
+    rights.set(CapRight::CAP_READ).set(CapRight::CAP_FSTAT);
+    cap_rights_limit(&file, &rights).unwrap();
+    assert!(cap_rights_get(&file).unwrap().contains(&rights));
+
+    let mut buf = [0; 1];
+    assert_eq!(file.read(&mut buf).unwrap(), 0);
+    let err = file.write(b"x").unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(Errno::ENOTCAPABLE as i32));
+
+    // Rights can't be added back.
+    rights.set(CapRight::CAP_WRITE);
+    assert_eq!(cap_rights_limit(&file, &rights), Err(Errno::ENOTCAPABLE));
+}
+
+#[test]
+fn test_cap_ioctls_fcntls_limit() {
+    let file = tempfile::tempfile().unwrap();
+    assert_eq!(cap_ioctls_get(&file), Ok(None));
+    cap_ioctls_limit(&file, &[libc::FIONREAD]).unwrap();
+    assert_eq!(cap_ioctls_get(&file), Ok(Some(vec![libc::FIONREAD])));
+
+    cap_fcntls_limit(&file, FcntlRights::CAP_FCNTL_GETFL).unwrap();
+    assert_eq!(cap_fcntls_get(&file), Ok(FcntlRights::CAP_FCNTL_GETFL));
+}
+
+#[test]
+fn test_cap_enter() {
+    let _m = crate::FORK_MTX.lock();
+    assert_eq!(cap_getmode(), Ok(false));
+
+    // Capability mode can not be left.
+    match unsafe { fork() }.expect("fork failed") {
+        ForkResult::Child => {
+            let ok = cap_enter().is_ok()
+                && cap_getmode() == Ok(true)
+                && std::fs::File::open("/").is_err();
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
@@ -24,3 +24,83 @@ pub fn test_epoll_ctl() {
     epoll_ctl(efd, EpollOp::EpollCtlAdd, 1, &mut event).unwrap();
     epoll_ctl(efd, EpollOp::EpollCtlDel, 1, None).unwrap();
 }
+
+#[test]
+pub fn test_epoll_wait_with_sigmask() {
+    use nix::sys::epoll::{Epoll, Timeout};
+    use nix::sys::eventfd::EventFd;
+    use nix::sys::signal::SigSet;
+    use nix::sys::time::TimeSpec;
+    use std::time::{Duration, Instant};
+
+    let epoll = Epoll::new(EpollCreateFlags::empty()).unwrap();
+    let eventfd = EventFd::new().unwrap();
+    epoll
+        .add(&eventfd, EpollEvent::new(EpollFlags::EPOLLIN, 42))
+        .unwrap();
+
+    let mut events = [EpollEvent::empty()];
+    let start = Instant::now();
+    let timeout = TimeSpec::from_duration(Duration::from_micros(1500));
+    let n = epoll
+        .wait_with_sigmask(&mut events, Some(timeout), Some(&SigSet::empty()))
+        .unwrap();
+    assert_eq!(n, 0);
+    assert!(start.elapsed() >= Duration::from_micros(1500));
+
+    eventfd.write(1).unwrap();
+    let n = epoll
+        .wait_with_sigmask(&mut events, Timeout::Infinite, None)
+        .unwrap();
+    assert_eq!(n, 1);
+    assert_eq!(events[0].data(), 42);
+    assert_eq!(events[0].events(), EpollFlags::EPOLLIN);
+}
+
+#[test]
+pub fn test_epoll_token() {
+    use nix::sys::epoll::Epoll;
+    use nix::sys::eventfd::EventFd;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum Token {
+        Timer,
+        Wakeup,
+    }
+
+    impl From<Token> for u64 {
+        fn from(token: Token) -> u64 {
+            token as u64
+        }
+    }
+
+    impl From<u64> for Token {
+        fn from(data: u64) -> Token {
+            match data {
+                0 => Token::Timer,
+                _ => Token::Wakeup,
+            }
+        }
+    }
+
+    let epoll = Epoll::new(EpollCreateFlags::empty()).unwrap();
+    let timer = EventFd::new().unwrap();
+    let wakeup = EventFd::new().unwrap();
+    epoll
+        .add(
+            &timer,
+            EpollEvent::with_token(EpollFlags::EPOLLIN, Token::Timer),
+        )
+        .unwrap();
+    epoll
+        .add(
+            &wakeup,
+            EpollEvent::with_token(EpollFlags::EPOLLIN, Token::Wakeup),
+        )
+        .unwrap();
+
+    wakeup.write(1).unwrap();
+    let mut events = [EpollEvent::empty(); 2];
+    assert_eq!(epoll.wait(&mut events, 0u8).unwrap(), 1);
+    assert_eq!(events[0].token::<Token>(), Token::Wakeup);
+}
@@ -39,3 +39,105 @@ fn test_kevent_filter() {
     );
     assert_eq!(EventFilter::EVFILT_READ, actual.filter().unwrap());
 }
+
+#[test]
+fn test_kevent_udata_ptr() {
+    let mut value = 42u32;
+    let ev = KEvent::new(
+        0,
+        EventFilter::EVFILT_READ,
+        EventFlag::empty(),
+        FilterFlag::empty(),
+        0,
+        0,
+    )
+    .with_udata_ptr(&mut value as *mut u32);
+    assert_eq!(ev.udata_ptr::<u32>(), &mut value as *mut u32);
+}
+
+#[test]
+#[cfg(any(freebsdlike, apple_targets))]
+fn test_kevent_user() {
+    use nix::sys::event::{Kqueue, Timeout, UserFflagsOp};
+
+    let kq = Kqueue::new().unwrap();
+    kq.kevent(
+        &[KEvent::user(7, EventFlag::EV_CLEAR, 0)],
+        &mut [],
+        Timeout::ZeroPoll,
+    )
+    .unwrap();
+
+    let mut events = [KEvent::user(0, EventFlag::empty(), 0)];
+    assert_eq!(kq.kevent(&[], &mut events, Timeout::ZeroPoll), Ok(0));
+
+    kq.trigger_user(7, 0b0101).unwrap();
+    kq.kevent(
+        &[KEvent::user_trigger(7, UserFflagsOp::Or(0b1000))],
+        &mut [],
+        Timeout::ZeroPoll,
+    )
+    .unwrap();
+    assert_eq!(kq.kevent(&[], &mut events, Timeout::ZeroPoll), Ok(1));
+    assert_eq!(events[0].ident(), 7);
+    assert_eq!(events[0].filter().unwrap(), EventFilter::EVFILT_USER);
+    assert_eq!(events[0].user_fflags(), 0b1101);
+
+    // The flags are kept, but EV_CLEAR resets the trigger.
+    assert_eq!(kq.kevent(&[], &mut events, Timeout::ZeroPoll), Ok(0));
+    kq.kevent(
+        &[KEvent::user_trigger(7, UserFflagsOp::And(0b0100))],
+        &mut [],
+        Timeout::ZeroPoll,
+    )
+    .unwrap();
+    assert_eq!(kq.kevent(&[], &mut events, Timeout::ZeroPoll), Ok(1));
+    assert_eq!(events[0].user_fflags(), 0b0100);
+}
+
+#[test]
+fn test_kevent_timer() {
+    use nix::sys::event::{Kqueue, Timeout};
+    use std::time::Duration;
+
+    let kq = Kqueue::new().unwrap();
+    let timer = KEvent::timer(
+        3,
+        Duration::from_millis(10),
+        EventFlag::empty(),
+        FilterFlag::empty(),
+        0,
+    )
+    .unwrap();
+    let mut events = [timer];
+    assert_eq!(kq.kevent(&[timer], &mut events, Timeout::Infinite), Ok(1));
+    assert_eq!(events[0].ident(), 3);
+    assert_eq!(events[0].filter().unwrap(), EventFilter::EVFILT_TIMER);
+    assert!(events[0].data() >= 1);
+
+    // The timer is periodic.
+    assert_eq!(kq.kevent(&[], &mut events, Timeout::Infinite), Ok(1));
+    assert!(events[0].data() >= 1);
+}
+
+#[test]
+#[cfg(any(apple_targets, target_os = "freebsd", target_os = "netbsd"))]
+fn test_kevent_timer_at() {
+    use nix::sys::event::{Kqueue, Timeout};
+    use std::time::{Duration, SystemTime};
+
+    let kq = Kqueue::new().unwrap();
+    let deadline = SystemTime::now() + Duration::from_millis(20);
+    let timer = KEvent::timer_at(
+        4,
+        deadline,
+        EventFlag::empty(),
+        FilterFlag::empty(),
+        0,
+    )
+    .unwrap();
+    let mut events = [timer];
+    assert_eq!(kq.kevent(&[timer], &mut events, Timeout::Infinite), Ok(1));
+    assert_eq!(events[0].ident(), 4);
+    assert!(SystemTime::now() >= deadline);
+}
@@ -0,0 +1,34 @@
+use nix::errno::Errno;
+use nix::sys::eventfd::{EfdFlags, EventFd};
+
+#[test]
+fn test_eventfd_values() {
+    let efd = EventFd::new().unwrap();
+    assert_eq!(efd.try_read(), Ok(None));
+
+    efd.write_value(3).unwrap();
+    efd.write_value(4).unwrap();
+    assert_eq!(efd.read_value(), Ok(7));
+    assert_eq!(efd.try_read(), Ok(None));
+
+    efd.write_value(1).unwrap();
+    assert_eq!(efd.try_read(), Ok(Some(1)));
+
+    assert_eq!(efd.write_value(u64::MAX), Err(Errno::EINVAL));
+}
+
+#[test]
+fn test_eventfd_semaphore() {
+    let efd = EventFd::from_value_and_flags(
+        2,
+        EfdFlags::EFD_SEMAPHORE | EfdFlags::EFD_NONBLOCK,
+    )
+    .unwrap();
+    efd.acquire().unwrap();
+    assert_eq!(efd.try_acquire(), Ok(true));
+    assert_eq!(efd.try_acquire(), Ok(false));
+    assert_eq!(efd.acquire(), Err(Errno::EAGAIN));
+
+    efd.post().unwrap();
+    assert_eq!(efd.try_read(), Ok(Some(1)));
+}
@@ -0,0 +1,74 @@
+use std::ffi::OsStr;
+use std::fs::File;
+use std::os::unix::fs::symlink;
+
+use nix::errno::Errno;
+use nix::sys::extattr::*;
+
+const USER: ExtattrNamespace = ExtattrNamespace::EXTATTR_NAMESPACE_USER;
+
+#[test]
+fn test_extattr_file() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    File::create(&path).unwrap();
+
+    match extattr_set_file(&path, USER, "nix", b"value") {
+        Err(Errno::EOPNOTSUPP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert_eq!(extattr_get_file(&path, USER, "nix").unwrap(), b"value");
+
+    extattr_set_file(&path, USER, "nix", b"").unwrap();
+    assert_eq!(extattr_get_file(&path, USER, "nix").unwrap(), b"");
+
+    extattr_delete_file(&path, USER, "nix").unwrap();
+    assert_eq!(extattr_get_file(&path, USER, "nix"), Err(Errno::ENOATTR));
+    assert_eq!(extattr_delete_file(&path, USER, "nix"), Err(Errno::ENOATTR));
+}
+
+#[test]
+fn test_extattr_fd_list() {
+    let file = tempfile::tempfile().unwrap();
+    match extattr_set_fd(&file, USER, "a", b"1") {
+        Err(Errno::EOPNOTSUPP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    extattr_set_fd(&file, USER, "b", &[0; 1000]).unwrap();
+    assert_eq!(extattr_get_fd(&file, USER, "b").unwrap(), [0; 1000]);
+
+    let list = extattr_list_fd(&file, USER).unwrap();
+    let mut names: Vec<_> = list.iter().collect();
+    names.sort();
+    assert_eq!(names, [OsStr::new("a"), OsStr::new("b")]);
+
+    extattr_delete_fd(&file, USER, "a").unwrap();
+    extattr_delete_fd(&file, USER, "b").unwrap();
+    assert!(extattr_list_fd(&file, USER).unwrap().is_empty());
+}
+
+#[test]
+fn test_extattr_link() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let target = tempdir.path().join("target");
+    let link = tempdir.path().join("link");
+    File::create(&target).unwrap();
+    symlink(&target, &link).unwrap();
+
+    match extattr_set_file(&link, USER, "nix", b"target") {
+        Err(Errno::EOPNOTSUPP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert_eq!(extattr_get_file(&target, USER, "nix").unwrap(), b"target");
+    assert_eq!(extattr_get_link(&link, USER, "nix"), Err(Errno::ENOATTR));
+    assert!(extattr_list_link(&link, USER).unwrap().is_empty());
+
+    extattr_delete_file(&link, USER, "nix").unwrap();
+    assert!(extattr_list_file(&target, USER).unwrap().is_empty());
+}
@@ -2,9 +2,10 @@ use crate::*;
 use nix::errno::Errno;
 use nix::fcntl::AT_FDCWD;
 use nix::sys::fanotify::{
-    EventFFlags, Fanotify, FanotifyResponse, InitFlags, MarkFlags, MaskFlags,
-    Response,
+    EventFFlags, Fanotify, FanotifyResponse, FidInfoType, InitFlags, MarkFlags,
+    MaskFlags, Response,
 };
+use std::ffi::OsStr;
 use std::fs::{read_link, read_to_string, File, OpenOptions};
 use std::io::ErrorKind;
 use std::io::{Read, Write};
@@ -19,6 +20,8 @@ pub fn test_fanotify() {
     test_fanotify_notifications();
     test_fanotify_responses();
     test_fanotify_overflow();
+    test_fanotify_fid();
+    test_fanotify_respond();
 }
 
 fn test_fanotify_notifications() {
@@ -218,3 +221,100 @@ fn test_fanotify_overflow() {
     assert_eq!(n, max_events + 1);
     assert_eq!(last_event, Some(MaskFlags::FAN_Q_OVERFLOW));
 }
+
+fn test_fanotify_fid() {
+    let group = Fanotify::init(
+        InitFlags::FAN_CLASS_NOTIF | InitFlags::FAN_REPORT_DFID_NAME,
+        EventFFlags::O_RDONLY,
+    )
+    .unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+
+    // Some filesystems, like overlayfs on older kernels, or FUSE, can't
+    // encode file handles.
+    match group.mark(
+        MarkFlags::FAN_MARK_ADD,
+        MaskFlags::FAN_CREATE,
+        AT_FDCWD,
+        Some(tempdir.path()),
+    ) {
+        Ok(()) => (),
+        Err(Errno::EOPNOTSUPP | Errno::EXDEV | Errno::ENODEV) => return,
+        Err(e) => panic!("{e:?}"),
+    }
+
+    File::create(tempdir.path().join("test")).unwrap();
+
+    let mut events = group.read_events().unwrap();
+    assert_eq!(events.len(), 1, "should have read exactly one event");
+    let event = events.pop().unwrap();
+    assert_eq!(event.mask(), MaskFlags::FAN_CREATE);
+    assert!(event.fd().is_none());
+
+    let records = event.fid_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].info_type(), FidInfoType::DfidName);
+    assert!(!records[0].handle().is_empty());
+    assert_eq!(records[0].name(), Some(OsStr::new("test")));
+
+    // A filesystem mark reports the objects of the whole filesystem.
+    let group = Fanotify::init(
+        InitFlags::FAN_CLASS_NOTIF | InitFlags::FAN_REPORT_FID,
+        EventFFlags::O_RDONLY,
+    )
+    .unwrap();
+    group
+        .mark(
+            MarkFlags::FAN_MARK_ADD | MarkFlags::FAN_MARK_FILESYSTEM,
+            MaskFlags::FAN_CLOSE_WRITE,
+            AT_FDCWD,
+            Some(tempdir.path()),
+        )
+        .unwrap();
+
+    let subdir = tempdir.path().join("subdir");
+    std::fs::create_dir(&subdir).unwrap();
+    File::create(subdir.join("test")).unwrap();
+
+    // Other processes may write to the filesystem too.
+    let events = group.read_events().unwrap();
+    assert!(events.iter().all(|e| e.fd().is_none()));
+    assert!(events.iter().any(|e| {
+        e.mask() == MaskFlags::FAN_CLOSE_WRITE
+            && e.fid_records()
+                .iter()
+                .any(|r| r.info_type() == FidInfoType::Fid)
+    }));
+}
+
+fn test_fanotify_respond() {
+    let group =
+        Fanotify::init(InitFlags::FAN_CLASS_CONTENT, EventFFlags::O_RDONLY)
+            .unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    let tempfile = tempdir.path().join("test");
+    File::create(&tempfile).unwrap();
+
+    group
+        .mark(
+            MarkFlags::FAN_MARK_ADD,
+            MaskFlags::FAN_OPEN_PERM,
+            AT_FDCWD,
+            Some(&tempfile),
+        )
+        .unwrap();
+
+    let file_thread = thread::spawn({
+        let tempfile = tempfile.clone();
+        move || File::open(tempfile).map(drop).map_err(|e| e.kind())
+    });
+
+    let events = group.read_events().unwrap();
+    assert_eq!(events.len(), 1, "should have read exactly one event");
+    group.respond(&events[0], Response::FAN_DENY).unwrap();
+
+    assert_eq!(
+        file_thread.join().unwrap(),
+        Err(ErrorKind::PermissionDenied)
+    );
+}
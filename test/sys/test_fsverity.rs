@@ -0,0 +1,38 @@
+use nix::errno::Errno;
+use nix::fcntl::{open, OFlag};
+use nix::sys::fsverity::{
+    enable_verity, measure_verity, EnableVerityArg, HashAlgorithm,
+};
+use nix::sys::stat::Mode;
+
+#[test]
+fn test_measure_verity_not_enabled() {
+    let tmp = tempfile::tempfile().unwrap();
+    let res = measure_verity(&tmp);
+    // ENODATA if the filesystem supports fs-verity, but it is not enabled on
+    // this file.
+    assert!(matches!(
+        res,
+        Err(Errno::ENODATA | Errno::EOPNOTSUPP | Errno::ENOTTY)
+    ));
+}
+
+#[test]
+fn test_enable_and_measure_verity() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    std::fs::write(&path, b"verity").unwrap();
+    let fd = open(&path, OFlag::O_RDONLY, Mode::empty()).unwrap();
+
+    let arg = EnableVerityArg::new(HashAlgorithm::Sha256).salt(b"salt");
+    match enable_verity(&fd, &arg) {
+        Ok(()) => (),
+        // fs-verity needs to be supported and enabled on the filesystem.
+        Err(Errno::EOPNOTSUPP | Errno::ENOTTY) => return,
+        Err(e) => panic!("enable_verity failed: {e}"),
+    }
+
+    let digest = measure_verity(&fd).unwrap();
+    assert_eq!(digest.algorithm(), Ok(HashAlgorithm::Sha256));
+    assert_eq!(digest.digest().len(), 32);
+}
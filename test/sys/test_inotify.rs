@@ -1,5 +1,5 @@
 use nix::errno::Errno;
-use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+use nix::sys::inotify::{pair_moves, AddWatchFlags, InitFlags, Inotify};
 use std::ffi::OsString;
 use std::fs::{rename, File};
 
@@ -63,3 +63,72 @@ pub fn test_inotify_multi_events() {
 
     assert_eq!(events[3].cookie, events[4].cookie);
 }
+
+#[test]
+pub fn test_inotify_mask_flags() {
+    let instance = Inotify::init(InitFlags::IN_NONBLOCK).unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+
+    let wd = instance
+        .add_watch(tempdir.path(), AddWatchFlags::IN_CREATE)
+        .unwrap();
+    assert_eq!(
+        instance.add_watch(
+            tempdir.path(),
+            AddWatchFlags::IN_DELETE | AddWatchFlags::IN_MASK_CREATE
+        ),
+        Err(Errno::EEXIST)
+    );
+    // Extending the mask keeps the watch descriptor.
+    let wd2 = instance
+        .add_watch(
+            tempdir.path(),
+            AddWatchFlags::IN_DELETE | AddWatchFlags::IN_MASK_ADD,
+        )
+        .unwrap();
+    assert_eq!(wd, wd2);
+
+    let path = tempdir.path().join("test");
+    File::create(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    let events = instance.read_events_with_buffer(&mut buffer).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].mask, AddWatchFlags::IN_CREATE);
+    assert_eq!(events[1].mask, AddWatchFlags::IN_DELETE);
+    assert_eq!(
+        instance.read_events_with_buffer(&mut buffer).unwrap_err(),
+        Errno::EAGAIN
+    );
+}
+
+#[test]
+pub fn test_inotify_pair_moves() {
+    let instance = Inotify::init(InitFlags::IN_NONBLOCK).unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    let unwatched = tempfile::tempdir().unwrap();
+
+    instance
+        .add_watch(tempdir.path(), AddWatchFlags::IN_MOVE)
+        .unwrap();
+
+    File::create(tempdir.path().join("a")).unwrap();
+    File::create(unwatched.path().join("c")).unwrap();
+    rename(tempdir.path().join("a"), tempdir.path().join("b")).unwrap();
+    rename(tempdir.path().join("b"), unwatched.path().join("b")).unwrap();
+    rename(unwatched.path().join("c"), tempdir.path().join("c")).unwrap();
+
+    let events = instance.read_events().unwrap();
+    let moves = pair_moves(&events);
+    assert_eq!(moves.len(), 3);
+
+    assert_eq!(moves[0].from.unwrap().name, Some(OsString::from("a")));
+    assert_eq!(moves[0].to.unwrap().name, Some(OsString::from("b")));
+
+    assert_eq!(moves[1].from.unwrap().name, Some(OsString::from("b")));
+    assert!(moves[1].to.is_none());
+
+    assert!(moves[2].from.is_none());
+    assert_eq!(moves[2].to.unwrap().name, Some(OsString::from("c")));
+}
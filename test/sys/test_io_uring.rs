@@ -0,0 +1,119 @@
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use nix::errno::Errno;
+use nix::sys::eventfd::EventFd;
+use nix::sys::io_uring::{
+    EnterFlags, IoUring, IoUringCqe, IoUringParams, IoUringSqe, SetupFlags,
+};
+
+// io_uring may be unavailable, or disabled with the io_uring_disabled sysctl.
+fn setup(entries: u32) -> Option<IoUring> {
+    match IoUring::setup(entries, IoUringParams::new()) {
+        Ok(ring) => Some(ring),
+        Err(Errno::ENOSYS | Errno::EPERM) => None,
+        Err(e) => panic!("io_uring_setup failed: {e}"),
+    }
+}
+
+#[test]
+fn test_io_uring_nop() {
+    let Some(ring) = setup(4) else {
+        skip!("io_uring is not available. Skipping test.");
+    };
+    let params = *ring.params();
+    assert_eq!(params.sq_entries(), 4);
+    assert_eq!(params.cq_entries(), 8);
+
+    let sq_ring = ring.map_sq_ring().unwrap();
+    let cq_ring = ring.map_cq_ring().unwrap();
+    let sqes = ring.map_sqes().unwrap();
+
+    let efd = EventFd::new().unwrap();
+    ring.register_eventfd(&efd).unwrap();
+
+    unsafe {
+        let sq = sq_ring.as_ptr().as_ptr().cast::<u8>();
+        let sq_off = params.sq_off();
+        let tail = &*sq.add(sq_off.tail as usize).cast::<AtomicU32>();
+        let mask = *sq.add(sq_off.ring_mask as usize).cast::<u32>();
+        let index = tail.load(Ordering::Relaxed) & mask;
+
+        // IORING_OP_NOP is the zero opcode.
+        let mut sqe = IoUringSqe::default();
+        sqe.user_data = 42;
+        ptr::write(
+            sqes.as_ptr()
+                .as_ptr()
+                .cast::<IoUringSqe>()
+                .add(index as usize),
+            sqe,
+        );
+        *sq.add(sq_off.array as usize)
+            .cast::<u32>()
+            .add(index as usize) = index;
+        tail.fetch_add(1, Ordering::Release);
+    }
+
+    let submitted = ring.enter(1, 1, EnterFlags::IORING_ENTER_GETEVENTS, None);
+    assert_eq!(submitted, Ok(1));
+
+    unsafe {
+        let cq = cq_ring.as_ptr().as_ptr().cast::<u8>();
+        let cq_off = params.cq_off();
+        let head = &*cq.add(cq_off.head as usize).cast::<AtomicU32>();
+        let tail = &*cq.add(cq_off.tail as usize).cast::<AtomicU32>();
+        let mask = *cq.add(cq_off.ring_mask as usize).cast::<u32>();
+        let h = head.load(Ordering::Relaxed);
+        assert_eq!(tail.load(Ordering::Acquire), h + 1);
+
+        let cqe = ptr::read(
+            cq.add(cq_off.cqes as usize)
+                .cast::<IoUringCqe>()
+                .add((h & mask) as usize),
+        );
+        assert_eq!(cqe.user_data, 42);
+        assert_eq!(cqe.res, 0);
+        head.store(h + 1, Ordering::Release);
+    }
+
+    assert_eq!(efd.read_value(), Ok(1));
+    ring.unregister_eventfd().unwrap();
+}
+
+#[test]
+fn test_io_uring_big_entries() {
+    let params = IoUringParams::new().set_flags(
+        SetupFlags::IORING_SETUP_SQE128 | SetupFlags::IORING_SETUP_CQE32,
+    );
+    let ring = match IoUring::setup(4, params) {
+        Ok(ring) => ring,
+        // Linux < 5.19 does not support big entries.
+        Err(Errno::ENOSYS | Errno::EPERM | Errno::EINVAL) => {
+            skip!("io_uring big entries are not available. Skipping test.");
+        }
+        Err(e) => panic!("io_uring_setup failed: {e}"),
+    };
+    let params = *ring.params();
+
+    let sqes = ring.map_sqes().unwrap();
+    assert_eq!(sqes.len(), params.sq_entries() as usize * 128);
+    let cq_ring = ring.map_cq_ring().unwrap();
+    assert_eq!(
+        cq_ring.len(),
+        params.cq_off().cqes as usize + params.cq_entries() as usize * 32
+    );
+}
+
+#[test]
+fn test_io_uring_register_files() {
+    let Some(ring) = setup(4) else {
+        skip!("io_uring is not available. Skipping test.");
+    };
+    let efd = EventFd::new().unwrap();
+    ring.register_files(&[std::os::fd::AsRawFd::as_raw_fd(&efd), -1])
+        .unwrap();
+    assert_eq!(ring.register_files(&[-1]), Err(Errno::EBUSY));
+    ring.unregister_files().unwrap();
+    assert_eq!(ring.unregister_files(), Err(Errno::ENXIO));
+}
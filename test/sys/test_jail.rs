@@ -0,0 +1,45 @@
+use nix::errno::Errno;
+use nix::sys::jail::*;
+use nix::unistd::Uid;
+
+#[test]
+fn test_jail_get_missing() {
+    let mut params = JailParams::new();
+    params.int_param("jid", i32::MAX);
+    let err = jail_get(&mut params, JailFlags::empty()).unwrap_err();
+    assert_eq!(err.error(), Errno::ENOENT);
+}
+
+#[test]
+fn test_jail_set_get_remove() {
+    if !Uid::current().is_root() {
+        skip!(
+            "test_jail_set_get_remove requires root privileges. Skipping test."
+        );
+    }
+
+    let jid = jail_set(
+        JailParams::new()
+            .str_param("name", "nix-test")
+            .str_param("path", "/")
+            .null_param("persist"),
+        JailFlags::JAIL_CREATE,
+    )
+    .unwrap();
+
+    let mut params = JailParams::new();
+    params.str_param("name", "nix-test").buf_param("path", 1024);
+    assert_eq!(jail_get(&mut params, JailFlags::empty()).unwrap(), jid);
+    assert_eq!(params.str_value("path").unwrap().to_bytes(), b"/");
+
+    // Creating the jail again fails.
+    let err = jail_set(
+        JailParams::new().str_param("name", "nix-test"),
+        JailFlags::JAIL_CREATE,
+    )
+    .unwrap_err();
+    assert_eq!(err.error(), Errno::EEXIST);
+
+    jail_remove(jid).unwrap();
+    assert_eq!(jail_remove(jid), Err(Errno::EINVAL));
+}
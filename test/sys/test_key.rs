@@ -0,0 +1,122 @@
+use nix::errno::Errno;
+use nix::sys::key::*;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, ForkResult};
+
+// The keyring of a thread is only reachable from it.
+fn in_thread_keyring<F: FnOnce() + Send + 'static>(f: F) {
+    std::thread::spawn(f).join().unwrap();
+}
+
+#[test]
+fn test_add_key_read_update_revoke() {
+    in_thread_keyring(|| {
+        let thread = KeySerial::KEY_SPEC_THREAD_KEYRING;
+        let key = match add_key("user", "nix:test", b"secret", thread) {
+            Err(Errno::ENOSYS) => return,
+            res => res.unwrap(),
+        };
+        assert!(key.as_raw() > 0);
+        assert_eq!(keyctl_read(key).unwrap(), b"secret");
+
+        keyctl_update(key, b"another secret").unwrap();
+        assert_eq!(keyctl_read(key).unwrap(), b"another secret");
+
+        // Adding a key with the same description updates it.
+        let same = add_key("user", "nix:test", b"yet another", thread).unwrap();
+        assert_eq!(same, key);
+        assert_eq!(keyctl_read(key).unwrap(), b"yet another");
+
+        keyctl_revoke(key).unwrap();
+        assert_eq!(keyctl_read(key), Err(Errno::EKEYREVOKED));
+    });
+}
+
+#[test]
+fn test_keyctl_search_link_unlink() {
+    in_thread_keyring(|| {
+        let thread = KeySerial::KEY_SPEC_THREAD_KEYRING;
+        let keyring = match add_key("keyring", "nix:keyring", b"", thread) {
+            Err(Errno::ENOSYS) => return,
+            res => res.unwrap(),
+        };
+        let key = add_key("user", "nix:linked", b"secret", keyring).unwrap();
+
+        assert_eq!(keyctl_search(keyring, "user", "nix:linked", None), Ok(key));
+        // The search is recursive.
+        assert_eq!(keyctl_search(thread, "user", "nix:linked", None), Ok(key));
+        assert_eq!(request_key("user", "nix:linked", None, None), Ok(key));
+        assert_eq!(
+            keyctl_search(keyring, "user", "nix:missing", None),
+            Err(Errno::ENOKEY)
+        );
+
+        // The payload of a keyring lists its keys.
+        let ids = keyctl_read(keyring).unwrap();
+        assert_eq!(ids, key.as_raw().to_ne_bytes());
+
+        // Keep the key reachable, and thus possessed.
+        keyctl_link(key, thread).unwrap();
+        keyctl_unlink(key, keyring).unwrap();
+        assert!(keyctl_read(keyring).unwrap().is_empty());
+        keyctl_link(key, keyring).unwrap();
+        assert_eq!(keyctl_read(keyring).unwrap(), key.as_raw().to_ne_bytes());
+    });
+}
+
+#[test]
+fn test_keyctl_setperm() {
+    in_thread_keyring(|| {
+        let thread = KeySerial::KEY_SPEC_THREAD_KEYRING;
+        let key = match add_key("user", "nix:perm", b"secret", thread) {
+            Err(Errno::ENOSYS) => return,
+            res => res.unwrap(),
+        };
+        // Without the search permission, the key isn't possessed anymore
+        // and only the permissions of its owner apply.
+        let perm = KeyPerm::KEY_POS_VIEW
+            | KeyPerm::KEY_USR_VIEW
+            | KeyPerm::KEY_USR_SETATTR;
+        keyctl_setperm(key, perm).unwrap();
+        assert_eq!(keyctl_read(key), Err(Errno::EACCES));
+        keyctl_setperm(key, KeyPerm::KEY_POS_ALL).unwrap();
+        assert_eq!(keyctl_read(key).unwrap(), b"secret");
+    });
+}
+
+#[test]
+fn test_keyctl_get_keyring_id() {
+    in_thread_keyring(|| {
+        let thread = KeySerial::KEY_SPEC_THREAD_KEYRING;
+        match keyctl_get_keyring_id(thread, false) {
+            Err(Errno::ENOSYS) => return,
+            res => assert_eq!(res, Err(Errno::ENOKEY)),
+        }
+        let id = keyctl_get_keyring_id(thread, true).unwrap();
+        assert!(id.as_raw() > 0);
+        assert_eq!(keyctl_get_keyring_id(thread, false), Ok(id));
+    });
+}
+
+#[test]
+fn test_keyctl_join_session_keyring() {
+    let _m = crate::FORK_MTX.lock();
+
+    // The session keyring is shared by the whole process.
+    match unsafe { fork() }.expect("fork failed") {
+        ForkResult::Child => {
+            let session = KeySerial::KEY_SPEC_SESSION_KEYRING;
+            let code = match keyctl_join_session_keyring(None) {
+                Ok(new) if keyctl_get_keyring_id(session, false) == Ok(new) => {
+                    0
+                }
+                Err(Errno::ENOSYS) => 0,
+                _ => 1,
+            };
+            unsafe { libc::_exit(code) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
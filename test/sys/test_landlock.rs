@@ -0,0 +1,121 @@
+use nix::errno::Errno;
+use nix::fcntl::{open, OFlag};
+use nix::sys::landlock::*;
+use nix::sys::prctl::set_no_new_privs;
+use nix::sys::stat::Mode;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, ForkResult};
+use std::ffi::{CStr, CString};
+use std::fs::{self, File};
+use std::os::fd::AsFd;
+use std::os::unix::ffi::OsStringExt;
+
+/// Get the Landlock ABI version, or `None` if Landlock is not available.
+fn abi_version() -> Option<u32> {
+    match landlock_abi_version() {
+        Err(Errno::ENOSYS | Errno::EOPNOTSUPP) => None,
+        r => Some(r.unwrap()),
+    }
+}
+
+#[test]
+fn test_landlock_abi_version() {
+    let Some(abi) = abi_version() else {
+        skip!("Landlock is not available. Skipping test.");
+    };
+    assert!(abi >= 1);
+}
+
+#[test]
+fn test_landlock_invalid_rules() {
+    if abi_version().is_none() {
+        skip!("Landlock is not available. Skipping test.");
+    }
+    let ruleset = landlock_create_ruleset(
+        AccessFs::LANDLOCK_ACCESS_FS_READ_FILE,
+        AccessNet::empty(),
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let dir = File::open(dir.path()).unwrap();
+
+    // Accesses that are not handled by the ruleset can't be allowed.
+    assert_eq!(
+        landlock_add_rule(
+            &ruleset,
+            &Rule::PathBeneath {
+                allowed_access: AccessFs::LANDLOCK_ACCESS_FS_WRITE_FILE,
+                parent_fd: dir.as_fd(),
+            }
+        ),
+        Err(Errno::EINVAL)
+    );
+    // An empty ruleset is rejected.
+    assert_eq!(
+        landlock_create_ruleset(AccessFs::empty(), AccessNet::empty())
+            .unwrap_err(),
+        Errno::ENOMSG
+    );
+}
+
+#[test]
+fn test_landlock_restrict_self() {
+    if abi_version().is_none() {
+        skip!("Landlock is not available. Skipping test.");
+    }
+    let allowed = tempfile::tempdir().unwrap();
+    let denied = tempfile::tempdir().unwrap();
+    fs::write(allowed.path().join("file"), b"allowed").unwrap();
+    fs::write(denied.path().join("file"), b"denied").unwrap();
+
+    let ruleset = landlock_create_ruleset(
+        AccessFs::LANDLOCK_ACCESS_FS_READ_FILE
+            | AccessFs::LANDLOCK_ACCESS_FS_WRITE_FILE,
+        AccessNet::empty(),
+    )
+    .unwrap();
+    let parent = File::open(allowed.path()).unwrap();
+    landlock_add_rule(
+        &ruleset,
+        &Rule::PathBeneath {
+            allowed_access: AccessFs::LANDLOCK_ACCESS_FS_READ_FILE,
+            parent_fd: parent.as_fd(),
+        },
+    )
+    .unwrap();
+
+    // The paths are prepared before forking, so that the child doesn't
+    // allocate.
+    let path = |dir: &tempfile::TempDir| {
+        CString::new(dir.path().join("file").into_os_string().into_vec())
+            .unwrap()
+    };
+    let allowed_file = path(&allowed);
+    let denied_file = path(&denied);
+    let can_open =
+        |path: &CStr, flags: OFlag| open(path, flags, Mode::empty()).is_ok();
+
+    let _m = crate::FORK_MTX.lock();
+    // Safe: the child only makes system calls before exiting.
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            let code = if set_no_new_privs().is_err()
+                || landlock_restrict_self(&ruleset).is_err()
+            {
+                1
+            } else if !can_open(&allowed_file, OFlag::O_RDONLY) {
+                2
+            } else if can_open(&allowed_file, OFlag::O_WRONLY) {
+                3
+            } else if can_open(&denied_file, OFlag::O_RDONLY) {
+                4
+            } else {
+                0
+            };
+            unsafe { libc::_exit(code) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::fd::AsFd;
+
+use nix::errno::Errno;
+use nix::sys::loopdev::*;
+
+// Open a free loop device, and a 1MiB backing file.
+fn loop_device() -> Option<(File, File)> {
+    let ctl = File::open("/dev/loop-control").ok()?;
+    let n = loop_ctl_get_free(&ctl).unwrap();
+    let dev = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!("/dev/loop{n}"))
+        .ok()?;
+    let mut backing = tempfile::tempfile().unwrap();
+    backing.write_all(&[0; 1 << 20]).unwrap();
+    Some((dev, backing))
+}
+
+#[test]
+fn test_loop_configure() {
+    require_capability!("test_loop_configure", CAP_SYS_ADMIN);
+    let _m = crate::LOOP_MTX.lock();
+    let Some((dev, backing)) = loop_device() else {
+        skip!("test_loop_configure requires loop devices. Skipping test.");
+    };
+
+    let info = LoopInfo64::new()
+        .set_offset(4096)
+        .set_flags(LoopFlags::LO_FLAGS_READ_ONLY)
+        .set_file_name("nix");
+    let config = LoopConfig::new(backing.as_fd()).info(info);
+    match loop_configure(&dev, &config) {
+        Err(Errno::EINVAL | Errno::ENOTTY) => {
+            skip!("test_loop_configure requires LOOP_CONFIGURE. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+
+    let status = loop_get_status64(&dev).unwrap();
+    assert_eq!(status.offset(), 4096);
+    assert!(status.flags().contains(LoopFlags::LO_FLAGS_READ_ONLY));
+    assert_eq!(status.file_name(), "nix");
+    // The loop device is busy.
+    assert_eq!(loop_configure(&dev, &config), Err(Errno::EBUSY));
+
+    loop_clr_fd(&dev).unwrap();
+}
+
+#[test]
+fn test_loop_set_fd_clr_fd() {
+    require_capability!("test_loop_set_fd_clr_fd", CAP_SYS_ADMIN);
+    let _m = crate::LOOP_MTX.lock();
+    let Some((dev, backing)) = loop_device() else {
+        skip!("test_loop_set_fd_clr_fd requires loop devices. Skipping test.");
+    };
+    assert_eq!(loop_get_status64(&dev).unwrap_err(), Errno::ENXIO);
+
+    loop_set_fd(&dev, &backing).unwrap();
+    loop_get_status64(&dev).unwrap();
+    // The backing file may not support direct I/O.
+    match loop_set_direct_io(&dev, true) {
+        Ok(()) => {
+            let flags = loop_get_status64(&dev).unwrap().flags();
+            assert!(flags.contains(LoopFlags::LO_FLAGS_DIRECT_IO));
+            loop_set_direct_io(&dev, false).unwrap();
+        }
+        Err(e) => assert_eq!(e, Errno::EINVAL),
+    }
+
+    loop_clr_fd(&dev).unwrap();
+}
@@ -19,3 +19,29 @@ fn test_memfd_create() {
 
     assert_eq!(contents, buf.as_slice());
 }
+
+#[test]
+fn test_sealed_memfd() {
+    use nix::errno::Errno;
+    use nix::fcntl::{fcntl, FcntlArg, SealFlag};
+    use nix::sys::memfd::SealedMemFd;
+    use nix::unistd::{read, write};
+
+    let mut memfd = SealedMemFd::create("test_sealed_memfd", 10).unwrap();
+    assert_eq!(memfd.len(), 10);
+    memfd.write_all_at(b"hello", 0).unwrap();
+    memfd
+        .fill_with(|buf| buf[5..].copy_from_slice(b"world"))
+        .unwrap();
+    assert_eq!(memfd.write_all_at(b"!", 10), Err(Errno::EFBIG));
+    let fd = memfd.seal_all().unwrap();
+
+    let seals = fcntl(&fd, FcntlArg::F_GET_SEALS).unwrap();
+    assert!(SealFlag::from_bits_truncate(seals).contains(
+        SealFlag::F_SEAL_WRITE | SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SEAL
+    ));
+    let mut buf = [0u8; 16];
+    assert_eq!(read(&fd, &mut buf).unwrap(), 10);
+    assert_eq!(&buf[..10], b"helloworld");
+    assert_eq!(write(&fd, b"x"), Err(Errno::EPERM));
+}
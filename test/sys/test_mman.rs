@@ -119,3 +119,398 @@ fn test_mremap_shrink() {
     // The first KB should still be accessible and have the old data in it.
     assert_eq!(slice[ONE_K - 1], 0xFF);
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mremap_fixed() {
+    use nix::sys::mman::{mremap, munmap, MRemapFlags};
+    use nix::unistd::{sysconf, SysconfVar};
+    use std::ptr::NonNull;
+
+    let page = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(2 * page).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        *mem.cast::<u8>().as_ptr() = 0xFF;
+
+        // Move the first page onto the second one.
+        let target = NonNull::new(mem.as_ptr().cast::<u8>().add(page))
+            .unwrap()
+            .cast();
+        let moved = mremap(
+            mem,
+            page,
+            page,
+            MRemapFlags::MREMAP_MAYMOVE | MRemapFlags::MREMAP_FIXED,
+            Some(target),
+        )
+        .unwrap();
+        assert_eq!(moved, target);
+        assert_eq!(*moved.cast::<u8>().as_ptr(), 0xFF);
+        munmap(moved, page).unwrap();
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mremap_dontunmap() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{mremap, munmap, MRemapFlags};
+    use nix::unistd::{sysconf, SysconfVar};
+
+    let page = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(page).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        *mem.cast::<u8>().as_ptr() = 0xFF;
+
+        let moved = match mremap(
+            mem,
+            page,
+            page,
+            MRemapFlags::MREMAP_MAYMOVE | MRemapFlags::MREMAP_DONTUNMAP,
+            None,
+        ) {
+            // Before Linux 5.7
+            Err(Errno::EINVAL) => {
+                munmap(mem, page).unwrap();
+                return;
+            }
+            res => res.unwrap(),
+        };
+        assert_ne!(moved, mem);
+        assert_eq!(*moved.cast::<u8>().as_ptr(), 0xFF);
+        // The old range is still mapped, but its content is gone.
+        assert_eq!(*mem.cast::<u8>().as_ptr(), 0x00);
+        munmap(moved, page).unwrap();
+        munmap(mem, page).unwrap();
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cachestat() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{cachestat, CachestatRange};
+    use std::io::Write;
+
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(&[0xa5; 8192]).unwrap();
+
+    let cstat = match cachestat(&file, CachestatRange::default()) {
+        Err(Errno::ENOSYS) => return,
+        res => res.unwrap(),
+    };
+    // The data just written is in the page cache, possibly still dirty.
+    assert!(cstat.nr_cache >= 1);
+    assert!(cstat.nr_dirty <= cstat.nr_cache);
+}
+
+#[test]
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+))]
+fn test_pkey_mprotect() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{
+        munmap, pkey_alloc, pkey_free, pkey_mprotect, PkeyAccessRights,
+    };
+
+    let pkey = match pkey_alloc(PkeyAccessRights::empty()) {
+        // No hardware or kernel support
+        Err(Errno::ENOSPC | Errno::EINVAL | Errno::ENOSYS) => return,
+        res => res.unwrap(),
+    };
+    assert!(pkey.as_raw() > 0);
+
+    let len = 4096;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_NONE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        pkey_mprotect(
+            mem,
+            len,
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            &pkey,
+        )
+        .unwrap();
+        // The calling thread has full access rights for the key.
+        let slice: &mut [u8] =
+            std::slice::from_raw_parts_mut(mem.as_ptr().cast(), len);
+        slice[0] = 0xff;
+        assert_eq!(slice[0], 0xff);
+        munmap(mem, len).unwrap();
+    }
+    pkey_free(pkey).unwrap();
+}
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    all(target_os = "linux", any(target_env = "gnu", target_env = "musl"))
+))]
+fn test_mlock2_onfault() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{mlock2, munlock, munmap, MlockFlags};
+
+    let len = 16 * 4096;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        match mlock2(mem, len, MlockFlags::MLOCK_ONFAULT) {
+            // RLIMIT_MEMLOCK too small
+            Err(Errno::ENOMEM | Errno::EPERM) => (),
+            res => {
+                res.unwrap();
+                *mem.cast::<u8>().as_ptr() = 0xFF;
+                munlock(mem, len).unwrap();
+            }
+        }
+        munmap(mem, len).unwrap();
+    }
+}
+
+#[test]
+#[cfg(any(linux_android, freebsdlike, apple_targets, target_os = "netbsd"))]
+fn test_mincore() {
+    use nix::sys::mman::{mincore, munmap};
+    use nix::unistd::{sysconf, SysconfVar};
+
+    let page = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let len = 4 * page;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        *mem.cast::<u8>().as_ptr() = 0xFF;
+
+        let residency = mincore(mem, len).unwrap();
+        assert_eq!(residency.len(), len / page);
+        // The first page was just written to.
+        assert!(residency[0]);
+        // The number of pages of the range can't even be computed.
+        assert_eq!(mincore(mem, usize::MAX), Err(nix::errno::Errno::EINVAL));
+        munmap(mem, len).unwrap();
+    }
+}
+
+#[test]
+#[cfg(linux_android)]
+fn test_madvise_recent_advice() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{madvise, munmap, MmapAdvise};
+
+    let len = 4 * 4096;
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        for advice in [
+            MmapAdvise::MADV_FREE,
+            MmapAdvise::MADV_WIPEONFORK,
+            MmapAdvise::MADV_KEEPONFORK,
+            MmapAdvise::MADV_COLD,
+            MmapAdvise::MADV_PAGEOUT,
+            MmapAdvise::MADV_POPULATE_WRITE,
+        ] {
+            match madvise(mem, len, advice) {
+                // Not supported by this kernel
+                Err(Errno::EINVAL) => (),
+                res => res.unwrap(),
+            }
+        }
+        munmap(mem, len).unwrap();
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mmap_huge_page_size() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{munmap, HugePageSize};
+
+    let size = HugePageSize::Mb2;
+    assert_eq!(size.bytes(), 2 * 1024 * 1024);
+    let len = size.bytes() as usize;
+    unsafe {
+        match mmap_anonymous(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE | MapFlags::huge_page_size(size),
+        ) {
+            // No huge pages of that size are configured
+            Err(Errno::ENOMEM | Errno::EINVAL) => (),
+            res => munmap(res.unwrap(), len).unwrap(),
+        }
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mmap_fixed_noreplace() {
+    use nix::errno::Errno;
+    use nix::sys::mman::munmap;
+
+    let len = NonZeroUsize::new(4096).unwrap();
+    unsafe {
+        let mem = mmap_anonymous(
+            None,
+            len,
+            ProtFlags::PROT_READ,
+            MapFlags::MAP_PRIVATE,
+        )
+        .unwrap();
+        let addr = NonZeroUsize::new(mem.as_ptr() as usize);
+
+        // The range is already mapped.
+        let res = mmap_anonymous(
+            addr,
+            len,
+            ProtFlags::PROT_READ,
+            MapFlags::MAP_PRIVATE | MapFlags::MAP_FIXED_NOREPLACE,
+        );
+        match res {
+            Err(e) => assert_eq!(e, Errno::EEXIST),
+            // Before Linux 4.17, the flag is ignored.
+            Ok(other) => {
+                assert_ne!(other, mem);
+                munmap(other, len.get()).unwrap();
+            }
+        }
+
+        munmap(mem, len.get()).unwrap();
+    }
+}
+
+#[test]
+fn test_mmap_region() {
+    use nix::sys::mman::{MmapRegion, MsFlags};
+    use std::io::Write;
+
+    let region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE | MapFlags::MAP_FIXED,
+    );
+    assert!(region.is_err());
+
+    // The mapping must stay readable.
+    let region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    );
+    assert_eq!(region.err(), Some(nix::errno::Errno::EINVAL));
+
+    let mut region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    assert_eq!(region.len(), 4096);
+    assert!(region.iter().all(|&b| b == 0));
+    region[..5].copy_from_slice(b"hello");
+    region.mprotect(ProtFlags::PROT_READ).unwrap();
+    assert_eq!(&region[..5], b"hello");
+    assert_eq!(
+        region.mprotect(ProtFlags::PROT_NONE),
+        Err(nix::errno::Errno::EINVAL)
+    );
+    assert_eq!(region.prot(), ProtFlags::PROT_READ);
+
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(b"world").unwrap();
+    let region = unsafe {
+        MmapRegion::map(
+            None,
+            NonZeroUsize::new(5).unwrap(),
+            ProtFlags::PROT_READ,
+            MapFlags::MAP_SHARED,
+            &file,
+            0,
+        )
+    }
+    .unwrap();
+    assert_eq!(&region[..], b"world");
+    region.msync(MsFlags::MS_SYNC).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "the mapping is not writable")]
+fn test_mmap_region_not_writable() {
+    use nix::sys::mman::MmapRegion;
+
+    let mut region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    region[0] = 1;
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn test_shared_mem() {
+    use nix::errno::Errno;
+    use nix::sys::mman::SharedMem;
+
+    let name = format!("/nix-test-shared-mem-{}", std::process::id());
+    let len = NonZeroUsize::new(4096).unwrap();
+    let mut shm = SharedMem::create(
+        name.as_str(),
+        len,
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+    )
+    .unwrap();
+    assert_eq!(shm.name().to_bytes(), name.as_bytes());
+    assert_eq!(
+        SharedMem::create(name.as_str(), len, ProtFlags::PROT_READ).err(),
+        Some(Errno::EEXIST)
+    );
+    let region = unsafe { shm.region_mut() };
+    region[..5].copy_from_slice(b"hello");
+
+    let other = SharedMem::open(name.as_str(), ProtFlags::PROT_READ).unwrap();
+    let region = unsafe { other.region() };
+    assert_eq!(region.len(), 4096);
+    assert_eq!(&region[..5], b"hello");
+    drop(other);
+
+    drop(shm);
+    assert_eq!(
+        SharedMem::open(name.as_str(), ProtFlags::PROT_READ).err(),
+        Some(Errno::ENOENT)
+    );
+}
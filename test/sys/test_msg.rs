@@ -0,0 +1,51 @@
+use nix::errno::Errno;
+use nix::sys::ipc::{IpcGetFlags, IPC_PRIVATE};
+use nix::sys::msg::*;
+use nix::sys::stat::Mode;
+use nix::unistd::getpid;
+
+#[test]
+fn test_msg() {
+    let id =
+        msgget(IPC_PRIVATE, IpcGetFlags::IPC_CREAT, Mode::S_IRWXU).unwrap();
+
+    msgsnd(id, &MsgBuf::new(1, b"first"), MsgFlags::empty()).unwrap();
+    msgsnd(id, &MsgBuf::new(2, b"second message"), MsgFlags::empty()).unwrap();
+
+    let stat = msgctl_stat(id).unwrap();
+    assert_eq!(stat.qnum(), 2);
+    assert_eq!(stat.lspid(), Some(getpid()));
+    assert_eq!(stat.lrpid(), None);
+    assert_eq!(stat.perm().mode(), Mode::S_IRWXU);
+
+    // Select by type.
+    let mut msg = MsgBuf::with_capacity(5);
+    assert_eq!(
+        msgrcv(id, &mut msg, 2, MsgFlags::IPC_NOWAIT),
+        Err(Errno::E2BIG)
+    );
+    assert_eq!(
+        msgrcv(
+            id,
+            &mut msg,
+            1,
+            MsgFlags::MSG_EXCEPT | MsgFlags::MSG_NOERROR
+        ),
+        Ok(msg.capacity().min(14))
+    );
+    assert_eq!(msg.mtype(), 2);
+    assert_eq!(msg.payload(), &b"second message"[..msg.payload().len()]);
+
+    assert_eq!(msgrcv(id, &mut msg, 0, MsgFlags::empty()), Ok(5));
+    assert_eq!(msg.mtype(), 1);
+    assert_eq!(msg.payload(), b"first");
+
+    assert_eq!(
+        msgrcv(id, &mut msg, 0, MsgFlags::IPC_NOWAIT),
+        Err(Errno::ENOMSG)
+    );
+    assert_eq!(msgctl_stat(id).unwrap().lrpid(), Some(getpid()));
+
+    msgctl_rmid(id).unwrap();
+    assert_eq!(msgctl_stat(id).map(drop), Err(Errno::EINVAL));
+}
@@ -0,0 +1,96 @@
+use nix::errno::Errno;
+use nix::sys::perf_event::*;
+use nix::unistd::Pid;
+use std::os::fd::{AsFd, OwnedFd};
+
+// Open a disabled counter of the CPU time of the calling thread, unless
+// perf events are not allowed.
+fn open_task_clock(
+    read_format: ReadFormat,
+    group: Option<&OwnedFd>,
+) -> Option<OwnedFd> {
+    let attr = PerfEventAttr::software(PerfSwId::PERF_COUNT_SW_TASK_CLOCK)
+        .read_format(read_format)
+        .flags(
+            PerfEventFlags::DISABLED
+                | PerfEventFlags::EXCLUDE_KERNEL
+                | PerfEventFlags::EXCLUDE_HV,
+        );
+    match perf_event_open(
+        &attr,
+        Some(Pid::from_raw(0)),
+        None,
+        group.map(AsFd::as_fd),
+        PerfFlags::PERF_FLAG_FD_CLOEXEC,
+    ) {
+        Err(Errno::EACCES | Errno::ENOENT | Errno::ENOSYS) => None,
+        res => Some(res.unwrap()),
+    }
+}
+
+fn busy_loop() {
+    let mut x = 0u64;
+    for i in 0..1_000_000 {
+        x = std::hint::black_box(x.wrapping_add(i));
+    }
+}
+
+#[test]
+fn test_perf_event_attr() {
+    let attr = PerfEventAttr::hardware(PerfHwId::PERF_COUNT_HW_INSTRUCTIONS)
+        .sample_freq(1000)
+        .flags(PerfEventFlags::DISABLED | PerfEventFlags::REMOVE_ON_EXEC)
+        .read_format(ReadFormat::PERF_FORMAT_ID);
+    assert_eq!(attr.type_(), PerfType::PERF_TYPE_HARDWARE);
+    assert_eq!(attr.config(), PerfHwId::PERF_COUNT_HW_INSTRUCTIONS as u64);
+    assert_eq!(
+        attr.get_flags(),
+        PerfEventFlags::DISABLED | PerfEventFlags::REMOVE_ON_EXEC
+    );
+    assert_eq!(attr.get_read_format(), ReadFormat::PERF_FORMAT_ID);
+}
+
+#[test]
+fn test_perf_event_enable_disable_reset() {
+    let format = ReadFormat::PERF_FORMAT_TOTAL_TIME_ENABLED
+        | ReadFormat::PERF_FORMAT_TOTAL_TIME_RUNNING;
+    let Some(fd) = open_task_clock(format, None) else {
+        skip!("perf events are not allowed. Skipping test.");
+    };
+
+    let count = perf_event_read(&fd, format).unwrap();
+    assert_eq!(count.values.len(), 1);
+    assert_eq!(count.values[0].value, 0);
+    assert_eq!(count.time_enabled, Some(0));
+
+    perf_event_enable(&fd, false).unwrap();
+    busy_loop();
+    perf_event_disable(&fd, false).unwrap();
+    let count = perf_event_read(&fd, format).unwrap();
+    assert!(count.values[0].value > 0);
+    assert!(count.time_enabled.unwrap() > 0);
+    assert!(count.time_running.unwrap() > 0);
+    assert_eq!(count.values[0].id, None);
+
+    perf_event_reset(&fd, false).unwrap();
+    assert_eq!(perf_event_read(&fd, format).unwrap().values[0].value, 0);
+}
+
+#[test]
+fn test_perf_event_read_group() {
+    let format = ReadFormat::PERF_FORMAT_GROUP | ReadFormat::PERF_FORMAT_ID;
+    let Some(leader) = open_task_clock(format, None) else {
+        skip!("perf events are not allowed. Skipping test.");
+    };
+    let _member = open_task_clock(format, Some(&leader)).unwrap();
+
+    perf_event_enable(&leader, true).unwrap();
+    busy_loop();
+    perf_event_disable(&leader, true).unwrap();
+
+    let count = perf_event_read(&leader, format).unwrap();
+    assert_eq!(count.time_enabled, None);
+    assert_eq!(count.values.len(), 2);
+    assert!(count.values.iter().all(|v| v.value > 0));
+    assert_ne!(count.values[0].id, count.values[1].id);
+}
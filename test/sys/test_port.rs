@@ -0,0 +1,63 @@
+use nix::poll::PollFlags;
+use nix::sys::port::{Port, PortEvent, PortSource, Timeout};
+use nix::unistd::{pipe, write};
+use std::os::fd::AsRawFd;
+
+#[test]
+fn test_port_user() {
+    let port = Port::new().unwrap();
+    port.send(3, 42).unwrap();
+    port.send(4, 43).unwrap();
+
+    let mut events = [PortEvent::default(); 4];
+    assert_eq!(port.get(&mut events, 2, Timeout::Infinite), Ok(2));
+    assert_eq!(events[0].source(), Ok(PortSource::PORT_SOURCE_USER));
+    assert_eq!(events[0].events(), 3);
+    assert_eq!(events[0].user(), 42);
+    assert_eq!(events[1].user(), 43);
+
+    // Nothing is left.
+    assert_eq!(port.get(&mut events, 1, Timeout::ZeroPoll), Ok(0));
+}
+
+#[test]
+fn test_port_fd() {
+    let port = Port::new().unwrap();
+    let (r, w) = pipe().unwrap();
+    port.associate_fd(&r, PollFlags::POLLIN, 7).unwrap();
+
+    let mut events = [PortEvent::default(); 1];
+    assert_eq!(port.get(&mut events, 1, Timeout::ZeroPoll), Ok(0));
+
+    write(&w, b"x").unwrap();
+    assert_eq!(port.get(&mut events, 1, Timeout::Infinite), Ok(1));
+    assert_eq!(events[0].source(), Ok(PortSource::PORT_SOURCE_FD));
+    assert_eq!(events[0].object(), r.as_raw_fd() as usize);
+    assert!(events[0].poll_events().contains(PollFlags::POLLIN));
+    assert_eq!(events[0].user(), 7);
+
+    // The association is one-shot.
+    assert_eq!(port.get(&mut events, 1, Timeout::ZeroPoll), Ok(0));
+}
+
+#[test]
+#[cfg(all(feature = "signal", feature = "time"))]
+fn test_port_timer() {
+    use nix::sys::timer::{Expiration, TimerSetTimeFlags};
+    use nix::time::ClockId;
+    use std::time::Duration;
+
+    let port = Port::new().unwrap();
+    let mut timer = port.create_timer(ClockId::CLOCK_MONOTONIC, 9).unwrap();
+    timer
+        .set(
+            Expiration::OneShot(Duration::from_millis(10).into()),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+
+    let mut events = [PortEvent::default(); 1];
+    assert_eq!(port.get(&mut events, 1, Timeout::Infinite), Ok(1));
+    assert_eq!(events[0].source(), Ok(PortSource::PORT_SOURCE_TIMER));
+    assert_eq!(events[0].user(), 9);
+}
@@ -156,4 +156,87 @@ mod test_prctl {
         .unwrap_or_default();
         prctl::set_vma_anon_name(ptr, sz, None).unwrap_or_default();
     }
+
+    #[test]
+    fn test_get_set_securebits() {
+        use prctl::SecureBits;
+
+        let original = prctl::get_securebits().unwrap();
+        if !caps::has_cap(
+            None,
+            caps::CapSet::Effective,
+            caps::Capability::CAP_SETPCAP,
+        )
+        .unwrap()
+        {
+            return;
+        }
+
+        // The securebits only apply to the calling thread.
+        std::thread::spawn(move || {
+            prctl::set_securebits(original | SecureBits::SECBIT_KEEP_CAPS)
+                .unwrap();
+            let bits = prctl::get_securebits().unwrap();
+            assert!(bits.contains(SecureBits::SECBIT_KEEP_CAPS));
+            assert!(prctl::get_keepcaps().unwrap());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(feature = "caps")]
+    #[test]
+    fn test_cap_ambient() {
+        use nix::sys::caps::{self, Capability};
+
+        // The ambient capabilities only apply to the calling thread.
+        std::thread::spawn(|| {
+            let cap = Capability::CAP_NET_BIND_SERVICE;
+            prctl::cap_ambient_clear_all().unwrap();
+            assert!(!prctl::cap_ambient_is_set(cap).unwrap());
+
+            let mut caps = caps::capget(None).unwrap();
+            if !caps.permitted.has(cap) {
+                return;
+            }
+            caps.inheritable.insert(cap.into());
+            caps::capset(&caps).unwrap();
+
+            prctl::cap_ambient_raise(cap).unwrap();
+            assert!(prctl::cap_ambient_is_set(cap).unwrap());
+            prctl::cap_ambient_lower(cap).unwrap();
+            assert!(!prctl::cap_ambient_is_set(cap).unwrap());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_set_speculation_ctrl() {
+        use nix::errno::Errno;
+        use prctl::PrctlSpeculationCtrl as Ctrl;
+        use prctl::PrctlSpeculationFeature::PR_SPEC_STORE_BYPASS;
+
+        let ctrl = match prctl::get_speculation_ctrl(PR_SPEC_STORE_BYPASS) {
+            Err(Errno::ENODEV | Errno::EINVAL) => return,
+            res => res.unwrap(),
+        };
+        if !ctrl.contains(Ctrl::PR_SPEC_PRCTL) {
+            return;
+        }
+
+        // The mitigation only applies to the calling thread.
+        std::thread::spawn(|| {
+            prctl::set_speculation_ctrl(
+                PR_SPEC_STORE_BYPASS,
+                prctl::PrctlSpeculationMode::PR_SPEC_DISABLE,
+            )
+            .unwrap();
+            let ctrl =
+                prctl::get_speculation_ctrl(PR_SPEC_STORE_BYPASS).unwrap();
+            assert!(ctrl.contains(Ctrl::PR_SPEC_DISABLE));
+        })
+        .join()
+        .unwrap();
+    }
 }
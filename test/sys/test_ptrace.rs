@@ -176,6 +176,382 @@ fn test_ptrace_interrupt() {
     }
 }
 
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6"
+    ))
+))]
+#[test]
+fn test_ptrace_listen() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    require_capability!("test_ptrace_listen", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => loop {
+            sleep(Duration::from_millis(1000));
+        },
+        Parent { child } => {
+            ptrace::seize(child, ptrace::Options::empty()).unwrap();
+
+            // The SIGSTOP is first reported as a signal-delivery-stop, and
+            // then as a group-stop once injected.
+            kill(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            ptrace::cont(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::PtraceEvent(
+                    child,
+                    Signal::SIGSTOP,
+                    ptrace::Event::PTRACE_EVENT_STOP as i32
+                ))
+            );
+
+            // The tracee stays stopped until it is resumed by a SIGCONT.
+            ptrace::listen(child).unwrap();
+            kill(child, Signal::SIGCONT).unwrap();
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::PtraceEvent(
+                    child,
+                    Signal::SIGTRAP,
+                    ptrace::Event::PTRACE_EVENT_STOP as i32
+                ))
+            );
+
+            kill(child, Signal::SIGKILL).unwrap();
+            while !matches!(
+                waitpid(child, None),
+                Ok(WaitStatus::Signaled(_, Signal::SIGKILL, _)) | Err(_)
+            ) {}
+        }
+    }
+}
+
+#[cfg(linux_android)]
+#[test]
+fn test_ptrace_read_write_bytes() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_read_write_bytes", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    // The child has the same buffer, at the same address.
+    let mut data = *b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let addr = data.as_mut_ptr();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            let expected = b"0123XYZ789abcdefghijklmnopqrstUVWxyz";
+            let data =
+                unsafe { std::ptr::read_volatile(addr as *const [u8; 36]) };
+            unsafe { libc::_exit((&data != expected) as i32) };
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+
+            // Unaligned reads and writes, within a word and across words.
+            let mut buf = [0; 27];
+            ptrace::read_bytes(child, addr.wrapping_add(3).cast(), &mut buf)
+                .unwrap();
+            assert_eq!(&buf, &data[3..30]);
+            ptrace::read_bytes(child, addr.cast(), &mut []).unwrap();
+
+            ptrace::write_bytes(child, addr.wrapping_add(4).cast(), b"XYZ")
+                .unwrap();
+            ptrace::write_bytes(child, addr.wrapping_add(30).cast(), b"UVW")
+                .unwrap();
+            let mut buf = [0; 36];
+            ptrace::read_bytes(child, addr.cast(), &mut buf).unwrap();
+            assert_eq!(&buf, b"0123XYZ789abcdefghijklmnopqrstUVWxyz");
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "seccomp"))]
+#[test]
+fn test_ptrace_seccomp_get_filter() {
+    use libc::{sock_filter, BPF_K, BPF_RET, BPF_STMT};
+    use nix::sys::prctl::set_no_new_privs;
+    use nix::sys::ptrace;
+    use nix::sys::seccomp::{seccomp_set_mode_filter, FilterFlags, SockFprog};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_seccomp_get_filter", CAP_SYS_ADMIN);
+
+    let _m = crate::FORK_MTX.lock();
+
+    let filter: [sock_filter; 1] = [unsafe {
+        BPF_STMT((BPF_RET | BPF_K) as u16, libc::SECCOMP_RET_ALLOW)
+    }];
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            set_no_new_privs().unwrap();
+            let prog = SockFprog::new(&filter).unwrap();
+            seccomp_set_mode_filter(FilterFlags::empty(), &prog).unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { libc::_exit(0) };
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            match ptrace::seccomp_get_filter(child, 0) {
+                // Without CONFIG_CHECKPOINT_RESTORE
+                Err(Errno::EINVAL) => (),
+                res => {
+                    assert_eq!(res.unwrap(), filter);
+                    assert_eq!(
+                        ptrace::seccomp_get_filter(child, 1),
+                        Err(Errno::ENOENT)
+                    );
+                }
+            }
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "x86_64"))]
+#[test]
+fn test_ptrace_get_syscall_user_dispatch_config() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    const PR_SET_SYSCALL_USER_DISPATCH: libc::c_int = 59;
+    const PR_SYS_DISPATCH_ON: libc::c_ulong = 1;
+
+    require_capability!(
+        "test_ptrace_get_syscall_user_dispatch_config",
+        CAP_SYS_PTRACE
+    );
+
+    let _m = crate::FORK_MTX.lock();
+
+    // SYSCALL_DISPATCH_FILTER_ALLOW, so that no system call is dispatched.
+    let selector = 0u8;
+    let selector_addr = &selector as *const u8 as u64;
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            let res = unsafe {
+                libc::prctl(
+                    PR_SET_SYSCALL_USER_DISPATCH,
+                    PR_SYS_DISPATCH_ON,
+                    0 as libc::c_ulong,
+                    0 as libc::c_ulong,
+                    selector_addr,
+                )
+            };
+            if res == 0 {
+                raise(Signal::SIGSTOP).unwrap();
+            }
+            unsafe { libc::_exit(0) };
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            match ptrace::get_syscall_user_dispatch_config(child) {
+                // Only since Linux 6.4
+                Err(Errno::EIO) => (),
+                res => assert_eq!(res.unwrap().mode(), 0),
+            }
+
+            ptrace::cont(child, None).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Stopped(_, Signal::SIGSTOP)) => {
+                    let config =
+                        ptrace::get_syscall_user_dispatch_config(child)
+                            .unwrap();
+                    assert_eq!(config.mode(), PR_SYS_DISPATCH_ON);
+                    assert_eq!(config.selector(), selector_addr);
+                    assert_eq!(config.offset(), 0);
+                    assert_eq!(config.length(), 0);
+                    ptrace::cont(child, None).unwrap();
+                    assert_eq!(
+                        waitpid(child, None),
+                        Ok(WaitStatus::Exited(child, 0))
+                    );
+                }
+                // Without CONFIG_GENERIC_ENTRY
+                status => assert_eq!(status, Ok(WaitStatus::Exited(child, 0))),
+            }
+        }
+    }
+}
+
+#[cfg(linux_android)]
+#[test]
+fn test_ptrace_read_cstring_struct() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ffi::CString;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Point {
+        x: u32,
+        y: u64,
+    }
+
+    require_capability!("test_ptrace_read_cstring_struct", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    // The child has the same data, at the same addresses.
+    let string = CString::new("x".repeat(5000)).unwrap();
+    let point = Point { x: 1, y: 2 };
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { libc::_exit(0) };
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+
+            let addr = string.as_ptr() as ptrace::AddressType;
+            assert_eq!(
+                ptrace::read_cstring(child, addr, 6000),
+                Ok(string.clone())
+            );
+            assert_eq!(
+                ptrace::read_cstring(child, addr, 5000),
+                Err(Errno::ERANGE)
+            );
+            let addr = addr.wrapping_add(4990);
+            assert_eq!(
+                ptrace::read_cstring(child, addr, 11).unwrap().as_bytes(),
+                b"xxxxxxxxxx".as_slice()
+            );
+
+            let addr = &point as *const Point as ptrace::AddressType;
+            assert_eq!(
+                unsafe { ptrace::read_struct::<Point>(child, addr) },
+                Ok(point)
+            );
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    target_arch = "x86_64"
+))]
+#[test]
+fn test_ptrace_sysemu() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_sysemu", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            let pid = unsafe { libc::getpid() };
+            unsafe {
+                libc::kill(pid, libc::SIGSTOP);
+                // Emulated by the tracer, and thus never executed.
+                libc::syscall(libc::SYS_kill, pid, libc::SIGKILL);
+                libc::_exit(0);
+            }
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            ptrace::setoptions(child, ptrace::Options::PTRACE_O_TRACESYSGOOD)
+                .unwrap();
+
+            // Emulate the system calls as successful, until the kill.
+            loop {
+                ptrace::sysemu(child, None).unwrap();
+                assert_eq!(
+                    waitpid(child, None),
+                    Ok(WaitStatus::PtraceSyscall(child))
+                );
+                let mut regs = ptrace::getregs(child).unwrap();
+                let nr = regs.orig_rax as libc::c_long;
+                regs.rax = 0;
+                ptrace::setregs(child, regs).unwrap();
+                if nr == libc::SYS_kill {
+                    break;
+                }
+            }
+
+            // Single-step past the emulated kill.
+            ptrace::sysemu_step(child, None).unwrap();
+            assert!(matches!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(_, Signal::SIGTRAP))
+                    | Ok(WaitStatus::PtraceSyscall(_))
+            ));
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
 // ptrace::{setoptions, getregs} are only available in these platforms
 #[cfg(all(
     target_os = "linux",
@@ -262,6 +638,26 @@ fn test_ptrace_syscall() {
             assert_eq!(get_syscall_id(), ::libc::SYS_kill);
             #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
             assert_eq!(get_syscall_from_user_area(), ::libc::SYS_kill);
+            #[cfg(all(target_arch = "aarch64", target_env = "gnu"))]
+            assert_eq!(
+                ptrace::getregset::<ptrace::regset::NT_ARM_SYSTEM_CALL>(child)
+                    .unwrap() as libc::c_long,
+                ::libc::SYS_kill
+            );
+            match ptrace::get_syscall_info(child) {
+                Ok(info) => match info.op {
+                    ptrace::SyscallInfoOp::Entry { nr, args } => {
+                        assert_eq!(nr as libc::c_long, ::libc::SYS_kill);
+                        assert_eq!(args[0] as libc::pid_t, child.as_raw());
+                        assert_eq!(args[1], Signal::SIGTERM as u64);
+                        assert_ne!(info.instruction_pointer, 0);
+                    }
+                    op => panic!("unexpected syscall info {op:?}"),
+                },
+                // Only since Linux 5.3
+                Err(Errno::EIO) => (),
+                Err(e) => panic!("get_syscall_info failed: {e}"),
+            }
 
             // kill exit
             ptrace::syscall(child, None).unwrap();
@@ -272,6 +668,15 @@ fn test_ptrace_syscall() {
             assert_eq!(get_syscall_id(), ::libc::SYS_kill);
             #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
             assert_eq!(get_syscall_from_user_area(), ::libc::SYS_kill);
+            if let Ok(info) = ptrace::get_syscall_info(child) {
+                assert_eq!(
+                    info.op,
+                    ptrace::SyscallInfoOp::Exit {
+                        rval: 0,
+                        is_error: false
+                    }
+                );
+            }
 
             // receive signal
             ptrace::syscall(child, None).unwrap();
@@ -371,3 +776,55 @@ fn test_ptrace_regsets() {
         }
     }
 }
+
+#[cfg(target_os = "freebsd")]
+#[test]
+fn test_ptrace_io_lwpinfo() {
+    use nix::sys::ptrace::{self, LwpEvent, LwpFlags};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    let _m = crate::FORK_MTX.lock();
+
+    // The child has the same buffer, at the same address.
+    let mut data = *b"0123456789abcdef";
+    let addr = data.as_mut_ptr();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            let data =
+                unsafe { std::ptr::read_volatile(addr as *const [u8; 16]) };
+            unsafe { libc::_exit((&data != b"0123XYZ789abcdef") as i32) };
+        }
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+
+            let info = ptrace::lwpinfo(child).unwrap();
+            assert_eq!(info.event(), Ok(LwpEvent::PL_EVENT_SIGNAL));
+            assert!(info.flags().contains(LwpFlags::PL_FLAG_SI));
+            assert_eq!(info.siginfo().unwrap().si_signo, libc::SIGSTOP);
+            assert_eq!(info.child_pid(), None);
+
+            let mut buf = [0; 16];
+            assert_eq!(
+                ptrace::read_bytes(child, addr.cast(), &mut buf),
+                Ok(())
+            );
+            assert_eq!(&buf, &data);
+            assert_eq!(
+                ptrace::write_bytes(child, addr.wrapping_add(4).cast(), b"XYZ"),
+                Ok(())
+            );
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use nix::errno::Errno;
+use nix::sys::quota::*;
+
+#[test]
+fn test_quotactl_fd_quotas_disabled() {
+    let file = tempfile::tempfile().unwrap();
+    match quotactl_fd_get(&file, QuotaType::USRQUOTA, 0) {
+        Err(Errno::ENOSYS) => {
+            skip!("quotactl_fd requires Linux 5.14. Skipping test.")
+        }
+        // Quotas are not enabled on the temporary directory's file system.
+        res => assert_eq!(res.err(), Some(Errno::ESRCH)),
+    }
+    assert_eq!(
+        quotactl_fd_get_next(&file, QuotaType::USRQUOTA, 0).err(),
+        Some(Errno::ESRCH)
+    );
+}
+
+#[test]
+fn test_quotactl_get_next_not_block_device() {
+    assert_eq!(
+        quotactl_get_next(QuotaType::USRQUOTA, "/dev/null", 0).err(),
+        Some(Errno::ENOTBLK)
+    );
+}
@@ -0,0 +1,130 @@
+use libc::{
+    sock_filter, BPF_ABS, BPF_JEQ, BPF_JMP, BPF_JUMP, BPF_K, BPF_LD, BPF_RET,
+    BPF_STMT, BPF_W,
+};
+use nix::errno::Errno;
+use nix::sys::prctl::set_no_new_privs;
+use nix::sys::seccomp::*;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, getppid, pipe, read, ForkResult};
+use std::os::fd::AsRawFd;
+
+/// A filter returning `action` for the system call `nr`, and allowing any
+/// other system call.
+fn filter_syscall(nr: libc::c_long, action: u32) -> [sock_filter; 4] {
+    unsafe {
+        [
+            // Offset of `seccomp_data::nr`.
+            BPF_STMT((BPF_LD | BPF_W | BPF_ABS) as u16, 0),
+            BPF_JUMP((BPF_JMP | BPF_JEQ | BPF_K) as u16, nr as u32, 0, 1),
+            BPF_STMT((BPF_RET | BPF_K) as u16, action),
+            BPF_STMT((BPF_RET | BPF_K) as u16, libc::SECCOMP_RET_ALLOW),
+        ]
+    }
+}
+
+#[test]
+fn test_seccomp_set_mode_strict() {
+    let _m = crate::FORK_MTX.lock();
+    let (r, w) = pipe().unwrap();
+
+    // Safe: the child only makes system calls before exiting.
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            if seccomp_set_mode_strict().is_ok() {
+                // Allowed.
+                unsafe { libc::write(w.as_raw_fd(), b"ok".as_ptr().cast(), 2) };
+                // Not allowed.
+                unsafe { libc::getpid() };
+            }
+            unsafe { libc::syscall(libc::SYS_exit, 1) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Signaled(child, Signal::SIGKILL, false))
+            );
+            let mut buf = [0; 2];
+            assert_eq!(read(&r, &mut buf), Ok(2));
+            assert_eq!(&buf, b"ok");
+        }
+    }
+}
+
+#[test]
+fn test_seccomp_set_mode_filter() {
+    // The filter only applies to the calling thread.
+    std::thread::spawn(|| {
+        let filter = filter_syscall(
+            libc::SYS_getppid,
+            libc::SECCOMP_RET_ERRNO | libc::EPERM as u32,
+        );
+        let prog = SockFprog::new(&filter).unwrap();
+        assert_eq!(prog.filter().len(), 4);
+        set_no_new_privs().unwrap();
+        seccomp_set_mode_filter(FilterFlags::empty(), &prog).unwrap();
+        // `getppid` can't fail, so the C library may not check for errors.
+        assert_eq!(unsafe { libc::syscall(libc::SYS_getppid) }, -1);
+        assert_eq!(Errno::last(), Errno::EPERM);
+    })
+    .join()
+    .unwrap();
+    assert!(getppid().as_raw() > 0);
+}
+
+#[test]
+fn test_seccomp_user_notif() {
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let notified = std::thread::spawn(move || {
+        let filter =
+            filter_syscall(libc::SYS_getppid, libc::SECCOMP_RET_USER_NOTIF);
+        let prog = SockFprog::new(&filter).unwrap();
+        set_no_new_privs().unwrap();
+        let listener =
+            match seccomp_set_mode_filter_listener(FilterFlags::empty(), &prog)
+            {
+                Err(e) => {
+                    tx.send(Err(e)).unwrap();
+                    return None;
+                }
+                Ok(listener) => listener,
+            };
+        let tid = unsafe { libc::syscall(libc::SYS_gettid) };
+        tx.send(Ok((listener, tid))).unwrap();
+        // Handled by the supervisor.
+        let first = unsafe { libc::syscall(libc::SYS_getppid) };
+        let second = unsafe { libc::syscall(libc::SYS_getppid) };
+        Some((first, second, Errno::last()))
+    });
+
+    let (listener, tid) = match rx.recv().unwrap() {
+        Err(Errno::EINVAL) => {
+            notified.join().unwrap();
+            skip!("User notifications are not supported. Skipping test.");
+        }
+        r => r.unwrap(),
+    };
+
+    let notif = seccomp_notif_recv(&listener).unwrap();
+    assert_eq!(notif.syscall(), libc::SYS_getppid as libc::c_int);
+    assert_eq!(notif.pid().as_raw() as libc::c_long, tid);
+    seccomp_notif_id_valid(&listener, notif.id()).unwrap();
+    seccomp_notif_send(&listener, &SeccompNotifResp::new_val(notif.id(), 42))
+        .unwrap();
+    // The notification has been answered.
+    assert_eq!(
+        seccomp_notif_id_valid(&listener, notif.id()),
+        Err(Errno::ENOENT)
+    );
+
+    let notif = seccomp_notif_recv(&listener).unwrap();
+    seccomp_notif_send(
+        &listener,
+        &SeccompNotifResp::new_error(notif.id(), Errno::EACCES),
+    )
+    .unwrap();
+
+    assert_eq!(notified.join().unwrap(), Some((42, -1, Errno::EACCES)));
+}
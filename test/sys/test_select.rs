@@ -5,6 +5,8 @@ use nix::unistd::{pipe, write};
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
 
 #[test]
+// `&TimeSpec` is still accepted as a timeout.
+#[allow(clippy::needless_borrows_for_generic_args)]
 pub fn test_pselect() {
     let _mtx = crate::SIGNAL_MTX.lock();
 
@@ -27,6 +29,8 @@ pub fn test_pselect() {
 }
 
 #[test]
+// `&TimeSpec` is still accepted as a timeout.
+#[allow(clippy::needless_borrows_for_generic_args)]
 pub fn test_pselect_nfds2() {
     let (r1, w1) = pipe().unwrap();
     write(&w1, b"hi!").unwrap();
@@ -0,0 +1,54 @@
+use nix::errno::Errno;
+use nix::sys::ipc::{IpcGetFlags, IPC_PRIVATE};
+use nix::sys::sem::*;
+use nix::sys::stat::Mode;
+
+#[test]
+fn test_sem() {
+    let id =
+        semget(IPC_PRIVATE, 2, IpcGetFlags::IPC_CREAT, Mode::S_IRWXU).unwrap();
+
+    semctl_setval(id, 0, 2).unwrap();
+    assert_eq!(semctl_getval(id, 0).unwrap(), 2);
+    assert_eq!(semctl_getval(id, 1).unwrap(), 0);
+
+    semop(
+        id,
+        &[
+            SemBuf::new(0).op(-2).flags(SemOpFlags::SEM_UNDO),
+            SemBuf::new(1).op(1),
+        ],
+    )
+    .unwrap();
+    assert_eq!(semctl_getval(id, 0).unwrap(), 0);
+    assert_eq!(semctl_getval(id, 1).unwrap(), 1);
+
+    assert_eq!(
+        semop(id, &[SemBuf::new(0).op(-1).flags(SemOpFlags::IPC_NOWAIT)]),
+        Err(Errno::EAGAIN)
+    );
+
+    #[cfg(target_os = "linux")]
+    {
+        use nix::sys::time::TimeSpec;
+        use std::time::Duration;
+
+        let timeout = TimeSpec::from_duration(Duration::from_millis(10));
+        assert_eq!(
+            semtimedop(id, &[SemBuf::new(0).op(-1)], Some(&timeout)),
+            Err(Errno::EAGAIN)
+        );
+        semtimedop(id, &[SemBuf::new(1).op(-1)], Some(&timeout)).unwrap();
+    }
+
+    #[cfg(any(all(target_os = "linux", target_env = "gnu"), apple_targets))]
+    {
+        let stat = semctl_stat(id).unwrap();
+        assert_eq!(stat.nsems(), 2);
+        assert_eq!(stat.perm().mode(), Mode::S_IRWXU);
+        assert_eq!(stat.perm().uid(), nix::unistd::geteuid());
+    }
+
+    semctl_rmid(id).unwrap();
+    assert_eq!(semctl_getval(id, 0), Err(Errno::EINVAL));
+}
@@ -18,6 +18,8 @@ use libc::{S_IFLNK, S_IFMT};
 use nix::errno::Errno;
 #[cfg(not(target_os = "redox"))]
 use nix::fcntl;
+#[cfg(not(target_os = "redox"))]
+use nix::fcntl::AtFlags;
 #[cfg(any(
     target_os = "linux",
     apple_targets,
@@ -27,11 +29,7 @@ use nix::fcntl;
 use nix::sys::stat::lutimes;
 #[cfg(not(any(target_os = "redox", target_os = "haiku")))]
 use nix::sys::stat::utimensat;
-#[cfg(not(target_os = "redox"))]
-use nix::sys::stat::FchmodatFlags;
 use nix::sys::stat::Mode;
-#[cfg(not(any(target_os = "redox", target_os = "haiku")))]
-use nix::sys::stat::UtimensatFlags;
 #[cfg(not(target_os = "redox"))]
 use nix::sys::stat::{self};
 use nix::sys::stat::{fchmod, stat};
@@ -182,7 +180,7 @@ fn test_fchmodat() {
     let mut mode1 = Mode::empty();
     mode1.insert(Mode::S_IRUSR);
     mode1.insert(Mode::S_IWUSR);
-    fchmodat(&dirfd, filename, mode1, FchmodatFlags::FollowSymlink).unwrap();
+    fchmodat(&dirfd, filename, mode1, AtFlags::empty()).unwrap();
 
     let file_stat1 = stat(&fullpath).unwrap();
     assert_eq!(file_stat1.st_mode as mode_t & 0o7777, mode1.bits());
@@ -191,18 +189,49 @@ fn test_fchmodat() {
 
     let mut mode2 = Mode::empty();
     mode2.insert(Mode::S_IROTH);
-    fchmodat(
-        fcntl::AT_FDCWD,
-        filename,
-        mode2,
-        FchmodatFlags::FollowSymlink,
-    )
-    .unwrap();
+    fchmodat(fcntl::AT_FDCWD, filename, mode2, AtFlags::empty()).unwrap();
 
     let file_stat2 = stat(&fullpath).unwrap();
     assert_eq!(file_stat2.st_mode as mode_t & 0o7777, mode2.bits());
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_fchmodat2() {
+    use nix::sys::stat::fchmodat2;
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let fullpath = tempdir.path().join("foo.txt");
+    File::create(&fullpath).unwrap();
+    symlink("foo.txt", tempdir.path().join("link")).unwrap();
+    let dirfd =
+        fcntl::open(tempdir.path(), fcntl::OFlag::empty(), stat::Mode::empty())
+            .unwrap();
+
+    let mode1 = Mode::S_IRUSR | Mode::S_IWUSR;
+    match fchmodat2(&dirfd, "foo.txt", mode1, AtFlags::AT_SYMLINK_NOFOLLOW) {
+        Err(Errno::ENOSYS) => {
+            skip!("fchmodat2 is not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    let file_stat1 = stat(&fullpath).unwrap();
+    assert_eq!(file_stat1.st_mode as mode_t & 0o7777, mode1.bits());
+
+    // The mode of a symbolic link can't be changed on Linux.
+    assert_eq!(
+        fchmodat2(&dirfd, "link", Mode::S_IRWXU, AtFlags::AT_SYMLINK_NOFOLLOW),
+        Err(Errno::EOPNOTSUPP)
+    );
+    let file_stat2 = stat(&fullpath).unwrap();
+    assert_eq!(file_stat2.st_mode as mode_t & 0o7777, mode1.bits());
+
+    // Without AT_SYMLINK_NOFOLLOW, the link is followed.
+    fchmodat2(&dirfd, "link", Mode::S_IRUSR, AtFlags::empty()).unwrap();
+    let file_stat3 = stat(&fullpath).unwrap();
+    assert_eq!(file_stat3.st_mode as mode_t & 0o7777, Mode::S_IRUSR.bits());
+}
+
 /// Asserts that the atime and mtime in a file's metadata match expected values.
 ///
 /// The atime and mtime are expressed with a resolution of seconds because some file systems
@@ -299,7 +328,7 @@ fn test_utimensat() {
         filename,
         &TimeSpec::seconds(12345),
         &TimeSpec::seconds(678),
-        UtimensatFlags::FollowSymlink,
+        AtFlags::empty(),
     )
     .unwrap();
     assert_times_eq(12345, 678, &fs::metadata(&fullpath).unwrap());
@@ -311,12 +340,54 @@ fn test_utimensat() {
         filename,
         &TimeSpec::seconds(500),
         &TimeSpec::seconds(800),
-        UtimensatFlags::FollowSymlink,
+        AtFlags::empty(),
     )
     .unwrap();
     assert_times_eq(500, 800, &fs::metadata(&fullpath).unwrap());
 }
 
+#[test]
+#[cfg(not(any(target_os = "redox", target_os = "haiku")))]
+#[allow(deprecated)]
+fn test_deprecated_at_flags_variants() {
+    use nix::sys::stat::{FchmodatFlags, UtimensatFlags};
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let fullpath = tempdir.path().join("foo.txt");
+    drop(File::create(&fullpath).unwrap());
+    let dirfd =
+        fcntl::open(tempdir.path(), fcntl::OFlag::empty(), stat::Mode::empty())
+            .unwrap();
+
+    assert_eq!(FchmodatFlags::FollowSymlink, AtFlags::empty());
+    assert_eq!(
+        UtimensatFlags::NoFollowSymlink,
+        AtFlags::AT_SYMLINK_NOFOLLOW
+    );
+
+    fchmodat(
+        &dirfd,
+        "foo.txt",
+        Mode::S_IRUSR,
+        FchmodatFlags::FollowSymlink,
+    )
+    .unwrap();
+    assert_eq!(
+        stat(&fullpath).unwrap().st_mode as mode_t & 0o7777,
+        Mode::S_IRUSR.bits()
+    );
+
+    utimensat(
+        &dirfd,
+        "foo.txt",
+        &TimeSpec::seconds(12345),
+        &TimeSpec::seconds(678),
+        UtimensatFlags::NoFollowSymlink,
+    )
+    .unwrap();
+    assert_times_eq(12345, 678, &fs::metadata(&fullpath).unwrap());
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_mkdirat_success_path() {
@@ -464,7 +535,7 @@ fn test_utimensat_unchanged() {
         filename,
         &TimeSpec::UTIME_OMIT,
         &TimeSpec::UTIME_OMIT,
-        UtimensatFlags::NoFollowSymlink,
+        AtFlags::AT_SYMLINK_NOFOLLOW,
     )
     .unwrap();
     let new_atime = fs::metadata(fullpath.as_path())
@@ -479,6 +550,30 @@ fn test_utimensat_unchanged() {
     assert_eq!(old_mtime, new_mtime);
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_utimensat_empty_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let fullpath = tempdir.path().join("foo.txt");
+    drop(File::create(&fullpath).unwrap());
+    let fd = fcntl::open(&fullpath, fcntl::OFlag::O_PATH, stat::Mode::empty())
+        .unwrap();
+
+    let mtime = TimeSpec::seconds(678);
+    utimensat(
+        &fd,
+        "",
+        &TimeSpec::UTIME_OMIT,
+        &mtime,
+        AtFlags::AT_EMPTY_PATH,
+    )
+    .unwrap();
+    assert_eq!(
+        fs::metadata(&fullpath).unwrap().modified().unwrap(),
+        UNIX_EPOCH + Duration::from_secs(678)
+    );
+}
+
 // The conversion is not useless on all platforms.
 #[allow(clippy::useless_conversion)]
 #[cfg(target_os = "freebsd")]
@@ -505,3 +600,64 @@ fn test_chflags() {
 
     assert_eq!(commanded, changed);
 }
+
+// The conversion is not useless on all platforms.
+#[allow(clippy::useless_conversion)]
+#[cfg(target_os = "freebsd")]
+#[test]
+fn test_fchflags() {
+    use nix::{
+        sys::stat::{fstat, FileFlag},
+        unistd::fchflags,
+    };
+    use tempfile::NamedTempFile;
+
+    let f = NamedTempFile::new().unwrap();
+
+    let initial =
+        FileFlag::from_bits_truncate(fstat(&f).unwrap().st_flags.into());
+    let commanded = initial ^ FileFlag::UF_OFFLINE;
+
+    fchflags(&f, commanded).unwrap();
+
+    let changed =
+        FileFlag::from_bits_truncate(fstat(&f).unwrap().st_flags.into());
+
+    assert_eq!(commanded, changed);
+}
+
+// The conversion is not useless on all platforms.
+#[allow(clippy::useless_conversion)]
+#[cfg(target_os = "freebsd")]
+#[test]
+fn test_lchflags() {
+    use nix::{
+        sys::stat::{lstat, stat, FileFlag},
+        unistd::lchflags,
+    };
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target");
+    let link = dir.path().join("link");
+    std::fs::File::create(&target).unwrap();
+    symlink(&target, &link).unwrap();
+
+    let target_initial =
+        FileFlag::from_bits_truncate(stat(&target).unwrap().st_flags.into());
+    let link_initial =
+        FileFlag::from_bits_truncate(lstat(&link).unwrap().st_flags.into());
+    let commanded = link_initial ^ FileFlag::UF_OFFLINE;
+
+    lchflags(&link, commanded).unwrap();
+
+    let link_changed =
+        FileFlag::from_bits_truncate(lstat(&link).unwrap().st_flags.into());
+    let target_changed =
+        FileFlag::from_bits_truncate(stat(&target).unwrap().st_flags.into());
+
+    assert_eq!(commanded, link_changed);
+    // The target of the symlink is left untouched.
+    assert_eq!(target_initial, target_changed);
+}
@@ -96,3 +96,18 @@ fn assert_fs_equals_strict(fs: Statfs, vfs: Statvfs) {
     assert_eq!(fs.blocks() as u64, vfs.blocks() as u64);
     assert_eq!(fs.block_size() as u64, vfs.fragment_size() as u64);
 }
+
+#[test]
+#[cfg(linux_android)]
+fn statfs_filesystem_type() {
+    let (rd, _wr) = nix::unistd::pipe().unwrap();
+    assert_eq!(fstatfs(&rd).unwrap().filesystem_type(), PIPEFS_MAGIC);
+
+    let fd = nix::sys::eventfd::EventFd::new().unwrap();
+    assert_eq!(fstatfs(&fd).unwrap().filesystem_type(), ANON_INODE_FS_MAGIC);
+
+    if Path::new("/proc/self").exists() {
+        let fs = statfs("/proc/self").unwrap();
+        assert_eq!(fs.filesystem_type(), PROC_SUPER_MAGIC);
+    }
+}
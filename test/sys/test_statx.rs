@@ -0,0 +1,54 @@
+use nix::fcntl::{AtFlags, AT_FDCWD};
+use nix::sys::stat::fstat;
+use nix::sys::statx::{statx, StatxMask};
+
+#[test]
+fn test_statx_matches_fstat() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), b"hello").unwrap();
+
+    let stx = statx(
+        AT_FDCWD,
+        file.path(),
+        AtFlags::empty(),
+        StatxMask::STATX_BASIC_STATS,
+    )
+    .unwrap();
+    let st = fstat(&file).unwrap();
+
+    assert!(stx.mask().contains(StatxMask::STATX_BASIC_STATS));
+    assert_eq!(stx.size(), 5);
+    assert_eq!(stx.ino(), st.st_ino as u64);
+    assert_eq!(stx.mode(), st.st_mode);
+    assert_eq!(stx.nlink() as u64, st.st_nlink as u64);
+    assert_eq!(stx.mtime().tv_sec(), st.st_mtime);
+}
+
+#[test]
+fn test_statx_empty_path() {
+    let file = tempfile::tempfile().unwrap();
+    let stx = statx(
+        &file,
+        "",
+        AtFlags::AT_EMPTY_PATH,
+        StatxMask::STATX_INO | StatxMask::STATX_MNT_ID,
+    )
+    .unwrap();
+
+    assert_eq!(stx.ino(), fstat(&file).unwrap().st_ino as u64);
+    // The mount ID is only available since Linux 5.8.
+    if stx.mask().contains(StatxMask::STATX_MNT_ID) {
+        assert!(stx.mnt_id().is_some());
+    }
+}
+
+#[test]
+fn test_dio_alignment() {
+    use nix::sys::statx::dio_alignment;
+
+    let file = tempfile::tempfile().unwrap();
+    if let Some(align) = dio_alignment(&file).unwrap() {
+        assert!(align.mem_align.is_power_of_two());
+        assert!(align.offset_align.is_power_of_two());
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use nix::errno::Errno;
+use nix::sys::swap::*;
+use nix::unistd::{sysconf, SysconfVar};
+
+// Write a swap area of `pages` pages to `path`, like `mkswap(8)` would.
+fn make_swap_file(path: &Path, pages: usize) {
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let mut data = vec![0; page_size * pages];
+    // The version and the index of the last page follow the boot sector.
+    data[1024..1028].copy_from_slice(&1u32.to_ne_bytes());
+    data[1028..1032].copy_from_slice(&(pages as u32 - 1).to_ne_bytes());
+    data[page_size - 10..page_size].copy_from_slice(b"SWAPSPACE2");
+
+    let mut file = File::create(path).unwrap();
+    file.write_all(&data).unwrap();
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+        .unwrap();
+}
+
+// Returns the priority of the swap area at `path`, if it is in use.
+fn swap_priority(path: &Path) -> Option<i32> {
+    let swaps = fs::read_to_string("/proc/swaps").unwrap();
+    swaps.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        (Path::new(fields[0]) == path).then(|| fields[4].parse().unwrap())
+    })
+}
+
+#[test]
+fn test_swap_flags_priority() {
+    let flags = SwapFlags::with_priority(100) | SwapFlags::SWAP_FLAG_DISCARD;
+    assert!(flags.contains(SwapFlags::SWAP_FLAG_PREFER));
+    assert_eq!(flags.priority(), Some(100));
+    assert_eq!(SwapFlags::SWAP_FLAG_DISCARD.priority(), None);
+    assert_eq!(
+        SwapFlags::with_priority(SwapFlags::MAX_PRIORITY).priority(),
+        Some(SwapFlags::MAX_PRIORITY)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_swap_flags_priority_too_high() {
+    SwapFlags::with_priority(SwapFlags::MAX_PRIORITY + 1);
+}
+
+#[test]
+fn test_swapon_swapoff() {
+    require_capability!("test_swapon_swapoff", CAP_SYS_ADMIN);
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().canonicalize().unwrap().join("swapfile");
+    make_swap_file(&path, 16);
+
+    match swapon(&path, SwapFlags::with_priority(42)) {
+        // Some file systems, like tmpfs, can't hold swap files.
+        Err(Errno::EINVAL) => {
+            skip!("test_swapon_swapoff requires a file system supporting swap files. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert_eq!(swap_priority(&path), Some(42));
+    // The swap area is already in use.
+    assert_eq!(swapon(&path, SwapFlags::empty()), Err(Errno::EBUSY));
+
+    swapoff(&path).unwrap();
+    assert_eq!(swap_priority(&path), None);
+    assert_eq!(swapoff(&path), Err(Errno::EINVAL));
+}
@@ -0,0 +1,63 @@
+use std::os::unix::ffi::OsStrExt;
+
+use libc::{c_int, CTL_KERN, KERN_MAXPROC, KERN_OSTYPE};
+use nix::errno::Errno;
+use nix::sys::sysctl::*;
+
+#[test]
+fn test_sysctl_get() {
+    let maxproc: c_int = sysctl_get(&[CTL_KERN, KERN_MAXPROC]).unwrap();
+    assert!(maxproc > 0);
+
+    // kern.maxproc is an int, which doesn't fill a 64-bit integer.
+    assert_eq!(
+        sysctl_get::<u64>(&[CTL_KERN, KERN_MAXPROC]),
+        Err(Errno::EINVAL)
+    );
+}
+
+#[test]
+fn test_sysctl_get_string() {
+    let ostype = sysctl_get_string(&[CTL_KERN, KERN_OSTYPE]).unwrap();
+    assert!(!ostype.is_empty());
+
+    let bytes = sysctl_get_bytes(&[CTL_KERN, KERN_OSTYPE]).unwrap();
+    assert_eq!(bytes.last(), Some(&0));
+    assert_eq!(&bytes[..bytes.len() - 1], ostype.as_bytes());
+}
+
+#[test]
+#[cfg(not(target_os = "openbsd"))]
+fn test_sysctlbyname() {
+    let ostype = sysctlbyname_get_string("kern.ostype").unwrap();
+    assert_eq!(ostype, sysctl_get_string(&[CTL_KERN, KERN_OSTYPE]).unwrap());
+
+    let maxproc: c_int = sysctlbyname_get("kern.maxproc").unwrap();
+    assert!(maxproc > 0);
+
+    assert_eq!(
+        sysctlbyname_get_bytes("nix.does.not.exist"),
+        Err(Errno::ENOENT)
+    );
+}
+
+#[test]
+#[cfg(not(target_os = "openbsd"))]
+fn test_sysctlnametomib() {
+    let mib = sysctlnametomib("kern.ostype").unwrap();
+    assert_eq!(mib, [CTL_KERN, KERN_OSTYPE]);
+}
+
+#[test]
+fn test_sysctl_set_unprivileged() {
+    if nix::unistd::Uid::effective().is_root() {
+        skip!(
+            "test_sysctl_set_unprivileged must not run as root. Skipping test."
+        );
+    }
+    let maxproc: c_int = sysctl_get(&[CTL_KERN, KERN_MAXPROC]).unwrap();
+    assert_eq!(
+        sysctl_set(&[CTL_KERN, KERN_MAXPROC], maxproc),
+        Err(Errno::EPERM)
+    );
+}
@@ -17,4 +17,7 @@ fn sysinfo_works() {
         info.swap_free(),
         info.swap_total()
     );
+    assert!(info.ram_unused() <= info.ram_total());
+    assert!(info.ram_shared() <= info.ram_total());
+    assert!(info.ram_buffer() <= info.ram_total());
 }
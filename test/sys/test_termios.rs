@@ -114,3 +114,177 @@ fn test_local_flags() {
     let read = read(&pty.master, &mut buf).unwrap_err();
     assert_eq!(read, Errno::EAGAIN);
 }
+
+#[test]
+fn test_window_size() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let ws = termios::Winsize {
+        ws_row: 24,
+        ws_col: 80,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    let pty = openpty(&ws, None).unwrap();
+    assert_eq!(termios::window_size(&pty.slave).unwrap(), ws);
+
+    // On the master, the size is the one seen on the slave.
+    let ws = termios::Winsize {
+        ws_row: 50,
+        ws_col: 132,
+        ..ws
+    };
+    termios::set_window_size(&pty.master, &ws).unwrap();
+    assert_eq!(termios::window_size(&pty.slave).unwrap(), ws);
+    assert_eq!(termios::window_size(&pty.master).unwrap(), ws);
+}
+
+#[test]
+fn test_window_size_enotty() {
+    let file = tempfile().unwrap();
+    assert_eq!(termios::window_size(&file), Err(Errno::ENOTTY));
+}
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "powerpc", target_arch = "powerpc64"))
+))]
+#[test]
+fn test_set_speed() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    termios::set_speed(&pty.slave, termios::SetArg::TCSANOW, 250000, 250000)
+        .unwrap();
+    assert_eq!(termios::get_speed(&pty.slave), Ok((250000, 250000)));
+
+    termios::set_speed(&pty.slave, termios::SetArg::TCSADRAIN, 9600, 115200)
+        .unwrap();
+    assert_eq!(termios::get_speed(&pty.slave), Ok((9600, 115200)));
+}
+
+#[test]
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike, solarish))]
+fn test_controlling_terminal() {
+    use nix::sys::signal::{signal, SigHandler, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, getpid, setsid, ForkResult};
+
+    let _m0 = crate::PTSNAME_MTX.lock();
+    let _m1 = crate::FORK_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            // Releasing the terminal hangs up its foreground process group.
+            unsafe { signal(Signal::SIGHUP, SigHandler::SigIgn) }.unwrap();
+            let ok = setsid().is_ok()
+                && termios::tcgetsid(&pty.slave) == Err(Errno::ENOTTY)
+                && termios::set_controlling_terminal(&pty.slave, false).is_ok()
+                && termios::tcgetsid(&pty.slave) == Ok(getpid())
+                && termios::release_controlling_terminal(&pty.slave).is_ok()
+                && termios::tcgetsid(&pty.slave) == Err(Errno::ENOTTY);
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[test]
+fn test_raw_mode_guard() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    let original = tcgetattr(&pty.slave).unwrap();
+    assert!(original.local_flags.contains(LocalFlags::ICANON));
+
+    let guard = termios::Termios::raw_mode_guard(&pty.slave).unwrap();
+    assert_eq!(guard.original(), &original);
+    let raw = tcgetattr(&pty.slave).unwrap();
+    assert!(!raw
+        .local_flags
+        .intersects(LocalFlags::ICANON | LocalFlags::ECHO));
+    drop(guard);
+    assert_eq!(tcgetattr(&pty.slave).unwrap(), original);
+
+    let guard = termios::Termios::raw_mode_guard(&pty.slave).unwrap();
+    guard.restore().unwrap();
+    assert_eq!(tcgetattr(&pty.slave).unwrap(), original);
+
+    // The settings are also restored when unwinding.
+    let res = std::panic::catch_unwind(|| {
+        let _guard = termios::Termios::raw_mode_guard(&pty.slave).unwrap();
+        panic!("in raw mode");
+    });
+    assert!(res.is_err());
+    assert_eq!(tcgetattr(&pty.slave).unwrap(), original);
+}
+
+#[test]
+#[cfg(linux_android)]
+fn test_simulate_input() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    let _guard = termios::Termios::raw_mode_guard(&pty.slave).unwrap();
+    match termios::simulate_input(&pty.slave, b"hello") {
+        Err(Errno::EIO | Errno::EPERM) => {
+            skip!("TIOCSTI is disabled or needs CAP_SYS_ADMIN. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+
+    let mut buf = [0u8; 5];
+    crate::read_exact(&pty.slave, &mut buf);
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_hangup() {
+    require_capability!("test_hangup", CAP_SYS_ADMIN);
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    termios::hangup(&pty.slave).unwrap();
+    let mut buf = [0u8; 1];
+    assert_eq!(read(&pty.slave, &mut buf), Ok(0));
+    assert_eq!(write(&pty.slave, b"x"), Err(Errno::EIO));
+}
+
+#[test]
+#[cfg(linux_android)]
+fn test_modem_lines_and_break() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    // Pseudoterminals have no modem control lines.
+    let pty = openpty(None, None).unwrap();
+    assert_eq!(termios::modem_lines(&pty.slave), Err(Errno::ENOTTY));
+    let lines = termios::ModemLines::TIOCM_DTR | termios::ModemLines::TIOCM_RTS;
+    assert_eq!(
+        termios::enable_modem_lines(&pty.slave, lines),
+        Err(Errno::ENOTTY)
+    );
+    assert_eq!(
+        termios::disable_modem_lines(&pty.slave, lines),
+        Err(Errno::ENOTTY)
+    );
+    assert_eq!(
+        termios::set_modem_lines(&pty.slave, lines),
+        Err(Errno::ENOTTY)
+    );
+
+    termios::set_break(&pty.slave).unwrap();
+    termios::clear_break(&pty.slave).unwrap();
+
+    let file = tempfile().unwrap();
+    assert_eq!(termios::set_break(&file), Err(Errno::ENOTTY));
+}
@@ -89,3 +89,38 @@ pub fn test_timeval_fmt() {
     assert_eq!(TimeVal::nanoseconds(1402).to_string(), "0.000001 seconds");
     assert_eq!(TimeVal::seconds(-86401).to_string(), "-86401 seconds");
 }
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+#[test]
+pub fn test_clock_adjtime() {
+    use nix::sys::time::{
+        adjtimex, clock_adjtime, Timex, TimexModes, TimexOffsetUnit,
+    };
+    use nix::time::{clock_gettime, ClockId};
+
+    let before = clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
+    let mut timex = Timex::new();
+    assert_eq!(timex.modes(), TimexModes::empty());
+    clock_adjtime(ClockId::CLOCK_REALTIME, &mut timex).unwrap();
+    assert!(timex.tick() > 0);
+    assert!(timex.time() >= before);
+
+    let mut timex = Timex::new();
+    adjtimex(&mut timex).unwrap();
+    assert!(timex.time() >= before);
+
+    // Setters select the parameters to change.
+    let mut timex = Timex::new();
+    timex.set_freq(0);
+    assert!(timex.modes().contains(TimexModes::ADJ_FREQUENCY));
+
+    // The unit of the time offset is the one chosen last.
+    let mut timex = Timex::new();
+    timex.set_offset(1000, TimexOffsetUnit::Nanoseconds);
+    timex.set_offset(1, TimexOffsetUnit::Microseconds);
+    assert_eq!(
+        timex.modes(),
+        TimexModes::ADJ_OFFSET | TimexModes::ADJ_MICRO
+    );
+    assert_eq!(timex.offset(), 1);
+}
@@ -100,3 +100,73 @@ fn alarm_fires() {
         sigaction(SIG, &old_handler).expect("unable to reset signal handler");
     }
 }
+
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn thread_timer_realtime_signal() {
+    use nix::poll::{poll, PollFd, PollFlags};
+    use nix::sys::signalfd::SignalFd;
+    use nix::unistd::gettid;
+    use std::mem::MaybeUninit;
+    use std::os::fd::AsFd;
+
+    const SI_VALUE: libc::intptr_t = 0x5eed;
+
+    // Run in a dedicated thread, which is the only one with the signal
+    // blocked, and the only one it is delivered to.
+    thread::spawn(|| {
+        let sigevent = SigEvent::new(SigevNotify::SigevThreadId {
+            signal: Signal::SIGALRM,
+            thread_id: gettid().as_raw(),
+            si_value: SI_VALUE,
+        })
+        .with_realtime_signal(2)
+        .unwrap();
+        let signo = libc::SIGRTMIN() + 2;
+
+        // `Signal` has no realtime signals, so the set can't be built with
+        // `SigSet::add`.
+        let mask = unsafe {
+            let mut sigset = MaybeUninit::<libc::sigset_t>::uninit();
+            libc::sigemptyset(sigset.as_mut_ptr());
+            libc::sigaddset(sigset.as_mut_ptr(), signo);
+            SigSet::from_sigset_t_unchecked(sigset.assume_init())
+        };
+        mask.thread_block().unwrap();
+        let fd = SignalFd::new(&mask).unwrap();
+
+        let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+        timer
+            .set(
+                Expiration::OneShot(Duration::from_millis(10).into()),
+                TimerSetTimeFlags::empty(),
+            )
+            .unwrap();
+
+        let mut fds = [PollFd::new(fd.as_fd(), PollFlags::POLLIN)];
+        assert_eq!(poll(&mut fds, 3000u16).unwrap(), 1);
+        let info = fd.read_signal().unwrap().unwrap();
+        assert_eq!(info.ssi_signo as libc::c_int, signo);
+        assert_eq!(info.ssi_code, libc::SI_TIMER);
+        assert_eq!(info.ssi_ptr as libc::intptr_t, SI_VALUE);
+        assert_eq!(timer.overruns(), 0);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "linux", solarish))]
+fn sigevent_realtime_signal_out_of_range() {
+    let sigevent = SigEvent::new(SigevNotify::SigevSignal {
+        signal: SIG,
+        si_value: 0,
+    });
+    let offset = libc::SIGRTMAX() - libc::SIGRTMIN();
+    assert!(sigevent.clone().with_realtime_signal(offset).is_ok());
+    assert!(sigevent.clone().with_realtime_signal(offset + 1).is_err());
+    assert!(sigevent.with_realtime_signal(-1).is_err());
+    assert!(SigEvent::new(SigevNotify::SigevNone)
+        .with_realtime_signal(0)
+        .is_err());
+}
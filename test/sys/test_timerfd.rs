@@ -67,3 +67,83 @@ pub fn test_timerfd_unset() {
 
     assert!(timer.get().unwrap().is_none());
 }
+
+#[test]
+pub fn test_timerfd_read_expirations() {
+    let timer =
+        TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::TFD_NONBLOCK)
+            .unwrap();
+    assert_eq!(timer.read_expirations(), Err(nix::errno::Errno::EAGAIN));
+
+    timer
+        .set(
+            Expiration::Interval(TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(55));
+
+    // Several periods elapsed before reading.
+    assert!(timer.read_expirations().unwrap() >= 4);
+}
+
+#[test]
+pub fn test_timerfd_cancel_on_set() {
+    use nix::errno::Errno;
+    use nix::time::{clock_gettime, ClockId as TimeClockId};
+
+    let timer = TimerFd::new(ClockId::CLOCK_REALTIME, TimerFlags::TFD_NONBLOCK)
+        .unwrap();
+    let now = clock_gettime(TimeClockId::CLOCK_REALTIME).unwrap();
+    timer
+        .set(
+            Expiration::OneShot(now + TimeSpec::seconds(60)),
+            TimerSetTimeFlags::TFD_TIMER_ABSTIME
+                | TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET,
+        )
+        .unwrap();
+
+    // The timer is armed, and reports the time left until it expires.
+    match timer.get().unwrap() {
+        Some(Expiration::OneShot(left)) => {
+            assert!(left > TimeSpec::seconds(0));
+            assert!(left <= TimeSpec::seconds(60));
+        }
+        other => panic!("unexpected expiration {other:?}"),
+    }
+    assert_eq!(timer.read_expirations(), Err(Errno::EAGAIN));
+}
+
+// This test is ignored because it sets CLOCK_REALTIME, which, with
+// CAP_SYS_TIME, steps the clock of the whole machine back by the time elapsed
+// between reading and setting it.
+#[test]
+#[ignore]
+pub fn test_timerfd_cancel_on_set_clock_change() {
+    use nix::errno::Errno;
+    use nix::time::{clock_gettime, clock_settime, ClockId as TimeClockId};
+
+    require_capability!(
+        "test_timerfd_cancel_on_set_clock_change",
+        CAP_SYS_TIME
+    );
+
+    let timer = TimerFd::new(ClockId::CLOCK_REALTIME, TimerFlags::TFD_NONBLOCK)
+        .unwrap();
+    let now = clock_gettime(TimeClockId::CLOCK_REALTIME).unwrap();
+    timer
+        .set(
+            Expiration::OneShot(now + TimeSpec::seconds(60)),
+            TimerSetTimeFlags::TFD_TIMER_ABSTIME
+                | TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET,
+        )
+        .unwrap();
+    assert_eq!(timer.read_expirations(), Err(Errno::EAGAIN));
+
+    // Setting the clock, even to its current value, is a discontinuous change.
+    let now = clock_gettime(TimeClockId::CLOCK_REALTIME).unwrap();
+    if clock_settime(TimeClockId::CLOCK_REALTIME, now).is_err() {
+        skip!("Cannot set CLOCK_REALTIME. Skipping test.");
+    }
+    assert_eq!(timer.read_expirations(), Err(Errno::ECANCELED));
+}
@@ -271,3 +271,35 @@ fn test_process_vm_readv() {
         }
     }
 }
+
+#[test]
+#[cfg(all(target_os = "linux", not(target_env = "uclibc")))]
+// qemu-user doesn't implement process_vm_readv/writev on most arches
+#[cfg_attr(qemu, ignore)]
+fn test_process_vm_helpers() {
+    use nix::errno::Errno;
+    use nix::sys::uio::{
+        process_vm_read_c_string, process_vm_read_exact, process_vm_write_all,
+    };
+    use nix::unistd::getpid;
+
+    // Accessing the memory of the calling process needs no privilege.
+    let pid = getpid();
+    let src = b"hello\0world".to_vec();
+    let mut dst = vec![0u8; src.len()];
+    process_vm_read_exact(pid, src.as_ptr() as usize, &mut dst).unwrap();
+    assert_eq!(dst, src);
+
+    process_vm_write_all(pid, dst.as_mut_ptr() as usize, b"HELLO").unwrap();
+    assert_eq!(&dst[..5], b"HELLO");
+
+    let string = process_vm_read_c_string(pid, dst.as_ptr() as usize, 64);
+    assert_eq!(string.unwrap().as_bytes(), b"HELLO");
+    assert_eq!(
+        process_vm_read_c_string(pid, dst.as_ptr() as usize, 3),
+        Err(Errno::ENAMETOOLONG)
+    );
+    let string =
+        process_vm_read_c_string(pid, dst.as_ptr() as usize, usize::MAX);
+    assert_eq!(string.unwrap().as_bytes(), b"HELLO");
+}
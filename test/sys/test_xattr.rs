@@ -0,0 +1,104 @@
+use std::ffi::OsStr;
+use std::fs::File;
+use std::os::unix::fs::symlink;
+
+use nix::errno::Errno;
+use nix::sys::xattr::*;
+
+#[test]
+fn test_setxattr_getxattr_removexattr() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    File::create(&path).unwrap();
+
+    match setxattr(&path, "user.nix", b"value", XattrFlags::XATTR_CREATE) {
+        Err(Errno::ENOTSUP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert_eq!(getxattr(&path, "user.nix").unwrap(), b"value");
+
+    assert_eq!(
+        setxattr(&path, "user.nix", b"other", XattrFlags::XATTR_CREATE),
+        Err(Errno::EEXIST)
+    );
+    assert_eq!(
+        setxattr(&path, "user.missing", b"", XattrFlags::XATTR_REPLACE),
+        Err(Errno::ENODATA)
+    );
+    setxattr(&path, "user.nix", b"", XattrFlags::XATTR_REPLACE).unwrap();
+    assert_eq!(getxattr(&path, "user.nix").unwrap(), b"");
+
+    removexattr(&path, "user.nix").unwrap();
+    assert_eq!(getxattr(&path, "user.nix"), Err(Errno::ENODATA));
+    assert_eq!(removexattr(&path, "user.nix"), Err(Errno::ENODATA));
+}
+
+#[test]
+fn test_fsetxattr_flistxattr() {
+    let file = tempfile::tempfile().unwrap();
+    match fsetxattr(&file, "user.a", b"1", XattrFlags::empty()) {
+        Err(Errno::ENOTSUP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    fsetxattr(&file, "user.b", &[0; 1000], XattrFlags::empty()).unwrap();
+    assert_eq!(fgetxattr(&file, "user.b").unwrap(), [0; 1000]);
+
+    let list = flistxattr(&file).unwrap();
+    let mut names: Vec<_> = list.iter_namespace(XattrNamespace::User).collect();
+    names.sort();
+    assert_eq!(names, [OsStr::new("a"), OsStr::new("b")]);
+    assert!(list.iter().any(|name| name == "user.a"));
+
+    fremovexattr(&file, "user.a").unwrap();
+    fremovexattr(&file, "user.b").unwrap();
+    let list = flistxattr(&file).unwrap();
+    assert_eq!(list.iter_namespace(XattrNamespace::User).count(), 0);
+}
+
+#[test]
+fn test_lgetxattr_symlink() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    let link = tempdir.path().join("link");
+    File::create(&path).unwrap();
+    symlink(&path, &link).unwrap();
+
+    match setxattr(&link, "user.nix", b"value", XattrFlags::empty()) {
+        Err(Errno::ENOTSUP) => {
+            skip!("user extended attributes are not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    // The attribute was set on the target of the link.
+    assert_eq!(getxattr(&path, "user.nix").unwrap(), b"value");
+    assert_eq!(lgetxattr(&link, "user.nix"), Err(Errno::ENODATA));
+    assert!(!llistxattr(&link)
+        .unwrap()
+        .iter()
+        .any(|name| name == "user.nix"));
+    assert!(listxattr(&link)
+        .unwrap()
+        .iter()
+        .any(|name| name == "user.nix"));
+    // User attributes are not allowed on symbolic links.
+    assert_eq!(
+        lsetxattr(&link, "user.nix", b"value", XattrFlags::empty()),
+        Err(Errno::EPERM)
+    );
+    assert_eq!(lremovexattr(&link, "user.nix"), Err(Errno::EPERM));
+    removexattr(&link, "user.nix").unwrap();
+}
+
+#[test]
+fn test_xattr_namespace_split() {
+    assert_eq!(
+        XattrNamespace::split(OsStr::new("security.selinux")),
+        Some((XattrNamespace::Security, OsStr::new("selinux")))
+    );
+    assert_eq!(XattrNamespace::split(OsStr::new("unknown.name")), None);
+    assert_eq!(XattrNamespace::Trusted.prefix(), "trusted.");
+}
@@ -56,3 +56,96 @@ fn rewind() {
     assert_eq!(entries1, entries2);
     assert_eq!(entries2, entries3);
 }
+
+#[cfg(linux_android)]
+#[test]
+fn raw_dir() {
+    use nix::dir::RawDir;
+    use nix::fcntl::open;
+    use nix::unistd::{lseek, Whence};
+
+    let tmp = tempdir().unwrap();
+    for i in 0..100 {
+        File::create(tmp.path().join(format!("file{i:03}"))).unwrap();
+    }
+    std::os::unix::fs::symlink("file000", tmp.path().join("link")).unwrap();
+    let fd = open(tmp.path(), flags(), Mode::empty()).unwrap();
+
+    // A small buffer forces several getdents64 calls.
+    let mut buf = [0u8; 256];
+    let mut dir = RawDir::new(&fd, &mut buf);
+    let mut entries = Vec::new();
+    while let Some(entry) = dir.next_entry() {
+        let entry = entry.unwrap();
+        assert_ne!(entry.ino(), 0);
+        let name = entry.file_name().to_str().unwrap().to_owned();
+        let expected = match name.as_str() {
+            "." | ".." => Type::Directory,
+            "link" => Type::Symlink,
+            _ => Type::File,
+        };
+        assert!(&[Some(expected), None].contains(&entry.file_type()));
+        entries.push(name);
+    }
+    entries.sort();
+    assert_eq!(entries.len(), 103);
+    assert_eq!(&entries[..3], &[".", "..", "file000"]);
+    assert_eq!(entries[102], "link");
+
+    // Exhausted until rewound.
+    assert!(dir.next_entry().is_none());
+    lseek(&fd, 0, Whence::SeekSet).unwrap();
+    let mut count = 0;
+    while let Some(entry) = dir.next_entry() {
+        entry.unwrap();
+        count += 1;
+    }
+    assert_eq!(count, 103);
+}
+
+#[cfg(linux_android)]
+#[test]
+fn raw_dir_buffer_too_small() {
+    use nix::dir::RawDir;
+    use nix::errno::Errno;
+    use nix::fcntl::open;
+
+    let tmp = tempdir().unwrap();
+    let fd = open(tmp.path(), flags(), Mode::empty()).unwrap();
+    let mut buf = [0u8; 16];
+    let mut dir = RawDir::new(&fd, &mut buf);
+    assert_eq!(dir.next_entry(), Some(Err(Errno::EINVAL)));
+}
+
+#[test]
+fn tell_and_seek() {
+    let tmp = tempdir().unwrap();
+    for name in ["a", "b", "c", "d"] {
+        File::create(tmp.path().join(name)).unwrap();
+    }
+    let mut dir = Dir::open(tmp.path(), flags(), Mode::empty()).unwrap();
+
+    let mut iter = dir.iter();
+    let first: Vec<_> = iter
+        .by_ref()
+        .take(2)
+        .map(|e| e.unwrap().file_name().to_owned())
+        .collect();
+    let pos = iter.tell().unwrap();
+    let rest1: Vec<_> =
+        iter.map(|e| e.unwrap().file_name().to_owned()).collect();
+    assert_eq!(first.len() + rest1.len(), 6);
+
+    // Dropping the iterator rewound the directory; seek back to resume.
+    dir.seek(pos);
+    let rest2: Vec<_> = dir
+        .iter()
+        .map(|e| e.unwrap().file_name().to_owned())
+        .collect();
+    assert_eq!(rest1, rest2);
+
+    // Rewinding picks up newly created entries.
+    File::create(tmp.path().join("e")).unwrap();
+    dir.rewind();
+    assert_eq!(dir.into_iter().count(), 7);
+}
@@ -18,6 +18,8 @@ use nix::fcntl::{openat2, OpenHow, ResolveFlag};
     )
 ))]
 use nix::fcntl::{renameat2, RenameFlags};
+#[cfg(apple_targets)]
+use nix::fcntl::{renameatx_np, RenameFlags};
 #[cfg(not(target_os = "redox"))]
 use nix::sys::stat::Mode;
 #[cfg(not(target_os = "redox"))]
@@ -241,6 +243,46 @@ fn test_renameat2_noreplace() {
     assert!(old_dir.path().join("old").exists());
 }
 
+#[test]
+#[cfg(apple_targets)]
+fn test_renameatx_np_swap() {
+    let dir = tempfile::tempdir().unwrap();
+    let dirfd = open(dir.path(), OFlag::empty(), Mode::empty()).unwrap();
+    std::fs::write(dir.path().join("old"), b"old").unwrap();
+    std::fs::write(dir.path().join("new"), b"new").unwrap();
+
+    match renameatx_np(&dirfd, "old", &dirfd, "new", RenameFlags::RENAME_SWAP) {
+        Err(Errno::ENOTSUP) => {
+            skip!("RENAME_SWAP is not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert_eq!(std::fs::read(dir.path().join("new")).unwrap(), b"old");
+    assert_eq!(std::fs::read(dir.path().join("old")).unwrap(), b"new");
+}
+
+#[test]
+#[cfg(apple_targets)]
+fn test_renameatx_np_excl() {
+    let dir = tempfile::tempdir().unwrap();
+    let dirfd = open(dir.path(), OFlag::empty(), Mode::empty()).unwrap();
+    File::create(dir.path().join("old")).unwrap();
+    File::create(dir.path().join("new")).unwrap();
+
+    match renameatx_np(&dirfd, "old", &dirfd, "new", RenameFlags::RENAME_EXCL) {
+        Err(Errno::ENOTSUP) => {
+            skip!("RENAME_EXCL is not supported. Skipping test.")
+        }
+        res => assert_eq!(res, Err(Errno::EEXIST)),
+    }
+    assert!(dir.path().join("old").exists());
+
+    renameatx_np(&dirfd, "old", &dirfd, "other", RenameFlags::RENAME_EXCL)
+        .unwrap();
+    assert!(!dir.path().join("old").exists());
+    assert!(dir.path().join("other").exists());
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_readlink() {
@@ -295,12 +337,120 @@ fn test_copy_file_range() {
     assert_eq!(from_offset, 6);
 }
 
+#[cfg(any(
+        linux_android,
+        // Not available until FreeBSD 13.0
+        all(target_os = "freebsd", fbsd14),
+))]
+#[test]
+// QEMU does not support copy_file_range. Skip under qemu
+#[cfg_attr(qemu, ignore)]
+fn test_copy_file_all() {
+    use nix::fcntl::copy_file_all;
+
+    const CONTENTS: &[u8] = b"foobarbaz";
+
+    let mut tmp1 = tempfile::tempfile().unwrap();
+    let mut tmp2 = tempfile::tempfile().unwrap();
+
+    tmp1.write_all(CONTENTS).unwrap();
+    tmp1.flush().unwrap();
+
+    // Asking for more than what is available stops at the end of the file.
+    let mut from_offset: i64 = 3;
+    let copied =
+        copy_file_all(&tmp1, Some(&mut from_offset), &tmp2, None, 100).unwrap();
+    assert_eq!(copied, 6);
+    assert_eq!(from_offset, 9);
+
+    let mut res: String = String::new();
+    tmp2.rewind().unwrap();
+    tmp2.read_to_string(&mut res).unwrap();
+
+    assert_eq!(res, String::from("barbaz"));
+}
+
+// `copy_file_range` can not be used on pipes, so this goes through the
+// `read`/`write` fallback.
+#[cfg(any(
+        linux_android,
+        // Not available until FreeBSD 13.0
+        all(target_os = "freebsd", fbsd14),
+))]
+#[test]
+#[cfg_attr(qemu, ignore)]
+fn test_copy_file_all_pipe() {
+    use nix::fcntl::copy_file_all;
+    use nix::unistd::{pipe, read, write};
+
+    const CONTENTS: &[u8] = b"foobarbaz";
+
+    // From a pipe to a file.
+    let (rd, wr) = pipe().unwrap();
+    write(&wr, CONTENTS).unwrap();
+    drop(wr);
+    let mut tmp = tempfile::tempfile().unwrap();
+    let copied = copy_file_all(&rd, None, &tmp, None, 100).unwrap();
+    assert_eq!(copied, CONTENTS.len());
+
+    let mut res = Vec::new();
+    tmp.rewind().unwrap();
+    tmp.read_to_end(&mut res).unwrap();
+    assert_eq!(res, CONTENTS);
+
+    // From a file, at an offset, to a pipe.
+    let (rd, wr) = pipe().unwrap();
+    let mut from_offset: i64 = 3;
+    let copied =
+        copy_file_all(&tmp, Some(&mut from_offset), &wr, None, 100).unwrap();
+    assert_eq!(copied, 6);
+    assert_eq!(from_offset, 9);
+
+    let mut buf = [0u8; 16];
+    let n = read(&rd, &mut buf).unwrap();
+    assert_eq!(&buf[..n], b"barbaz");
+}
+
+// `EINVAL` for overlapping ranges of the same file is returned, rather than
+// falling back to a copy that would overwrite the data it reads.
+#[cfg(target_os = "linux")]
+#[test]
+#[cfg_attr(qemu, ignore)]
+fn test_copy_file_all_overlapping() {
+    use nix::errno::Errno;
+    use nix::fcntl::copy_file_all;
+
+    const CONTENTS: &[u8] = b"foobarbaz";
+
+    let mut tmp = tempfile::tempfile().unwrap();
+    tmp.write_all(CONTENTS).unwrap();
+
+    let mut from_offset: i64 = 0;
+    let mut to_offset: i64 = 3;
+    assert_eq!(
+        copy_file_all(
+            &tmp,
+            Some(&mut from_offset),
+            &tmp,
+            Some(&mut to_offset),
+            6
+        ),
+        Err(Errno::EINVAL)
+    );
+
+    let mut res = Vec::new();
+    tmp.rewind().unwrap();
+    tmp.read_to_end(&mut res).unwrap();
+    assert_eq!(res, CONTENTS);
+}
+
 #[cfg(linux_android)]
 mod linux_android {
     use libc::loff_t;
     use std::io::prelude::*;
     use std::io::IoSlice;
 
+    use nix::errno::Errno;
     use nix::fcntl::*;
     use nix::unistd::{pipe, read, write};
 
@@ -370,6 +520,139 @@ mod linux_android {
         assert_eq!(b"abcdef", &buf[0..6]);
     }
 
+    #[test]
+    fn test_tmpfile_in_and_materialize() {
+        use nix::sys::stat::Mode;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let fd = match tmpfile_in(tempdir.path(), Mode::S_IRUSR | Mode::S_IWUSR)
+        {
+            Ok(fd) => fd,
+            // Not every filesystem supports O_TMPFILE.
+            Err(nix::errno::Errno::EOPNOTSUPP) => return,
+            Err(e) => panic!("tmpfile_in failed: {e}"),
+        };
+        write(&fd, b"published").unwrap();
+
+        let dirfd =
+            open(tempdir.path(), OFlag::O_DIRECTORY, Mode::empty()).unwrap();
+        materialize(&fd, &dirfd, "file").unwrap();
+        assert_eq!(
+            std::fs::read(tempdir.path().join("file")).unwrap(),
+            b"published"
+        );
+
+        // The name is not replaced if it already exists.
+        assert_eq!(
+            materialize(&fd, &dirfd, "file"),
+            Err(nix::errno::Errno::EEXIST)
+        );
+    }
+
+    #[test]
+    fn test_ficlone() {
+        use std::os::unix::fs::FileExt;
+
+        let src = tempfile().unwrap();
+        src.write_all_at(&[0xaa; 4096], 0).unwrap();
+        let dest = tempfile().unwrap();
+
+        match ficlone(&dest, &src) {
+            Ok(()) => (),
+            // Reflinks are not supported by every filesystem.
+            Err(Errno::EOPNOTSUPP | Errno::EXDEV | Errno::EINVAL) => return,
+            Err(e) => panic!("ficlone failed: {e}"),
+        }
+        let mut buf = [0u8; 4096];
+        dest.read_exact_at(&mut buf, 0).unwrap();
+        assert_eq!(buf, [0xaa; 4096]);
+
+        ficlonerange(&dest, 0, &src, 0, 0).unwrap();
+    }
+
+    #[test]
+    fn test_fideduperange() {
+        use std::os::fd::AsFd;
+        use std::os::unix::fs::FileExt;
+
+        let src = tempfile().unwrap();
+        src.write_all_at(&[0xaa; 4096], 0).unwrap();
+        let same = tempfile().unwrap();
+        same.write_all_at(&[0xaa; 4096], 0).unwrap();
+        let differs = tempfile().unwrap();
+        differs.write_all_at(&[0xbb; 4096], 0).unwrap();
+
+        let dests = [(same.as_fd(), 0), (differs.as_fd(), 0)];
+        let status = match fideduperange(&src, 0, 4096, &dests) {
+            Ok(status) => status,
+            // Deduplication is not supported by every filesystem.
+            Err(Errno::EOPNOTSUPP | Errno::EINVAL) => return,
+            Err(e) => panic!("fideduperange failed: {e}"),
+        };
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[0], DedupeRangeStatus::Same(4096));
+        assert_eq!(status[1], DedupeRangeStatus::Differs);
+    }
+
+    #[test]
+    fn test_fiemap() {
+        use std::os::unix::fs::FileExt;
+
+        let tmp = tempfile().unwrap();
+        tmp.write_all_at(&[0xaa; 8192], 0).unwrap();
+
+        let extents =
+            match fiemap(&tmp, 0, u64::MAX, FiemapFlags::FIEMAP_FLAG_SYNC) {
+                Ok(extents) => extents,
+                // Not every filesystem supports FIEMAP.
+                Err(Errno::EOPNOTSUPP) => return,
+                Err(e) => panic!("fiemap failed: {e}"),
+            };
+        assert!(!extents.is_empty());
+        assert_eq!(extents[0].logical, 0);
+        let last = extents.last().unwrap();
+        assert!(last.logical + last.length >= 8192);
+        assert!(last.flags.contains(FiemapExtentFlags::FIEMAP_EXTENT_LAST));
+
+        // An empty file has no extents.
+        let empty = tempfile().unwrap();
+        assert!(fiemap(&empty, 0, u64::MAX, FiemapFlags::empty())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_inode_flags() {
+        let tmp = tempfile().unwrap();
+        let flags = match get_inode_flags(&tmp) {
+            Ok(flags) => flags,
+            // Not every filesystem supports inode flags.
+            Err(Errno::ENOTTY | Errno::EOPNOTSUPP) => return,
+            Err(e) => panic!("get_inode_flags failed: {e}"),
+        };
+
+        set_inode_flags(&tmp, flags | InodeFlags::FS_NODUMP_FL).unwrap();
+        assert!(get_inode_flags(&tmp)
+            .unwrap()
+            .contains(InodeFlags::FS_NODUMP_FL));
+        set_inode_flags(&tmp, flags).unwrap();
+        assert_eq!(get_inode_flags(&tmp).unwrap(), flags);
+    }
+
+    #[test]
+    fn test_pipe_size() {
+        let (rd, _wr) = pipe().unwrap();
+
+        let orig = fcntl(&rd, FcntlArg::F_GETPIPE_SZ).unwrap();
+        assert!(orig > 0);
+
+        // The kernel rounds the requested size up to a power-of-two number
+        // of pages.
+        let granted = fcntl(&rd, FcntlArg::F_SETPIPE_SZ(orig + 1)).unwrap();
+        assert!(granted > orig);
+        assert_eq!(granted, fcntl(&rd, FcntlArg::F_GETPIPE_SZ).unwrap());
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_fallocate() {
@@ -382,6 +665,65 @@ mod linux_android {
         assert_eq!(100, read(&tmp, &mut buf).unwrap());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_fallocate_punch_hole() {
+        use nix::sys::uio::pread;
+
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(&[0xffu8; 8192]).unwrap();
+
+        let mode = FallocateFlags::FALLOC_FL_PUNCH_HOLE
+            | FallocateFlags::FALLOC_FL_KEEP_SIZE;
+        match fallocate(&tmp, mode, 0, 4096) {
+            Ok(()) => (),
+            // Not every filesystem supports hole punching.
+            Err(nix::errno::Errno::EOPNOTSUPP) => return,
+            Err(e) => panic!("fallocate failed: {e}"),
+        }
+
+        let mut buf = [0u8; 8192];
+        assert_eq!(8192, pread(&tmp, &mut buf, 0).unwrap());
+        assert!(buf[..4096].iter().all(|&b| b == 0));
+        assert!(buf[4096..].iter().all(|&b| b == 0xff));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sync_file_range() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"abcdef").unwrap();
+
+        sync_file_range(
+            &tmp,
+            0,
+            0,
+            SyncFileRangeFlags::SYNC_FILE_RANGE_WAIT_BEFORE
+                | SyncFileRangeFlags::SYNC_FILE_RANGE_WRITE
+                | SyncFileRangeFlags::SYNC_FILE_RANGE_WAIT_AFTER,
+        )
+        .unwrap();
+
+        // Negative offsets are invalid.
+        assert_eq!(
+            sync_file_range(&tmp, -1, 0, SyncFileRangeFlags::empty()),
+            Err(nix::errno::Errno::EINVAL)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_readahead() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"abcdef").unwrap();
+
+        readahead(&tmp, 0, 6).unwrap();
+
+        // readahead() only works on regular files.
+        let (rd, _wr) = pipe().unwrap();
+        assert_eq!(readahead(&rd, 0, 6), Err(nix::errno::Errno::EINVAL));
+    }
+
     // The tests below are disabled for the listed targets
     // due to OFD locks not being available in the kernel/libc
     // versions used in the CI environment, probably because
@@ -518,6 +860,7 @@ mod test_posix_fadvise {
 #[cfg(any(
     linux_android,
     freebsdlike,
+    solarish,
     target_os = "emscripten",
     target_os = "fuchsia",
     target_os = "wasi",
@@ -581,6 +924,60 @@ fn test_f_get_path() {
     );
 }
 
+#[cfg(apple_targets)]
+#[test]
+fn test_f_nocache_preallocate_rdadvise() {
+    use nix::fcntl::*;
+
+    let mut tmp = NamedTempFile::new().unwrap();
+    fcntl(&tmp, FcntlArg::F_NOCACHE(true)).unwrap();
+
+    let mut fstore = libc::fstore_t {
+        fst_flags: libc::F_ALLOCATEALL,
+        fst_posmode: libc::F_PEOFPOSMODE,
+        fst_offset: 0,
+        fst_length: 65536,
+        fst_bytesalloc: 0,
+    };
+    fcntl(&tmp, FcntlArg::F_PREALLOCATE(&mut fstore)).unwrap();
+    assert!(fstore.fst_bytesalloc >= 65536);
+
+    tmp.write_all(&[0u8; 4096]).unwrap();
+    fcntl(&tmp, FcntlArg::F_FULLFSYNC).unwrap();
+    let radvisory = libc::radvisory {
+        ra_offset: 0,
+        ra_count: 4096,
+    };
+    fcntl(&tmp, FcntlArg::F_RDADVISE(&radvisory)).unwrap();
+    fcntl(&tmp, FcntlArg::F_NOCACHE(false)).unwrap();
+}
+
+#[cfg(apple_targets)]
+#[test]
+fn test_clonefileat_and_fcopyfile() {
+    use nix::fcntl::*;
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dirfd = open(tmpdir.path(), OFlag::O_DIRECTORY, Mode::empty()).unwrap();
+    std::fs::write(tmpdir.path().join("src"), b"hello").unwrap();
+
+    match clonefileat(&dirfd, "src", &dirfd, "clone", ClonefileFlags::empty()) {
+        // Not on APFS
+        Err(Errno::ENOTSUP) => (),
+        res => {
+            res.unwrap();
+            let clone = std::fs::read(tmpdir.path().join("clone")).unwrap();
+            assert_eq!(clone, b"hello");
+        }
+    }
+
+    let src = File::open(tmpdir.path().join("src")).unwrap();
+    let dst = File::create(tmpdir.path().join("copy")).unwrap();
+    fcopyfile(&src, &dst, CopyfileFlags::COPYFILE_DATA).unwrap();
+    let copy = std::fs::read(tmpdir.path().join("copy")).unwrap();
+    assert_eq!(copy, b"hello");
+}
+
 #[cfg(apple_targets)]
 #[test]
 fn test_f_get_path_nofirmlink() {
@@ -631,6 +1028,25 @@ fn test_f_kinfo() {
     assert_eq!(path, tmp.path());
 }
 
+#[cfg(any(
+    linux_android,
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    apple_targets,
+    all(target_os = "freebsd", target_arch = "x86_64"),
+))]
+#[test]
+fn test_fd_path() {
+    use nix::fcntl::fd_path;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let path = fd_path(&tmp).unwrap();
+    assert_eq!(
+        path.canonicalize().unwrap(),
+        tmp.path().canonicalize().unwrap()
+    );
+}
+
 /// Test `Flock` and associated functions.
 ///
 #[cfg(not(any(target_os = "redox", target_os = "solaris")))]
@@ -38,7 +38,6 @@ fn test_poll() {
 // ppoll(2) is the same as poll except for how it handles timeouts and signals.
 // Repeating the test for poll(2) should be sufficient to check that our
 // bindings are correct.
-#[cfg(any(linux_android, freebsdlike))]
 #[test]
 fn test_ppoll() {
     use nix::poll::ppoll;
@@ -61,6 +60,10 @@ fn test_ppoll() {
     let nfds = ppoll(&mut fds, Some(timeout), None).unwrap();
     assert_eq!(nfds, 1);
     assert!(fds[0].revents().unwrap().contains(PollFlags::POLLIN));
+
+    // `None` still means no timeout.
+    let nfds = ppoll(&mut fds, None, None).unwrap();
+    assert_eq!(nfds, 1);
 }
 
 #[test]
@@ -71,3 +74,42 @@ fn test_pollfd_events() {
     pfd.set_events(PollFlags::POLLOUT);
     assert_eq!(pfd.events(), PollFlags::POLLOUT);
 }
+
+#[test]
+fn test_timeout_conversions() {
+    use nix::poll::Timeout;
+    use nix::sys::time::TimeSpec;
+    use std::time::Duration;
+
+    assert_eq!(Timeout::from(None), Timeout::Infinite);
+    assert_eq!(Timeout::from(Duration::ZERO), Timeout::ZeroPoll);
+    assert_eq!(
+        Timeout::from(TimeSpec::new(1, 500)),
+        Timeout::Duration(Duration::new(1, 500))
+    );
+    assert_eq!(
+        Timeout::from(PollTimeout::from(250u8)),
+        Timeout::Duration(Duration::from_millis(250))
+    );
+    assert_eq!(Timeout::from(PollTimeout::NONE), Timeout::Infinite);
+
+    assert_eq!(Timeout::Infinite.as_timespec(), None);
+    assert_eq!(Timeout::ZeroPoll.as_timespec(), Some(TimeSpec::new(0, 0)));
+    assert_eq!(
+        PollTimeout::from(Timeout::Duration(Duration::from_nanos(1))),
+        PollTimeout::from(1u8)
+    );
+    assert_eq!(
+        PollTimeout::from(Timeout::Duration(Duration::from_secs(u64::MAX))),
+        PollTimeout::MAX
+    );
+}
+
+#[test]
+fn test_poll_with_timeout() {
+    use nix::poll::Timeout;
+
+    let (r, _w) = pipe().unwrap();
+    let mut fds = [PollFd::new(r.as_fd(), PollFlags::POLLIN)];
+    assert_eq!(poll(&mut fds, Timeout::ZeroPoll), Ok(0));
+}
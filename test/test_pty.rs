@@ -139,6 +139,30 @@ fn test_open_ptty_pair() {
     let (_, _) = open_ptty_pair();
 }
 
+/// Test opening the slave with `open_slave`, without going through its name
+#[test]
+#[cfg(linux_android)]
+fn test_open_slave() {
+    let mut master = posix_openpt(OFlag::O_RDWR).unwrap();
+    grantpt(&master).unwrap();
+    unlockpt(&master).unwrap();
+
+    let slave = open_slave(&master, OFlag::O_RDWR | OFlag::O_NOCTTY).unwrap();
+    let mut slave = File::from(slave);
+    make_raw(&slave);
+
+    master.write_all(b"hello").unwrap();
+    let mut buf = [0u8; 5];
+    slave.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    // The slave is the device named by `ptsname_r`.
+    let name = ptsname_r(&master).unwrap();
+    let st = stat::stat(Path::new(&name)).unwrap();
+    let fst = stat::fstat(&slave).unwrap();
+    assert_eq!(st.st_rdev, fst.st_rdev);
+}
+
 /// Put the terminal in raw mode.
 fn make_raw<Fd: AsFd>(fd: Fd) {
     let mut termios = tcgetattr(&fd).unwrap();
@@ -279,3 +303,68 @@ fn test_forkpty() {
         }
     }
 }
+
+#[test]
+#[cfg(not(target_os = "aix"))]
+fn test_login_tty() {
+    use nix::unistd::{fork, getpid, getsid, isatty, tcgetpgrp, ForkResult};
+    use std::io::stdin;
+
+    let _m0 = crate::PTSNAME_MTX.lock();
+    let _m1 = crate::FORK_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            drop(pty.master);
+            let ok = login_tty(pty.slave).is_ok()
+                && getsid(None) == Ok(getpid())
+                && tcgetpgrp(stdin()) == Ok(getpid())
+                && (0..3).all(|fd| {
+                    isatty(unsafe { BorrowedFd::borrow_raw(fd) }) == Ok(true)
+                });
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            drop(pty.slave);
+            let status = nix::sys::wait::waitpid(child, None).unwrap();
+            assert_eq!(status, WaitStatus::Exited(child, 0));
+        }
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "linux", apple_targets, freebsdlike))]
+fn test_packet_mode() {
+    // openpty uses ptname(3) internally
+    let _m = crate::PTSNAME_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    set_packet_mode(&pty.master, true).unwrap();
+
+    let mut buf = [0u8; 16];
+    write(&pty.slave, b"hi").unwrap();
+    assert_eq!(read_packet(&pty.master, &mut buf), Ok(Packet::Data(b"hi")));
+
+    tcflush(&pty.slave, FlushArg::TCIFLUSH).unwrap();
+    assert_eq!(
+        read_packet(&pty.master, &mut buf),
+        Ok(Packet::Control(PacketControl::TIOCPKT_FLUSHREAD))
+    );
+
+    tcflow(&pty.slave, FlowArg::TCOOFF).unwrap();
+    assert_eq!(
+        read_packet(&pty.master, &mut buf),
+        Ok(Packet::Control(PacketControl::TIOCPKT_STOP))
+    );
+    tcflow(&pty.slave, FlowArg::TCOON).unwrap();
+    assert_eq!(
+        read_packet(&pty.master, &mut buf),
+        Ok(Packet::Control(PacketControl::TIOCPKT_START))
+    );
+
+    set_packet_mode(&pty.master, false).unwrap();
+    write(&pty.slave, b"hi").unwrap();
+    crate::read_exact(&pty.master, &mut buf[..2]);
+    assert_eq!(&buf[..2], b"hi");
+}
@@ -37,3 +37,230 @@ fn test_sched_affinity() {
     // Finally, reset the initial CPU set
     sched_setaffinity(Pid::from_raw(0), &initial_affinity).unwrap();
 }
+
+#[cfg(target_os = "linux")]
+mod nsfs {
+    use nix::errno::Errno;
+    use nix::sched::nsfs::{
+        ns_get_nstype, ns_get_owner_uid, ns_get_parent, ns_get_userns,
+    };
+    use nix::sched::CloneFlags;
+    use nix::sys::stat::fstat;
+    use std::fs::File;
+
+    #[test]
+    fn test_ns_get_nstype() {
+        for (name, nstype) in [
+            ("mnt", CloneFlags::CLONE_NEWNS),
+            ("net", CloneFlags::CLONE_NEWNET),
+            ("pid", CloneFlags::CLONE_NEWPID),
+            ("user", CloneFlags::CLONE_NEWUSER),
+            ("uts", CloneFlags::CLONE_NEWUTS),
+        ] {
+            let ns = File::open(format!("/proc/self/ns/{name}")).unwrap();
+            assert_eq!(ns_get_nstype(&ns).unwrap(), nstype, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_ns_get_userns() {
+        let net = File::open("/proc/self/ns/net").unwrap();
+        let user = File::open("/proc/self/ns/user").unwrap();
+        let userns = match ns_get_userns(&net) {
+            // The network namespace is owned by an ancestor user namespace.
+            Err(Errno::EPERM) => return,
+            r => r.unwrap(),
+        };
+        assert_eq!(ns_get_nstype(&userns).unwrap(), CloneFlags::CLONE_NEWUSER);
+        // Otherwise, the network namespace is owned by our user namespace.
+        assert_eq!(
+            fstat(&userns).unwrap().st_ino,
+            fstat(&user).unwrap().st_ino
+        );
+        assert!(ns_get_owner_uid(&userns).is_ok());
+    }
+
+    #[test]
+    fn test_ns_get_parent() {
+        // Only PID and user namespaces are hierarchical.
+        let uts = File::open("/proc/self/ns/uts").unwrap();
+        assert_eq!(ns_get_parent(&uts).unwrap_err(), Errno::EINVAL);
+
+        match ns_get_parent(File::open("/proc/self/ns/user").unwrap()) {
+            // The initial user namespace has no parent.
+            Err(Errno::EPERM) => (),
+            r => assert_eq!(
+                ns_get_nstype(r.unwrap()).unwrap(),
+                CloneFlags::CLONE_NEWUSER
+            ),
+        }
+    }
+
+    #[test]
+    fn test_ns_get_owner_uid() {
+        // Not a user namespace.
+        let net = File::open("/proc/self/ns/net").unwrap();
+        assert_eq!(ns_get_owner_uid(&net).unwrap_err(), Errno::EINVAL);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_setns_pidfd() {
+    use nix::sched::{setns_pidfd, unshare, CloneFlags};
+    use nix::sys::utsname::uname;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::ForkResult::*;
+    use nix::unistd::{fork, pipe, read, sethostname, write};
+    use std::os::unix::io::{FromRawFd, OwnedFd};
+
+    require_capability!("test_setns_pidfd", CAP_SYS_ADMIN);
+    let _m = crate::FORK_MTX.lock();
+
+    let (ready_r, ready_w) = pipe().unwrap();
+    let (done_r, done_w) = pipe().unwrap();
+    // Safe: the child only makes system calls before exiting.
+    let owner = match unsafe { fork() }.unwrap() {
+        Child => {
+            let ok =
+                unshare(CloneFlags::CLONE_NEWUTS | CloneFlags::CLONE_NEWIPC)
+                    .and_then(|_| sethostname("nix-setns-pidfd"))
+                    .is_ok();
+            write(&ready_w, &[ok as u8]).unwrap();
+            read(&done_r, &mut [0]).unwrap();
+            unsafe { libc::_exit(0) };
+        }
+        Parent { child } => child,
+    };
+    let mut ok = [0];
+    read(&ready_r, &mut ok).unwrap();
+    assert_eq!(ok, [1]);
+
+    let res = unsafe { libc::syscall(libc::SYS_pidfd_open, owner.as_raw(), 0) };
+    if res < 0 {
+        write(&done_w, &[0]).unwrap();
+        waitpid(owner, None).unwrap();
+        skip!("pidfd_open is not supported by this kernel. Skipping test.");
+    }
+    let pidfd = unsafe { OwnedFd::from_raw_fd(res as i32) };
+
+    // Safe: the child only makes system calls before exiting.
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            let joined = setns_pidfd(
+                &pidfd,
+                CloneFlags::CLONE_NEWUTS | CloneFlags::CLONE_NEWIPC,
+            )
+            .is_ok();
+            // `uname` fills a buffer on the stack, unlike `gethostname`.
+            let code = match uname() {
+                Ok(name) if joined && name.nodename() == "nix-setns-pidfd" => 0,
+                _ => 1,
+            };
+            unsafe { libc::_exit(code) };
+        }
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+
+    write(&done_w, &[0]).unwrap();
+    assert_eq!(waitpid(owner, None), Ok(WaitStatus::Exited(owner, 0)));
+}
+
+#[cfg(linux_android)]
+#[test]
+fn test_setup_userns_invalid_maps() {
+    use nix::errno::Errno;
+    use nix::sched::{setup_userns, IdMapping, Setgroups};
+
+    let valid = [IdMapping::single(0, 1000)];
+    for invalid in [
+        &[][..],
+        &[IdMapping::new(0, 1000, 0)],
+        &[IdMapping::new(0, u32::MAX, 1)],
+        &[IdMapping::new(0, 1000, 10), IdMapping::new(5, 2000, 10)],
+        &[IdMapping::new(0, 1000, 10), IdMapping::new(100, 1009, 10)],
+    ] {
+        // Nothing is written, since the maps are validated first.
+        assert_eq!(
+            setup_userns(None, invalid, &valid, Setgroups::Deny),
+            Err(Errno::EINVAL),
+            "{invalid:?}"
+        );
+        assert_eq!(
+            setup_userns(None, &valid, invalid, Setgroups::Deny),
+            Err(Errno::EINVAL),
+            "{invalid:?}"
+        );
+    }
+    let too_long: Vec<_> = (0..341).map(|i| IdMapping::single(i, i)).collect();
+    assert_eq!(
+        setup_userns(None, &too_long, &valid, Setgroups::Deny),
+        Err(Errno::EINVAL)
+    );
+}
+
+#[cfg(linux_android)]
+#[test]
+fn test_setup_userns() {
+    use nix::sched::{setup_userns, unshare, CloneFlags, IdMapping, Setgroups};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::ForkResult::*;
+    use nix::unistd::{fork, pipe, read, write};
+
+    let _m = crate::FORK_MTX.lock();
+
+    let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    // Formatted before forking, as the child must not allocate.
+    let uid_str = uid.to_string();
+    let (ready_r, ready_w) = pipe().unwrap();
+    let (done_r, done_w) = pipe().unwrap();
+    // Safe: the child only makes system calls before exiting.  The maps are
+    // written by the parent, as `setup_userns` allocates.
+    let child = match unsafe { fork() }.unwrap() {
+        Child => {
+            let ok = unshare(CloneFlags::CLONE_NEWUSER).is_ok();
+            write(&ready_w, &[ok as u8]).unwrap();
+            if !ok {
+                unsafe { libc::_exit(2) };
+            }
+            read(&done_r, &mut [0]).unwrap();
+
+            let mut buf = [0u8; 128];
+            let n = unsafe {
+                let fd = libc::open(
+                    b"/proc/self/uid_map\0".as_ptr().cast(),
+                    libc::O_RDONLY | libc::O_CLOEXEC,
+                );
+                libc::read(fd, buf.as_mut_ptr().cast(), buf.len())
+            };
+            let mapped = n > 0
+                && std::str::from_utf8(&buf[..n as usize]).map_or(
+                    false,
+                    |map| {
+                        map.split_whitespace().eq(["0", uid_str.as_str(), "1"])
+                    },
+                );
+            let root = unsafe { libc::geteuid() == 0 && libc::getegid() == 0 };
+            unsafe { libc::_exit(if mapped && root { 0 } else { 1 }) };
+        }
+        Parent { child } => child,
+    };
+    let mut ok = [0];
+    read(&ready_r, &mut ok).unwrap();
+    if ok == [0] {
+        waitpid(child, None).unwrap();
+        skip!("User namespaces are not available. Skipping test.");
+    }
+
+    let res = setup_userns(
+        Some(child),
+        &[IdMapping::single(0, uid)],
+        &[IdMapping::single(0, gid)],
+        Setgroups::Deny,
+    );
+    write(&done_w, &[0]).unwrap();
+    assert_eq!(res, Ok(()));
+    assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+}
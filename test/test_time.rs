@@ -64,3 +64,69 @@ pub fn test_clock_nanosleep() {
     let expected = TimeSpec::microseconds(0);
     assert_eq!(res, Ok(expected));
 }
+
+#[cfg(any(
+    linux_android,
+    solarish,
+    freebsdlike,
+    target_os = "netbsd",
+    target_os = "hurd",
+    target_os = "aix"
+))]
+#[test]
+pub fn test_clock_nanosleep_interrupted_and_absolute() {
+    use nix::{
+        sys::pthread::{pthread_kill, pthread_self, Pthread},
+        sys::signal::{
+            sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal,
+        },
+        sys::time::{TimeSpec, TimeValLike},
+        time::{clock_nanosleep, ClockNanosleepFlags},
+    };
+    use std::time::Duration;
+
+    extern "C" fn noop(_: libc::c_int) {}
+
+    let _m = crate::SIGNAL_MTX.lock();
+
+    let action = SigAction::new(
+        SigHandler::Handler(noop),
+        SaFlags::empty(),
+        SigSet::empty(),
+    );
+    let old_action = unsafe { sigaction(Signal::SIGUSR2, &action) }.unwrap();
+
+    // A relative sleep returns the remaining time when interrupted.
+    // `Pthread` is a raw pointer, which isn't `Send`, on some platforms.
+    struct Sleeper(Pthread);
+    unsafe impl Send for Sleeper {}
+
+    let sleeper = Sleeper(pthread_self());
+    let killer = std::thread::spawn(move || {
+        let sleeper = sleeper;
+        std::thread::sleep(Duration::from_millis(50));
+        pthread_kill(sleeper.0, Signal::SIGUSR2).unwrap();
+    });
+    let remain = clock_nanosleep(
+        ClockId::CLOCK_MONOTONIC,
+        ClockNanosleepFlags::empty(),
+        &TimeSpec::seconds(5),
+    )
+    .unwrap();
+    killer.join().unwrap();
+    assert!(remain > TimeSpec::seconds(4));
+    assert!(remain < TimeSpec::seconds(5));
+
+    unsafe { sigaction(Signal::SIGUSR2, &old_action) }.unwrap();
+
+    // An absolute sleep waits until the deadline.
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap()
+        + TimeSpec::milliseconds(20);
+    let res = clock_nanosleep(
+        ClockId::CLOCK_MONOTONIC,
+        ClockNanosleepFlags::TIMER_ABSTIME,
+        &deadline,
+    );
+    assert_eq!(res, Ok(TimeSpec::seconds(0)));
+    assert!(clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() >= deadline);
+}
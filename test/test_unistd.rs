@@ -249,6 +249,35 @@ fn test_getsid() {
     assert_eq!(none_sid, pid_sid);
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_vhangup() {
+    use nix::pty::openpty;
+    use nix::sys::signal::signal;
+    use nix::sys::termios::set_controlling_terminal;
+
+    require_capability!("test_vhangup", CAP_SYS_ADMIN);
+    let _m0 = crate::PTSNAME_MTX.lock();
+    let _m1 = crate::FORK_MTX.lock();
+
+    let pty = openpty(None, None).unwrap();
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            // The hangup is also signaled to the session.
+            unsafe { signal(Signal::SIGHUP, SigHandler::SigIgn) }.unwrap();
+            let mut buf = [0u8; 1];
+            let ok = setsid().is_ok()
+                && set_controlling_terminal(&pty.slave, false).is_ok()
+                && vhangup().is_ok()
+                && read(&pty.slave, &mut buf) == Ok(0);
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
 #[cfg(linux_android)]
 mod linux_android {
     use nix::unistd::gettid;
@@ -604,6 +633,33 @@ fn test_lseek64() {
     assert_eq!(b"f123456", &buf);
 }
 
+#[cfg(any(
+    freebsdlike,
+    solarish,
+    linux_android,
+    apple_targets,
+    target_os = "hurd",
+))]
+#[test]
+fn test_data_extents() {
+    use std::os::unix::fs::FileExt;
+
+    const HOLE_END: u64 = 1024 * 1024;
+    let tmp = tempfile().unwrap();
+    tmp.write_all_at(b"head", 0).unwrap();
+    tmp.write_all_at(b"tail", HOLE_END).unwrap();
+    let size = HOLE_END as off_t + 4;
+
+    let extents = data_extents(&tmp).collect::<nix::Result<Vec<_>>>().unwrap();
+    assert_eq!(extents.first().unwrap().start, 0);
+    assert_eq!(extents.last().unwrap().end, size);
+    // Filesystems without hole support report a single extent.
+    if extents.len() > 1 {
+        assert!(extents[0].end < HOLE_END as off_t);
+        assert!(extents.last().unwrap().start <= HOLE_END as off_t);
+    }
+}
+
 cfg_if! {
     if #[cfg(linux_android)] {
         macro_rules! require_acct{
@@ -927,6 +983,20 @@ fn test_linkat_file() {
     assert!(newfilepath.exists());
 }
 
+#[test]
+#[cfg(linux_android)]
+fn test_linkat_empty_path() {
+    use nix::fcntl::{AtFlags, AT_FDCWD};
+
+    require_capability!("test_linkat_empty_path", CAP_DAC_READ_SEARCH);
+    let tempdir = tempdir().unwrap();
+    let file = File::create(tempdir.path().join("foo.txt")).unwrap();
+    let newfilepath = tempdir.path().join("bar.txt");
+
+    linkat(&file, "", AT_FDCWD, &newfilepath, AtFlags::AT_EMPTY_PATH).unwrap();
+    assert!(newfilepath.exists());
+}
+
 #[test]
 #[cfg(not(any(target_os = "redox", target_os = "haiku")))]
 fn test_linkat_olddirfd_none() {
@@ -1391,3 +1461,100 @@ fn test_group_from() {
     assert_eq!(group.gid, group_id);
     assert_eq!(group.name, "wheel");
 }
+
+#[test]
+#[cfg(target_os = "openbsd")]
+fn test_pledge() {
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            pledge(Some(Promises::STDIO), None).unwrap();
+            // Adding promises is not permitted.
+            if pledge(Some(Promises::STDIO | Promises::RPATH), None)
+                != Err(Errno::EPERM)
+            {
+                unsafe { _exit(1) };
+            }
+            // Killed with SIGABRT.
+            let _ = File::open("/etc/passwd");
+            unsafe { _exit(2) };
+        }
+        Parent { child } => {
+            assert!(matches!(
+                waitpid(child, None),
+                Ok(WaitStatus::Signaled(_, Signal::SIGABRT, _))
+            ));
+        }
+    }
+}
+
+#[test]
+#[cfg(target_os = "openbsd")]
+fn test_unveil() {
+    let _m = crate::FORK_MTX.lock();
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    File::create(&path).unwrap();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            unveil(Some(tempdir.path()), Some(UnveilPermissions::READ))
+                .unwrap();
+            unveil(None::<&Path>, None).unwrap();
+            let ok = File::open(&path).is_ok()
+                && File::open("/etc/passwd").is_err()
+                && unveil(Some("/"), Some(UnveilPermissions::READ))
+                    == Err(Errno::EPERM);
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[test]
+#[cfg(any(linux_android, target_os = "illumos", target_os = "hurd"))]
+fn test_syncfs() {
+    let mut file = tempfile().unwrap();
+    file.write_all(b"data").unwrap();
+    syncfs(&file).unwrap();
+
+    let (r, _w) = pipe().unwrap();
+    // Pipes live on an in-memory filesystem, which has nothing to flush.
+    syncfs(&r).unwrap();
+}
+
+#[test]
+#[cfg(any(linux_android, freebsdlike, target_os = "netbsd", solarish))]
+fn test_getrandom() {
+    let mut buf = vec![0u8; 4096];
+    getrandom(&mut buf, GetRandomFlags::empty()).unwrap();
+    // The odds of 4096 random bytes all being zero are negligible.
+    assert!(buf.iter().any(|&b| b != 0));
+}
+
+#[test]
+#[cfg(any(linux_android, freebsdlike, target_os = "netbsd"))]
+fn test_getrandom_insecure() {
+    let mut buf = [0u8; 64];
+    match getrandom(&mut buf, GetRandomFlags::GRND_INSECURE) {
+        Err(Errno::EINVAL) => {
+            skip!("GRND_INSECURE is not supported. Skipping test.")
+        }
+        res => res.unwrap(),
+    }
+    assert!(buf.iter().any(|&b| b != 0));
+}
+
+#[test]
+#[cfg(any(bsd, solarish, all(target_os = "linux", target_env = "gnu")))]
+fn test_getentropy() {
+    let mut buf = [0u8; 256];
+    getentropy(&mut buf).unwrap();
+    assert!(buf.iter().any(|&b| b != 0));
+
+    let mut too_long = [0u8; 257];
+    assert!(getentropy(&mut too_long).is_err());
+}